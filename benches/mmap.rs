@@ -0,0 +1,91 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use unity_rs::bundle::{Bundle, BundleNode, CompressionType, FileData};
+use unity_rs::Env;
+
+const SIZE: usize = 64 << 20;
+
+/// Tracks the heap bytes in use and their peak, which is what mapping a bundle saves over
+/// reading it into memory.
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// The peak heap growth while `f` runs and keeps its result.
+fn peak_heap<T>(f: impl FnOnce() -> T) -> usize {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let result = f();
+    let peak = PEAK.load(Ordering::Relaxed);
+    drop(result);
+    peak - start
+}
+
+/// An uncompressed bundle holding `SIZE` bytes of streamed data, written to a temporary file.
+fn bundle_file() -> std::path::PathBuf {
+    let bundle = Bundle {
+        version: 6,
+        unity_version: "5.x.x".to_string(),
+        unity_revision: "2019.4.40f1".to_string(),
+        compression: CompressionType::None,
+        nodes: vec![BundleNode {
+            path: "CAB-bench.resS".to_string(),
+            flags: 0,
+            offset: 0,
+            data: (0..SIZE).map(|x| x as u8).collect(),
+        }],
+    };
+    let path = std::env::temp_dir().join("unity_rs_bench_mmap.ab");
+    std::fs::write(&path, bundle.to_bytes().expect("Write failure")).expect("Write failure");
+    path
+}
+
+fn load_copied(path: &Path) -> Env {
+    let mut env = Env::new();
+    env.load_from_data(FileData::from(std::fs::read(path).expect("Read failure"))).expect("Load failure");
+    env
+}
+
+/// With the `mmap` feature the file is mapped rather than read.
+fn load_mapped(path: &Path) -> Env {
+    let mut env = Env::new();
+    env.load_from_file(path).expect("Load failure");
+    env
+}
+
+fn bench_mmap(c: &mut Criterion) {
+    let path = bundle_file();
+    // Criterion only times, so the heap each way of loading needs is printed up front.
+    println!("load copied: {} bytes of heap for a {} byte bundle", peak_heap(|| load_copied(&path)), SIZE);
+    println!("load mapped: {} bytes of heap for a {} byte bundle", peak_heap(|| load_mapped(&path)), SIZE);
+    c.bench_function("load copied", |b| b.iter(|| load_copied(black_box(&path))));
+    c.bench_function("load mapped", |b| b.iter(|| load_mapped(black_box(&path))));
+    std::fs::remove_file(&path).expect("Remove failure");
+}
+
+criterion_group!(benches, bench_mmap);
+criterion_main!(benches);
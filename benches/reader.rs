@@ -0,0 +1,70 @@
+use std::hint::black_box;
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde::Deserialize;
+use unity_rs::object::Deserializer;
+use unity_rs::reader::{ByteOrder, ByteSource, Reader};
+use unity_rs::typetree::TypeTreeBuilder;
+
+const VALUES: i32 = 100_000;
+
+/// An aligned string followed by an array of `VALUES` ints.
+fn data() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&5i32.to_le_bytes());
+    data.extend_from_slice(b"bench\0\0\0");
+    data.extend_from_slice(&VALUES.to_le_bytes());
+    for i in 0..VALUES {
+        data.extend_from_slice(&i.to_le_bytes());
+    }
+    data
+}
+
+fn read_primitives<'a, S: ByteSource<'a>>(mut reader: Reader<'a, S>) -> i64 {
+    let mut sum = reader.read_aligned_string().expect("Read Failure").len() as i64;
+    for _ in 0..=VALUES {
+        sum += i64::from(reader.read_i32().expect("Read Failure"));
+    }
+    sum
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Values {
+    #[serde(rename = "m_Name")]
+    name: String,
+    #[serde(rename = "m_Values")]
+    values: Vec<i32>,
+}
+
+fn bench_reader(c: &mut Criterion) {
+    let data = data();
+    c.bench_function("primitives slice", |b| b.iter(|| read_primitives(Reader::new(black_box(&data), ByteOrder::Little))));
+    c.bench_function("primitives bulk", |b| {
+        b.iter(|| {
+            let mut reader = Reader::new(black_box(&data), ByteOrder::Little);
+            reader.read_aligned_string().expect("Read Failure");
+            let size = reader.read_i32().expect("Read Failure") as usize;
+            reader.read_i32_slice(size).expect("Read Failure").into_iter().map(i64::from).sum::<i64>()
+        })
+    });
+    c.bench_function("primitives stream", |b| b.iter(|| read_primitives(Reader::from_stream(Cursor::new(black_box(&data)), ByteOrder::Little).expect("Seek Failure"))));
+
+    let nodes = TypeTreeBuilder::new("MonoBehaviour").string("m_Name").array("vector", "m_Values", |b| b.field("int", "data")).build();
+    c.bench_function("type tree slice", |b| {
+        b.iter(|| {
+            let mut reader = Reader::new(black_box(&data), ByteOrder::Little);
+            Values::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure")
+        })
+    });
+    c.bench_function("type tree stream", |b| {
+        b.iter(|| {
+            let mut reader = Reader::from_stream(Cursor::new(black_box(&data)), ByteOrder::Little).expect("Seek Failure");
+            Values::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure")
+        })
+    });
+}
+
+criterion_group!(benches, bench_reader);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use unity_rs::asset::SerializedFile;
+use unity_rs::bundle::FileData;
+use unity_rs::ClassID;
+
+const OBJECTS: usize = 200_000;
+
+/// A format 17 file without type trees holding `OBJECTS` empty TextAssets, the size of a large
+/// `resources.assets`.
+fn serialized_file() -> Vec<u8> {
+    let mut metadata = Vec::new();
+    metadata.extend_from_slice(b"2019.4.40f1\0");
+    metadata.extend_from_slice(&5i32.to_le_bytes());
+    metadata.push(0);
+    metadata.extend_from_slice(&1i32.to_le_bytes());
+    metadata.extend_from_slice(&(ClassID::TextAsset as i32).to_le_bytes());
+    metadata.push(0);
+    metadata.extend_from_slice(&(-1i16).to_le_bytes());
+    metadata.extend_from_slice(&[0; 16]);
+    metadata.extend_from_slice(&(OBJECTS as i32).to_le_bytes());
+    for i in 0..OBJECTS {
+        while (20 + metadata.len()) % 4 != 0 {
+            metadata.push(0);
+        }
+        metadata.extend_from_slice(&(i as i64 + 1).to_le_bytes());
+        metadata.extend_from_slice(&(i as u32 * 8).to_le_bytes());
+        metadata.extend_from_slice(&8u32.to_le_bytes());
+        metadata.extend_from_slice(&0i32.to_le_bytes());
+    }
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.push(0);
+
+    let data_offset = (20 + metadata.len()).next_multiple_of(16);
+    let mut file = Vec::new();
+    file.extend_from_slice(&(metadata.len() as u32).to_be_bytes());
+    file.extend_from_slice(&((data_offset + OBJECTS * 8) as u32).to_be_bytes());
+    file.extend_from_slice(&17u32.to_be_bytes());
+    file.extend_from_slice(&(data_offset as u32).to_be_bytes());
+    file.extend_from_slice(&[0, 0, 0, 0]);
+    file.extend_from_slice(&metadata);
+    file.resize(data_offset + OBJECTS * 8, 0);
+    file
+}
+
+fn bench_serialized_file(c: &mut Criterion) {
+    let data = FileData::from(serialized_file());
+    // Loading reads the object table only; building every object is what loading used to do.
+    c.bench_function("load object table", |b| b.iter(|| SerializedFile::new(black_box(data.clone()).into(), "resources.assets").expect("Load failure")));
+    c.bench_function("load and build objects", |b| {
+        b.iter(|| {
+            let file = SerializedFile::new(black_box(data.clone()).into(), "resources.assets").expect("Load failure");
+            file.objects().count()
+        })
+    });
+    let file = SerializedFile::new(data.into(), "resources.assets").expect("Load failure");
+    c.bench_function("look up by path id", |b| b.iter(|| file.object(black_box(OBJECTS as i64 / 2)).map(|x| x.path_id)));
+}
+
+criterion_group!(benches, bench_serialized_file);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+//! Decodes every Texture2D of the bundles given on the command line on all cores, writing them
+//! as PNGs to the current directory.
+
+use std::time::Instant;
+
+use rayon::prelude::*;
+use unity_rs::classes::Texture2D;
+use unity_rs::{ClassID, Env};
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+fn main() -> Result<(), Error> {
+    let mut env = Env::new();
+    for path in std::env::args().skip(1) {
+        env.load_from_file(path)?;
+    }
+
+    let start = Instant::now();
+    let results: Vec<_> = env
+        .par_objects()
+        .filter(|x| x.class() == ClassID::Texture2D)
+        .map(|object| -> Result<String, Error> {
+            let texture: Texture2D = object.read()?;
+            // Path ids are only unique within a file, so the name is not enough either.
+            let name = format!("{}_{}.png", texture.name, object.info.path_id);
+            texture.decode_image_without_cache()?.save(&name)?;
+            Ok(name)
+        })
+        .collect();
+
+    let failed = results.iter().filter(|x| x.is_err()).count();
+    for error in results.iter().filter_map(|x| x.as_ref().err()) {
+        // Context errors leave their cause to `source`, so print the whole chain.
+        let mut message = error.to_string();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            message = format!("{}: {}", message, cause);
+            source = cause.source();
+        }
+        eprintln!("{}", message);
+    }
+    println!("decoded {} textures in {:?}, {} failed", results.len() - failed, start.elapsed(), failed);
+    Ok(())
+}
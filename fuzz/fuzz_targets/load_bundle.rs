@@ -0,0 +1,35 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use unity_rs::bundle::{BundleFileLoader, FileData, FileLoader, LoadOptions, LoadOutput};
+
+/// LZMA blocks are only bounded by `LoadOptions::max_block_size`, so without it any small input
+/// can decompress to gigabytes.
+const MAX_BLOCK_SIZE: usize = 16 << 20;
+
+fn read_all(output: LoadOutput) {
+    for file in &output.serialized_files {
+        for object in file.objects() {
+            if object.raw_data().is_ok() {
+                let _ = object.read_value();
+            }
+        }
+    }
+    for file in &output.loaded_files {
+        let _ = file.data.read(0..file.data.len());
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let loader = BundleFileLoader::new(LoadOptions {
+        max_block_size: Some(MAX_BLOCK_SIZE),
+        ..Default::default()
+    });
+    // Both the whole bundle read at once and its blocks decompressed as they are read.
+    if let Ok(output) = loader.load(data) {
+        read_all(output);
+    }
+    if let Ok(output) = loader.load_from_data(FileData::from(data.to_vec())) {
+        read_all(output);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use unity_rs::asset::SerializedFile;
+use unity_rs::bundle::FileData;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(file) = SerializedFile::new(FileData::from(data.to_vec()).into(), "fuzz") else {
+        return;
+    };
+    for object in file.objects() {
+        if object.data_slice().is_ok() {
+            let _ = object.read_value();
+        }
+    }
+});
@@ -0,0 +1,59 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde::Deserialize;
+use unity_rs::object::Deserializer;
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::typetree::{skip_subtree, TypeTreeNode};
+
+/// The types the deserializer reads differently, and a struct.
+const TYPES: &[&str] = &[
+    "bool",
+    "UInt8",
+    "SInt16",
+    "UInt16",
+    "int",
+    "unsigned int",
+    "SInt64",
+    "float",
+    "double",
+    "string",
+    "Array",
+    "map",
+    "pair",
+    "vector",
+    "TypelessData",
+    "ManagedReferencesRegistry",
+    "ReferencedObject",
+    "Struct",
+];
+
+/// Reads the type tree from the start of the input, two bytes a node after their count: the
+/// level and alignment flag, then the type. The rest is the object's data.
+fn nodes(data: &[u8]) -> Option<(Vec<TypeTreeNode>, &[u8])> {
+    let (&count, data) = data.split_first()?;
+    let count = usize::from(count % 64) + 1;
+    let tree = data.get(..count * 2)?;
+    let nodes = tree
+        .chunks(2)
+        .enumerate()
+        .map(|(i, x)| TypeTreeNode {
+            type_: TYPES[usize::from(x[1]) % TYPES.len()].to_string(),
+            name: format!("m_{}", i),
+            level: i32::from(x[0] & 0x3f),
+            meta_flag: if x[0] & 0x40 != 0 { 0x4000 } else { 0 },
+            ..TypeTreeNode::default()
+        })
+        .collect();
+    Some((nodes, &data[count * 2..]))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((nodes, data)) = nodes(data) else {
+        return;
+    };
+    let mut reader = Reader::new(data, ByteOrder::Little);
+    let _ = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader));
+    let mut reader = Reader::new(data, ByteOrder::Little);
+    let _ = skip_subtree(&mut reader, &nodes, 0);
+});
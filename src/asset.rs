@@ -1,9 +1,15 @@
+use crate::bundle::{CompressionType, FileSource};
+use crate::classes::ClassID;
 use crate::common::common_string;
-use crate::error::UnityResult;
-use crate::object::ObjectInfo;
+use crate::error::{Section, UnityError, UnityResult};
+use crate::object::{ObjectInfo, ReadCache};
 use crate::reader::{ByteOrder, Reader};
 use crate::typetree::{TypeTree, TypeTreeNode};
-use std::sync::Arc;
+use crate::version::UnityVersion;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+mod writer;
 
 #[derive(Default, Debug)]
 pub struct SerializedFileHeader {
@@ -29,12 +35,38 @@ pub struct SerializedType {
     pub asm_name: String,
 }
 
+/// An entry of the object table, read when the file is loaded. Its [`ObjectInfo`] is only built
+/// when the object is first accessed, see [`SerializedFile::object_at`].
+#[derive(Clone, Debug)]
+pub struct ObjectEntry {
+    pub path_id: i64,
+    /// Absolute in the file, with the data offset added.
+    pub bytes_start: usize,
+    pub bytes_size: usize,
+    pub type_id: i32,
+    pub class_id: i32,
+    pub is_destroyed: u16,
+    pub stripped: u8,
+    /// Where the entry stores its byte start in the metadata, for [`SerializedFile::write_to`].
+    offset: usize,
+    /// Where in `types` the object's type is.
+    type_index: Option<usize>,
+    script_type_index: Option<i16>,
+}
+
+impl ObjectEntry {
+    pub fn class(&self) -> ClassID {
+        ClassID::from(self.class_id)
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct LocalSerializedObjectIdentifier {
     pub local_serialized_file_index: i32,
     pub local_identifier_in_file: i64,
 }
 
+/// An entry of `m_Externals`, a file that pointers with a non-zero `m_FileID` point into.
 #[derive(Default, Debug)]
 pub struct FileIdentifier {
     pub guid: [u8; 16],
@@ -42,6 +74,20 @@ pub struct FileIdentifier {
     pub path_name: String,
 }
 
+impl FileIdentifier {
+    /// The name the file is loaded under, see [`file_name`].
+    pub fn file_name(&self) -> &str {
+        file_name(&self.path_name)
+    }
+}
+
+/// The last component of a file reference such as `archive:/CAB-xxx/CAB-xxx`,
+/// `library/unity default resources` or `sharedassets0.assets`, which is how files are matched
+/// (ignoring case) across bundles.
+pub fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum BuildType {
     Unknown,
@@ -67,28 +113,41 @@ impl BuildType {
 #[derive(Debug)]
 pub struct SerializedFile {
     pub path: String,
-    pub version: [i32; 4],
+    pub version: UnityVersion,
     pub build_type: BuildType,
     pub header: SerializedFileHeader,
     pub file_endian: u8,
     pub unity_version: String,
     pub target_platform: i32,
     pub enable_type_tree: bool,
-    pub types: Vec<SerializedType>,
+    /// Shared by the objects of each type.
+    pub types: Vec<Arc<SerializedType>>,
     pub big_id_enabled: bool,
-    pub objects_info: Vec<ObjectInfo>,
     pub script_types: Vec<LocalSerializedObjectIdentifier>,
     pub externals: Vec<FileIdentifier>,
     pub ref_types: Vec<SerializedType>,
     pub user_information: String,
+    /// How the data blocks of the bundle holding the file are compressed, `None` for a file
+    /// loaded on its own.
+    pub compression: Option<CompressionType>,
+    /// The file as loaded, which [`SerializedFile::write_to`] copies unchanged parts from.
+    source: FileSource,
+    object_entries: Vec<ObjectEntry>,
+    /// The [`ObjectInfo`] of each entry once accessed.
+    objects: Vec<OnceLock<Box<ObjectInfo>>>,
+    /// Object data swapped in with [`SerializedFile::replace_object`].
+    replaced_objects: HashMap<i64, Vec<u8>>,
+    /// Where each path id is in `object_entries`, see [`SerializedFile::object`].
+    object_indices: HashMap<i64, usize>,
 }
 
 impl SerializedFile {
-    pub fn new(src: Arc<Vec<u8>>, path: &str) -> UnityResult<Self> {
-        let mut r = Reader::new(src.as_slice(), ByteOrder::Big);
+    pub fn new(src: FileSource, path: &str) -> UnityResult<Self> {
+        let metadata = metadata_end(&src).and_then(|end| src.read(0..end)).map_err(|e| e.in_section(path, Section::new("header"), 0))?;
+        let mut r = Reader::new(&metadata, ByteOrder::Big);
         let mut ret = Self {
             path: path.to_string(),
-            version: [0; 4],
+            version: UnityVersion::default(),
             build_type: BuildType::Unknown,
             header: SerializedFileHeader::default(),
             file_endian: 0,
@@ -97,152 +156,172 @@ impl SerializedFile {
             enable_type_tree: false,
             types: Vec::new(),
             big_id_enabled: false,
-            objects_info: Vec::new(),
             script_types: Vec::new(),
             externals: Vec::new(),
             ref_types: Vec::new(),
             user_information: String::default(),
+            compression: None,
+            source: src.clone(),
+            object_entries: Vec::new(),
+            objects: Vec::new(),
+            replaced_objects: HashMap::new(),
+            object_indices: HashMap::new(),
         };
-        ret.header.metadata_size = r.read_u32()? as usize;
-        ret.header.file_size = r.read_u32()? as usize;
-        ret.header.version = r.read_u32()?;
-        ret.header.data_offset = r.read_u32()? as usize;
-        if ret.header.version >= 9 {
-            ret.header.endian = r.read_u8()?;
-            ret.header.reserved = r.read_u8_array()?;
-            ret.file_endian = ret.header.endian;
+        let mut section = Section::new("header");
+        match ret.read_metadata(&mut r, &mut section) {
+            Ok(()) => Ok(ret),
+            Err(e) => Err(e.in_section(path, section, r.get_offset())),
+        }
+    }
+
+    /// Reads the header and the tables after it, keeping `section` at the part being read.
+    fn read_metadata(&mut self, r: &mut Reader, section: &mut Section) -> UnityResult<()> {
+        self.header.metadata_size = r.read_u32()? as usize;
+        self.header.file_size = r.read_u32()? as usize;
+        self.header.version = r.read_u32()?;
+        self.header.data_offset = r.read_u32()? as usize;
+        if self.header.version >= 9 {
+            self.header.endian = r.read_u8()?;
+            self.header.reserved = r.read_u8_array()?;
+            self.file_endian = self.header.endian;
         } else {
-            r.set_offset(ret.header.file_size - ret.header.metadata_size)?;
-            ret.file_endian = r.read_u8()?;
+            r.set_offset(self.header.file_size.checked_sub(self.header.metadata_size).ok_or(UnityError::Eof)?)?;
+            self.file_endian = r.read_u8()?;
         }
-        if ret.header.version >= 22 {
-            ret.header.metadata_size = r.read_u32()? as usize;
-            ret.header.file_size = r.read_i64()? as usize;
-            ret.header.data_offset = r.read_i64()? as usize;
+        if self.header.version >= 22 {
+            self.header.metadata_size = r.read_u32()? as usize;
+            self.header.file_size = r.read_i64()? as usize;
+            self.header.data_offset = r.read_i64()? as usize;
             r.read_i64()?;
         }
-        if ret.file_endian == 0 {
+        if self.file_endian == 0 {
             r.set_little_order()
         }
-        if ret.header.version >= 7 {
+        *section = Section::new("metadata");
+        if self.header.version >= 7 {
             let string_version = r.read_string_util_null()?;
-            ret.set_unity_revision(&string_version)?;
+            self.set_unity_revision(&string_version)?;
         }
-        if ret.header.version >= 8 {
-            ret.target_platform = r.read_i32()?;
+        if self.header.version >= 8 {
+            self.target_platform = r.read_i32()?;
         }
 
-        if ret.header.version >= 13 {
-            ret.enable_type_tree = r.read_bool()?;
+        if self.header.version >= 13 {
+            self.enable_type_tree = r.read_bool()?;
+        } else {
+            // Older files have no flag and always carry their type trees.
+            self.enable_type_tree = true;
         }
 
+        *section = Section::new("types");
         let type_count = r.read_i32()?;
-        for _ in 0..type_count {
-            let st = ret.read_serialized_type(&mut r, false)?;
-            ret.types.push(st)
+        for i in 0..type_count.max(0) as usize {
+            *section = Section::entry("types", i);
+            let st = self.read_serialized_type(r, false)?;
+            self.types.push(Arc::new(st))
         }
-        if ret.header.version >= 7 && ret.header.version < 14 {
-            ret.big_id_enabled = r.read_i32()? != 0;
+        if self.header.version >= 7 && self.header.version < 14 {
+            self.big_id_enabled = r.read_i32()? != 0;
         }
+        *section = Section::new("objects");
         let object_count = r.read_i32()?;
-        for _ in 0..object_count {
-            let mut object_info = ObjectInfo {
-                build_type: ret.build_type.clone(),
-                data: src.clone(),
-                bytes_order: r.get_order(),
-                asset_version: ret.header.version,
-                bytes_start: 0,
-                bytes_size: 0,
-                type_id: 0,
+        // Entries take at least 16 bytes in every version.
+        self.object_entries.reserve(r.check_length(object_count.into(), 16)?);
+        for i in 0..object_count.max(0) as usize {
+            *section = Section::entry("objects", i);
+            let path_id = if self.big_id_enabled {
+                r.read_i64()?
+            } else if self.header.version < 14 {
+                r.read_i32()? as i64
+            } else {
+                r.align(4)?;
+                r.read_i64()?
+            };
+            let offset = r.get_offset();
+            let bytes_start = if self.header.version >= 22 { r.read_i64()? as usize } else { r.read_u32()? as usize };
+            let bytes_start = bytes_start
+                .checked_add(self.header.data_offset)
+                .ok_or_else(|| UnityError::CustomError(format!("object {} starts past the end of the file", path_id)))?;
+            let mut entry = ObjectEntry {
+                path_id,
+                bytes_start,
+                bytes_size: r.read_u32()? as usize,
+                type_id: r.read_i32()?,
                 class_id: 0,
                 is_destroyed: 0,
                 stripped: 0,
-                path_id: 0,
-                serialized_type: SerializedType::default(),
-                version: [0; 4],
+                offset,
+                type_index: None,
+                script_type_index: None,
             };
-            if ret.big_id_enabled {
-                object_info.path_id = r.read_i64()?;
-            } else if ret.header.version < 14 {
-                object_info.path_id = r.read_i32()? as i64;
-            } else {
-                r.align(4)?;
-                object_info.path_id = r.read_i64()?;
-            }
-            if ret.header.version >= 22 {
-                object_info.bytes_start = r.read_i64()? as usize;
-            } else {
-                object_info.bytes_start = r.read_u32()? as usize;
-            }
-            object_info.bytes_start += ret.header.data_offset;
-            object_info.version = ret.version;
-            object_info.bytes_size = r.read_u32()? as usize;
-            object_info.type_id = r.read_i32()?;
-            if ret.header.version < 16 {
-                object_info.class_id = r.read_u16()? as i32;
-                for i in &ret.types {
-                    if i.class_id == object_info.type_id {
-                        object_info.serialized_type = (*i).clone();
-                        break;
-                    }
-                }
+            if self.header.version < 16 {
+                entry.class_id = r.read_u16()? as i32;
+                entry.type_index = self.types.iter().position(|x| x.class_id == entry.type_id);
             } else {
-                let type_ = ret.types[object_info.type_id as usize].clone();
-                object_info.class_id = type_.class_id;
-                object_info.serialized_type = type_;
+                let type_ = self.types.get(entry.type_id as usize).ok_or_else(|| UnityError::CustomError(format!("object {} has no type {}", path_id, entry.type_id)))?;
+                entry.class_id = type_.class_id;
+                entry.type_index = Some(entry.type_id as usize);
             }
-            if ret.header.version < 11 {
-                object_info.is_destroyed = r.read_u16()?;
+            if self.header.version < 11 {
+                entry.is_destroyed = r.read_u16()?;
             }
-            if ret.header.version >= 11 && ret.header.version < 17 {
-                let script_type_index = r.read_i16()?;
-                object_info.serialized_type.script_type_index = Some(script_type_index);
+            if self.header.version >= 11 && self.header.version < 17 {
+                entry.script_type_index = Some(r.read_i16()?);
             }
-            if ret.header.version == 15 || ret.header.version == 16 {
-                object_info.stripped = r.read_u8()?;
+            if self.header.version == 15 || self.header.version == 16 {
+                entry.stripped = r.read_u8()?;
             }
-            ret.objects_info.push(object_info);
+            self.object_indices.entry(path_id).or_insert(self.object_entries.len());
+            self.object_entries.push(entry);
         }
-        if ret.header.version >= 11 {
+        self.objects = self.object_entries.iter().map(|_| OnceLock::new()).collect();
+        if self.header.version >= 11 {
+            *section = Section::new("script_types");
             let script_count = r.read_i32()?;
-            for _ in 0..script_count {
+            for i in 0..script_count.max(0) as usize {
+                *section = Section::entry("script_types", i);
                 let mut script_type = LocalSerializedObjectIdentifier {
                     local_serialized_file_index: r.read_i32()?,
                     ..LocalSerializedObjectIdentifier::default()
                 };
-                if ret.header.version < 14 {
+                if self.header.version < 14 {
                     script_type.local_identifier_in_file = r.read_i32()? as i64;
                 } else {
                     r.align(4)?;
                     script_type.local_identifier_in_file = r.read_i64()?;
                 }
-                ret.script_types.push(script_type)
+                self.script_types.push(script_type)
             }
         }
+        *section = Section::new("externals");
         let externals_count = r.read_i32()?;
-        for _ in 0..externals_count {
+        for i in 0..externals_count.max(0) as usize {
+            *section = Section::entry("externals", i);
             let mut external = FileIdentifier::default();
-            if ret.header.version >= 6 {
+            if self.header.version >= 6 {
                 r.read_string_util_null()?;
             }
-            if ret.header.version >= 5 {
+            if self.header.version >= 5 {
                 external.guid = r.read_u8_array()?;
                 external.type_ = r.read_i32()?;
             }
             external.path_name = r.read_string_util_null()?;
-            ret.externals.push(external)
+            self.externals.push(external)
         }
-        if ret.header.version >= 20 {
+        if self.header.version >= 20 {
+            *section = Section::new("ref_types");
             let ref_type_count = r.read_i32()?;
-            for _ in 0..ref_type_count {
-                let st = ret.read_serialized_type(&mut r, true)?;
-                ret.ref_types.push(st)
+            for i in 0..ref_type_count.max(0) as usize {
+                *section = Section::entry("ref_types", i);
+                let st = self.read_serialized_type(r, true)?;
+                self.ref_types.push(st)
             }
         }
-        if ret.header.version >= 5 {
-            ret.user_information = r.read_string_util_null()?;
+        if self.header.version >= 5 {
+            *section = Section::new("user_information");
+            self.user_information = r.read_string_util_null()?;
         }
-        Ok(ret)
+        Ok(())
     }
 
     fn set_unity_revision(&mut self, string_version: &str) -> UnityResult<()> {
@@ -252,9 +331,7 @@ impl SerializedFile {
             self.build_type = BuildType::new(c.to_string());
         }
 
-        let nums: Vec<i32> = string_version.chars().map(|c| if c.is_ascii_digit() { c } else { '.' }).collect::<String>().split('.').filter_map(|s| s.parse().ok()).collect();
-
-        self.version[..nums.len().min(4)].copy_from_slice(&nums[..nums.len().min(4)]);
+        self.version = string_version.parse().unwrap_or_default();
         Ok(())
     }
 
@@ -272,7 +349,7 @@ impl SerializedFile {
         }
 
         if self.header.version >= 13 {
-            if (is_ref_type && result.script_type_index.is_some()) || (self.header.version < 16 && result.class_id < 0) || (self.header.version >= 16 && result.class_id == 114) {
+            if (is_ref_type && result.script_type_index.is_some_and(|x| x >= 0)) || (self.header.version < 16 && result.class_id < 0) || (self.header.version >= 16 && result.class_id == 114) {
                 result.script_id = r.read_u8_array()?;
             }
             result.old_type_hash = r.read_u8_array()?;
@@ -281,7 +358,7 @@ impl SerializedFile {
             if self.header.version >= 12 || self.header.version == 10 {
                 self.read_type_tree_blob(r, &mut result.type_tree)?
             } else {
-                self.read_type_tree(r, &mut result.type_tree, 0)?;
+                result.type_tree.read_legacy_node(r, self.header.version, 0)?;
             }
             if self.header.version >= 21 {
                 if is_ref_type {
@@ -339,32 +416,137 @@ impl SerializedFile {
     }
 
     pub fn read_type_tree(&mut self, r: &mut Reader, type_tree: &mut TypeTree, level: i32) -> UnityResult<()> {
-        let mut type_tree_node = TypeTreeNode {
-            level,
-            type_: r.read_string_util_null()?,
-            name: r.read_string_util_null()?,
-            size: r.read_i32()?,
-            ..TypeTreeNode::default()
+        type_tree.read_legacy_node(r, self.header.version, level)
+    }
+
+    /// Whether this is a built scene such as `level0`, recognised by the scene settings objects
+    /// every scene carries.
+    pub fn is_scene(&self) -> bool {
+        self.object_entries.iter().any(|x| matches!(x.class(), ClassID::OcclusionCullingSettings | ClassID::RenderSettings))
+    }
+
+    /// The object table as loaded, which can be filtered without building any [`ObjectInfo`].
+    pub fn object_entries(&self) -> &[ObjectEntry] {
+        &self.object_entries
+    }
+
+    pub fn object_count(&self) -> usize {
+        self.object_entries.len()
+    }
+
+    /// The type of an entry of [`SerializedFile::object_entries`], if the file has it.
+    pub fn entry_type(&self, entry: &ObjectEntry) -> Option<&SerializedType> {
+        self.types.get(entry.type_index?).map(|x| &**x)
+    }
+
+    /// The object at `index` in the object table, built on first access.
+    pub fn object_at(&self, index: usize) -> Option<&ObjectInfo> {
+        let entry = self.object_entries.get(index)?;
+        Some(self.objects[index].get_or_init(|| Box::new(self.object_info(entry))))
+    }
+
+    /// The objects in table order, each built as the iterator reaches it.
+    pub fn objects(&self) -> impl Iterator<Item = &ObjectInfo> + '_ {
+        (0..self.object_count()).filter_map(|i| self.object_at(i))
+    }
+
+    /// Every object, for code written when the objects were the public `objects_info` vector.
+    /// Since objects are built on first access, this builds the whole table at once.
+    #[deprecated(note = "builds every object of the table; filter `object_entries()` and build only the objects needed with `object_at()` or `object(path_id)`")]
+    pub fn objects_info(&self) -> Vec<&ObjectInfo> {
+        self.objects().collect()
+    }
+
+    pub(crate) fn source(&self) -> &FileSource {
+        &self.source
+    }
+
+    /// The objects built so far, which hold on to their data once read.
+    pub(crate) fn built_objects(&self) -> impl Iterator<Item = &ObjectInfo> + '_ {
+        self.objects.iter().filter_map(|x| x.get()).map(|x| &**x)
+    }
+
+    /// The object with `path_id`, found through an index built when the file is loaded.
+    pub fn object(&self, path_id: i64) -> Option<&ObjectInfo> {
+        self.object_at(*self.object_indices.get(&path_id)?)
+    }
+
+    fn object_info(&self, entry: &ObjectEntry) -> ObjectInfo {
+        let type_ = entry.type_index.and_then(|i| self.types.get(i));
+        let serialized_type = match (type_, entry.script_type_index) {
+            // Before version 17 the script index is stored with each object instead of its type.
+            (Some(type_), Some(index)) if type_.script_type_index != Some(index) => Arc::new(SerializedType {
+                script_type_index: Some(index),
+                ..SerializedType::clone(type_)
+            }),
+            (Some(type_), _) => type_.clone(),
+            (None, index) => Arc::new(SerializedType {
+                script_type_index: index,
+                ..SerializedType::default()
+            }),
         };
-        if self.header.version == 2 {
-            let _variable_count = r.read_i32()?;
-        }
-        if self.header.version != 3 {
-            type_tree_node.index = r.read_i32()?;
-        }
-        type_tree_node.type_flag = r.read_i32()?;
-        type_tree_node.version = r.read_i32()?;
-        if self.header.version != 3 {
-            type_tree_node.meta_flag = r.read_i32()?;
-        }
-        type_tree.nodes.push(type_tree_node);
-        for _ in 0..r.read_i32()? {
-            self.read_type_tree(r, type_tree, level + 1)?;
+        ObjectInfo {
+            build_type: self.build_type.clone(),
+            data: self.source.clone(),
+            bytes_order: if self.file_endian == 0 { ByteOrder::Little } else { ByteOrder::Big },
+            asset_version: self.header.version,
+            bytes_start: entry.bytes_start,
+            bytes_size: entry.bytes_size,
+            type_id: entry.type_id,
+            class_id: entry.class_id,
+            is_destroyed: entry.is_destroyed,
+            stripped: entry.stripped,
+            path_id: entry.path_id,
+            serialized_type,
+            version: self.version,
+            read_cache: ReadCache::default(),
+            bytes: OnceLock::new(),
         }
-        Ok(())
     }
 
+    /// The files this one references, in `m_FileID` order from 1.
+    pub fn externals(&self) -> &[FileIdentifier] {
+        &self.externals
+    }
+
+    /// The external a pointer's `m_FileID` refers to; 0, the file itself, has none.
+    pub fn external(&self, file_id: i32) -> Option<&FileIdentifier> {
+        usize::try_from(file_id).ok()?.checked_sub(1).and_then(|i| self.externals.get(i))
+    }
+
+    /// The type of the `[SerializeReference]` class `namespace.class` of `assembly`, whose type
+    /// tree lays out the managed references of that class since version 20.
+    pub fn ref_type(&self, class: &str, namespace: &str, assembly: &str) -> Option<&SerializedType> {
+        self.ref_types.iter().find(|x| x.klass_name == class && x.name_space == namespace && x.asm_name == assembly)
+    }
+
+    /// Whether the Unity version is at least `other`, compared part by part as major, minor,
+    /// patch and build.
     pub fn version_greater_or_equal(&self, other: &[i32]) -> bool {
-        !self.version.iter().zip(other.iter()).any(|(x, y)| *x < *y)
+        let version = [self.version.major, self.version.minor, self.version.patch, self.version.build];
+        let len = other.len().min(version.len());
+        version[..len] >= other[..len]
+    }
+}
+
+/// How much of the start of `src` holds the header and metadata, so that files decompressed on
+/// demand do not need their object data up front.
+fn metadata_end(src: &FileSource) -> UnityResult<usize> {
+    if src.as_data().is_some() {
+        return Ok(src.len());
+    }
+    let head = src.read(0..src.len().min(48))?;
+    let mut r = Reader::new(&head, ByteOrder::Big);
+    r.set_offset(8)?;
+    let version = r.read_u32()?;
+    let mut data_offset = r.read_u32()? as usize;
+    if version < 9 {
+        // The metadata is at the end of the file.
+        return Ok(src.len());
+    }
+    if version >= 22 {
+        r.set_offset(32)?;
+        data_offset = r.read_i64()? as usize;
     }
+    Ok(data_offset.min(src.len()))
 }
@@ -0,0 +1,80 @@
+use std::io::Write;
+
+use super::SerializedFile;
+use crate::bundle::FileData;
+use crate::error::{UnityError, UnityResult};
+
+/// Objects start at multiples of this, relative to the data offset.
+const OBJECT_ALIGNMENT: usize = 8;
+
+impl SerializedFile {
+    /// Swaps the data of object `path_id` for [`SerializedFile::write_to`]. Objects already
+    /// loaded from this file keep reading the original data.
+    pub fn replace_object(&mut self, path_id: i64, data: Vec<u8>) -> UnityResult<()> {
+        if !self.object_indices.contains_key(&path_id) {
+            return Err(UnityError::CustomError(format!("{} has no object {}", self.path, path_id)));
+        }
+        self.replaced_objects.insert(path_id, data);
+        Ok(())
+    }
+
+    /// Serializes the file with replaced objects swapped in.
+    ///
+    /// The header, type trees and every other part of the metadata are kept as loaded; only the
+    /// object table's offsets and sizes and the file size are recomputed. Files older than
+    /// version 9, which keep their metadata after the objects, are not supported.
+    pub fn write_to(&self, w: &mut impl Write) -> UnityResult<()> {
+        if self.header.version < 9 {
+            return Err(UnityError::Unimplemented);
+        }
+        let big_endian = self.file_endian != 0;
+        let mut head = self.source.read(0..self.header.data_offset)?.to_vec();
+        let mut objects = Vec::with_capacity(self.object_entries.len());
+        let mut start = 0;
+        for entry in &self.object_entries {
+            let data = match self.replaced_objects.get(&entry.path_id) {
+                Some(data) => FileData::from(data.clone()),
+                None => self.source.read(entry.bytes_start..entry.bytes_start + entry.bytes_size)?,
+            };
+            start = start.next_multiple_of(OBJECT_ALIGNMENT);
+            let start_size = if self.header.version >= 22 { 8 } else { 4 };
+            put(&mut head, entry.offset, start as u64, start_size, big_endian)?;
+            put(&mut head, entry.offset + start_size, data.len() as u64, 4, big_endian)?;
+            start += data.len();
+            objects.push(data);
+        }
+
+        let file_size = (self.header.data_offset + start) as u64;
+        if self.header.version >= 22 {
+            put(&mut head, 24, file_size, 8, true)?;
+        } else {
+            put(&mut head, 4, file_size, 4, true)?;
+        }
+        w.write_all(&head)?;
+        let mut position = 0;
+        for data in objects {
+            let padding = position.next_multiple_of(OBJECT_ALIGNMENT) - position;
+            w.write_all(&[0; OBJECT_ALIGNMENT][..padding])?;
+            w.write_all(&data)?;
+            position += padding + data.len();
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> UnityResult<Vec<u8>> {
+        let mut result = Vec::new();
+        self.write_to(&mut result)?;
+        Ok(result)
+    }
+}
+
+/// Overwrites the `len` byte integer at `offset`.
+fn put(data: &mut [u8], offset: usize, value: u64, len: usize, big_endian: bool) -> UnityResult<()> {
+    let target = data.get_mut(offset..offset + len).ok_or(UnityError::Eof)?;
+    if big_endian {
+        target.copy_from_slice(&value.to_be_bytes()[8 - len..]);
+    } else {
+        target.copy_from_slice(&value.to_le_bytes()[..len]);
+    }
+    Ok(())
+}
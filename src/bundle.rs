@@ -1,8 +1,19 @@
 use crate::asset::SerializedFile;
-use crate::error::{UnityError, UnityResult};
+use crate::error::{Section, UnityError, UnityResult};
 use crate::reader::{ByteOrder, Reader};
+use crate::unitycn::{self, UnityCN};
+use crate::warning::LoadWarning;
+use lzma_rs::decompress::UnpackedSize;
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::{Deref, Range};
+use std::sync::{Arc, Mutex, PoisonError};
+
+mod writer;
+
+pub use writer::{Bundle, BundleNode};
 
 #[derive(PartialEq)]
 pub enum FileType {
@@ -23,6 +34,7 @@ pub enum ArchiveFlags {
     BlocksInfoAtTheEnd = 0x80,
     OldWebPluginCompatibility = 0x100,
     BlockInfoNeedPaddingAtStart = 0x200,
+    UnityCNEncryption = 0x400,
 }
 
 impl ArchiveFlags {
@@ -34,6 +46,7 @@ impl ArchiveFlags {
             0x80 => Self::BlocksInfoAtTheEnd,
             0x100 => Self::OldWebPluginCompatibility,
             0x200 => Self::BlockInfoNeedPaddingAtStart,
+            0x400 => Self::UnityCNEncryption,
             _ => return Err(UnityError::InvalidValue),
         };
         Ok(ret)
@@ -45,9 +58,10 @@ impl ArchiveFlags {
 pub enum StorageBlockFlags {
     CompressionTypeMask = 0x3f,
     Streamed = 0x40,
+    Encrypted = 0x100,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum CompressionType {
     None,
     Lzma,
@@ -68,6 +82,13 @@ impl CompressionType {
         };
         Ok(ret)
     }
+
+    /// How the data blocks are compressed, taken from the first compressed one since bundles
+    /// compress all of them alike.
+    fn of_blocks(block_infos: &[StorageBlock]) -> Self {
+        let mut types = block_infos.iter().filter_map(|x| Self::from_magic_num((x.flags & StorageBlockFlags::CompressionTypeMask as u16) as u32).ok());
+        types.find(|x| *x != Self::None).unwrap_or(Self::None)
+    }
 }
 
 #[derive(Debug)]
@@ -110,7 +131,7 @@ pub struct StorageBlock {
     pub flags: u16,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Node {
     pub offset: i64,
     pub size: i64,
@@ -118,28 +139,437 @@ pub struct Node {
     pub path: String,
 }
 
+impl Node {
+    /// Where the node's bytes lie in the decompressed data of the blocks. The offset and size come
+    /// from the bundle, so a negative or overflowing range fails rather than wrapping.
+    pub fn range(&self) -> UnityResult<Range<usize>> {
+        let start = usize::try_from(self.offset).map_err(|_| UnityError::Eof)?;
+        let end = usize::try_from(self.size).ok().and_then(|x| start.checked_add(x)).ok_or(UnityError::Eof)?;
+        Ok(start..end)
+    }
+}
+
+/// Shared, immutable file contents: either an owned buffer or a range borrowed from a larger one
+/// such as a memory map.
+#[derive(Clone)]
+pub struct FileData {
+    source: Arc<dyn AsRef<[u8]> + Send + Sync>,
+    range: Range<usize>,
+}
+
+impl FileData {
+    pub fn new(source: impl AsRef<[u8]> + Send + Sync + 'static) -> Self {
+        let len = source.as_ref().len();
+        Self { source: Arc::new(source), range: 0..len }
+    }
+
+    /// A view of `range` within this data that shares the same backing buffer.
+    pub fn slice(&self, range: Range<usize>) -> UnityResult<Self> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(UnityError::Eof);
+        }
+        Ok(Self {
+            source: Arc::clone(&self.source),
+            range: self.range.start + range.start..self.range.start + range.end,
+        })
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &(*self.source).as_ref()[self.range.clone()]
+    }
+}
+
+impl Deref for FileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl From<Vec<u8>> for FileData {
+    fn from(value: Vec<u8>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Debug for FileData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileData").field("len", &self.len()).finish()
+    }
+}
+
+/// The contents of a file inside a bundle, either in memory or decompressed on demand.
+#[derive(Debug, Clone)]
+pub enum FileSource {
+    Data(FileData),
+    Blocks(BlockView),
+}
+
+impl FileSource {
+    pub fn len(&self) -> usize {
+        match self {
+            FileSource::Data(data) => data.len(),
+            FileSource::Blocks(view) => view.range.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The bytes of `range`, decompressing only the blocks it overlaps.
+    pub fn read(&self, range: Range<usize>) -> UnityResult<FileData> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(UnityError::Eof);
+        }
+        match self {
+            FileSource::Data(data) => data.slice(range),
+            FileSource::Blocks(view) => view.store.read(view.range.start + range.start..view.range.start + range.end),
+        }
+    }
+
+    /// The whole file when it is already in memory.
+    pub fn as_data(&self) -> Option<&FileData> {
+        match self {
+            FileSource::Data(data) => Some(data),
+            FileSource::Blocks(_) => None,
+        }
+    }
+
+    fn file_type(&self) -> UnityResult<FileType> {
+        match self {
+            FileSource::Data(data) => check_file_type(data),
+            FileSource::Blocks(_) => check_file_type_prefix(&self.read(0..self.len().min(HEADER_PROBE_SIZE as usize))?, self.len()),
+        }
+    }
+
+    /// Calls `f` with each buffer this reads from, and whether it is a decompressed block of the
+    /// cache rather than file data.
+    pub(crate) fn for_each_buffer(&self, f: &mut impl FnMut(&FileData, bool)) {
+        match self {
+            FileSource::Data(data) => f(data, false),
+            FileSource::Blocks(view) => {
+                f(&view.store.data, false);
+                let cache = view.store.cache.lock().unwrap_or_else(PoisonError::into_inner);
+                for (_, block) in cache.iter() {
+                    f(block, true);
+                }
+            }
+        }
+    }
+}
+
+/// Counts the buffers behind [`FileData`] views, each once however many views share it.
+#[derive(Default)]
+pub(crate) struct BufferCounter(HashSet<usize>);
+
+impl BufferCounter {
+    /// The size of the whole buffer `data` is a view into, or 0 if it was counted before.
+    pub(crate) fn count(&mut self, data: &FileData) -> usize {
+        let id = Arc::as_ptr(&data.source).cast::<()>() as usize;
+        if self.0.insert(id) {
+            (*data.source).as_ref().len()
+        } else {
+            0
+        }
+    }
+}
+
+impl From<FileData> for FileSource {
+    fn from(value: FileData) -> Self {
+        Self::Data(value)
+    }
+}
+
+/// A file's range within the decompressed stream of a [`BlockStore`].
+#[derive(Debug, Clone)]
+pub struct BlockView {
+    store: Arc<BlockStore>,
+    range: Range<usize>,
+}
+
+/// Blocks that have been decompressed recently, kept per bundle.
+const BLOCK_CACHE_SIZE: usize = 16;
+
+/// What errors name a bundle as, since bundles are loaded from data without a name.
+const BUNDLE: &str = "bundle";
+
+struct StoredBlock {
+    info: StorageBlock,
+    compressed_offset: usize,
+    uncompressed_offset: usize,
+}
+
+/// The still compressed data blocks of a bundle, decompressed as they are read.
+pub struct BlockStore {
+    data: FileData,
+    blocks: Vec<StoredBlock>,
+    crypto: BlockCrypto,
+    /// Most recently used first.
+    cache: Mutex<VecDeque<(usize, FileData)>>,
+}
+
+impl BlockStore {
+    fn new(data: FileData, data_start: usize, block_infos: Vec<StorageBlock>, crypto: BlockCrypto) -> Self {
+        let mut compressed_offset = data_start;
+        let mut uncompressed_offset = 0;
+        let mut blocks = Vec::with_capacity(block_infos.len());
+        for info in block_infos {
+            let compressed_size = info.compressed_size as usize;
+            let uncompressed_size = info.uncompressed_size as usize;
+            blocks.push(StoredBlock {
+                info,
+                compressed_offset,
+                uncompressed_offset,
+            });
+            compressed_offset += compressed_size;
+            uncompressed_offset += uncompressed_size;
+        }
+        Self {
+            data,
+            blocks,
+            crypto,
+            cache: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// The size of the decompressed stream, as the blocks info gives it.
+    fn len(&self) -> usize {
+        self.blocks.last().map_or(0, |x| x.uncompressed_offset + x.info.uncompressed_size as usize)
+    }
+
+    /// Stitches `range` of the decompressed stream together from the blocks overlapping it.
+    fn read(&self, range: Range<usize>) -> UnityResult<FileData> {
+        let first = self.blocks.partition_point(|x| x.uncompressed_offset + x.info.uncompressed_size as usize <= range.start);
+        let Some(block) = self.blocks.get(first) else {
+            return if range.is_empty() { Ok(FileData::from(Vec::new())) } else { Err(UnityError::Eof) };
+        };
+        let block_end = block.uncompressed_offset + block.info.uncompressed_size as usize;
+        if range.end <= block_end {
+            return self.block(first)?.slice(range.start - block.uncompressed_offset..range.end - block.uncompressed_offset);
+        }
+
+        let mut result = Vec::with_capacity(range.len());
+        for (index, block) in self.blocks.iter().enumerate().skip(first) {
+            if block.uncompressed_offset >= range.end {
+                break;
+            }
+            let data = self.block(index)?;
+            let start = range.start.max(block.uncompressed_offset) - block.uncompressed_offset;
+            let end = range.end.min(block.uncompressed_offset + data.len()) - block.uncompressed_offset;
+            result.extend_from_slice(&data[start..end]);
+        }
+        if result.len() != range.len() {
+            return Err(UnityError::Eof);
+        }
+        Ok(FileData::from(result))
+    }
+
+    fn block(&self, index: usize) -> UnityResult<FileData> {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(position) = cache.iter().position(|(i, _)| *i == index) {
+            let entry = cache.remove(position).ok_or(UnityError::InvalidValue)?;
+            let data = entry.1.clone();
+            cache.push_front(entry);
+            return Ok(data);
+        }
+        drop(cache);
+
+        let offset = self.blocks[index].compressed_offset;
+        let data = FileData::from(self.decompress(index).map_err(in_bundle(Section::entry("blocks", index), offset))?);
+
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.push_front((index, data.clone()));
+        cache.truncate(BLOCK_CACHE_SIZE);
+        Ok(data)
+    }
+
+    fn decompress(&self, index: usize) -> UnityResult<Vec<u8>> {
+        let block = &self.blocks[index];
+        let compressed_bytes = self.data.slice(block.compressed_offset..block.compressed_offset + block.info.compressed_size as usize)?;
+        // LZMA sizes are unchecked, so the capacity is only a hint.
+        let mut decompressed = Vec::with_capacity((block.info.uncompressed_size as usize).min(compressed_bytes.len().saturating_mul(255)));
+        if self.crypto.is_active() {
+            let mut decrypted = compressed_bytes.to_vec();
+            self.crypto.decrypt(index, &block.info, &mut decrypted);
+            BundleFileLoader::decompress_block(&block.info, &decrypted, &mut decompressed)?;
+        } else {
+            BundleFileLoader::decompress_block(&block.info, &compressed_bytes, &mut decompressed)?;
+        }
+        // As when loading from a stream, but found too late to be a warning.
+        decompressed.resize(block.info.uncompressed_size as usize, 0);
+        Ok(decompressed)
+    }
+}
+
+impl Debug for BlockStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockStore").field("blocks", &self.blocks.len()).finish()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LoadedFile {
     pub path: String,
     pub name: String,
-    pub data: Arc<Vec<u8>>,
+    pub data: FileSource,
+}
+
+impl LoadedFile {
+    fn new(node: &Node, data: FileSource) -> Self {
+        let name = std::path::Path::file_name(node.path.as_ref()).map(|x| x.to_string_lossy().to_string()).unwrap_or_else(|| node.path.to_string());
+        Self {
+            path: node.path.to_string(),
+            name,
+            data,
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct LoadOutput {
     pub serialized_files: Vec<SerializedFile>,
     pub loaded_files: Vec<LoadedFile>,
+    /// Problems that did not stop the files from loading, see [`LoadWarning`].
+    pub warnings: Vec<LoadWarning>,
+}
+
+impl LoadOutput {
+    /// Records the bundle's `compression` in its serialized files.
+    fn with_compression(mut self, compression: CompressionType) -> Self {
+        for file in &mut self.serialized_files {
+            file.compression = Some(compression);
+        }
+        self
+    }
+}
+
+/// Game specific decryption of raw bundle data, for schemes the crate does not know about.
+///
+/// [`BlockDecryptor::decrypt`] is called on every data block as it is stored in the bundle,
+/// after UnityCN decryption and before decompression. [`BlockDecryptor::decrypt_blocks_info`]
+/// is called on the blocks info and directory, also before it is decompressed.
+pub trait BlockDecryptor: Send + Sync {
+    /// Decrypts block `block_index` in place; `flags` are the block's storage flags.
+    fn decrypt(&self, block_index: usize, flags: u16, data: &mut [u8]);
+
+    /// Decrypts the blocks info in place; `flags` are the bundle's archive flags. Does nothing by default.
+    fn decrypt_blocks_info(&self, _flags: u32, _data: &mut [u8]) {}
+}
+
+/// How to find and decrypt the bundle inside an obfuscated file.
+#[derive(Clone, Default)]
+pub struct LoadOptions {
+    /// Bytes to skip before the bundle, e.g. a junk prefix.
+    pub offset: usize,
+    /// The string found in place of `UnityFS`, for bundles with a renamed signature. It should be
+    /// as long as `UnityFS` since later sections are aligned from the start of the bundle.
+    pub signature: Option<Vec<u8>>,
+    /// Searches this many bytes after `offset` for the signature instead of expecting it at `offset`.
+    pub scan_limit: Option<usize>,
+    /// The per game key of UnityCN encrypted bundles. Bundles without the encryption flag are
+    /// read as usual.
+    pub unitycn_key: Option<[u8; 16]>,
+    /// Applied to the raw blocks of UnityFS bundles.
+    pub decryptor: Option<Arc<dyn BlockDecryptor>>,
+    /// Rejects UnityFS bundles with a block, or blocks info, decompressing to more than this many
+    /// bytes, before anything is allocated for it, and `UnityWeb` bundles with a larger payload.
+    /// Unity writes LZ4 blocks of 128 KiB, while LZMA bundles are one block of the whole data.
+    /// LZ4 and uncompressed blocks are always checked against their compressed size, but LZMA
+    /// ones can only be bounded by this, so it should be set for untrusted input.
+    pub max_block_size: Option<usize>,
+}
+
+impl Debug for LoadOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadOptions")
+            .field("offset", &self.offset)
+            .field("signature", &self.signature)
+            .field("scan_limit", &self.scan_limit)
+            .field("unitycn_key", &self.unitycn_key.is_some())
+            .field("decryptor", &self.decryptor.is_some())
+            .field("max_block_size", &self.max_block_size)
+            .finish()
+    }
+}
+
+impl LoadOptions {
+    /// The bundle within `data`, with a renamed signature restored to `UnityFS`.
+    pub fn locate<'d>(&self, data: &'d [u8]) -> UnityResult<Cow<'d, [u8]>> {
+        let rest = data.get(self.offset..).unwrap_or_default();
+        let signature = self.signature.as_deref().unwrap_or(b"UnityFS");
+        let mut pattern = signature.to_vec();
+        pattern.push(0);
+
+        let start = match self.scan_limit {
+            Some(limit) => rest[..rest.len().min(limit)].windows(pattern.len()).position(|x| x == pattern.as_slice()),
+            None => rest.starts_with(&pattern).then_some(0),
+        };
+        let Some(start) = start else {
+            let place = match self.scan_limit {
+                Some(limit) => format!("within {} bytes of offset {}", limit, self.offset),
+                None => format!("at offset {}", self.offset),
+            };
+            return Err(UnityError::SignatureNotFound(format!("expected {:?} {}, saw \"{}\"", String::from_utf8_lossy(signature), place, escape_bytes(&rest[..rest.len().min(32)]))));
+        };
+        let bundle = &rest[start..];
+        if signature == b"UnityFS" {
+            return Ok(Cow::Borrowed(bundle));
+        }
+        let mut restored = b"UnityFS\0".to_vec();
+        restored.extend_from_slice(&bundle[pattern.len()..]);
+        Ok(Cow::Owned(restored))
+    }
+}
+
+/// Printable ASCII as is, everything else escaped, for showing unrecognised headers.
+fn escape_bytes(data: &[u8]) -> String {
+    data.iter().flat_map(|x| std::ascii::escape_default(*x)).map(char::from).collect()
 }
 
-pub trait FileLoader {
+/// Loads the files of a container format. Loaders are shared by every thread an [`Env`](crate::Env)
+/// is used from.
+pub trait FileLoader: Send + Sync {
     fn name(&self) -> &str;
     fn check(&self, data: &[u8]) -> bool;
     fn load(&self, data: &[u8]) -> UnityResult<LoadOutput>;
 }
 
-pub struct BundleFileLoader;
+/// Decryption applied to raw blocks before they are decompressed.
+#[derive(Clone, Default)]
+struct BlockCrypto {
+    unity_cn: Option<UnityCN>,
+    decryptor: Option<Arc<dyn BlockDecryptor>>,
+}
+
+impl BlockCrypto {
+    fn is_active(&self) -> bool {
+        self.unity_cn.is_some() || self.decryptor.is_some()
+    }
+
+    fn decrypt(&self, index: usize, block_info: &StorageBlock, data: &mut [u8]) {
+        if let Some(unity_cn) = &self.unity_cn {
+            if block_info.flags & StorageBlockFlags::Encrypted as u16 != 0 {
+                unity_cn.decrypt_block(data, index);
+            }
+        }
+        if let Some(decryptor) = &self.decryptor {
+            decryptor.decrypt(index, block_info.flags, data);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BundleFileLoader {
+    pub options: LoadOptions,
+}
 
 impl BundleFileLoader {
+    pub fn new(options: LoadOptions) -> Self {
+        Self { options }
+    }
+
     pub fn read_header(&self, r: &mut Reader) -> UnityResult<BundleHead> {
         let signature = r.read_string_util_null_with_limit(20);
         if !matches!(signature.as_bytes(), b"UnityWeb" | b"UnityRaw" | b"UnityArchive" | b"UnityFS") {
@@ -149,24 +579,90 @@ impl BundleFileLoader {
         let version = r.read_u32()?;
         let unity_version = r.read_string_util_null()?;
         let unity_revision = r.read_string_util_null()?;
-
-        let size = r.read_i64()? as u64;
-        let compressed_blocks_info_size = r.read_u32()?;
-        let uncompressed_blocks_info_size = r.read_u32()?;
-        let flags = r.read_u32()?;
-        if signature != "UnityFS" {
-            r.read_u8()?;
-        }
-        Ok(BundleHead {
+        let mut header = BundleHead {
             signature,
             version,
             unity_version,
             unity_revision,
-            size,
-            compressed_blocks_info_size,
-            uncompressed_blocks_info_size,
-            flags,
-        })
+            ..BundleHead::default()
+        };
+        // `UnityWeb` and `UnityRaw` continue with a different layout, read by `read_web_raw`.
+        if header.signature != "UnityFS" {
+            return Ok(header);
+        }
+        header.size = r.read_i64()? as u64;
+        header.compressed_blocks_info_size = r.read_u32()?;
+        header.uncompressed_blocks_info_size = r.read_u32()?;
+        header.flags = r.read_u32()?;
+        Ok(header)
+    }
+
+    /// Reads the directory and files of a `UnityWeb` or `UnityRaw` bundle, the format used before
+    /// UnityFS. Its payload is a single stream, LZMA compressed for `UnityWeb`.
+    pub fn read_web_raw(&self, r: &mut Reader, header: &BundleHead) -> UnityResult<Vec<LoadedFile>> {
+        if header.version >= 4 {
+            let _hash = r.read_u8_array::<16>()?;
+            let _crc = r.read_u32()?;
+        }
+        let _minimum_streamed_bytes = r.read_u32()?;
+        let header_size = r.read_u32()? as usize;
+        let _levels_before_streaming = r.read_u32()?;
+        let level_count = r.read_i32()?;
+        // Every level's sizes include the levels before it, so the last one spans the whole payload.
+        let mut compressed_size = 0;
+        for _ in 0..level_count {
+            compressed_size = r.read_u32()? as usize;
+            let _uncompressed_size = r.read_u32()?;
+        }
+        if header.version >= 2 {
+            let _complete_file_size = r.read_u32()?;
+        }
+        if header.version >= 3 {
+            let _file_info_header_size = r.read_u32()?;
+        }
+        r.set_offset(header_size)?;
+        let payload = r.read_u8_slice(compressed_size)?;
+        let data = if header.signature == "UnityWeb" {
+            // The stream has a whole `.lzma` header, with the uncompressed size after the properties.
+            let uncompressed_size = payload.get(5..13).and_then(|x| x.try_into().ok()).map_or(u64::MAX, u64::from_le_bytes);
+            self.check_block_size(CompressionType::Lzma, payload.len(), usize::try_from(uncompressed_size).unwrap_or(usize::MAX))?;
+            FileData::from(decompress_lzma(payload, None)?)
+        } else {
+            FileData::from(payload.to_vec())
+        };
+
+        let mut directory_reader = Reader::new(&data, ByteOrder::Big);
+        let node_count = directory_reader.read_i32()?;
+        let mut nodes = Vec::new();
+        for _ in 0..node_count {
+            let path = directory_reader.read_string_util_null()?;
+            let offset = directory_reader.read_u32()? as i64;
+            let size = directory_reader.read_u32()? as i64;
+            nodes.push(Node { offset, size, flags: 0, path })
+        }
+        self.read_files(&data, &nodes)
+    }
+
+    /// Sets up block decryption, reading the UnityCN info that follows the header of encrypted
+    /// bundles when a key is set.
+    fn read_crypto(&self, r: &mut Reader, header: &mut BundleHead) -> UnityResult<BlockCrypto> {
+        let mut crypto = BlockCrypto {
+            unity_cn: None,
+            decryptor: self.options.decryptor.clone(),
+        };
+        let Some(key) = &self.options.unitycn_key else {
+            return Ok(crypto);
+        };
+        let flag = unitycn::encryption_flag(&header.unity_revision);
+        if header.flags & flag == 0 {
+            return Ok(crypto);
+        }
+        if flag == ArchiveFlags::BlockInfoNeedPaddingAtStart as u32 {
+            // Older releases reuse the padding flag, such bundles are not padded.
+            header.flags &= !flag;
+        }
+        crypto.unity_cn = Some(UnityCN::read(r, key)?);
+        Ok(crypto)
     }
 
     pub fn read_blocks_info_and_directory(&self, r: &mut Reader, header: &BundleHead) -> UnityResult<(Vec<StorageBlock>, Vec<Node>)> {
@@ -174,20 +670,34 @@ impl BundleFileLoader {
         if header.version >= 7 {
             r.align(16)?;
         }
-        let offset = r.get_offset();
         if header.flags & ArchiveFlags::BlocksInfoAtTheEnd as u32 != 0 {
-            r.set_offset(r.len() - header.compressed_blocks_info_size as usize)?;
-            block_info_bytes = r.read_u8_list(header.compressed_blocks_info_size as usize)?;
-            r.set_offset(offset)?;
+            let size = header.compressed_blocks_info_size as usize;
+            let start = r.len().checked_sub(size).ok_or(UnityError::Eof)?;
+            block_info_bytes = r.at(start, size)?.read_u8_list(size)?;
         } else {
             block_info_bytes = r.read_u8_list(header.compressed_blocks_info_size as usize)?;
         }
+        let result = self.parse_blocks_info(&block_info_bytes, header)?;
+        if header.flags & ArchiveFlags::BlockInfoNeedPaddingAtStart as u32 != 0 {
+            r.align(16)?;
+        }
+        Ok(result)
+    }
+
+    /// Decompresses and parses the blocks info and directory, `block_info_bytes` being as stored in the bundle.
+    pub fn parse_blocks_info(&self, block_info_bytes: &[u8], header: &BundleHead) -> UnityResult<(Vec<StorageBlock>, Vec<Node>)> {
+        let mut block_info_bytes = Cow::Borrowed(block_info_bytes);
+        if let Some(decryptor) = &self.options.decryptor {
+            decryptor.decrypt_blocks_info(header.flags, block_info_bytes.to_mut());
+        }
+        let block_info_bytes = block_info_bytes.as_ref();
         let uncompressed_size = header.uncompressed_blocks_info_size;
         let compressed_type = CompressionType::from_magic_num(header.flags & ArchiveFlags::CompressionTypeMask as u32)?;
+        self.check_block_size(compressed_type, block_info_bytes.len(), uncompressed_size as usize)?;
         let block_info_uncompressed_bytes = match compressed_type {
-            CompressionType::None => block_info_bytes,
-            CompressionType::Lzma => return Err(UnityError::Unimplemented),
-            CompressionType::Lz4 | CompressionType::Lz4HC => lz4_flex::decompress(&block_info_bytes, uncompressed_size as usize)?,
+            CompressionType::None => block_info_bytes.to_vec(),
+            CompressionType::Lzma => decompress_lzma(block_info_bytes, Some(uncompressed_size as usize))?,
+            CompressionType::Lz4 | CompressionType::Lz4HC => lz4_flex::decompress(block_info_bytes, uncompressed_size as usize)?,
             CompressionType::LzInv => return Err(UnityError::Unimplemented),
         };
         let mut block_info_reader = Reader::new(&block_info_uncompressed_bytes, ByteOrder::Big);
@@ -202,6 +712,10 @@ impl BundleFileLoader {
                 compressed_size: block_info_reader.read_u32()?,
                 flags: block_info_reader.read_u16()?,
             };
+            // Unknown compression fails once the block is read.
+            if let Ok(compress_type) = CompressionType::from_magic_num((s.flags & StorageBlockFlags::CompressionTypeMask as u16) as u32) {
+                self.check_block_size(compress_type, s.compressed_size as usize, s.uncompressed_size as usize)?;
+            }
             block_infos.push(s)
         }
 
@@ -218,63 +732,215 @@ impl BundleFileLoader {
             };
             nodes.push(n)
         }
-        if header.flags & ArchiveFlags::BlockInfoNeedPaddingAtStart as u32 != 0 {
-            r.align(16)?;
+        let data_size: u64 = block_infos.iter().map(|x| x.uncompressed_size as u64).sum();
+        for node in &nodes {
+            let end = u64::try_from(node.offset).ok().zip(u64::try_from(node.size).ok()).and_then(|(offset, size)| offset.checked_add(size));
+            if !end.is_some_and(|x| x <= data_size) {
+                return Err(UnityError::CustomError(format!("node {} lies outside the {} bytes of the blocks", node.path, data_size)));
+            }
         }
         Ok((block_infos, nodes))
     }
 
+    /// Fails for a block that cannot or may not decompress to `uncompressed_size` bytes, see
+    /// [`LoadOptions::max_block_size`].
+    fn check_block_size(&self, compress_type: CompressionType, compressed_size: usize, uncompressed_size: usize) -> UnityResult<()> {
+        // An LZ4 sequence expands at most 255 times, uncompressed blocks are stored as they are.
+        let bound = match compress_type {
+            CompressionType::None => Some(compressed_size),
+            CompressionType::Lz4 | CompressionType::Lz4HC | CompressionType::LzInv => Some(compressed_size.saturating_mul(255).saturating_add(16)),
+            CompressionType::Lzma => None,
+        };
+        let limit = bound.into_iter().chain(self.options.max_block_size).min();
+        match limit {
+            Some(limit) if uncompressed_size > limit => Err(UnityError::CustomError(format!("block of {} bytes cannot decompress to {} bytes", compressed_size, uncompressed_size))),
+            _ => Ok(()),
+        }
+    }
+
     pub fn read_blocks(&self, r: &mut Reader, block_infos: &[StorageBlock]) -> UnityResult<Vec<u8>> {
         let mut result = Vec::new();
         for block_info in block_infos {
-            let compress_type = CompressionType::from_magic_num((block_info.flags & StorageBlockFlags::CompressionTypeMask as u16) as u32)?;
-            match compress_type {
-                CompressionType::None => {
-                    result.extend_from_slice(r.read_u8_slice(block_info.compressed_size as usize)?);
-                }
-                CompressionType::Lzma => {
-                    let in_buf = r.read_u8_slice(block_info.compressed_size as usize)?;
-                    let mut in_buf_new = Vec::new();
-                    in_buf_new.extend_from_slice(&in_buf[..5]);
-                    in_buf_new.extend_from_slice(&(block_info.uncompressed_size as u64).to_le_bytes());
-                    in_buf_new.extend_from_slice(&in_buf[5..]);
-                    let mut out_buf = std::io::Cursor::new(&mut result);
-                    lzma_rs::lzma_decompress(&mut std::io::Cursor::new(in_buf_new), &mut out_buf)?;
-                }
-                CompressionType::Lz4 | CompressionType::Lz4HC | CompressionType::LzInv => {
-                    let compressed_size = block_info.compressed_size;
-                    let compressed_bytes = r.read_u8_slice(compressed_size as usize)?;
-                    let uncompressed_size = block_info.uncompressed_size;
-
-                    let uncompressed_bytes = if compress_type == CompressionType::LzInv {
-                        let mut buf = compressed_bytes.to_vec();
-                        lz4_inv::swap(&mut buf, uncompressed_size as usize)?;
-                        lz4_flex::decompress(&buf, uncompressed_size as usize)?
-                    } else {
-                        lz4_flex::decompress(compressed_bytes, uncompressed_size as usize)?
-                    };
-
-                    result.extend_from_slice(&uncompressed_bytes);
+            let compressed_bytes = r.read_u8_slice(block_info.compressed_size as usize)?;
+            Self::decompress_block(block_info, compressed_bytes, &mut result)?;
+        }
+        Ok(result)
+    }
+
+    /// Decompresses one storage block, appending its data to `out`.
+    pub fn decompress_block(block_info: &StorageBlock, compressed_bytes: &[u8], out: &mut impl Write) -> UnityResult<()> {
+        let compress_type = CompressionType::from_magic_num((block_info.flags & StorageBlockFlags::CompressionTypeMask as u16) as u32)?;
+        let uncompressed_size = block_info.uncompressed_size as usize;
+        match compress_type {
+            CompressionType::None => out.write_all(compressed_bytes)?,
+            CompressionType::Lzma => {
+                let options = lzma_rs::decompress::Options {
+                    unpacked_size: UnpackedSize::UseProvided(Some(uncompressed_size as u64)),
+                    ..Default::default()
+                };
+                lzma_rs::lzma_decompress_with_options(&mut std::io::Cursor::new(compressed_bytes), out, &options)?;
+            }
+            CompressionType::Lz4 | CompressionType::Lz4HC => out.write_all(&lz4_flex::decompress(compressed_bytes, uncompressed_size)?)?,
+            CompressionType::LzInv => {
+                let mut buf = compressed_bytes.to_vec();
+                lz4_inv::swap(&mut buf, uncompressed_size)?;
+                out.write_all(&lz4_flex::decompress(&buf, uncompressed_size)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads a bundle held in `data`, e.g. a memory mapped file.
+    ///
+    /// When no block is compressed the files inside are views into `data` rather than copies.
+    /// Otherwise `data` is kept and each block is only decompressed once an object or resource
+    /// overlapping it is read, so pulling one texture out of a large bundle stays cheap.
+    ///
+    /// A block that decompresses to another size than the blocks info gives is padded with zeros
+    /// or cut to that size, as [`BundleFileLoader::load_from_reader`] does. Since that is only
+    /// found when the block is read, it is not reported as a [`LoadWarning::BlockSize`].
+    pub fn load_from_data(&self, data: FileData) -> UnityResult<LoadOutput> {
+        let mut r = Reader::new(data.as_slice(), ByteOrder::Big);
+        let mut header = self.read_header(&mut r).map_err(in_bundle(Section::new("header"), r.get_offset()))?;
+        if header.signature != "UnityFS" {
+            return self.load(&data);
+        }
+        let crypto = self.read_crypto(&mut r, &mut header).map_err(in_bundle(Section::new("header"), r.get_offset()))?;
+        let (block_infos, nodes) = self.read_blocks_info_and_directory(&mut r, &header).map_err(in_bundle(Section::new("blocks info"), r.get_offset()))?;
+        let compression = CompressionType::of_blocks(&block_infos);
+        let data_start = r.get_offset();
+        let uncompressed = !crypto.is_active() && block_infos.iter().all(|block_info| block_info.flags & StorageBlockFlags::CompressionTypeMask as u16 == 0);
+
+        let files = if uncompressed {
+            let blocks = data.slice(data_start..data.len())?;
+            self.read_files(&blocks, &nodes)?
+        } else {
+            let mut files = Vec::with_capacity(nodes.len());
+            let store = Arc::new(BlockStore::new(data, data_start, block_infos, crypto));
+            for node in &nodes {
+                let range = node.range()?;
+                if range.end > store.len() {
+                    return Err(UnityError::CustomError(format!("node {} lies outside the {} bytes of the blocks", node.path, store.len())));
                 }
+                let view = BlockView { store: Arc::clone(&store), range };
+                files.push(LoadedFile::new(node, FileSource::Blocks(view)));
             }
+            files
+        };
+        Ok(self.load_assets(&files)?.with_compression(compression))
+    }
+
+    /// Loads a bundle from a seekable stream such as a file.
+    ///
+    /// UnityFS blocks are read and decompressed one at a time into one buffer per file, so the
+    /// compressed and decompressed data of the whole bundle are never held in memory together.
+    ///
+    /// A block that decompresses to another size than the blocks info gives is padded with zeros
+    /// or cut to that size, which keeps the files after it in place, and reported as a
+    /// [`LoadWarning::BlockSize`].
+    pub fn load_from_reader<R: Read + Seek>(&self, mut src: R) -> UnityResult<LoadOutput> {
+        let start = src.stream_position()?;
+        let mut signature = Vec::new();
+        src.by_ref().take(8).read_to_end(&mut signature)?;
+        src.seek(SeekFrom::Start(start))?;
+        if signature != b"UnityFS\0" {
+            // The older formats are a single stream that has to be read whole anyway.
+            let mut data = Vec::new();
+            src.read_to_end(&mut data)?;
+            return self.load(&data);
         }
-        Ok(result)
+        let ((_, nodes, files), compression, warnings) = self.read_unity_fs_blocks(src)?;
+        let files: Vec<_> = nodes.iter().zip(files).map(|(node, data)| LoadedFile::new(node, FileData::from(data).into())).collect();
+        let mut output = self.load_assets(&files)?.with_compression(compression);
+        output.warnings = warnings;
+        Ok(output)
     }
 
-    pub fn read_files(&self, data: &[u8], nodes: &[Node]) -> UnityResult<Vec<LoadedFile>> {
-        let mut r = Reader::new(data, ByteOrder::Big);
+    /// Reads the header, directory and decompressed files of a UnityFS bundle from a stream.
+    pub fn read_unity_fs<R: Read + Seek>(&self, src: R) -> UnityResult<UnityFsContents> {
+        Ok(self.read_unity_fs_blocks(src)?.0)
+    }
+
+    /// [`BundleFileLoader::read_unity_fs`], also returning how the data blocks were compressed and
+    /// the blocks that decompressed to another size than given.
+    fn read_unity_fs_blocks<R: Read + Seek>(&self, mut src: R) -> UnityResult<(UnityFsContents, CompressionType, Vec<LoadWarning>)> {
+        let start = src.stream_position()?;
+        let mut head = Vec::new();
+        src.by_ref().take(HEADER_PROBE_SIZE).read_to_end(&mut head)?;
+        let mut r = Reader::new(&head, ByteOrder::Big);
+        let mut header = self.read_header(&mut r).map_err(in_bundle(Section::new("header"), r.get_offset()))?;
+        if header.signature != "UnityFS" {
+            return Err(UnityError::UnsupportFileType(header.signature));
+        }
+        let crypto = self.read_crypto(&mut r, &mut header).map_err(in_bundle(Section::new("header"), r.get_offset()))?;
 
+        let mut offset = r.get_offset() as u64;
+        if header.version >= 7 {
+            offset = offset.next_multiple_of(16);
+        }
+        let end = src.seek(SeekFrom::End(0))?;
+        let blocks_info_offset = if header.flags & ArchiveFlags::BlocksInfoAtTheEnd as u32 != 0 {
+            (end - start).saturating_sub(header.compressed_blocks_info_size.into())
+        } else {
+            offset
+        };
+        let blocks_info = in_bundle(Section::new("blocks info"), blocks_info_offset as usize);
+        if u64::from(header.compressed_blocks_info_size) > end - start {
+            return Err(blocks_info(UnityError::Eof));
+        }
+        let mut block_info_bytes = vec![0; header.compressed_blocks_info_size as usize];
+        src.seek(SeekFrom::Start(start + blocks_info_offset))?;
+        src.read_exact(&mut block_info_bytes).map_err(|e| blocks_info(e.into()))?;
+        if header.flags & ArchiveFlags::BlocksInfoAtTheEnd as u32 == 0 {
+            offset += block_info_bytes.len() as u64;
+        }
+        if header.flags & ArchiveFlags::BlockInfoNeedPaddingAtStart as u32 != 0 {
+            offset = offset.next_multiple_of(16);
+        }
+        let (block_infos, nodes) = self.parse_blocks_info(&block_info_bytes, &header).map_err(blocks_info)?;
+
+        let compressed_size: u64 = block_infos.iter().map(|x| x.compressed_size as u64).sum();
+        if start + offset + compressed_size > end {
+            return Err(UnityError::Eof.in_section(BUNDLE, Section::new("blocks"), offset as usize));
+        }
+        src.seek(SeekFrom::Start(start + offset))?;
+        let mut writer = NodeWriter::new(&nodes, compressed_size);
+        let mut compressed_bytes = Vec::new();
+        let mut decompressed = Vec::new();
+        let mut warnings = Vec::new();
+        let mut block_start = 0;
+        for (index, block_info) in block_infos.iter().enumerate() {
+            let block = in_bundle(Section::entry("blocks", index), offset as usize);
+            compressed_bytes.resize(block_info.compressed_size as usize, 0);
+            src.read_exact(&mut compressed_bytes).map_err(|e| block(e.into()))?;
+            crypto.decrypt(index, block_info, &mut compressed_bytes);
+            decompressed.clear();
+            Self::decompress_block(block_info, &compressed_bytes, &mut decompressed).map_err(block)?;
+            let actual = decompressed.len();
+            if actual != block_info.uncompressed_size as usize {
+                let file = nodes.iter().find(|x| x.offset <= block_start && block_start < x.offset + x.size).map(|x| x.path.clone()).unwrap_or_default();
+                warnings.push(LoadWarning::BlockSize {
+                    file,
+                    block: index,
+                    expected: block_info.uncompressed_size as usize,
+                    actual,
+                });
+            }
+            // Padded or cut to the size the blocks info gives, where the next block starts.
+            decompressed.resize(block_info.uncompressed_size as usize, 0);
+            writer.write_all(&decompressed)?;
+            offset += u64::from(block_info.compressed_size);
+            block_start += i64::from(block_info.uncompressed_size);
+        }
+        let NodeWriter { files, .. } = writer;
+        Ok(((header, nodes, files), CompressionType::of_blocks(&block_infos), warnings))
+    }
+
+    /// The files of `nodes` as views into `data`, which they share rather than copy.
+    pub fn read_files(&self, data: &FileData, nodes: &[Node]) -> UnityResult<Vec<LoadedFile>> {
         let mut files = Vec::with_capacity(nodes.len());
         for node in nodes {
-            r.set_offset(node.offset as usize)?;
-            let data = r.read_u8_list(node.size as usize)?;
-
-            let name = std::path::Path::file_name(node.path.as_ref()).map(|x| x.to_string_lossy().to_string()).unwrap_or_else(|| node.path.to_string());
-            files.push(LoadedFile {
-                path: node.path.to_string(),
-                name,
-                data: Arc::from(data),
-            })
+            files.push(LoadedFile::new(node, data.slice(node.range()?)?.into()))
         }
         Ok(files)
     }
@@ -284,14 +950,18 @@ impl BundleFileLoader {
         let mut loaded_files = Vec::new();
 
         for file in files {
-            if let Ok(FileType::AssetsFile) = check_file_type(&file.data) {
-                let serialized_file = SerializedFile::new(Arc::clone(&file.data), &file.path)?;
+            if let Ok(FileType::AssetsFile) = file.data.file_type() {
+                let serialized_file = SerializedFile::new(file.data.clone(), &file.path)?;
                 serialized_files.push(serialized_file);
             } else {
                 loaded_files.push(file.clone());
             }
         }
-        Ok(LoadOutput { serialized_files, loaded_files })
+        Ok(LoadOutput {
+            serialized_files,
+            loaded_files,
+            warnings: Vec::new(),
+        })
     }
 }
 
@@ -312,22 +982,121 @@ impl FileLoader for BundleFileLoader {
         }
 
         let mut r = Reader::new(data, ByteOrder::Big);
-        let header = self.read_header(&mut r)?;
-        if header.signature != "UnityFS" {
-            return Err(UnityError::UnsupportFileType("UnityWeb or UnityRaw".into()));
+        let header = self.read_header(&mut r).map_err(in_bundle(Section::new("header"), r.get_offset()))?;
+        match header.signature.as_str() {
+            "UnityFS" => {}
+            "UnityWeb" | "UnityRaw" => {
+                let files = self.read_web_raw(&mut r, &header).map_err(in_bundle(Section::new("payload"), r.get_offset()))?;
+                let compression = if header.signature == "UnityWeb" { CompressionType::Lzma } else { CompressionType::None };
+                return Ok(self.load_assets(&files)?.with_compression(compression));
+            }
+            _ => return Err(UnityError::UnsupportFileType(header.signature)),
+        }
+
+        self.load_from_reader(Cursor::new(data))
+    }
+}
+
+/// The header, directory and decompressed files of a UnityFS bundle.
+pub type UnityFsContents = (BundleHead, Vec<Node>, Vec<Vec<u8>>);
+
+/// Adds where in the bundle an error happened, see [`UnityError::Parse`].
+fn in_bundle(section: Section, offset: usize) -> impl Fn(UnityError) -> UnityError + Copy {
+    move |e| e.in_section(BUNDLE, section, offset)
+}
+
+/// Enough to hold any bundle header; the strings in it are short version numbers.
+pub(crate) const HEADER_PROBE_SIZE: u64 = 1024;
+
+/// Splits the decompressed block stream into one buffer per directory node as it is written.
+struct NodeWriter<'n> {
+    nodes: &'n [Node],
+    /// Indices of `nodes` by offset, the first `done` of them written whole.
+    order: Vec<usize>,
+    done: usize,
+    files: Vec<Vec<u8>>,
+    position: u64,
+    /// The most reserved up front for a file, since node sizes are untrusted.
+    reserve_limit: u64,
+}
+
+impl<'n> NodeWriter<'n> {
+    /// `compressed_size` is that of all blocks, which LZ4 expands at most 255 times.
+    fn new(nodes: &'n [Node], compressed_size: u64) -> Self {
+        let mut order: Vec<usize> = (0..nodes.len()).collect();
+        order.sort_by_key(|&i| nodes[i].offset);
+        Self {
+            nodes,
+            order,
+            done: 0,
+            files: nodes.iter().map(|_| Vec::new()).collect(),
+            position: 0,
+            reserve_limit: compressed_size.saturating_mul(255),
         }
+    }
+
+    fn bounds(node: &Node) -> (u64, u64) {
+        let start = node.offset.max(0) as u64;
+        (start, start.saturating_add(node.size.max(0) as u64))
+    }
+}
 
-        let (block_infos, nodes) = self.read_blocks_info_and_directory(&mut r, &header)?;
-        let block_datas = self.read_blocks(&mut r, &block_infos)?;
-        let files = self.read_files(&block_datas, &nodes)?;
-        let assets = self.load_assets(&files)?;
+impl Write for NodeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let start = self.position;
+        let end = start + buf.len() as u64;
+        while let Some(&index) = self.order.get(self.done) {
+            if Self::bounds(&self.nodes[index]).1 > start {
+                break;
+            }
+            self.done += 1;
+        }
+        for &index in &self.order[self.done..] {
+            let (node_start, node_end) = Self::bounds(&self.nodes[index]);
+            if node_start >= end {
+                break;
+            }
+            if node_end <= start {
+                continue;
+            }
+            let file = &mut self.files[index];
+            if file.is_empty() {
+                file.reserve_exact((node_end - node_start).min(self.reserve_limit) as usize);
+            }
+            let from = start.max(node_start);
+            let to = end.min(node_end);
+            file.extend_from_slice(&buf[(from - start) as usize..(to - start) as usize]);
+        }
+        self.position = end;
+        Ok(buf.len())
+    }
 
-        Ok(assets)
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
     }
 }
 
+/// Decompresses an LZMA stream. Unity's bundle blocks keep the 5 byte properties header but drop the
+/// 8 byte uncompressed size that `.lzma` files carry, so it is passed in; `None` reads it from the stream.
+fn decompress_lzma(data: &[u8], uncompressed_size: Option<usize>) -> UnityResult<Vec<u8>> {
+    let unpacked_size = match uncompressed_size {
+        Some(size) => UnpackedSize::UseProvided(Some(size as u64)),
+        None => UnpackedSize::ReadFromHeader,
+    };
+    let options = lzma_rs::decompress::Options { unpacked_size, ..Default::default() };
+    // The size is unchecked, so the capacity is only a hint.
+    let mut result = Vec::with_capacity(uncompressed_size.unwrap_or_default().min(data.len().saturating_mul(255)));
+    lzma_rs::lzma_decompress_with_options(&mut std::io::Cursor::new(data), &mut result, &options)?;
+    Ok(result)
+}
+
 pub fn check_file_type(data: &[u8]) -> UnityResult<FileType> {
-    fn is_serialized_file(r: &mut Reader) -> UnityResult<bool> {
+    check_file_type_prefix(data, data.len())
+}
+
+/// Like [`check_file_type`], with `data` being the start of a file `len` bytes long.
+pub(crate) fn check_file_type_prefix(data: &[u8], len: usize) -> UnityResult<FileType> {
+    let is_serialized_file = |r: &mut Reader| -> UnityResult<bool> {
         if r.len() < 20 {
             return Ok(false);
         }
@@ -345,15 +1114,15 @@ pub fn check_file_type(data: &[u8]) -> UnityResult<FileType> {
             file_size = r.read_i64()?;
             data_offset = r.read_i64()?;
         }
-        if r.len() != file_size as usize {
+        if len != file_size as usize {
             return Ok(false);
         }
         if data_offset > file_size {
             return Ok(false);
         }
         Ok(true)
-    }
-    if data.len() < 20 {
+    };
+    if len < 20 {
         return Ok(FileType::ResourceFile);
     }
     let gzip_magic = [0x1f, 0x8b];
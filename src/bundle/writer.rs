@@ -0,0 +1,237 @@
+use std::io::{Cursor, Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+
+use lzma_rs::compress::{Options, UnpackedSize};
+
+use super::{ArchiveFlags, BundleFileLoader, CompressionType, LoadOptions, Node};
+use crate::error::{UnityError, UnityResult};
+
+/// Data blocks are split at this size, as Unity does for chunk based compression.
+const BLOCK_SIZE: usize = 0x20000;
+
+/// A file inside a [`Bundle`].
+#[derive(Debug, Clone)]
+pub struct BundleNode {
+    pub path: String,
+    pub flags: u32,
+    /// Where the file starts in the decompressed data of the bundle it was read from, 0 for a file
+    /// built in code. [`Bundle::write_to`] lays the files out anew and ignores it.
+    pub offset: i64,
+    pub data: Vec<u8>,
+}
+
+/// An editable UnityFS bundle, with every file held decompressed.
+///
+/// ```no_run
+/// # fn main() -> unity_rs::UnityResult<()> {
+/// let mut bundle = unity_rs::bundle::Bundle::from_reader(std::fs::File::open("in.ab")?)?;
+/// let mut data = bundle.node("CAB-0123").map(|x| x.data.clone()).unwrap_or_default();
+/// data.push(0);
+/// bundle.replace_node("CAB-0123", data)?;
+/// bundle.write_to(&mut std::fs::File::create("out.ab")?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub version: u32,
+    pub unity_version: String,
+    pub unity_revision: String,
+    /// How [`Bundle::write_to`] compresses data blocks, that of the source bundle when read. The
+    /// blocks info is always LZ4 compressed.
+    pub compression: CompressionType,
+    pub nodes: Vec<BundleNode>,
+}
+
+impl Bundle {
+    pub fn from_reader<R: Read + Seek>(src: R) -> UnityResult<Self> {
+        Self::from_reader_with(src, &LoadOptions::default())
+    }
+
+    /// Reads a bundle that needs decryption, see [`LoadOptions`].
+    ///
+    /// The bundle keeps the compression of its source, so that writing it back does not re-encode
+    /// the blocks another way. `LzInv` blocks can only be read and are written as plain LZ4.
+    pub fn from_reader_with<R: Read + Seek>(src: R, options: &LoadOptions) -> UnityResult<Self> {
+        let ((header, nodes, files), compression, _) = BundleFileLoader::new(options.clone()).read_unity_fs_blocks(src)?;
+        Ok(Self {
+            version: header.version,
+            unity_version: header.unity_version,
+            unity_revision: header.unity_revision,
+            compression: if compression == CompressionType::LzInv { CompressionType::Lz4 } else { compression },
+            nodes: nodes
+                .into_iter()
+                .zip(files)
+                .map(|(node, data)| BundleNode {
+                    path: node.path,
+                    flags: node.flags,
+                    offset: node.offset,
+                    data,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn node(&self, path: &str) -> Option<&BundleNode> {
+        self.nodes.iter().find(|x| x.path == path)
+    }
+
+    /// The raw contents of the file at `path`, including resource files such as `.resS`.
+    pub fn node_data(&self, path: &str) -> Option<&[u8]> {
+        self.node(path).map(|x| x.data.as_slice())
+    }
+
+    /// The directory of the bundle as it was read: every file's path and flags, its offset within
+    /// the decompressed data of the source and the size of its current contents.
+    pub fn directory(&self) -> Vec<Node> {
+        self.nodes
+            .iter()
+            .map(|node| Node {
+                offset: node.offset,
+                size: node.data.len() as i64,
+                flags: node.flags,
+                path: node.path.clone(),
+            })
+            .collect()
+    }
+
+    /// The directory as [`Bundle::write_to`] lays it out, the files one after the other.
+    fn layout(&self) -> Vec<Node> {
+        let mut offset = 0;
+        let mut result = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            result.push(Node {
+                offset,
+                size: node.data.len() as i64,
+                flags: node.flags,
+                path: node.path.clone(),
+            });
+            offset += node.data.len() as i64;
+        }
+        result
+    }
+
+    /// Writes every file into `dir` under its path, creating the directories it needs. Nothing is
+    /// written if a path is absolute or contains `..`, which could reach outside `dir`.
+    pub fn extract_to(&self, dir: impl AsRef<Path>) -> UnityResult<()> {
+        let dir = dir.as_ref();
+        let paths = self.nodes.iter().map(|node| extract_path(dir, &node.path)).collect::<UnityResult<Vec<_>>>()?;
+        for (node, path) in self.nodes.iter().zip(paths) {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, &node.data)?;
+        }
+        Ok(())
+    }
+
+    /// Swaps the contents of the file at `path`.
+    pub fn replace_node(&mut self, path: &str, data: Vec<u8>) -> UnityResult<()> {
+        let node = self.nodes.iter_mut().find(|x| x.path == path).ok_or_else(|| UnityError::CustomError(format!("bundle has no node {}", path)))?;
+        node.data = data;
+        Ok(())
+    }
+
+    /// Serializes the bundle: header, blocks info and directory, then the data blocks.
+    pub fn write_to(&self, w: &mut impl Write) -> UnityResult<()> {
+        let mut directory = Vec::new();
+        for node in self.layout() {
+            directory.extend_from_slice(&node.offset.to_be_bytes());
+            directory.extend_from_slice(&node.size.to_be_bytes());
+            directory.extend_from_slice(&node.flags.to_be_bytes());
+            directory.extend_from_slice(node.path.as_bytes());
+            directory.push(0);
+        }
+        let mut stream = Vec::with_capacity(self.nodes.iter().map(|x| x.data.len()).sum());
+        for node in &self.nodes {
+            stream.extend_from_slice(&node.data);
+        }
+
+        let mut blocks_info = vec![0; 16];
+        let mut blocks = Vec::new();
+        let chunks: Vec<&[u8]> = if self.compression == CompressionType::Lzma { vec![stream.as_slice()] } else { stream.chunks(BLOCK_SIZE).collect() };
+        blocks_info.extend_from_slice(&(chunks.len() as i32).to_be_bytes());
+        for chunk in chunks {
+            let (compressed, flags) = compress_block(chunk, self.compression)?;
+            blocks_info.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+            blocks_info.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+            blocks_info.extend_from_slice(&flags.to_be_bytes());
+            blocks.extend_from_slice(&compressed);
+        }
+        blocks_info.extend_from_slice(&(self.nodes.len() as i32).to_be_bytes());
+        blocks_info.extend_from_slice(&directory);
+        let compressed_blocks_info = lz4_flex::compress(&blocks_info);
+
+        let mut header = Vec::new();
+        header.extend_from_slice(b"UnityFS\0");
+        header.extend_from_slice(&self.version.to_be_bytes());
+        header.extend_from_slice(self.unity_version.as_bytes());
+        header.push(0);
+        header.extend_from_slice(self.unity_revision.as_bytes());
+        header.push(0);
+        let header_size = header.len() + 20;
+        let padding = if self.version >= 7 { header_size.next_multiple_of(16) - header_size } else { 0 };
+        let size = header_size + padding + compressed_blocks_info.len() + blocks.len();
+        // LZ4 compressed blocks info.
+        let flags = 2 | ArchiveFlags::BlocksAndDirectoryInfoCombined as u32;
+        header.extend_from_slice(&(size as i64).to_be_bytes());
+        header.extend_from_slice(&(compressed_blocks_info.len() as u32).to_be_bytes());
+        header.extend_from_slice(&(blocks_info.len() as u32).to_be_bytes());
+        header.extend_from_slice(&flags.to_be_bytes());
+        header.resize(header.len() + padding, 0);
+
+        w.write_all(&header)?;
+        w.write_all(&compressed_blocks_info)?;
+        w.write_all(&blocks)?;
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> UnityResult<Vec<u8>> {
+        let mut result = Vec::new();
+        self.write_to(&mut result)?;
+        Ok(result)
+    }
+}
+
+/// Where the node at `path` goes under `dir`, keeping its directories.
+fn extract_path(dir: &Path, path: &str) -> UnityResult<PathBuf> {
+    let mut result = dir.to_path_buf();
+    let mut named = false;
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(x) => {
+                result.push(x);
+                named = true;
+            }
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return Err(UnityError::CustomError(format!("can not extract node {} outside {}", path, dir.display()))),
+        }
+    }
+    if !named {
+        return Err(UnityError::CustomError(format!("can not extract node {}", path)));
+    }
+    Ok(result)
+}
+
+/// Compresses one data block, returning it with its storage flags.
+fn compress_block(data: &[u8], compression: CompressionType) -> UnityResult<(Vec<u8>, u16)> {
+    let result = match compression {
+        CompressionType::None => data.to_vec(),
+        CompressionType::Lz4 | CompressionType::Lz4HC => lz4_flex::compress(data),
+        CompressionType::Lzma => {
+            let mut result = Vec::new();
+            let options = Options {
+                unpacked_size: UnpackedSize::SkipWritingToHeader,
+            };
+            lzma_rs::lzma_compress_with_options(&mut Cursor::new(data), &mut result, &options)?;
+            result
+        }
+        CompressionType::LzInv => return Err(UnityError::Unimplemented),
+    };
+    let flags = match compression {
+        CompressionType::None => 0,
+        CompressionType::Lzma => 1,
+        _ => 2,
+    };
+    Ok((result, flags))
+}
@@ -1,15 +1,23 @@
-use crate::error::UnityResult;
-use crate::math::Vector3;
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::{UnityError, UnityResult};
+use crate::math::{Quaternion, Vector3};
 use crate::object::ObjectInfo;
 use crate::reader::Reader;
 
+use super::animation_curve::AnimationCurve;
+use super::id::ClassID;
+use super::mono_script::MonoScript;
+use super::pptr::PPtr;
+use super::sprite::Sprite;
+
 #[derive(Default, Debug)]
-pub struct AnimationClip {
+pub struct Aabb {
     pub center: Vector3,
     pub extent: Vector3,
 }
 
-impl AnimationClip {
+impl Aabb {
     pub(super) fn load(r: &mut Reader) -> UnityResult<Self> {
         let center = r.read_vector3()?;
         let extent = r.read_vector3()?;
@@ -128,3 +136,302 @@ impl PackedIntVector {
         unpacked_data
     }
 }
+
+#[derive(Debug)]
+pub struct PackedQuatVector {
+    pub num_items: u32,
+    pub data: Vec<u8>,
+}
+
+impl PackedQuatVector {
+    pub(super) fn load(r: &mut Reader) -> UnityResult<Self> {
+        let num_items = r.read_u32()?;
+        let len = r.read_i32()? as usize;
+        let data = r.read_u8_list(len)?;
+        r.align(4)?;
+        Ok(Self { num_items, data })
+    }
+}
+
+#[derive(Debug)]
+pub struct QuaternionCurve {
+    pub curve: AnimationCurve<Quaternion>,
+    pub path: String,
+}
+
+impl QuaternionCurve {
+    fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let curve = AnimationCurve::load(object, r)?;
+        let path = r.read_aligned_string()?;
+        Ok(Self { curve, path })
+    }
+}
+
+#[derive(Debug)]
+pub struct Vector3Curve {
+    pub curve: AnimationCurve<Vector3>,
+    pub path: String,
+}
+
+impl Vector3Curve {
+    fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let curve = AnimationCurve::load(object, r)?;
+        let path = r.read_aligned_string()?;
+        Ok(Self { curve, path })
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatCurve<'a> {
+    pub curve: AnimationCurve<f32>,
+    pub attribute: String,
+    pub path: String,
+    pub class_id: ClassID,
+    pub script: PPtr<'a, MonoScript>,
+    pub flags: i32,
+}
+
+impl<'a> FloatCurve<'a> {
+    fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.info.version;
+        let curve = AnimationCurve::load(object.info, r)?;
+        let attribute = r.read_aligned_string()?;
+        let path = r.read_aligned_string()?;
+        let class_id = ClassID::from(r.read_i32()?);
+        let script = PPtr::load(object, r)?;
+        let mut flags = 0;
+        if version.is_at_least(2022, 2) {
+            flags = r.read_i32()?;
+        }
+        Ok(Self {
+            curve,
+            attribute,
+            path,
+            class_id,
+            script,
+            flags,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct PPtrKeyframe<'a> {
+    pub time: f32,
+    /// Usually a Sprite swap; use [`PPtr::get_obj_any`] for other targets.
+    pub value: PPtr<'a, Sprite<'a>>,
+}
+
+#[derive(Debug)]
+pub struct PPtrCurve<'a> {
+    pub curve: Vec<PPtrKeyframe<'a>>,
+    pub attribute: String,
+    pub path: String,
+    pub class_id: ClassID,
+    pub script: PPtr<'a, MonoScript>,
+    pub flags: i32,
+}
+
+impl<'a> PPtrCurve<'a> {
+    fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.info.version;
+        let count = r.read_i32()? as usize;
+        let mut curve = Vec::with_capacity(count);
+        for _ in 0..count {
+            let time = r.read_f32()?;
+            let value = PPtr::load(object, r)?;
+            curve.push(PPtrKeyframe { time, value });
+        }
+        let attribute = r.read_aligned_string()?;
+        let path = r.read_aligned_string()?;
+        let class_id = ClassID::from(r.read_i32()?);
+        let script = PPtr::load(object, r)?;
+        let mut flags = 0;
+        if version.is_at_least(2022, 2) {
+            flags = r.read_i32()?;
+        }
+        Ok(Self {
+            curve,
+            attribute,
+            path,
+            class_id,
+            script,
+            flags,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct CompressedAnimationCurve {
+    pub path: String,
+    pub times: PackedIntVector,
+    pub values: PackedQuatVector,
+    pub slopes: PackedFloatVector,
+    pub pre_infinity: i32,
+    pub post_infinity: i32,
+}
+
+impl CompressedAnimationCurve {
+    fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        Ok(Self {
+            path: r.read_aligned_string()?,
+            times: PackedIntVector::load(object, r)?,
+            values: PackedQuatVector::load(r)?,
+            slopes: PackedFloatVector::load(object, r)?,
+            pre_infinity: r.read_i32()?,
+            post_infinity: r.read_i32()?,
+        })
+    }
+}
+
+/// A legacy curve of an [`AnimationClip`], bound to a transform path and property.
+#[derive(Debug, Clone, Copy)]
+pub enum ClipCurve<'c, 'a> {
+    Rotation(&'c QuaternionCurve),
+    Euler(&'c Vector3Curve),
+    Position(&'c Vector3Curve),
+    Scale(&'c Vector3Curve),
+    Float(&'c FloatCurve<'a>),
+}
+
+impl<'c> ClipCurve<'c, '_> {
+    pub fn path(&self) -> &'c str {
+        match *self {
+            Self::Rotation(curve) => &curve.path,
+            Self::Euler(curve) | Self::Position(curve) | Self::Scale(curve) => &curve.path,
+            Self::Float(curve) => &curve.path,
+        }
+    }
+
+    /// The animated property, using the same names as Unity's editor curve bindings.
+    pub fn property(&self) -> &'c str {
+        match *self {
+            Self::Rotation(_) => "m_LocalRotation",
+            Self::Euler(_) => "localEulerAnglesRaw",
+            Self::Position(_) => "m_LocalPosition",
+            Self::Scale(_) => "m_LocalScale",
+            Self::Float(curve) => &curve.attribute,
+        }
+    }
+}
+
+pub struct AnimationClip<'a> {
+    pub name: String,
+    pub legacy: bool,
+    pub compressed: bool,
+    pub use_high_quality_curve: bool,
+    pub rotation_curves: Vec<QuaternionCurve>,
+    pub compressed_rotation_curves: Vec<CompressedAnimationCurve>,
+    pub euler_curves: Vec<Vector3Curve>,
+    pub position_curves: Vec<Vector3Curve>,
+    pub scale_curves: Vec<Vector3Curve>,
+    pub float_curves: Vec<FloatCurve<'a>>,
+    pub pptr_curves: Vec<PPtrCurve<'a>>,
+    pub sample_rate: f32,
+    pub wrap_mode: i32,
+    pub bounds: Aabb,
+    /// Size of the serialized muscle clip; non zero when the animation is stored as streamed/dense/constant clips.
+    pub muscle_clip_size: u32,
+}
+
+fn read_list<T>(r: &mut Reader, mut f: impl FnMut(&mut Reader) -> UnityResult<T>) -> UnityResult<Vec<T>> {
+    let count = r.read_i32()? as usize;
+    let mut result = Vec::with_capacity(count);
+    for _ in 0..count {
+        result.push(f(r)?);
+    }
+    Ok(result)
+}
+
+impl<'a> FromObject<'a> for AnimationClip<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let version = object.info.version;
+        let info = object.info;
+        let mut r = info.get_reader()?;
+        let name = r.read_aligned_string()?;
+        let legacy = if version.major >= 5 {
+            r.read_bool()?
+        } else if version.major >= 4 {
+            // AnimationType: 1 Legacy, 2 Generic, 3 Humanoid
+            r.read_i32()? == 1
+        } else {
+            true
+        };
+        let compressed = r.read_bool()?;
+        let mut use_high_quality_curve = false;
+        if version.is_at_least(4, 3) {
+            use_high_quality_curve = r.read_bool()?;
+        }
+        r.align(4)?;
+        let rotation_curves = read_list(&mut r, |r| QuaternionCurve::load(info, r))?;
+        let compressed_rotation_curves = read_list(&mut r, |r| CompressedAnimationCurve::load(info, r))?;
+        let mut euler_curves = Vec::new();
+        if version.is_at_least(5, 3) {
+            euler_curves = read_list(&mut r, |r| Vector3Curve::load(info, r))?;
+        }
+        let position_curves = read_list(&mut r, |r| Vector3Curve::load(info, r))?;
+        let scale_curves = read_list(&mut r, |r| Vector3Curve::load(info, r))?;
+        let float_curves = read_list(&mut r, |r| FloatCurve::load(object, r))?;
+        let mut pptr_curves = Vec::new();
+        if version.is_at_least(4, 3) {
+            pptr_curves = read_list(&mut r, |r| PPtrCurve::load(object, r))?;
+        }
+        let sample_rate = r.read_f32()?;
+        let wrap_mode = r.read_i32()?;
+        let mut bounds = Aabb::default();
+        if version.is_at_least(3, 4) {
+            bounds = Aabb::load(&mut r)?;
+        }
+        let mut muscle_clip_size = 0;
+        if version.major >= 4 {
+            muscle_clip_size = r.read_u32()?;
+        }
+        Ok(Self {
+            name,
+            legacy,
+            compressed,
+            use_high_quality_curve,
+            rotation_curves,
+            compressed_rotation_curves,
+            euler_curves,
+            position_curves,
+            scale_curves,
+            float_curves,
+            pptr_curves,
+            sample_rate,
+            wrap_mode,
+            bounds,
+            muscle_clip_size,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::AnimationClip
+    }
+}
+
+impl<'a> AnimationClip<'a> {
+    fn has_legacy_curves(&self) -> bool {
+        !(self.rotation_curves.is_empty() && self.euler_curves.is_empty() && self.position_curves.is_empty() && self.scale_curves.is_empty() && self.float_curves.is_empty())
+    }
+
+    /// Enumerates the legacy curves by path and property.
+    ///
+    /// Clips that only carry a muscle clip (the streamed/dense/constant form used by Mecanim)
+    /// return an error, as decoding that form is not supported yet.
+    pub fn curves(&self) -> UnityResult<Vec<ClipCurve<'_, 'a>>> {
+        if !self.has_legacy_curves() && self.muscle_clip_size > 0 {
+            return Err(UnityError::CustomError(format!("AnimationClip {} is stored as a muscle clip, which is not supported yet", self.name)));
+        }
+        let mut curves = Vec::new();
+        curves.extend(self.rotation_curves.iter().map(ClipCurve::Rotation));
+        curves.extend(self.euler_curves.iter().map(ClipCurve::Euler));
+        curves.extend(self.position_curves.iter().map(ClipCurve::Position));
+        curves.extend(self.scale_curves.iter().map(ClipCurve::Scale));
+        curves.extend(self.float_curves.iter().map(ClipCurve::Float));
+        Ok(curves)
+    }
+
+    pub fn find_curve(&self, path: &str, property: &str) -> UnityResult<Option<ClipCurve<'_, 'a>>> {
+        Ok(self.curves()?.into_iter().find(|curve| curve.path() == path && curve.property() == property))
+    }
+}
@@ -0,0 +1,250 @@
+use serde::Deserialize;
+
+use crate::error::UnityResult;
+use crate::math::{Quaternion, Vector3};
+use crate::object::ObjectInfo;
+use crate::reader::Reader;
+
+/// A value that can be animated by an [`AnimationCurve`].
+pub trait KeyframeValue: Sized + Copy {
+    fn read(r: &mut Reader) -> UnityResult<Self>;
+    /// The weight Unity assumes for keyframes serialized before weighted tangents existed (2018.1).
+    fn default_weight() -> Self;
+}
+
+impl KeyframeValue for f32 {
+    fn read(r: &mut Reader) -> UnityResult<Self> {
+        Ok(r.read_f32()?)
+    }
+
+    fn default_weight() -> Self {
+        1.0 / 3.0
+    }
+}
+
+impl KeyframeValue for Vector3 {
+    fn read(r: &mut Reader) -> UnityResult<Self> {
+        Ok(r.read_vector3()?)
+    }
+
+    fn default_weight() -> Self {
+        Vector3::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)
+    }
+}
+
+impl KeyframeValue for Quaternion {
+    fn read(r: &mut Reader) -> UnityResult<Self> {
+        Ok(Quaternion::from_array(r.read_f32_array::<4>()?))
+    }
+
+    fn default_weight() -> Self {
+        Quaternion::new(1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de> + KeyframeValue"))]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+    #[serde(rename = "inSlope")]
+    pub in_slope: T,
+    #[serde(rename = "outSlope")]
+    pub out_slope: T,
+    /// `WeightedMode` bit flags: 1 for the in weight, 2 for the out weight. Added in 2018.1.
+    #[serde(rename = "weightedMode", default)]
+    pub weighted_mode: i32,
+    #[serde(rename = "inWeight", default = "KeyframeValue::default_weight")]
+    pub in_weight: T,
+    #[serde(rename = "outWeight", default = "KeyframeValue::default_weight")]
+    pub out_weight: T,
+}
+
+impl<T: KeyframeValue> Keyframe<T> {
+    pub(super) fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let time = r.read_f32()?;
+        let value = T::read(r)?;
+        let in_slope = T::read(r)?;
+        let out_slope = T::read(r)?;
+        let (weighted_mode, in_weight, out_weight) = if object.version.major >= 2018 {
+            (r.read_i32()?, T::read(r)?, T::read(r)?)
+        } else {
+            (0, T::default_weight(), T::default_weight())
+        };
+        Ok(Self {
+            time,
+            value,
+            in_slope,
+            out_slope,
+            weighted_mode,
+            in_weight,
+            out_weight,
+        })
+    }
+}
+
+/// How a curve is extended outside its keyframe range, as serialized in `m_PreInfinity`/`m_PostInfinity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    PingPong,
+    Loop,
+    Clamp,
+}
+
+impl From<i32> for WrapMode {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::PingPong,
+            1 => Self::Loop,
+            _ => Self::Clamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de> + KeyframeValue"))]
+pub struct AnimationCurve<T> {
+    #[serde(rename = "m_Curve")]
+    pub curve: Vec<Keyframe<T>>,
+    #[serde(rename = "m_PreInfinity")]
+    pub pre_infinity: i32,
+    #[serde(rename = "m_PostInfinity")]
+    pub post_infinity: i32,
+    #[serde(rename = "m_RotationOrder", default)]
+    pub rotation_order: i32,
+}
+
+impl<T: KeyframeValue> AnimationCurve<T> {
+    pub(super) fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        let count = r.read_i32()? as usize;
+        let mut curve = Vec::with_capacity(count);
+        for _ in 0..count {
+            curve.push(Keyframe::load(object, r)?);
+        }
+        let pre_infinity = r.read_i32()?;
+        let post_infinity = r.read_i32()?;
+        let mut rotation_order = 0;
+        if version.is_at_least(5, 3) {
+            rotation_order = r.read_i32()?;
+        }
+        Ok(Self {
+            curve,
+            pre_infinity,
+            post_infinity,
+            rotation_order,
+        })
+    }
+}
+
+const WEIGHTED_IN: i32 = 1;
+const WEIGHTED_OUT: i32 = 2;
+
+impl AnimationCurve<f32> {
+    pub fn new(curve: Vec<Keyframe<f32>>) -> Self {
+        Self {
+            curve,
+            pre_infinity: 2,
+            post_infinity: 2,
+            rotation_order: 4,
+        }
+    }
+
+    pub fn pre_wrap_mode(&self) -> WrapMode {
+        WrapMode::from(self.pre_infinity)
+    }
+
+    pub fn post_wrap_mode(&self) -> WrapMode {
+        WrapMode::from(self.post_infinity)
+    }
+
+    /// Samples the curve at `time` the way `UnityEngine.AnimationCurve.Evaluate` does.
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let (first, last) = match (self.curve.first(), self.curve.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+        let duration = last.time - first.time;
+        if self.curve.len() == 1 || duration <= 0.0 {
+            return first.value;
+        }
+        let time = if time < first.time {
+            wrap(self.pre_wrap_mode(), time, first.time, duration)
+        } else if time > last.time {
+            wrap(self.post_wrap_mode(), time, first.time, duration)
+        } else {
+            time
+        };
+        if time >= last.time {
+            return last.value;
+        }
+        let index = self.curve.partition_point(|key| key.time <= time).clamp(1, self.curve.len() - 1);
+        interpolate(&self.curve[index - 1], &self.curve[index], time)
+    }
+}
+
+fn wrap(mode: WrapMode, time: f32, start: f32, duration: f32) -> f32 {
+    let offset = time - start;
+    match mode {
+        WrapMode::Clamp => start + offset.clamp(0.0, duration),
+        WrapMode::Loop => start + offset.rem_euclid(duration),
+        WrapMode::PingPong => {
+            let offset = offset.rem_euclid(duration * 2.0);
+            start + if offset > duration { duration * 2.0 - offset } else { offset }
+        }
+    }
+}
+
+fn interpolate(lhs: &Keyframe<f32>, rhs: &Keyframe<f32>, time: f32) -> f32 {
+    let dt = rhs.time - lhs.time;
+    if dt <= 0.0 {
+        return rhs.value;
+    }
+    // Infinite tangents mark stepped (constant) segments.
+    if !lhs.out_slope.is_finite() || !rhs.in_slope.is_finite() {
+        return lhs.value;
+    }
+    let t = (time - lhs.time) / dt;
+    let m0 = lhs.out_slope * dt;
+    let m1 = rhs.in_slope * dt;
+    if lhs.weighted_mode & WEIGHTED_OUT == 0 && rhs.weighted_mode & WEIGHTED_IN == 0 {
+        return hermite(t, lhs.value, m0, m1, rhs.value);
+    }
+    let out_weight = if lhs.weighted_mode & WEIGHTED_OUT != 0 { lhs.out_weight } else { 1.0 / 3.0 };
+    let in_weight = if rhs.weighted_mode & WEIGHTED_IN != 0 { rhs.in_weight } else { 1.0 / 3.0 };
+    bezier(t, lhs.value, m0, out_weight, rhs.value, m1, in_weight)
+}
+
+fn hermite(t: f32, p0: f32, m0: f32, m1: f32, p1: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (2.0 * t3 - 3.0 * t2 + 1.0) * p0 + (t3 - 2.0 * t2 + t) * m0 + (-2.0 * t3 + 3.0 * t2) * p1 + (t3 - t2) * m1
+}
+
+/// Weighted segments are cubic beziers whose control points sit at the tangent weights along the time axis.
+fn bezier(t: f32, p0: f32, m0: f32, out_weight: f32, p1: f32, m1: f32, in_weight: f32) -> f32 {
+    let x1 = out_weight;
+    let x2 = 1.0 - in_weight;
+    let y1 = p0 + m0 * out_weight;
+    let y2 = p1 - m1 * in_weight;
+    let cubic = |u: f32, a: f32, b: f32, c: f32, d: f32| {
+        let v = 1.0 - u;
+        v * v * v * a + 3.0 * v * v * u * b + 3.0 * v * u * u * c + u * u * u * d
+    };
+    // x(u) is monotonic for weights in [0, 1], so bisection always converges.
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    let mut u = t;
+    for _ in 0..32 {
+        let x = cubic(u, 0.0, x1, x2, 1.0);
+        if (x - t).abs() < 1e-6 {
+            break;
+        }
+        if x < t {
+            low = u;
+        } else {
+            high = u;
+        }
+        u = (low + high) / 2.0;
+    }
+    cubic(u, p0, y1, y2, p1)
+}
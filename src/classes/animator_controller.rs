@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+use super::animation_clip::AnimationClip;
+use super::pptr::{PPtr, RawPPtr};
+
+/// Mecanim constants wrap nested blobs in `OffsetPtr { data }`.
+#[derive(Deserialize)]
+pub(super) struct OffsetPtr<T> {
+    pub(super) data: T,
+}
+
+#[derive(Deserialize)]
+struct RawBlendTreeNode {
+    #[serde(rename = "m_ChildIndices", default)]
+    child_indices: Vec<u32>,
+    #[serde(rename = "m_ClipID")]
+    clip_id: u32,
+}
+
+#[derive(Deserialize)]
+struct RawBlendTree {
+    #[serde(rename = "m_NodeArray")]
+    node_array: Vec<OffsetPtr<RawBlendTreeNode>>,
+}
+
+#[derive(Deserialize)]
+struct RawState {
+    #[serde(rename = "m_BlendTreeConstantIndexArray", default)]
+    blend_tree_constant_index_array: Vec<i32>,
+    #[serde(rename = "m_BlendTreeConstantArray")]
+    blend_tree_constant_array: Vec<OffsetPtr<RawBlendTree>>,
+    #[serde(rename = "m_NameID")]
+    name_id: u32,
+    #[serde(rename = "m_FullPathID", default)]
+    full_path_id: u32,
+}
+
+#[derive(Deserialize)]
+struct RawStateMachine {
+    #[serde(rename = "m_StateConstantArray")]
+    state_constant_array: Vec<OffsetPtr<RawState>>,
+}
+
+#[derive(Deserialize)]
+struct RawLayer {
+    #[serde(rename = "m_StateMachineIndex")]
+    state_machine_index: u32,
+    #[serde(rename = "m_StateMachineMotionSetIndex", default)]
+    state_machine_motion_set_index: u32,
+    #[serde(rename = "m_Binding")]
+    binding: u32,
+}
+
+#[derive(Deserialize)]
+struct RawController {
+    #[serde(rename = "m_LayerArray")]
+    layer_array: Vec<OffsetPtr<RawLayer>>,
+    #[serde(rename = "m_StateMachineArray")]
+    state_machine_array: Vec<OffsetPtr<RawStateMachine>>,
+}
+
+#[derive(Deserialize)]
+struct RawAnimatorController {
+    #[serde(rename = "m_Name")]
+    name: String,
+    #[serde(rename = "m_Controller")]
+    controller: RawController,
+    #[serde(rename = "m_TOS")]
+    tos: HashMap<u32, String>,
+    #[serde(rename = "m_AnimationClips")]
+    animation_clips: Vec<RawPPtr>,
+}
+
+/// What a state plays. Clip indices point into [`AnimatorController::animation_clips`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateMotion {
+    None,
+    Clip(usize),
+    /// Blend trees are not decoded further; this lists the clips of their leaf nodes.
+    BlendTree(Vec<usize>),
+}
+
+#[derive(Debug, Clone)]
+pub struct AnimatorState {
+    pub name: String,
+    /// `Layer.StateMachine.State`, as used by `Animator.Play`.
+    pub full_path: String,
+    pub motion: StateMotion,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnimatorLayer {
+    pub name: String,
+    pub states: Vec<AnimatorState>,
+}
+
+/// The controller constant is read through the type tree. Files without one read through the
+/// [`builtin_nodes`](crate::typetree::builtin_nodes) template of Unity 5.5 and later.
+pub struct AnimatorController<'a> {
+    pub name: String,
+    pub tos: HashMap<u32, String>,
+    pub layers: Vec<AnimatorLayer>,
+    pub animation_clips: Vec<PPtr<'a, AnimationClip<'a>>>,
+}
+
+impl<'a> FromObject<'a> for AnimatorController<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let raw: RawAnimatorController = object.read_type_tree()?;
+        let resolve = |hash: u32| raw.tos.get(&hash).cloned().unwrap_or_else(|| hash.to_string());
+        let mut layers = Vec::with_capacity(raw.controller.layer_array.len());
+        for layer in &raw.controller.layer_array {
+            let layer = &layer.data;
+            let Some(state_machine) = raw.controller.state_machine_array.get(layer.state_machine_index as usize) else {
+                continue;
+            };
+            let layer_name = resolve(layer.binding);
+            let states = state_machine
+                .data
+                .state_constant_array
+                .iter()
+                .map(|state| {
+                    let state = &state.data;
+                    let name = resolve(state.name_id);
+                    let full_path = raw.tos.get(&state.full_path_id).cloned().unwrap_or_else(|| format!("{}.{}", layer_name, name));
+                    AnimatorState {
+                        name,
+                        full_path,
+                        motion: state_motion(state, layer.state_machine_motion_set_index as usize),
+                    }
+                })
+                .collect();
+            layers.push(AnimatorLayer { name: layer_name, states });
+        }
+        let animation_clips = raw.animation_clips.iter().map(|clip| clip.bind(object)).collect();
+        Ok(Self {
+            name: raw.name,
+            layers,
+            animation_clips,
+            tos: raw.tos,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::AnimatorController
+    }
+}
+
+fn state_motion(state: &RawState, motion_set_index: usize) -> StateMotion {
+    // Before 5.0 there is no index array and each motion set owns the blend tree at the same index.
+    let blend_tree_index = match state.blend_tree_constant_index_array.get(motion_set_index) {
+        Some(&index) if index < 0 => return StateMotion::None,
+        Some(&index) => index as usize,
+        None if state.blend_tree_constant_index_array.is_empty() => motion_set_index,
+        None => return StateMotion::None,
+    };
+    let Some(blend_tree) = state.blend_tree_constant_array.get(blend_tree_index) else {
+        return StateMotion::None;
+    };
+    let nodes = &blend_tree.data.node_array;
+    let clip = |node: &RawBlendTreeNode| (node.clip_id != u32::MAX).then_some(node.clip_id as usize);
+    match nodes.as_slice() {
+        [] => StateMotion::None,
+        [single] if single.data.child_indices.is_empty() => clip(&single.data).map_or(StateMotion::None, StateMotion::Clip),
+        _ => StateMotion::BlendTree(nodes.iter().filter(|node| node.data.child_indices.is_empty()).filter_map(|node| clip(&node.data)).collect()),
+    }
+}
+
+impl AnimatorController<'_> {
+    /// Maps each state's full path to the path_id of the clip it plays; blend tree states are skipped.
+    pub fn state_clips(&self) -> HashMap<String, i64> {
+        let mut result = HashMap::new();
+        for state in self.layers.iter().flat_map(|layer| &layer.states) {
+            if let StateMotion::Clip(index) = state.motion {
+                if let Some(clip) = self.animation_clips.get(index) {
+                    result.insert(state.full_path.clone(), clip.path_id);
+                }
+            }
+        }
+        result
+    }
+}
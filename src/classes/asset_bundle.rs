@@ -0,0 +1,86 @@
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::reader::Reader;
+
+use super::pptr::PPtr;
+
+#[derive(Debug)]
+pub struct AssetInfo<'a> {
+    pub preload_index: i32,
+    pub preload_size: i32,
+    pub asset: PPtr<'a, Object<'a>>,
+}
+
+impl<'a> AssetInfo<'a> {
+    fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
+        Ok(Self {
+            preload_index: r.read_i32()?,
+            preload_size: r.read_i32()?,
+            asset: PPtr::load(object, r)?,
+        })
+    }
+}
+
+pub struct AssetBundle<'a> {
+    pub name: String,
+    pub preload_table: Vec<PPtr<'a, Object<'a>>>,
+    /// Kept as a list since one path can have several entries.
+    pub container: Vec<(String, AssetInfo<'a>)>,
+    pub main_asset: AssetInfo<'a>,
+    pub runtime_compatibility: u32,
+    pub asset_bundle_name: String,
+    pub dependencies: Vec<String>,
+    pub is_streamed_scene_asset_bundle: bool,
+}
+
+impl<'a> FromObject<'a> for AssetBundle<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        let name = r.read_aligned_string()?;
+        let mut preload_table = Vec::new();
+        for _ in 0..r.read_i32()? {
+            preload_table.push(PPtr::load(object, &mut r)?);
+        }
+        let mut container = Vec::new();
+        for _ in 0..r.read_i32()? {
+            let path = r.read_aligned_string()?;
+            container.push((path, AssetInfo::load(object, &mut r)?));
+        }
+        let main_asset = AssetInfo::load(object, &mut r)?;
+        let runtime_compatibility = r.read_u32()?;
+        let mut asset_bundle_name = String::new();
+        let mut dependencies = Vec::new();
+        let mut is_streamed_scene_asset_bundle = false;
+        if version.major >= 5 {
+            asset_bundle_name = r.read_aligned_string()?;
+            dependencies = r.read_string_list()?;
+            is_streamed_scene_asset_bundle = r.read_bool()?;
+            r.align(4)?;
+        }
+        Ok(Self {
+            name,
+            preload_table,
+            container,
+            main_asset,
+            runtime_compatibility,
+            asset_bundle_name,
+            dependencies,
+            is_streamed_scene_asset_bundle,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::AssetBundle
+    }
+}
+
+impl<'a> AssetBundle<'a> {
+    /// The objects preloaded with a container entry, i.e. the asset and its dependencies.
+    pub fn preload(&self, info: &AssetInfo) -> &[PPtr<'a, Object<'a>>] {
+        let start = info.preload_index.max(0) as usize;
+        let end = start + info.preload_size.max(0) as usize;
+        self.preload_table.get(start..end).unwrap_or_default()
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+#[derive(Debug, Clone)]
+pub struct AssetBundleInfo {
+    pub hash: [u8; 16],
+    /// Indices into [`AssetBundleManifest::bundle_names`]; Unity already stores the full closure here.
+    pub dependencies: Vec<i32>,
+}
+
+/// The manifest in a build's root bundle, describing every bundle and what it depends on.
+pub struct AssetBundleManifest {
+    pub name: String,
+    pub bundle_names: HashMap<i32, String>,
+    pub bundles_with_variant: Vec<i32>,
+    pub bundle_infos: HashMap<i32, AssetBundleInfo>,
+}
+
+impl FromObject<'_> for AssetBundleManifest {
+    fn load(object: &Object) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
+        let name = r.read_aligned_string()?;
+        let mut bundle_names = HashMap::new();
+        for _ in 0..r.read_i32()? {
+            let index = r.read_i32()?;
+            bundle_names.insert(index, r.read_aligned_string()?);
+        }
+        let count = r.read_i32()? as usize;
+        let mut bundles_with_variant = Vec::with_capacity(count);
+        for _ in 0..count {
+            bundles_with_variant.push(r.read_i32()?);
+        }
+        let mut bundle_infos = HashMap::new();
+        for _ in 0..r.read_i32()? {
+            let index = r.read_i32()?;
+            let hash = r.read_u8_array::<16>()?;
+            let count = r.read_i32()? as usize;
+            let mut dependencies = Vec::with_capacity(count);
+            for _ in 0..count {
+                dependencies.push(r.read_i32()?);
+            }
+            bundle_infos.insert(index, AssetBundleInfo { hash, dependencies });
+        }
+        Ok(Self {
+            name,
+            bundle_names,
+            bundles_with_variant,
+            bundle_infos,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::AssetBundleManifest
+    }
+}
+
+impl AssetBundleManifest {
+    fn index_of(&self, bundle_name: &str) -> Option<i32> {
+        self.bundle_names.iter().find(|(_, name)| name.as_str() == bundle_name).map(|(index, _)| *index)
+    }
+
+    pub fn bundles(&self) -> impl Iterator<Item = &str> {
+        self.bundle_names.values().map(String::as_str)
+    }
+
+    /// Names of all bundles `bundle_name` depends on, directly or indirectly.
+    pub fn dependencies(&self, bundle_name: &str) -> Vec<String> {
+        let Some(info) = self.index_of(bundle_name).and_then(|index| self.bundle_infos.get(&index)) else {
+            return Vec::new();
+        };
+        info.dependencies.iter().filter_map(|index| self.bundle_names.get(index).cloned()).collect()
+    }
+
+    pub fn hash(&self, bundle_name: &str) -> Option<[u8; 16]> {
+        Some(self.bundle_infos.get(&self.index_of(bundle_name)?)?.hash)
+    }
+
+    /// The hash as 32 lowercase hex digits, in serialized byte order.
+    pub fn hash_string(&self, bundle_name: &str) -> Option<String> {
+        Some(self.hash(bundle_name)?.iter().map(|x| format!("{:02x}", x)).collect())
+    }
+}
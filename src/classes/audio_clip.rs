@@ -5,7 +5,6 @@ use num_enum::FromPrimitive;
 use crate::classes::FromObject;
 use crate::env::Object;
 use crate::error::UnityResult;
-use crate::reader::{ByteOrder, Reader};
 use crate::UnityError;
 
 #[derive(Debug, Eq, PartialEq, FromPrimitive, Clone, Copy)]
@@ -98,13 +97,13 @@ pub struct AudioClip {
 
 impl FromObject<'_> for AudioClip {
     fn load(object: &Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+        let mut r = object.info.get_reader()?;
         let name = r.read_aligned_string()?;
         let meta: AudioClipMeta;
         let size: i64;
         let mut offset: Option<i64> = None;
         let mut source: Option<String> = None;
-        if object.asset.version[0] < 5 {
+        if object.asset.version.major < 5 {
             meta = AudioClipMeta::Low {
                 format: r.read_i32()?,
                 typ: r.read_i32()?.into(),
@@ -112,7 +111,7 @@ impl FromObject<'_> for AudioClip {
                 use_hardware: r.read_bool()?,
             };
             r.align(4)?;
-            if object.asset.version_greater_or_equal(&[3, 2]) {
+            if object.asset.version.is_at_least(3, 2) {
                 let _stream = r.read_i32()?;
                 size = r.read_i32()? as i64;
                 let tsize = if size % 4 != 0 { size + 4 - size % 4 } else { size };
@@ -149,15 +148,16 @@ impl FromObject<'_> for AudioClip {
                 compression_format: { r.read_i32()?.into() },
             }
         }
+        let size_bytes = usize::try_from(size).map_err(|_| UnityError::Eof)?;
         let data = match (source.as_deref(), offset) {
             (Some(source), Some(offset)) => {
                 let mut data = None;
                 let name = source.split('/').next_back().ok_or(UnityError::InvalidValue)?;
 
                 if let Some(buf) = object.env.get_loaded_file(name) {
-                    let mut r = Reader::new(buf.as_slice(), ByteOrder::Big);
-                    r.set_offset(offset as usize)?;
-                    data = Some(r.read_u8_list(size as usize)?);
+                    let offset = usize::try_from(offset).map_err(|_| UnityError::Eof)?;
+                    let end = offset.checked_add(size_bytes).ok_or(UnityError::Eof)?;
+                    data = Some(buf.read(offset..end)?.to_vec());
                 }
 
                 match data {
@@ -165,7 +165,7 @@ impl FromObject<'_> for AudioClip {
                     None => return Err(UnityError::CustomError("can not find resource".to_string())),
                 }
             }
-            _ => r.read_u8_list(size as usize)?,
+            _ => r.read_u8_list(size_bytes)?,
         };
         Ok(Self { name, meta, source, offset, size, data })
     }
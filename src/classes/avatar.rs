@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+use super::animator_controller::OffsetPtr;
+
+#[derive(Deserialize)]
+struct RawSkeleton {
+    #[serde(rename = "m_ID")]
+    id: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+struct RawHuman {
+    #[serde(rename = "m_Skeleton")]
+    skeleton: OffsetPtr<RawSkeleton>,
+    #[serde(rename = "m_HumanBoneIndex")]
+    human_bone_index: Vec<i32>,
+}
+
+#[derive(Deserialize)]
+struct RawAvatarConstant {
+    #[serde(rename = "m_AvatarSkeleton")]
+    avatar_skeleton: OffsetPtr<RawSkeleton>,
+    #[serde(rename = "m_Human")]
+    human: OffsetPtr<RawHuman>,
+}
+
+#[derive(Deserialize)]
+struct RawAvatar {
+    #[serde(rename = "m_Name")]
+    name: String,
+    #[serde(rename = "m_Avatar")]
+    avatar: RawAvatarConstant,
+    #[serde(rename = "m_TOS")]
+    tos: HashMap<u32, String>,
+}
+
+/// Like [`AnimatorController`](super::AnimatorController), the avatar constant is read through the
+/// type tree. Files without one read through the [`builtin_nodes`](crate::typetree::builtin_nodes)
+/// template of Unity 5.4 and later.
+pub struct Avatar {
+    pub name: String,
+    /// CRC32 of a transform path → the path, relative to the avatar root.
+    pub tos: HashMap<u32, String>,
+    /// Path hashes of the avatar skeleton nodes.
+    pub skeleton: Vec<u32>,
+    /// Path hashes of the human skeleton nodes, indexed by `human_bone_index`.
+    pub human_skeleton: Vec<u32>,
+    /// Mecanim human bone index → human skeleton node, `-1` for bones missing from the rig.
+    pub human_bone_index: Vec<i32>,
+}
+
+impl FromObject<'_> for Avatar {
+    fn load(object: &Object) -> UnityResult<Self> {
+        let raw: RawAvatar = object.read_type_tree()?;
+        let human = raw.avatar.human.data;
+        Ok(Self {
+            name: raw.name,
+            tos: raw.tos,
+            skeleton: raw.avatar.avatar_skeleton.data.id,
+            human_skeleton: human.skeleton.data.id,
+            human_bone_index: human.human_bone_index,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::Avatar
+    }
+}
+
+impl Avatar {
+    pub fn path(&self, hash: u32) -> Option<&str> {
+        self.tos.get(&hash).map(String::as_str)
+    }
+
+    /// Transform paths of the human bones, indexed like `human_bone_index`.
+    pub fn human_bone_paths(&self) -> Vec<Option<&str>> {
+        self.human_bone_index
+            .iter()
+            .map(|&index| {
+                let hash = *self.human_skeleton.get(usize::try_from(index).ok()?)?;
+                self.path(hash)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::version::UnityVersion;
+
+/// The scenes and flags a player was built with, and the Unity version behind it. Read through the
+/// type tree when present; otherwise from the binary layout, which names only the first four flags.
+#[derive(Debug, Default, Deserialize)]
+pub struct BuildSettings {
+    #[serde(alias = "levels", default)]
+    pub scenes: Vec<String>,
+    #[serde(rename = "hasRenderTexture")]
+    pub has_render_texture: Option<bool>,
+    #[serde(rename = "hasPROVersion")]
+    pub has_pro_version: Option<bool>,
+    #[serde(rename = "hasPublishingRights")]
+    pub has_publishing_rights: Option<bool>,
+    #[serde(rename = "hasShadows")]
+    pub has_shadows: Option<bool>,
+    #[serde(rename = "isDebugBuild")]
+    pub is_debug_build: Option<bool>,
+    #[serde(rename = "enableDynamicBatching")]
+    pub enable_dynamic_batching: Option<bool>,
+    /// The Unity version the player was built with.
+    #[serde(rename = "m_Version")]
+    pub version: Option<String>,
+}
+
+impl FromObject<'_> for BuildSettings {
+    fn load(object: &Object) -> UnityResult<Self> {
+        if object.type_tree_nodes().is_some() {
+            return Ok(object.read_type_tree()?);
+        }
+        Self::read_raw(object)
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::BuildSettings
+    }
+}
+
+impl BuildSettings {
+    /// Reads the scenes, the flags and then `m_Version` as AssetStudio does, also skipping the
+    /// string lists 5.x added before the flags. Nearly every version added flags after the first
+    /// four, so the rest are skipped as the run of 0 and 1 bytes ahead of the aligned version
+    /// string. A version that does not parse is left `None` rather than returned as garbage.
+    fn read_raw(object: &Object) -> UnityResult<Self> {
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        let scenes = r.read_string_list()?;
+        if version.is_at_least(5, 0) {
+            let _preloaded_plugins = r.read_string_list()?;
+        }
+        if version.is_at_least(5, 1) {
+            let _enabled_vr_devices = r.read_string_list()?;
+        }
+        if version.is_at_least(5, 6) {
+            let _build_tags = r.read_string_list()?;
+            let _build_guid = r.read_aligned_string()?;
+        }
+        let mut flags = Vec::new();
+        let version = loop {
+            match r.read_u8() {
+                Ok(x @ (0 | 1)) => flags.push(x == 1),
+                // The low byte of the version string's length ends the run, but a big endian
+                // length starts with zeros that were taken for flags.
+                Ok(_) => {
+                    let end = r.get_offset() - 1;
+                    r.set_offset(end - end % 4)?;
+                    break r.read_aligned_string().ok().filter(|x| x.contains('.') && x.parse::<UnityVersion>().is_ok());
+                }
+                Err(_) => break None,
+            }
+        };
+        Ok(Self {
+            scenes,
+            has_render_texture: flags.first().copied(),
+            has_pro_version: flags.get(1).copied(),
+            has_publishing_rights: flags.get(2).copied(),
+            has_shadows: flags.get(3).copied(),
+            version,
+            ..Self::default()
+        })
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::math::{Color, RectF32, Vector2};
+
+use super::game_object::GameObject;
+use super::math::{RawColor, RawRect, RawVector2};
+use super::pptr::{PPtr, RawPPtr};
+
+/// A `BitField` type tree node, as used by layer masks.
+#[derive(Deserialize)]
+pub(super) struct RawBitField {
+    #[serde(rename = "m_Bits")]
+    pub(super) bits: u32,
+}
+
+#[derive(Deserialize)]
+struct RawCamera {
+    #[serde(rename = "m_GameObject")]
+    game_object: RawPPtr,
+    #[serde(rename = "m_Enabled")]
+    enabled: u8,
+    #[serde(rename = "m_ClearFlags")]
+    clear_flags: u32,
+    #[serde(rename = "m_BackGroundColor")]
+    background_color: RawColor,
+    #[serde(rename = "m_projectionMatrixMode")]
+    projection_matrix_mode: Option<i32>,
+    #[serde(rename = "m_GateFitMode")]
+    gate_fit_mode: Option<i32>,
+    #[serde(rename = "m_SensorSize")]
+    sensor_size: Option<RawVector2>,
+    #[serde(rename = "m_LensShift")]
+    lens_shift: Option<RawVector2>,
+    #[serde(rename = "m_FocalLength")]
+    focal_length: Option<f32>,
+    #[serde(rename = "m_NormalizedViewPortRect")]
+    viewport_rect: RawRect,
+    #[serde(rename = "near clip plane")]
+    near_clip_plane: f32,
+    #[serde(rename = "far clip plane")]
+    far_clip_plane: f32,
+    #[serde(rename = "field of view")]
+    field_of_view: f32,
+    orthographic: bool,
+    #[serde(rename = "orthographic size")]
+    orthographic_size: f32,
+    #[serde(rename = "m_Depth")]
+    depth: f32,
+    #[serde(rename = "m_CullingMask")]
+    culling_mask: RawBitField,
+    #[serde(rename = "m_TargetTexture")]
+    target_texture: RawPPtr,
+    #[serde(rename = "m_TargetDisplay")]
+    target_display: Option<i32>,
+}
+
+/// Physical camera parameters, serialized since 2018.2.
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicalCamera {
+    /// `Camera.GateFitMode`, serialized since 2018.3.
+    pub gate_fit_mode: Option<i32>,
+    pub sensor_size: Vector2,
+    pub lens_shift: Vector2,
+    pub focal_length: f32,
+}
+
+/// A Camera component: how it clears the screen and projects the scene, which part of the screen
+/// and which layers it renders, and the texture or display it renders to.
+///
+/// Files without type trees read through [`builtin_nodes`](crate::typetree::builtin_nodes), which
+/// knows the layout of Unity 2017 and 2018.
+pub struct Camera<'a> {
+    pub game_object: PPtr<'a, GameObject<'a>>,
+    pub enabled: bool,
+    /// `CameraClearFlags`: 1 skybox, 2 solid color, 3 depth, 4 nothing.
+    pub clear_flags: u32,
+    pub background_color: Color,
+    /// 0 for a regular camera, 1 when the projection comes from [`physical`](Self::physical).
+    pub projection_matrix_mode: Option<i32>,
+    pub physical: Option<PhysicalCamera>,
+    pub viewport_rect: RectF32,
+    pub near_clip_plane: f32,
+    pub far_clip_plane: f32,
+    /// Vertical field of view in degrees.
+    pub field_of_view: f32,
+    pub orthographic: bool,
+    pub orthographic_size: f32,
+    pub depth: f32,
+    pub culling_mask: u32,
+    pub target_texture: PPtr<'a, Object<'a>>,
+    pub target_display: Option<i32>,
+}
+
+impl<'a> FromObject<'a> for Camera<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let raw: RawCamera = object.read_type_tree()?;
+        let physical = match (raw.sensor_size, raw.lens_shift, raw.focal_length) {
+            (Some(sensor_size), Some(lens_shift), Some(focal_length)) => Some(PhysicalCamera {
+                gate_fit_mode: raw.gate_fit_mode,
+                sensor_size: sensor_size.to_vector2(),
+                lens_shift: lens_shift.to_vector2(),
+                focal_length,
+            }),
+            _ => None,
+        };
+        Ok(Self {
+            game_object: raw.game_object.bind(object),
+            enabled: raw.enabled != 0,
+            clear_flags: raw.clear_flags,
+            background_color: raw.background_color.to_color(),
+            projection_matrix_mode: raw.projection_matrix_mode,
+            physical,
+            viewport_rect: raw.viewport_rect.to_rect(),
+            near_clip_plane: raw.near_clip_plane,
+            far_clip_plane: raw.far_clip_plane,
+            field_of_view: raw.field_of_view,
+            orthographic: raw.orthographic,
+            orthographic_size: raw.orthographic_size,
+            depth: raw.depth,
+            culling_mask: raw.culling_mask.bits,
+            target_texture: raw.target_texture.bind(object),
+            target_display: raw.target_display,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::Camera
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use crate::classes::{ClassID, FromObject};
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::reader::Reader;
+
+use super::game_object::GameObject;
+use super::mono_behaviour::MonoBehaviour;
+use super::pptr::PPtr;
+use super::transform::Transform;
+use super::ui::WellKnownBehaviour;
+use super::Component;
+
+pub struct Canvas<'a> {
+    pub game_object: PPtr<'a, GameObject<'a>>,
+    pub enabled: bool,
+    /// `RenderMode`: 0 screen space overlay, 1 screen space camera, 2 world space.
+    pub render_mode: i32,
+    pub camera: PPtr<'a, Object<'a>>,
+    pub plane_distance: f32,
+    pub pixel_perfect: bool,
+}
+
+impl<'a> FromObject<'a> for Canvas<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
+        Self::from_reader(object, &mut r)
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::Canvas
+    }
+}
+
+pub struct CanvasRenderer<'a> {
+    pub game_object: PPtr<'a, GameObject<'a>>,
+}
+
+impl<'a> FromObject<'a> for CanvasRenderer<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
+        Ok(Self {
+            game_object: Component::from_reader(object, &mut r)?.game_object,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::CanvasRenderer
+    }
+}
+
+/// A UI behaviour found under a canvas, with its `/` separated GameObject path from the canvas.
+pub struct UiElement<'a> {
+    pub path: String,
+    pub behaviour: WellKnownBehaviour<'a>,
+}
+
+impl<'a> Canvas<'a> {
+    fn from_reader(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
+        let game_object = Component::from_reader(object, r)?.game_object;
+        let enabled = r.read_bool()?;
+        r.align(4)?;
+        Ok(Self {
+            game_object,
+            enabled,
+            render_mode: r.read_i32()?,
+            camera: PPtr::load(object, r)?,
+            plane_distance: r.read_f32()?,
+            pixel_perfect: r.read_bool()?,
+        })
+    }
+
+    /// Walks the canvas hierarchy and collects every Image, RawImage and Text behaviour.
+    ///
+    /// Each transform is visited once, so a malformed hierarchy listing a parent among its
+    /// children ends the walk there.
+    pub fn elements(&self) -> UnityResult<Vec<UiElement<'a>>> {
+        let mut result = Vec::new();
+        let Some(game_object) = self.game_object.get_obj() else {
+            return Ok(result);
+        };
+        let Some(root) = game_object.read::<GameObject>()?.components().find(|x| matches!(x.class(), ClassID::Transform | ClassID::RectTransform)) else {
+            return Ok(result);
+        };
+        let mut visited = HashSet::new();
+        let mut stack = vec![(root, String::new())];
+        while let Some((transform, parent_path)) = stack.pop() {
+            if !visited.insert((transform.asset.path.as_str(), transform.info.path_id)) {
+                continue;
+            }
+            let transform = transform.read::<Transform>()?;
+            let Some(game_object) = transform.game_object() else {
+                continue;
+            };
+            let game_object = game_object.read::<GameObject>()?;
+            let path = if parent_path.is_empty() { game_object.name.clone() } else { format!("{}/{}", parent_path, game_object.name) };
+            for component in game_object.components().filter(|x| x.class() == ClassID::MonoBehaviour) {
+                if let Some(behaviour) = component.read::<MonoBehaviour>()?.well_known_behaviour()? {
+                    result.push(UiElement { path: path.clone(), behaviour });
+                }
+            }
+            stack.extend(transform.children().map(|child| (child, path.clone())));
+        }
+        Ok(result)
+    }
+}
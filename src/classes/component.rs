@@ -11,8 +11,8 @@ pub struct Component<'a> {
 }
 
 impl<'a> FromObject<'a> for Component<'a> {
-    fn load(object: &'a Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
         Self::from_reader(object, &mut r)
     }
 
@@ -22,7 +22,7 @@ impl<'a> FromObject<'a> for Component<'a> {
 }
 
 impl<'a> Component<'a> {
-    pub(crate) fn from_reader(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub(crate) fn from_reader(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         Ok(Self { game_object: PPtr::load(object, r)? })
     }
 }
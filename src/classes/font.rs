@@ -0,0 +1,154 @@
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::math::RectF32;
+use crate::reader::Reader;
+use crate::version::UnityVersion;
+
+use super::material::Material;
+use super::pptr::PPtr;
+use super::texture2d::Texture2D;
+
+#[derive(Debug, Clone)]
+pub struct CharacterInfo {
+    pub index: i32,
+    pub uv: RectF32,
+    pub vert: RectF32,
+    pub advance: f32,
+    pub flipped: bool,
+}
+
+impl CharacterInfo {
+    fn load(version: UnityVersion, r: &mut Reader) -> UnityResult<Self> {
+        let index = r.read_i32()?;
+        let uv = r.read_rect_f32()?;
+        let vert = r.read_rect_f32()?;
+        let advance = r.read_f32()?;
+        let mut flipped = false;
+        if version.major >= 4 {
+            flipped = r.read_bool()?;
+            r.align(4)?;
+        }
+        Ok(Self { index, uv, vert, advance, flipped })
+    }
+}
+
+pub struct Font<'a> {
+    pub name: String,
+    pub line_spacing: f32,
+    pub font_size: f32,
+    pub ascii_start_offset: i32,
+    pub character_spacing: i32,
+    pub character_padding: i32,
+    pub convert_case: i32,
+    pub default_material: PPtr<'a, Material<'a>>,
+    pub texture: PPtr<'a, Texture2D>,
+    /// Glyph rects of bitmap fonts, in `texture`.
+    pub character_rects: Vec<CharacterInfo>,
+    pub kerning_values: Vec<((i16, i16), f32)>,
+    pub pixel_scale: f32,
+    pub font_data: Vec<u8>,
+}
+
+impl<'a> FromObject<'a> for Font<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        let name = r.read_aligned_string()?;
+        let (mut font_size, mut pixel_scale) = (0.0, 1.0);
+        let (mut character_spacing, mut character_padding) = (0, 0);
+        let line_spacing;
+        let ascii_start_offset;
+        let convert_case;
+        let default_material;
+        let texture;
+        let mut character_rects = Vec::new();
+        if version.is_at_least(5, 5) {
+            line_spacing = r.read_f32()?;
+            default_material = PPtr::load(object, &mut r)?;
+            font_size = r.read_f32()?;
+            texture = PPtr::load(object, &mut r)?;
+            ascii_start_offset = r.read_i32()?;
+            let _tracking = r.read_f32()?;
+            character_spacing = r.read_i32()?;
+            character_padding = r.read_i32()?;
+            convert_case = r.read_i32()?;
+            for _ in 0..r.read_i32()? {
+                character_rects.push(CharacterInfo::load(version, &mut r)?);
+            }
+        } else {
+            ascii_start_offset = r.read_i32()?;
+            if version.major <= 3 {
+                let _font_count_x = r.read_i32()?;
+                let _font_count_y = r.read_i32()?;
+            }
+            let _kerning = r.read_f32()?;
+            line_spacing = r.read_f32()?;
+            if version.major <= 3 {
+                for _ in 0..r.read_i32()? {
+                    let _first = r.read_i32()?;
+                    let _second = r.read_f32()?;
+                }
+            } else {
+                character_spacing = r.read_i32()?;
+                character_padding = r.read_i32()?;
+            }
+            convert_case = r.read_i32()?;
+            default_material = PPtr::load(object, &mut r)?;
+            for _ in 0..r.read_i32()? {
+                character_rects.push(CharacterInfo::load(version, &mut r)?);
+            }
+            texture = PPtr::load(object, &mut r)?;
+        }
+        let mut kerning_values = Vec::new();
+        for _ in 0..r.read_i32()? {
+            let first = r.read_i16()?;
+            let second = r.read_i16()?;
+            kerning_values.push(((first, second), r.read_f32()?));
+        }
+        if version.major <= 3 {
+            let _grid_font = r.read_bool()?;
+            r.align(4)?;
+        } else {
+            pixel_scale = r.read_f32()?;
+        }
+        let length = r.read_i32()?;
+        let font_data = if length > 0 { r.read_u8_list(length as usize)? } else { Vec::new() };
+        Ok(Self {
+            name,
+            line_spacing,
+            font_size,
+            ascii_start_offset,
+            character_spacing,
+            character_padding,
+            convert_case,
+            default_material,
+            texture,
+            character_rects,
+            kerning_values,
+            pixel_scale,
+            font_data,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::Font
+    }
+}
+
+impl Font<'_> {
+    /// The embedded TTF/OTF file, `None` for built-in and dynamic OS fonts that ship no data.
+    pub fn embedded_data(&self) -> Option<&[u8]> {
+        (!self.font_data.is_empty()).then_some(self.font_data.as_slice())
+    }
+
+    /// Sniffs the embedded data: `otf` for CFF fonts, `ttc` for collections, `ttf` otherwise.
+    pub fn font_file_extension(&self) -> Option<&'static str> {
+        let data = self.embedded_data()?;
+        Some(match data.get(..4) {
+            Some(b"OTTO") => "otf",
+            Some(b"ttcf") => "ttc",
+            _ => "ttf",
+        })
+    }
+}
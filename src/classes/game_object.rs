@@ -1,4 +1,4 @@
-use crate::classes::FromObject;
+use crate::classes::{ClassID, FromObject};
 use crate::env::Object;
 use crate::error::UnityResult;
 
@@ -7,27 +7,54 @@ use super::Component;
 
 pub struct GameObject<'a> {
     pub components: Vec<PPtr<'a, Component<'a>>>,
+    /// Class ids stored alongside each component before Unity 5.5.
+    pub component_classes: Vec<ClassID>,
+    pub layer: u32,
     pub name: String,
+    pub tag: u16,
+    pub is_active: bool,
 }
 
 impl<'a> FromObject<'a> for GameObject<'a> {
-    fn load(object: &'a Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
         let version = object.info.version;
         let count = r.read_i32()? as usize;
         let mut components = Vec::new();
+        let mut component_classes = Vec::new();
         for _ in 0..count {
-            if (version[0] == 5 && version[1] < 5) || version[0] < 5 {
-                r.read_i32()?;
+            if !version.is_at_least(5, 5) {
+                component_classes.push(ClassID::from(r.read_i32()?));
             }
             components.push(PPtr::load(object, &mut r)?);
         }
-        let _layer = r.read_i32()?;
+        let layer = r.read_u32()?;
         let name = r.read_aligned_string()?;
-        Ok(Self { components, name })
+        let tag = r.read_u16()?;
+        let is_active = r.read_bool()?;
+        Ok(Self {
+            components,
+            component_classes,
+            layer,
+            name,
+            tag,
+            is_active,
+        })
     }
 
     fn class() -> super::ClassID {
         super::ClassID::GameObject
     }
 }
+
+impl<'a> GameObject<'a> {
+    /// Resolves the component list, skipping pointers whose target is not loaded.
+    pub fn components(&self) -> impl Iterator<Item = Object<'a>> + '_ {
+        self.components.iter().filter_map(|x| x.get_obj_any())
+    }
+
+    /// The first component whose class matches `T`.
+    pub fn component<T: FromObject<'a>>(&self) -> Option<Object<'a>> {
+        self.components().find(|x| x.class() == T::class())
+    }
+}
@@ -0,0 +1,183 @@
+use image::{Rgba, RgbaImage};
+use serde::Deserialize;
+
+use crate::error::UnityResult;
+use crate::math::Color;
+use crate::object::ObjectInfo;
+use crate::reader::Reader;
+
+use super::math::RawColor;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientMode {
+    Blend,
+    Fixed,
+    PerceptualBlend,
+}
+
+impl From<i32> for GradientMode {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Fixed,
+            2 => Self::PerceptualBlend,
+            _ => Self::Blend,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GradientColorKey {
+    pub color: Color,
+    pub time: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GradientAlphaKey {
+    pub alpha: f32,
+    pub time: f32,
+}
+
+#[derive(Deserialize)]
+struct RawGradient {
+    key0: RawColor,
+    key1: RawColor,
+    key2: RawColor,
+    key3: RawColor,
+    key4: RawColor,
+    key5: RawColor,
+    key6: RawColor,
+    key7: RawColor,
+    ctime0: u16,
+    ctime1: u16,
+    ctime2: u16,
+    ctime3: u16,
+    ctime4: u16,
+    ctime5: u16,
+    ctime6: u16,
+    ctime7: u16,
+    atime0: u16,
+    atime1: u16,
+    atime2: u16,
+    atime3: u16,
+    atime4: u16,
+    atime5: u16,
+    atime6: u16,
+    atime7: u16,
+    #[serde(rename = "m_Mode")]
+    mode: Option<i32>,
+    #[serde(rename = "m_ColorSpace")]
+    color_space: Option<i32>,
+    #[serde(rename = "m_NumColorKeys")]
+    num_color_keys: u8,
+    #[serde(rename = "m_NumAlphaKeys")]
+    num_alpha_keys: u8,
+}
+
+impl From<RawGradient> for Gradient {
+    fn from(raw: RawGradient) -> Self {
+        let keys = [&raw.key0, &raw.key1, &raw.key2, &raw.key3, &raw.key4, &raw.key5, &raw.key6, &raw.key7].map(RawColor::to_color);
+        let color_times = [raw.ctime0, raw.ctime1, raw.ctime2, raw.ctime3, raw.ctime4, raw.ctime5, raw.ctime6, raw.ctime7];
+        let alpha_times = [raw.atime0, raw.atime1, raw.atime2, raw.atime3, raw.atime4, raw.atime5, raw.atime6, raw.atime7];
+        let mode = raw.mode.map_or(GradientMode::Blend, GradientMode::from);
+        Self::from_keys(&keys, &color_times, &alpha_times, raw.num_color_keys, raw.num_alpha_keys, mode, raw.color_space.unwrap_or(-1))
+    }
+}
+
+/// Unity stores up to 8 keys; rgb and alpha share the key slots but have separate times and counts.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "RawGradient")]
+pub struct Gradient {
+    pub color_keys: Vec<GradientColorKey>,
+    pub alpha_keys: Vec<GradientAlphaKey>,
+    pub mode: GradientMode,
+    pub color_space: i32,
+}
+
+impl Gradient {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        let mut keys = [Color::new(0.0, 0.0, 0.0, 0.0); 8];
+        for key in &mut keys {
+            *key = if version.is_at_least(5, 6) {
+                Color::from_array(r.read_f32_array::<4>()?)
+            } else {
+                Color::from_rgba32(r.read_u32()?)
+            };
+        }
+        let mut color_times = [0u16; 8];
+        for time in &mut color_times {
+            *time = r.read_u16()?;
+        }
+        let mut alpha_times = [0u16; 8];
+        for time in &mut alpha_times {
+            *time = r.read_u16()?;
+        }
+        let mut mode = GradientMode::Blend;
+        if version.is_at_least(5, 5) {
+            mode = GradientMode::from(r.read_i32()?);
+        }
+        let mut color_space = -1;
+        if version.is_at_least(2022, 2) {
+            color_space = r.read_i32()?;
+        }
+        let num_color_keys = r.read_u8()?;
+        let num_alpha_keys = r.read_u8()?;
+        r.align(4)?;
+        Ok(Self::from_keys(&keys, &color_times, &alpha_times, num_color_keys, num_alpha_keys, mode, color_space))
+    }
+
+    fn from_keys(keys: &[Color; 8], color_times: &[u16; 8], alpha_times: &[u16; 8], num_color_keys: u8, num_alpha_keys: u8, mode: GradientMode, color_space: i32) -> Self {
+        let color_keys = (0..(num_color_keys as usize).min(8))
+            .map(|i| GradientColorKey {
+                color: Color::new(keys[i].r, keys[i].g, keys[i].b, 1.0),
+                time: color_times[i] as f32 / 65535.0,
+            })
+            .collect();
+        let alpha_keys = (0..(num_alpha_keys as usize).min(8))
+            .map(|i| GradientAlphaKey {
+                alpha: keys[i].a,
+                time: alpha_times[i] as f32 / 65535.0,
+            })
+            .collect();
+        Self {
+            color_keys,
+            alpha_keys,
+            mode,
+            color_space,
+        }
+    }
+
+    /// Samples the gradient at `t` in `[0, 1]`, like `UnityEngine.Gradient.Evaluate`.
+    ///
+    /// Perceptual blending is approximated with a linear blend.
+    pub fn evaluate(&self, t: f32) -> Color {
+        let color = self.sample(&self.color_keys, t, |key| key.time, |key| key.color).unwrap_or(Color::new(1.0, 1.0, 1.0, 1.0));
+        let alpha = self.sample(&self.alpha_keys, t, |key| key.time, |key| Color::new(key.alpha, 0.0, 0.0, 0.0)).map(|x| x.r).unwrap_or(1.0);
+        Color::new(color.r, color.g, color.b, alpha)
+    }
+
+    fn sample<K>(&self, keys: &[K], t: f32, time: impl Fn(&K) -> f32, value: impl Fn(&K) -> Color) -> Option<Color> {
+        let Some(index) = keys.iter().position(|key| time(key) >= t) else {
+            return keys.last().map(value);
+        };
+        if index == 0 || self.mode == GradientMode::Fixed {
+            return Some(value(&keys[index]));
+        }
+        let (lhs, rhs) = (&keys[index - 1], &keys[index]);
+        let span = time(rhs) - time(lhs);
+        if span <= 0.0 {
+            return Some(value(rhs));
+        }
+        Some(value(lhs).lerp(&value(rhs), (t - time(lhs)) / span))
+    }
+
+    /// Renders the gradient into a `width` x 1 strip for previews.
+    pub fn to_image(&self, width: u32) -> RgbaImage {
+        let mut image = RgbaImage::new(width, 1);
+        for x in 0..width {
+            let t = if width > 1 { x as f32 / (width - 1) as f32 } else { 0.0 };
+            image.put_pixel(x, 0, Rgba(self.evaluate(t).to_rgba8()));
+        }
+        image
+    }
+}
@@ -377,3 +377,13 @@ pub enum ClassID {
     LocalizationAsset = 2083778819,
     ScriptedImporter = 2089858483,
 }
+
+impl ClassID {
+    /// The name of the class `class_id`, or `Class<id>` for a class the crate does not know.
+    pub(crate) fn name_of(class_id: i32) -> String {
+        match Self::from(class_id) {
+            ClassID::UnknownType => format!("Class{}", class_id),
+            class => format!("{:?}", class),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::math::{Color, Vector2};
+
+use super::camera::RawBitField;
+use super::game_object::GameObject;
+use super::math::{RawColor, RawVector2};
+use super::pptr::{PPtr, RawPPtr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightType {
+    Spot,
+    Directional,
+    Point,
+    Area,
+    Disc,
+    Unknown(i32),
+}
+
+impl From<i32> for LightType {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => LightType::Spot,
+            1 => LightType::Directional,
+            2 => LightType::Point,
+            3 => LightType::Area,
+            4 => LightType::Disc,
+            x => LightType::Unknown(x),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawShadowSettings {
+    #[serde(rename = "m_Type")]
+    type_: i32,
+    #[serde(rename = "m_Strength")]
+    strength: f32,
+}
+
+#[derive(Deserialize)]
+struct RawLight {
+    #[serde(rename = "m_GameObject")]
+    game_object: RawPPtr,
+    #[serde(rename = "m_Enabled")]
+    enabled: u8,
+    #[serde(rename = "m_Type")]
+    type_: i32,
+    #[serde(rename = "m_Shape")]
+    shape: Option<i32>,
+    #[serde(rename = "m_Color")]
+    color: RawColor,
+    #[serde(rename = "m_Intensity")]
+    intensity: f32,
+    #[serde(rename = "m_Range")]
+    range: f32,
+    #[serde(rename = "m_SpotAngle")]
+    spot_angle: f32,
+    #[serde(rename = "m_InnerSpotAngle")]
+    inner_spot_angle: Option<f32>,
+    #[serde(rename = "m_CookieSize")]
+    cookie_size: f32,
+    #[serde(rename = "m_Shadows")]
+    shadows: RawShadowSettings,
+    #[serde(rename = "m_Cookie")]
+    cookie: RawPPtr,
+    #[serde(rename = "m_RenderMode")]
+    render_mode: i32,
+    #[serde(rename = "m_CullingMask")]
+    culling_mask: RawBitField,
+    #[serde(rename = "m_AreaSize")]
+    area_size: Option<RawVector2>,
+    #[serde(rename = "m_BounceIntensity")]
+    bounce_intensity: Option<f32>,
+    #[serde(rename = "m_ColorTemperature")]
+    color_temperature: Option<f32>,
+    #[serde(rename = "m_UseColorTemperature")]
+    use_color_temperature: Option<bool>,
+}
+
+/// A Light component: its type and color, how far and in what cone it reaches, and the shadows
+/// and cookie it casts.
+///
+/// Files without type trees read through [`builtin_nodes`](crate::typetree::builtin_nodes) for
+/// Unity 2017 and 2018, which stops at the culling mask: the area size and color temperature are
+/// then `None`.
+pub struct Light<'a> {
+    pub game_object: PPtr<'a, GameObject<'a>>,
+    pub enabled: bool,
+    pub type_: LightType,
+    /// `LightShape` of spot lights, serialized since 2019.3.
+    pub shape: Option<i32>,
+    pub color: Color,
+    pub intensity: f32,
+    pub range: f32,
+    /// Outer cone angle in degrees.
+    pub spot_angle: f32,
+    /// Serialized since 2019.1.
+    pub inner_spot_angle: Option<f32>,
+    pub cookie_size: f32,
+    /// `LightShadows`: 0 none, 1 hard, 2 soft.
+    pub shadow_type: i32,
+    pub shadow_strength: f32,
+    pub cookie: PPtr<'a, Object<'a>>,
+    /// `LightRenderMode`: 0 auto, 1 important, 2 not important.
+    pub render_mode: i32,
+    pub culling_mask: u32,
+    pub area_size: Option<Vector2>,
+    pub bounce_intensity: Option<f32>,
+    /// Kelvin, only applied when `use_color_temperature` is set.
+    pub color_temperature: Option<f32>,
+    pub use_color_temperature: Option<bool>,
+}
+
+impl<'a> FromObject<'a> for Light<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let raw: RawLight = object.read_type_tree()?;
+        Ok(Self {
+            game_object: raw.game_object.bind(object),
+            enabled: raw.enabled != 0,
+            type_: LightType::from(raw.type_),
+            shape: raw.shape,
+            color: raw.color.to_color(),
+            intensity: raw.intensity,
+            range: raw.range,
+            spot_angle: raw.spot_angle,
+            inner_spot_angle: raw.inner_spot_angle,
+            cookie_size: raw.cookie_size,
+            shadow_type: raw.shadows.type_,
+            shadow_strength: raw.shadows.strength,
+            cookie: raw.cookie.bind(object),
+            render_mode: raw.render_mode,
+            culling_mask: raw.culling_mask.bits,
+            area_size: raw.area_size.as_ref().map(RawVector2::to_vector2),
+            bounce_intensity: raw.bounce_intensity,
+            color_temperature: raw.color_temperature,
+            use_color_temperature: raw.use_color_temperature,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::Light
+    }
+}
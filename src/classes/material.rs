@@ -17,38 +17,38 @@ pub struct Material<'a> {
 }
 
 impl<'a> FromObject<'a> for Material<'a> {
-    fn load(object: &'a Object<'a>) -> UnityResult<Self> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
         let version = object.info.version;
-        let r = &mut object.info.get_reader();
+        let r = &mut object.info.get_reader()?;
         let name = r.read_aligned_string()?;
         let shader = PPtr::load(object, r)?;
-        if version[0] == 4 && version[1] >= 1 {
+        if version.major == 4 && version.minor >= 1 {
             let _shader_keywords = r.read_string_list()?;
         }
-        if version[0] > 2021 || (version[0] == 2021 && version[1] >= 3) {
+        if version.is_at_least(2021, 3) {
             let _valid_keywords = r.read_string_list()?;
             let _invalid_keywords = r.read_string_list()?;
-        } else if version[0] >= 5 {
+        } else if version.major >= 5 {
             let _shader_keywords = r.read_aligned_string()?;
         }
-        if version[0] >= 5 {
+        if version.major >= 5 {
             let _lightmap_flags = r.read_u32()?;
         }
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 6) {
+        if version.is_at_least(5, 6) {
             let _enable_instancing_variants = r.read_bool()?;
             r.align(4)?;
         }
-        if version[0] > 4 || (version[0] == 4 && version[1] >= 3) {
+        if version.is_at_least(4, 3) {
             let _custom_render_queue = r.read_i32()?;
         }
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 1) {
+        if version.is_at_least(5, 1) {
             let string_tag_map_size = r.read_i32()?;
             for _ in 0..string_tag_map_size {
                 let _first = r.read_aligned_string()?;
                 let _second = r.read_aligned_string()?;
             }
         }
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 6) {
+        if version.is_at_least(5, 6) {
             let _disabled_shader_passes = r.read_string_list()?;
         }
         Ok(Self {
@@ -63,6 +63,44 @@ impl<'a> FromObject<'a> for Material<'a> {
     }
 }
 
+impl<'a> Material<'a> {
+    pub fn texture(&self, name: &str) -> Option<&PPtr<'a, Texture2D>> {
+        self.saved_properties.tex_envs.get(name).map(|x| &x.texture)
+    }
+
+    pub fn tex_env(&self, name: &str) -> Option<&UnityTexEnv<'a>> {
+        self.saved_properties.tex_envs.get(name)
+    }
+
+    pub fn int(&self, name: &str) -> Option<i32> {
+        self.saved_properties.ints.get(name).copied()
+    }
+
+    pub fn float(&self, name: &str) -> Option<f32> {
+        self.saved_properties.floats.get(name).copied()
+    }
+
+    pub fn color(&self, name: &str) -> Option<&Color> {
+        self.saved_properties.colors.get(name)
+    }
+
+    pub fn properties(&self) -> impl Iterator<Item = (&str, MaterialProperty<'_, 'a>)> {
+        let sheet = &self.saved_properties;
+        let tex_envs = sheet.tex_envs.iter().map(|(k, v)| (k.as_str(), MaterialProperty::Texture(v)));
+        let ints = sheet.ints.iter().map(|(k, v)| (k.as_str(), MaterialProperty::Int(*v)));
+        let floats = sheet.floats.iter().map(|(k, v)| (k.as_str(), MaterialProperty::Float(*v)));
+        let colors = sheet.colors.iter().map(|(k, v)| (k.as_str(), MaterialProperty::Color(v)));
+        tex_envs.chain(ints).chain(floats).chain(colors)
+    }
+}
+
+pub enum MaterialProperty<'p, 'a> {
+    Texture(&'p UnityTexEnv<'a>),
+    Int(i32),
+    Float(f32),
+    Color(&'p Color),
+}
+
 pub struct UnityPropertySheet<'a> {
     pub tex_envs: HashMap<String, UnityTexEnv<'a>>,
     pub ints: HashMap<String, i32>,
@@ -71,7 +109,7 @@ pub struct UnityPropertySheet<'a> {
 }
 
 impl<'a> UnityPropertySheet<'a> {
-    pub(super) fn load(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub(super) fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         let version = object.info.version;
         let tex_envs_size = r.read_i32()? as usize;
         let mut tex_envs = HashMap::with_capacity(tex_envs_size);
@@ -79,7 +117,7 @@ impl<'a> UnityPropertySheet<'a> {
             tex_envs.insert(r.read_aligned_string()?, UnityTexEnv::load(object, r)?);
         }
         let mut ints = HashMap::new();
-        if version[0] >= 2021 {
+        if version.major >= 2021 {
             let ints_size = r.read_i32()? as usize;
             ints = HashMap::with_capacity(ints_size);
             for _ in 0..ints_size {
@@ -87,12 +125,12 @@ impl<'a> UnityPropertySheet<'a> {
             }
         }
         let floats_size = r.read_i32()? as usize;
-        let mut floats = HashMap::with_capacity(tex_envs_size);
+        let mut floats = HashMap::with_capacity(floats_size);
         for _ in 0..floats_size {
             floats.insert(r.read_aligned_string()?, r.read_f32()?);
         }
         let colors_size = r.read_i32()? as usize;
-        let mut colors = HashMap::with_capacity(tex_envs_size);
+        let mut colors = HashMap::with_capacity(colors_size);
         for _ in 0..colors_size {
             colors.insert(r.read_aligned_string()?, Color::from_array(r.read_f32_array::<4>()?));
         }
@@ -107,7 +145,7 @@ pub struct UnityTexEnv<'a> {
 }
 
 impl<'a> UnityTexEnv<'a> {
-    pub(super) fn load(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub(super) fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         Ok(Self {
             texture: PPtr::load(object, r)?,
             scale: r.read_vector2()?,
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+use crate::math::{Color, RectF32, Vector2};
+
+/// A `Vector2f` type tree node.
+#[derive(Deserialize)]
+pub(super) struct RawVector2 {
+    x: f32,
+    y: f32,
+}
+
+impl RawVector2 {
+    pub(super) fn to_vector2(&self) -> Vector2 {
+        Vector2 { x: self.x, y: self.y }
+    }
+}
+
+/// A `Rectf` type tree node.
+#[derive(Deserialize)]
+pub(super) struct RawRect {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl RawRect {
+    pub(super) fn to_rect(&self) -> RectF32 {
+        RectF32 {
+            x: self.x,
+            y: self.y,
+            w: self.width,
+            h: self.height,
+        }
+    }
+}
+
+/// A `ColorRGBA` type tree node: float channels since 5.6, a packed `rgba` before.
+#[derive(Deserialize)]
+pub(super) struct RawColor {
+    r: Option<f32>,
+    g: Option<f32>,
+    b: Option<f32>,
+    a: Option<f32>,
+    rgba: Option<u32>,
+}
+
+impl RawColor {
+    pub(super) fn to_color(&self) -> Color {
+        match self.rgba {
+            Some(rgba) => Color::from_rgba32(rgba),
+            None => Color::new(self.r.unwrap_or_default(), self.g.unwrap_or_default(), self.b.unwrap_or_default(), self.a.unwrap_or_default()),
+        }
+    }
+}
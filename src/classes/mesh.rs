@@ -1,11 +1,12 @@
 #![allow(non_upper_case_globals)]
-use super::animation_clip::{AnimationClip, PackedFloatVector, PackedIntVector};
+use super::animation_clip::{Aabb, PackedFloatVector, PackedIntVector};
 use super::texture2d::StreamingInfo;
 use super::FromObject;
 use crate::error::{UnityError, UnityResult};
 use crate::math::{Matrix4x4, Vector3};
 use crate::object::ObjectInfo;
-use crate::reader::{ByteOrder, Reader};
+use crate::reader::{f16_to_f32, snorm16_to_f32, snorm8_to_f32, unorm16_to_f32, unorm8_to_f32, ByteOrder, Reader};
+use crate::version::UnityVersion;
 use crate::Object;
 use num_enum::TryFromPrimitive;
 
@@ -51,22 +52,27 @@ pub struct Mesh {
 }
 
 impl Mesh {
+    pub fn blend_shapes(&self) -> Vec<BlendShape> {
+        self.shapes.as_ref().map(BlendShapeData::blend_shapes).unwrap_or_default()
+    }
+
     fn process_data(&mut self, object: &Object) -> UnityResult<()> {
         let version = object.info.version;
         if let (Some(stream), Some(vertex_data)) = (&self.stream_data, self.vertex_data.as_mut()) {
             if !stream.path.is_empty() && vertex_data.vertex_count > 0 {
                 let name = stream.path.split('/').next_back().ok_or(UnityError::InvalidValue)?;
                 if let Some(buf) = object.env.get_loaded_file(name) {
-                    let mut r = Reader::new(buf.as_slice(), ByteOrder::Big);
-                    r.set_offset(stream.offset as usize)?;
-                    vertex_data.data_size = r.read_u8_list(stream.size as usize)?;
+                    let offset = usize::try_from(stream.offset).map_err(|_| UnityError::Eof)?;
+                    let size = usize::try_from(stream.size).map_err(|_| UnityError::Eof)?;
+                    let end = offset.checked_add(size).ok_or(UnityError::Eof)?;
+                    vertex_data.data_size = buf.read(offset..end)?.to_vec();
                 }
             }
         }
-        if version[0] > 3 || (version[0] == 3 && version[1] >= 5) {
+        if version.is_at_least(3, 5) {
             self.read_vertex_data(object)?;
         }
-        if version[0] > 2 || (version[0] == 2 && version[1] >= 6) {
+        if version.is_at_least(2, 6) {
             self.decompress_compressed_mesh(object)?;
         }
         self.get_triangles(object)?;
@@ -90,7 +96,7 @@ impl Mesh {
             if (stream.channel_mask >> chn) & 0x1 == 0 {
                 continue;
             }
-            if version[0] < 2018 && chn == 2 && channel.format == 2 {
+            if version.major < 2018 && chn == 2 && channel.format == 2 {
                 channel.dimension = 4;
             }
             let vertex_format = VertexFormat::load(channel.format, version)?;
@@ -115,7 +121,7 @@ impl Mesh {
             } else {
                 components_f32_array = bytes_to_f32_vec(&component_bytes, vertex_format);
             }
-            if version[0] >= 2018 {
+            if version.major >= 2018 {
                 match chn {
                     0 => self.vertices = components_f32_array,
                     1 => self.normals = components_f32_array,
@@ -173,7 +179,7 @@ impl Mesh {
                     3 => self.uv0 = components_f32_array,
                     4 => self.uv1 = components_f32_array,
                     5 => {
-                        if version[0] >= 5 {
+                        if version.major >= 5 {
                             self.uv2 = components_f32_array;
                         } else {
                             self.tangents = components_f32_array;
@@ -236,7 +242,7 @@ impl Mesh {
                 }
             }
         }
-        if version[0] < 5 {
+        if version.major < 5 {
             if let Some(bind_poses) = &compressed_mesh.bind_poses {
                 let size = bind_poses.num_items as usize / 16;
                 self.bind_pose = Vec::with_capacity(size);
@@ -300,7 +306,7 @@ impl Mesh {
                 self.tangents.extend([x, y, z, w])
             }
         }
-        if version[0] >= 5 {
+        if version.major >= 5 {
             if let Some(float_colors) = &compressed_mesh.float_colors {
                 if float_colors.num_items > 0 {
                     self.colors = float_colors.unpack_floats(1, 4, 0, None)
@@ -349,7 +355,7 @@ impl Mesh {
                 colors.num_items *= 4;
                 colors.bit_size /= 4;
                 let temp_colors = colors.unpack_ints();
-                self.colors = temp_colors.into_iter().map(|x| x as f32 / 255.0).collect()
+                self.colors = temp_colors.into_iter().map(|x| unorm8_to_f32(x as u8)).collect()
             }
         }
         Ok(())
@@ -367,7 +373,7 @@ impl Mesh {
             if topology == GfxPrimitiveType::Triangles {
                 let sub = self.index_buffer.get(first_index..(first_index + index_count - index_count % 3)).ok_or(UnityError::Eof)?;
                 self.indices.extend_from_slice(sub)
-            } else if version[0] < 4 || topology == GfxPrimitiveType::TriangleStrip {
+            } else if version.major < 4 || topology == GfxPrimitiveType::TriangleStrip {
                 let mut tri_index = 0;
                 let mut iter = self.index_buffer.get(first_index..).ok_or(UnityError::Eof)?.windows(3).enumerate();
                 while let Some((i, &[a, b, c])) = iter.next() {
@@ -400,9 +406,9 @@ impl Mesh {
 }
 
 impl<'a> FromObject<'a> for Mesh {
-    fn load(object: &'a crate::Object<'a>) -> UnityResult<Self> {
+    fn load(object: &crate::Object<'a>) -> UnityResult<Self> {
         let version = object.info.version;
-        let mut r = object.info.get_reader();
+        let mut r = object.info.get_reader()?;
         let name = r.read_aligned_string()?;
         let mut ret = Mesh {
             name,
@@ -431,8 +437,8 @@ impl<'a> FromObject<'a> for Mesh {
             stream_data: None,
             indices: Vec::new(),
         };
-        ret.use_16_bit_indices = if version[0] < 3 || (version[0] == 3 && version[1] < 5) { r.read_i32()? > 0 } else { false };
-        if version[0] == 2 && version[1] <= 5 {
+        ret.use_16_bit_indices = if !version.is_at_least(3, 5) { r.read_i32()? > 0 } else { false };
+        if version.major == 2 && version.minor <= 5 {
             let index_buffer_size = r.read_i32()?;
             if ret.use_16_bit_indices {
                 let index_buffer_size = index_buffer_size as usize / 2;
@@ -449,10 +455,10 @@ impl<'a> FromObject<'a> for Mesh {
         for _ in 0..sub_meshes_size {
             ret.sub_meshes.push(SubMesh::load(object.info, &mut r)?)
         }
-        if version[0] > 4 || (version[0] == 4 && version[1] >= 1) {
+        if version.is_at_least(4, 1) {
             ret.shapes = Some(BlendShapeData::load(object.info, &mut r)?)
         };
-        if version[0] > 4 || (version[0] == 4 && version[1] >= 3) {
+        if version.is_at_least(4, 3) {
             let size = r.read_i32()?;
             ret.bind_pose = r.read_matrix4x4_list(size as usize)?;
             let size = r.read_i32()?;
@@ -460,8 +466,8 @@ impl<'a> FromObject<'a> for Mesh {
             let _root_bone_name_hash = r.read_u32()?;
         }
 
-        if version[0] > 2 || (version[0] == 2 && version[1] >= 6) {
-            if version[0] >= 2019 {
+        if version.is_at_least(2, 6) {
+            if version.major >= 2019 {
                 let _bones_aabb_size = r.read_i32()?;
                 let mut _bones_aabb = Vec::new();
                 for _ in 0.._bones_aabb_size {
@@ -470,8 +476,8 @@ impl<'a> FromObject<'a> for Mesh {
                 let _variable_bone_count_weights = r.read_u32()?;
             }
             let mesh_compression = r.read_u8()?;
-            if version[0] >= 4 {
-                if version[0] < 5 {
+            if version.major >= 4 {
+                if version.major < 5 {
                     let _stream_compression = r.read_u8()?;
                 }
                 let _is_readable = r.read_bool()?;
@@ -479,9 +485,9 @@ impl<'a> FromObject<'a> for Mesh {
                 let _keep_indices = r.read_bool()?;
             }
             r.align(4)?;
-            if (version[0] > 2017 || (version[0] == 2017 && version[1] >= 4)) || //2017.4
-            ((version[0] == 2017 && version[1] == 3 && version[2] == 1) && object.info.build_type.is_patch()) || //fixed after 2017.3.1px
-            ((version[0] == 2017 && version[1] == 3) && mesh_compression == 0)
+            if (version.is_at_least(2017, 4)) || //2017.4
+            ((version.major == 2017 && version.minor == 3 && version.patch == 1) && object.info.build_type.is_patch()) || //fixed after 2017.3.1px
+            ((version.major == 2017 && version.minor == 3) && mesh_compression == 0)
             {
                 let index_format = r.read_i32()?;
                 ret.use_16_bit_indices = index_format == 0;
@@ -498,7 +504,7 @@ impl<'a> FromObject<'a> for Mesh {
                 ret.index_buffer = r.read_u32_list(index_buffer_size as usize / 4)?;
             }
         }
-        if version[0] < 3 || (version[0] == 3 && version[1] < 5) {
+        if !version.is_at_least(3, 5) {
             ret.vertex_count = r.read_i32()? as usize;
             ret.vertices = r.read_f32_list(ret.vertex_count)?;
             let size = r.read_i32()?;
@@ -513,7 +519,7 @@ impl<'a> FromObject<'a> for Mesh {
             ret.uv0 = r.read_f32_list(size * 2)?;
             let size = r.read_i32()? as usize;
             ret.uv1 = r.read_f32_list(size * 2)?;
-            if version[0] == 2 && version[1] <= 5 {
+            if version.major == 2 && version.minor <= 5 {
                 let tangent_space_size = r.read_i32()? as usize;
                 ret.normals = Vec::with_capacity(tangent_space_size * 3);
                 ret.tangents = Vec::with_capacity(tangent_space_size * 4);
@@ -532,7 +538,7 @@ impl<'a> FromObject<'a> for Mesh {
                 ret.normals = r.read_f32_list(size * 3)?;
             }
         } else {
-            if version[0] < 2018 || (version[0] == 2018 && version[1] < 2) {
+            if !version.is_at_least(2018, 2) {
                 let size = r.read_i32()?;
                 let mut skin = Vec::with_capacity(size as usize);
                 for _ in 0..size {
@@ -540,22 +546,22 @@ impl<'a> FromObject<'a> for Mesh {
                 }
                 ret.skin = Some(skin);
             }
-            if version[0] == 3 || (version[0] == 4 && version[1] <= 2) {
+            if version.is_at_least(3, 0) && !version.is_at_least(4, 3) {
                 let size = r.read_i32()?;
                 ret.bind_pose = r.read_matrix4x4_list(size as usize)?;
             }
             ret.vertex_data = Some(VertexData::load(object.info, &mut r)?);
         }
-        if version[0] > 2 || (version[0] == 2 && version[1] >= 6) {
+        if version.is_at_least(2, 6) {
             ret.compressed_mesh = Some(CompressedMesh::load(object.info, &mut r)?);
         }
         let offset = r.get_offset() + 24;
         r.set_offset(offset)?;
-        if version[0] < 3 || (version[0] == 3 && version[1] <= 4) {
+        if !version.is_at_least(3, 5) {
             let color_size = r.read_i32()? as usize;
             ret.colors = Vec::with_capacity(color_size * 4);
             for _ in 0..(color_size * 4) {
-                ret.colors.push(r.read_u8()? as f32 / 255.0)
+                ret.colors.push(r.read_unorm8()?)
             }
             let collision_triangles_size = r.read_i32()? as usize;
             let offset = r.get_offset() + collision_triangles_size * 4;
@@ -563,10 +569,10 @@ impl<'a> FromObject<'a> for Mesh {
             let _collision_vertex_count = r.read_i32()?;
         }
         let _mesh_usage_flags = r.read_i32()?;
-        if version[0] > 2022 || (version[0] == 2022 && version[1] >= 1) {
+        if version.is_at_least(2022, 1) {
             let _cooking_options = r.read_i32()?;
         }
-        if version[0] >= 5 {
+        if version.major >= 5 {
             let size = r.read_i32()? as usize;
             let _baked_convex_collision_mesh = r.read_u8_list(size)?;
             r.align(4)?;
@@ -574,10 +580,10 @@ impl<'a> FromObject<'a> for Mesh {
             let _baked_triangle_collision_mesh = r.read_u8_list(size)?;
             r.align(4)?;
         }
-        if version[0] > 2018 || (version[0] == 2018 && version[1] >= 2) {
+        if version.is_at_least(2018, 2) {
             let _mesh_metrics = r.read_f32_array::<2>()?;
         }
-        if version[0] > 2018 || (version[0] == 2018 && version[1] >= 3) {
+        if version.is_at_least(2018, 3) {
             r.align(4)?;
             ret.stream_data = Some(StreamingInfo::load(object.info, &mut r)?);
         }
@@ -600,7 +606,7 @@ pub struct BlendShapeData {
 
 impl BlendShapeData {
     pub(super) fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
-        if object.version[0] > 4 || (object.version[0] == 4 && object.version[1] >= 3) {
+        if object.version.is_at_least(4, 3) {
             let num_verts = r.read_i32()?;
             let mut vertices = Vec::with_capacity(num_verts as usize);
             for _ in 0..num_verts {
@@ -641,7 +647,7 @@ impl BlendShapeData {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct BlendShapeVertex {
     pub vertex: Vector3,
     pub normal: Vector3,
@@ -662,6 +668,7 @@ impl BlendShapeVertex {
 
 #[derive(Debug)]
 pub struct MeshBlendShape {
+    pub name: String,
     pub first_vertex: u32,
     pub vertex_count: u32,
     pub has_normals: bool,
@@ -670,21 +677,23 @@ pub struct MeshBlendShape {
 
 impl MeshBlendShape {
     pub(super) fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
-        if object.version[0] == 4 && object.version[1] < 3 {
-            let _name = r.read_aligned_string()?;
+        let mut name = String::new();
+        if object.version.major == 4 && object.version.minor < 3 {
+            name = r.read_aligned_string()?;
         }
         let first_vertex = r.read_u32()?;
         let vertex_count = r.read_u32()?;
-        if object.version[0] == 4 && object.version[1] < 3 {
+        if object.version.major == 4 && object.version.minor < 3 {
             let _aabb_min_delta = r.read_vector3()?;
             let _aabb_max_delta = r.read_vector3()?;
         }
         let has_normals = r.read_bool()?;
         let has_tangent = r.read_bool()?;
-        if object.version[0] > 4 || (object.version[0] == 4 && object.version[1] >= 3) {
+        if object.version.is_at_least(4, 3) {
             r.align(4)?;
         }
         Ok(Self {
+            name,
             first_vertex,
             vertex_count,
             has_normals,
@@ -712,6 +721,67 @@ impl MeshBlendShapeChannel {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct BlendShapeFrame {
+    pub weight: f32,
+    pub has_normals: bool,
+    pub has_tangents: bool,
+    pub deltas: Vec<BlendShapeVertex>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlendShape {
+    pub name: String,
+    pub name_hash: u32,
+    pub frames: Vec<BlendShapeFrame>,
+}
+
+impl BlendShapeData {
+    fn frame(&self, shape: &MeshBlendShape, weight: f32) -> BlendShapeFrame {
+        let start = (shape.first_vertex as usize).min(self.vertices.len());
+        let end = (start + shape.vertex_count as usize).min(self.vertices.len());
+        BlendShapeFrame {
+            weight,
+            has_normals: shape.has_normals,
+            has_tangents: shape.has_tangent,
+            deltas: self.vertices[start..end].to_vec(),
+        }
+    }
+
+    pub fn blend_shapes(&self) -> Vec<BlendShape> {
+        if self.channels.is_empty() {
+            // before 4.3 every shape is a standalone channel with a single full-weight frame
+            return self
+                .shapes
+                .iter()
+                .map(|shape| BlendShape {
+                    name: shape.name.clone(),
+                    name_hash: 0,
+                    frames: vec![self.frame(shape, 100.0)],
+                })
+                .collect();
+        }
+        self.channels
+            .iter()
+            .map(|channel| {
+                let first = channel.frame_index.max(0) as usize;
+                let frames = (first..first + channel.frame_count.max(0) as usize)
+                    .filter_map(|i| {
+                        let shape = self.shapes.get(i)?;
+                        let weight = self.full_weights.get(i).copied().unwrap_or(100.0);
+                        Some(self.frame(shape, weight))
+                    })
+                    .collect();
+                BlendShape {
+                    name: channel.name.clone(),
+                    name_hash: channel.name_hash,
+                    frames,
+                }
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug)]
 pub struct CompressedMesh {
     pub vertices: PackedFloatVector,
@@ -734,19 +804,19 @@ impl CompressedMesh {
         let version = object.version;
         let vertices = PackedFloatVector::load(object, r)?;
         let uv = PackedFloatVector::load(object, r)?;
-        let bind_poses = if version[0] < 5 { Some(PackedFloatVector::load(object, r)?) } else { None };
+        let bind_poses = if version.major < 5 { Some(PackedFloatVector::load(object, r)?) } else { None };
         let normals = PackedFloatVector::load(object, r)?;
         let tangents = PackedFloatVector::load(object, r)?;
         let weights = PackedIntVector::load(object, r)?;
         let normal_signs = PackedIntVector::load(object, r)?;
         let tangent_signs = PackedIntVector::load(object, r)?;
-        let float_colors = if version[0] < 5 { None } else { Some(PackedFloatVector::load(object, r)?) };
+        let float_colors = if version.major < 5 { None } else { Some(PackedFloatVector::load(object, r)?) };
         let bone_indices = PackedIntVector::load(object, r)?;
         let triangles = PackedIntVector::load(object, r)?;
         let mut colors = None;
         let mut uv_info = 0;
-        if version[0] > 3 || (version[0] == 3 && version[1] >= 5) {
-            if version[0] < 5 {
+        if version.is_at_least(3, 5) {
+            if version.major < 5 {
                 colors = Some(PackedIntVector::load(object, r)?);
             } else {
                 uv_info = r.read_u32()?;
@@ -779,7 +849,7 @@ pub struct SubMesh {
     pub base_vertex: u32,
     pub first_vertex: u32,
     pub vertex_count: u32,
-    pub local_aabb: Option<AnimationClip>,
+    pub local_aabb: Option<Aabb>,
 }
 
 impl SubMesh {
@@ -789,18 +859,18 @@ impl SubMesh {
         result.first_bytes = r.read_u32()?;
         result.index_count = r.read_u32()?;
         result.topology = r.read_i32()?.try_into().or(Err(UnityError::InvalidValue))?;
-        if version[0] < 4 {
+        if version.major < 4 {
             result.triangle_count = r.read_u32()?;
         }
 
-        if version[0] > 2017 || (version[0] == 2017 && version[1] >= 3) {
+        if version.is_at_least(2017, 3) {
             result.base_vertex = r.read_u32()?;
         }
 
-        if version[0] >= 3 {
+        if version.major >= 3 {
             result.first_vertex = r.read_u32()?;
             result.vertex_count = r.read_u32()?;
-            result.local_aabb = Some(AnimationClip::load(r)?);
+            result.local_aabb = Some(Aabb::load(r)?);
         }
         Ok(result)
     }
@@ -844,7 +914,7 @@ impl StreamInfo {
             ..Self::default()
         };
 
-        if version[0] < 4 {
+        if version.major < 4 {
             result.stride = r.read_u32()? as u8;
             result.align = r.read_u32()? as u8;
         } else {
@@ -868,20 +938,20 @@ impl VertexData {
     pub(super) fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
         let version = object.version;
         let mut result = Self::default();
-        if version[0] < 2018 {
+        if version.major < 2018 {
             result.current_channels = r.read_u32()? as u8;
         }
 
         result.vertex_count = r.read_u32()? as usize;
 
-        if version[0] >= 4 {
+        if version.major >= 4 {
             let size = r.read_i32()?;
             for _ in 0..size {
                 result.channels.push(ChannelInfo::load(object, r)?)
             }
         }
-        if version[0] < 5 {
-            if version[0] < 4 {
+        if version.major < 5 {
+            if version.major < 4 {
                 result.streams = Vec::with_capacity(4);
             } else {
                 result.streams = Vec::with_capacity(r.read_i32()? as usize);
@@ -889,7 +959,7 @@ impl VertexData {
             for _ in 0..result.streams.capacity() {
                 result.streams.push(StreamInfo::load(object, r)?)
             }
-            if version[0] < 4 {
+            if version.major < 4 {
                 result.get_channels(version)?;
             }
         } else {
@@ -900,7 +970,7 @@ impl VertexData {
         Ok(result)
     }
 
-    fn get_channels(&mut self, _version: [i32; 4]) -> UnityResult<()> {
+    fn get_channels(&mut self, _version: UnityVersion) -> UnityResult<()> {
         self.channels = Vec::with_capacity(6);
         for _ in 0..6 {
             self.channels.push(ChannelInfo::default())
@@ -945,7 +1015,7 @@ impl VertexData {
         Ok(())
     }
 
-    fn get_streams(&mut self, version: [i32; 4]) -> UnityResult<()> {
+    fn get_streams(&mut self, version: UnityVersion) -> UnityResult<()> {
         let stream_count = {
             let mut max = 0;
             for i in &self.channels {
@@ -1027,8 +1097,8 @@ pub enum VertexFormat2017 {
     SInt32,
 }
 impl VertexFormat {
-    fn load(format: u8, version: [i32; 4]) -> UnityResult<Self> {
-        if version[0] < 2017 {
+    fn load(format: u8, version: UnityVersion) -> UnityResult<Self> {
+        if version.major < 2017 {
             let result = match VertexChannelFormat::try_from(format).or(Err(UnityError::InvalidValue))? {
                 VertexChannelFormat::Float => VertexFormat::Float,
                 VertexChannelFormat::Float16 => VertexFormat::Float16,
@@ -1038,7 +1108,7 @@ impl VertexFormat {
             };
             return Ok(result);
         }
-        if version[0] < 2019 {
+        if version.major < 2019 {
             let result = match VertexFormat2017::try_from(format).or(Err(UnityError::InvalidValue))? {
                 VertexFormat2017::Float => VertexFormat::Float,
                 VertexFormat2017::Float16 => VertexFormat::Float16,
@@ -1132,18 +1202,18 @@ fn bytes_to_f32_vec(data: &[u8], format: VertexFormat) -> Vec<f32> {
             data.chunks_exact(2)
                 .map(|x| {
                     buf.copy_from_slice(x);
-                    half::f16::from_le_bytes(buf).to_f32()
+                    f16_to_f32(u16::from_le_bytes(buf))
                 })
                 .collect()
         }
-        VertexFormat::UNorm8 => data.iter().copied().map(|x| x as f32 / 255.0).collect(),
-        VertexFormat::SNorm8 => data.iter().copied().map(|x| (x as i8 as f32 / 127.0).max(-1.0)).collect(),
+        VertexFormat::UNorm8 => data.iter().copied().map(unorm8_to_f32).collect(),
+        VertexFormat::SNorm8 => data.iter().map(|x| snorm8_to_f32(*x as i8)).collect(),
         VertexFormat::UNorm16 => {
             let mut buf = [0u8; 2];
             data.chunks_exact(2)
                 .map(|x| {
                     buf.copy_from_slice(x);
-                    u16::from_le_bytes(buf) as f32 / 65535.0
+                    unorm16_to_f32(u16::from_le_bytes(buf))
                 })
                 .collect()
         }
@@ -1152,7 +1222,7 @@ fn bytes_to_f32_vec(data: &[u8], format: VertexFormat) -> Vec<f32> {
             data.chunks_exact(2)
                 .map(|x| {
                     buf.copy_from_slice(x);
-                    (i16::from_le_bytes(buf) as f32 / 32767.0).max(-1.0)
+                    snorm16_to_f32(i16::from_le_bytes(buf))
                 })
                 .collect()
         }
@@ -0,0 +1,33 @@
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+use super::game_object::GameObject;
+use super::mesh::Mesh;
+use super::pptr::PPtr;
+use super::Component;
+
+pub struct MeshFilter<'a> {
+    pub game_object: PPtr<'a, GameObject<'a>>,
+    pub mesh: PPtr<'a, Mesh>,
+}
+
+impl<'a> FromObject<'a> for MeshFilter<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
+        let game_object = Component::from_reader(object, &mut r)?.game_object;
+        let mesh = PPtr::load(object, &mut r)?;
+        Ok(Self { game_object, mesh })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::MeshFilter
+    }
+}
+
+impl MeshFilter<'_> {
+    /// Resolves `m_Mesh`; `None` when the filter has no mesh or it is not loaded.
+    pub fn load_mesh(&self) -> UnityResult<Option<Mesh>> {
+        self.mesh.get_obj().map(|x| x.read()).transpose()
+    }
+}
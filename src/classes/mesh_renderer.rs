@@ -1,20 +1,29 @@
+use std::ops::Deref;
+
 use crate::UnityResult;
 
-use super::{pptr::PPtr, renderer::SubMeshInfo, FromObject, GameObject, Material, Renderer};
+use super::{FromObject, Renderer};
 
+/// A renderer drawing the mesh of its GameObject's MeshFilter. The serialized fields are all the
+/// [`Renderer`]'s, which it derefs to.
 pub struct MeshRenderer<'a> {
-    pub game_object: PPtr<'a, GameObject<'a>>,
-    pub materials: Vec<PPtr<'a, Material<'a>>>,
-    pub sub_mesh_info: Option<SubMeshInfo>,
+    pub renderer: Renderer<'a>,
 }
 
 impl<'a> FromObject<'a> for MeshRenderer<'a> {
-    fn load(object: &'a crate::Object<'a>) -> UnityResult<Self> {
-        let Renderer { game_object, materials, sub_mesh_info } = Renderer::load(object)?;
-        Ok(Self { game_object, materials, sub_mesh_info })
+    fn load(object: &crate::Object<'a>) -> UnityResult<Self> {
+        Ok(Self { renderer: Renderer::load(object)? })
     }
 
     fn class() -> super::ClassID {
         super::ClassID::MeshRenderer
     }
 }
+
+impl<'a> Deref for MeshRenderer<'a> {
+    type Target = Renderer<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.renderer
+    }
+}
@@ -1,44 +1,95 @@
 mod animation_clip;
+mod animation_curve;
+mod animator_controller;
+mod asset_bundle;
+mod asset_bundle_manifest;
 mod audio_clip;
+mod avatar;
+mod build_settings;
+mod camera;
+mod canvas;
 mod component;
+mod font;
 mod game_object;
+mod gradient;
 mod id;
+mod light;
 mod material;
+mod math;
 mod mesh;
+mod mesh_filter;
 mod mesh_renderer;
+mod min_max_curve;
 mod mono_behaviour;
 mod mono_script;
+mod named_object;
+mod particle_system;
+mod player_settings;
 mod pptr;
+mod rect_transform;
 mod renderer;
+mod resource_manager;
 mod shader;
 mod sprite;
 mod sprite_atlas;
+mod terrain_data;
 mod text_asset;
 mod texture2d;
 mod transform;
+mod ui;
+mod video_clip;
 
 use crate::error::UnityResult;
 pub use id::ClassID;
 
 use crate::env::Object;
+pub use animation_clip::{Aabb, AnimationClip, ClipCurve, FloatCurve, PPtrCurve, PPtrKeyframe, QuaternionCurve, Vector3Curve};
+pub use animation_curve::{AnimationCurve, Keyframe, KeyframeValue, WrapMode};
+pub use animator_controller::{AnimatorController, AnimatorLayer, AnimatorState, StateMotion};
+pub use asset_bundle::{AssetBundle, AssetInfo};
+pub use asset_bundle_manifest::{AssetBundleInfo, AssetBundleManifest};
 pub use audio_clip::AudioClip;
+pub use avatar::Avatar;
+pub use build_settings::BuildSettings;
+pub use camera::{Camera, PhysicalCamera};
+pub use canvas::{Canvas, CanvasRenderer, UiElement};
 pub use component::Component;
+pub use font::{CharacterInfo, Font};
 pub use game_object::GameObject;
-pub use material::Material;
-pub use mesh::Mesh;
+pub use gradient::{Gradient, GradientAlphaKey, GradientColorKey, GradientMode};
+pub use light::{Light, LightType};
+pub use material::{Material, MaterialProperty, UnityPropertySheet, UnityTexEnv};
+pub use mesh::{BlendShape, BlendShapeFrame, BlendShapeVertex, Mesh};
+pub use mesh_filter::MeshFilter;
 pub use mesh_renderer::MeshRenderer;
+pub use min_max_curve::{MinMaxCurve, MinMaxGradient};
 pub use mono_behaviour::MonoBehaviour;
-pub use mono_script::MonoScript;
+pub use mono_script::{MonoScript, PropertiesHash};
+pub use named_object::NamedObject;
+pub use particle_system::{Burst, ColorModule, EmissionModule, InitialModule, ParticleSystem, ShapeModule, SizeModule, UVModule};
+pub use player_settings::PlayerSettings;
+pub use pptr::{PPtr, RawPPtr};
+pub use rect_transform::RectTransform;
 pub use renderer::Renderer;
-pub use sprite::Sprite;
+pub use resource_manager::{ResourceManager, ResourceManagerDependency};
+pub use shader::Shader;
+pub use sprite::{Sprite, SpriteMeshType, SpritePackingMode, SpritePackingRotation, SpriteSettings};
+pub use sprite_atlas::{SpriteAtlas, SpriteAtlasData};
+pub use terrain_data::{SplatPrototype, TerrainData};
 pub use text_asset::TextAsset;
 pub use texture2d::Texture2D;
 pub use transform::Transform;
+pub use ui::{UiImage, UiRawImage, UiText, WellKnownBehaviour};
+pub use video_clip::{StreamedResource, VideoClip};
 
+/// A class that can be read from an [`Object`] of an [`Env`](crate::Env).
 pub trait FromObject<'a>
 where
     Self: Sized,
 {
-    fn load(object: &'a Object<'a>) -> UnityResult<Self>;
+    /// Reads the class from `object`. The object is only borrowed for the call, while what is read
+    /// may keep borrowing the environment for `'a`; that lets [`PPtr::deref`] and hierarchy walks
+    /// return classes read from objects they look up themselves.
+    fn load(object: &Object<'a>) -> UnityResult<Self>;
     fn class() -> ClassID;
 }
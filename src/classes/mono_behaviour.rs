@@ -1,6 +1,10 @@
+use serde::de::DeserializeOwned;
+
+use crate::asset::SerializedFile;
 use crate::classes::FromObject;
-use crate::env::Object;
-use crate::error::UnityResult;
+use crate::env::{Env, Object};
+use crate::error::{UnityError, UnityResult};
+use crate::object::{ObjectInfo, ReadTypeTreeError};
 
 use super::component::Component;
 use super::game_object::GameObject;
@@ -12,20 +16,62 @@ pub struct MonoBehaviour<'a> {
     pub enable: bool,
     pub script: PPtr<'a, MonoScript>,
     pub name: String,
+    env: &'a Env,
+    asset: &'a SerializedFile,
+    info: &'a ObjectInfo,
+    fields_offset: usize,
 }
 
 impl<'a> FromObject<'a> for MonoBehaviour<'a> {
-    fn load(object: &'a Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
         let game_object = Component::from_reader(object, &mut r)?.game_object;
         let enable = r.read_bool()?;
         r.align(4)?;
         let script = PPtr::load(object, &mut r)?;
         let name = r.read_aligned_string()?;
-        Ok(Self { game_object, enable, script, name })
+        Ok(Self {
+            game_object,
+            enable,
+            script,
+            name,
+            env: object.env,
+            asset: object.asset,
+            info: object.info,
+            fields_offset: r.get_offset(),
+        })
     }
 
     fn class() -> super::ClassID {
         super::ClassID::MonoBehaviour
     }
 }
+
+impl<'a> MonoBehaviour<'a> {
+    /// Resolves `m_Script` to the MonoScript describing this behaviour's C# class.
+    pub fn script(&self) -> UnityResult<MonoScript> {
+        let script = self.script.get_obj().ok_or_else(|| UnityError::CustomError(format!("can not find MonoScript {:?}", self.script)))?;
+        script.read()
+    }
+
+    /// Deserializes the script fields that follow the MonoBehaviour header.
+    ///
+    /// `T` can be a `#[derive(Deserialize)]` struct or a generic value such as `serde_json::Value`.
+    /// Stripped type trees are looked up through [`Env::typetree_provider`]; without a match this
+    /// returns [`ReadTypeTreeError::MissingTypeTree`].
+    pub fn fields<T: DeserializeOwned>(&self) -> Result<T, ReadTypeTreeError> {
+        let object = self.object();
+        let nodes = object.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.read_type_tree_after(&nodes, "m_Name", self.fields_offset)
+    }
+
+    /// The object this behaviour was read from.
+    pub(super) fn object(&self) -> Object<'a> {
+        Object {
+            env: self.env,
+            asset: self.asset,
+            info: self.info,
+            cache: self.env.cache.clone(),
+        }
+    }
+}
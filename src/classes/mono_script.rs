@@ -2,39 +2,64 @@ use crate::classes::FromObject;
 use crate::env::Object;
 use crate::error::UnityResult;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertiesHash {
+    Legacy(u32),
+    Hash128([u8; 16]),
+}
+
 pub struct MonoScript {
     pub name: String,
+    pub execution_order: i32,
+    pub properties_hash: PropertiesHash,
     pub class_name: String,
     pub namespace: Option<String>,
     pub assembly_name: String,
+    pub is_editor_script: bool,
 }
 
 impl FromObject<'_> for MonoScript {
     fn load(object: &Object) -> UnityResult<Self> {
         let version = object.info.version;
-        let mut r = object.info.get_reader();
+        let mut r = object.info.get_reader()?;
         let name = r.read_aligned_string()?;
-        if version[0] > 3 || (version[0] == 3 && version[1] >= 4) {
-            let _execution_order = r.read_i32()?;
+        let mut execution_order = 0;
+        if version.is_at_least(3, 4) {
+            execution_order = r.read_i32()?;
         }
-        if version[0] < 5 {
-            let _properties_hash = r.read_u32()?;
-        } else {
-            let _properties_hash = r.read_u8_array::<16>()?;
-        }
-        if version[0] < 3 {
+        let properties_hash = if version.major < 5 { PropertiesHash::Legacy(r.read_u32()?) } else { PropertiesHash::Hash128(r.read_u8_array::<16>()?) };
+        if version.major < 3 {
             let _path_name = r.read_aligned_string()?;
         }
         let class_name = r.read_aligned_string()?;
-        let namespace = if version[0] >= 3 { Some(r.read_aligned_string()?) } else { None };
+        let namespace = if version.major >= 3 { Some(r.read_aligned_string()?) } else { None };
         let assembly_name = r.read_aligned_string()?;
-        if version[0] < 2018 || (version[0] == 2018 && version[1] < 2) {
-            let _is_editor_script = r.read_bool()?;
+        let mut is_editor_script = false;
+        if !version.is_at_least(2018, 2) {
+            is_editor_script = r.read_bool()?;
         }
-        Ok(Self { name, class_name, namespace, assembly_name })
+        Ok(Self {
+            name,
+            execution_order,
+            properties_hash,
+            class_name,
+            namespace,
+            assembly_name,
+            is_editor_script,
+        })
     }
 
     fn class() -> super::ClassID {
         super::ClassID::MonoScript
     }
 }
+
+impl MonoScript {
+    /// The namespace qualified class name, e.g. `UnityEngine.UI.Image`.
+    pub fn full_name(&self) -> String {
+        match self.namespace.as_deref() {
+            Some(namespace) if !namespace.is_empty() => format!("{}.{}", namespace, self.class_name),
+            _ => self.class_name.clone(),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{
+    AnimationClip, AnimatorController, AssetBundle, AssetBundleManifest, AudioClip, Avatar, Font, GameObject, Material, Mesh, MonoBehaviour, MonoScript, Shader, Sprite, SpriteAtlas, TerrainData, TextAsset, Texture2D, VideoClip,
+};
+
+/// A class with an `m_Name`.
+pub trait NamedObject {
+    fn name(&self) -> &str;
+}
+
+macro_rules! impl_named_object {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl NamedObject for $t {
+                fn name(&self) -> &str {
+                    &self.name
+                }
+            }
+        )*
+    };
+}
+
+impl_named_object!(
+    AnimationClip<'_>,
+    AnimatorController<'_>,
+    AssetBundle<'_>,
+    AssetBundleManifest,
+    AudioClip,
+    Avatar,
+    Font<'_>,
+    GameObject<'_>,
+    Material<'_>,
+    Mesh,
+    MonoBehaviour<'_>,
+    MonoScript,
+    Shader,
+    Sprite<'_>,
+    SpriteAtlas<'_>,
+    TerrainData<'_>,
+    TextAsset,
+    Texture2D,
+    VideoClip<'_>,
+);
@@ -0,0 +1,181 @@
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+use super::min_max_curve::{MinMaxCurve, MinMaxGradient};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InitialModule {
+    pub enabled: Option<bool>,
+    #[serde(rename = "startLifetime")]
+    pub start_lifetime: Option<MinMaxCurve>,
+    #[serde(rename = "startSpeed")]
+    pub start_speed: Option<MinMaxCurve>,
+    #[serde(rename = "startColor")]
+    pub start_color: Option<MinMaxGradient>,
+    #[serde(rename = "startSize")]
+    pub start_size: Option<MinMaxCurve>,
+    #[serde(rename = "startRotation")]
+    pub start_rotation: Option<MinMaxCurve>,
+    #[serde(rename = "gravityModifier")]
+    pub gravity_modifier: Option<MinMaxCurve>,
+    #[serde(rename = "maxNumParticles")]
+    pub max_num_particles: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Burst {
+    pub time: f32,
+    /// Since 2017.2; older bursts use `min_count`/`max_count`.
+    #[serde(rename = "countCurve")]
+    pub count_curve: Option<MinMaxCurve>,
+    #[serde(rename = "minCount")]
+    pub min_count: Option<i32>,
+    #[serde(rename = "maxCount")]
+    pub max_count: Option<i32>,
+    #[serde(rename = "cycleCount")]
+    pub cycle_count: Option<i32>,
+    #[serde(rename = "repeatInterval")]
+    pub repeat_interval: Option<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmissionModule {
+    pub enabled: bool,
+    /// Named `rate` before 5.5.
+    #[serde(rename = "rateOverTime", alias = "rate")]
+    pub rate_over_time: Option<MinMaxCurve>,
+    #[serde(rename = "rateOverDistance")]
+    pub rate_over_distance: Option<MinMaxCurve>,
+    #[serde(rename = "m_Bursts", default)]
+    pub bursts: Vec<Burst>,
+}
+
+/// `radius`, `arc` and friends became `MultiModeParameter` structs in 2017.1.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ShapeValue {
+    Plain(f32),
+    MultiMode { value: f32 },
+}
+
+impl ShapeValue {
+    fn value(self) -> f32 {
+        match self {
+            Self::Plain(value) | Self::MultiMode { value } => value,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawShapeModule {
+    enabled: bool,
+    #[serde(rename = "type")]
+    type_: i32,
+    angle: Option<f32>,
+    length: Option<f32>,
+    radius: Option<ShapeValue>,
+    arc: Option<ShapeValue>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "RawShapeModule")]
+pub struct ShapeModule {
+    pub enabled: bool,
+    /// `ParticleSystemShapeType`, e.g. 0 sphere, 4 cone, 5 box.
+    pub type_: i32,
+    pub angle: Option<f32>,
+    pub length: Option<f32>,
+    pub radius: Option<f32>,
+    pub arc: Option<f32>,
+}
+
+impl From<RawShapeModule> for ShapeModule {
+    fn from(raw: RawShapeModule) -> Self {
+        Self {
+            enabled: raw.enabled,
+            type_: raw.type_,
+            angle: raw.angle,
+            length: raw.length,
+            radius: raw.radius.map(ShapeValue::value),
+            arc: raw.arc.map(ShapeValue::value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColorModule {
+    pub enabled: bool,
+    pub gradient: MinMaxGradient,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SizeModule {
+    pub enabled: bool,
+    pub curve: MinMaxCurve,
+    pub y: Option<MinMaxCurve>,
+    pub z: Option<MinMaxCurve>,
+    #[serde(rename = "separateAxes", default)]
+    pub separate_axes: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UVModule {
+    pub enabled: bool,
+    pub mode: Option<i32>,
+    #[serde(rename = "frameOverTime")]
+    pub frame_over_time: MinMaxCurve,
+    #[serde(rename = "startFrame")]
+    pub start_frame: Option<MinMaxCurve>,
+    #[serde(rename = "tilesX")]
+    pub tiles_x: i32,
+    #[serde(rename = "tilesY")]
+    pub tiles_y: i32,
+    #[serde(rename = "animationType")]
+    pub animation_type: i32,
+    #[serde(rename = "rowIndex")]
+    pub row_index: Option<i32>,
+    pub cycles: f32,
+}
+
+/// A ParticleSystem's timing and playback settings, with the initial, shape, emission, size,
+/// color and texture sheet modules. A module the file's Unity version does not have is `None`.
+///
+/// Without a type tree only Unity 2017.2 to 2017.4 files read, through
+/// [`builtin_nodes`](crate::typetree::builtin_nodes), and only the playback settings: every module
+/// is `None`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParticleSystem {
+    #[serde(rename = "lengthInSec")]
+    pub length_in_sec: f32,
+    #[serde(rename = "simulationSpeed")]
+    pub simulation_speed: Option<f32>,
+    pub looping: bool,
+    pub prewarm: bool,
+    #[serde(rename = "playOnAwake")]
+    pub play_on_awake: bool,
+    #[serde(rename = "InitialModule")]
+    pub initial_module: Option<InitialModule>,
+    #[serde(rename = "ShapeModule")]
+    pub shape_module: Option<ShapeModule>,
+    #[serde(rename = "EmissionModule")]
+    pub emission_module: Option<EmissionModule>,
+    #[serde(rename = "SizeModule")]
+    pub size_module: Option<SizeModule>,
+    #[serde(rename = "ColorModule")]
+    pub color_module: Option<ColorModule>,
+    #[serde(rename = "UVModule")]
+    pub uv_module: Option<UVModule>,
+}
+
+impl FromObject<'_> for ParticleSystem {
+    fn load(object: &Object) -> UnityResult<Self> {
+        Ok(object.read_type_tree()?)
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::ParticleSystem
+    }
+}
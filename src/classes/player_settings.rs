@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+/// The project's identity as the player reports it: company and product names, bundle version and
+/// default screen size. A file without type trees yields only the two names.
+#[derive(Debug, Default, Deserialize)]
+pub struct PlayerSettings {
+    #[serde(rename = "companyName")]
+    pub company_name: Option<String>,
+    #[serde(rename = "productName")]
+    pub product_name: Option<String>,
+    #[serde(rename = "bundleVersion")]
+    pub bundle_version: Option<String>,
+    #[serde(rename = "AndroidBundleVersionCode")]
+    pub android_bundle_version_code: Option<i32>,
+    #[serde(rename = "defaultScreenWidth")]
+    pub default_screen_width: Option<i32>,
+    #[serde(rename = "defaultScreenHeight")]
+    pub default_screen_height: Option<i32>,
+}
+
+impl FromObject<'_> for PlayerSettings {
+    fn load(object: &Object) -> UnityResult<Self> {
+        if object.type_tree_nodes().is_some() {
+            return Ok(object.read_type_tree()?);
+        }
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        if version.is_at_least(5, 4) {
+            let _product_guid = r.read_u8_array::<16>()?;
+        }
+        let _android_profiler = r.read_bool()?;
+        r.align(4)?;
+        let _default_screen_orientation = r.read_i32()?;
+        let _target_device = r.read_i32()?;
+        if !version.is_at_least(5, 3) {
+            if version.major < 5 {
+                let _target_platform = r.read_i32()?;
+                if version.major == 4 && version.minor >= 6 {
+                    let _target_ios_graphics = r.read_i32()?;
+                }
+            }
+            let _target_resolution = r.read_i32()?;
+        } else {
+            let _use_on_demand_resources = r.read_bool()?;
+            r.align(4)?;
+        }
+        if version.is_at_least(3, 5) {
+            let _accelerometer_frequency = r.read_i32()?;
+        }
+        Ok(Self {
+            company_name: Some(r.read_aligned_string()?),
+            product_name: Some(r.read_aligned_string()?),
+            ..Self::default()
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::PlayerSettings
+    }
+}
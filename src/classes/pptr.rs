@@ -1,50 +1,136 @@
-use crate::classes::FromObject;
+use crate::asset::SerializedFile;
+use crate::classes::{ClassID, FromObject};
 use crate::env::{Env, Object};
-use crate::error::UnityResult;
+use crate::error::{UnityError, UnityResult};
+use crate::object::ObjectInfo;
 use crate::reader::Reader;
+use serde::Deserialize;
 use std::{any::type_name, marker::PhantomData};
 
+/// A reference to an object, `m_FileID` being 0 for the file the pointer was read from and
+/// otherwise an index (from 1) into that file's externals table.
 pub struct PPtr<'a, T: FromObject<'a> + 'a> {
     env: &'a Env,
+    /// The file the pointer was read from; `None` for pointers built from bare ids, which are
+    /// looked up by `path_id` in every loaded file.
+    source: Option<&'a SerializedFile>,
     pub file_id: i32,
     pub path_id: i64,
     target: PhantomData<T>,
 }
 
 impl<'a, T: FromObject<'a>> PPtr<'a, T> {
-    pub fn load(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         let file_id = r.read_i32()?;
         let path_id = if object.info.asset_version < 14 { r.read_i32()? as i64 } else { r.read_i64()? };
         Ok(Self {
             env: object.env,
+            source: Some(object.asset),
             file_id,
             path_id,
             target: PhantomData,
         })
     }
 
-    pub fn get_obj(&self) -> Option<Object<'a>> {
-        if self.path_id == 0 {
-            return None;
+    /// Builds a pointer bound to `env`, e.g. to keep following a chain of objects read from temporaries.
+    pub(crate) fn new(env: &'a Env, file_id: i32, path_id: i64) -> Self {
+        Self {
+            env,
+            source: None,
+            file_id,
+            path_id,
+            target: PhantomData,
+        }
+    }
+
+    /// The same reference, typed as a pointer to `U`.
+    pub fn cast<U: FromObject<'a>>(&self) -> PPtr<'a, U> {
+        PPtr {
+            env: self.env,
+            source: self.source,
+            file_id: self.file_id,
+            path_id: self.path_id,
+            target: PhantomData,
+        }
+    }
+
+    pub fn env(&self) -> &'a Env {
+        self.env
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.path_id == 0
+    }
+
+    /// Finds the referenced object, following `m_FileID` through the externals table of the file
+    /// the pointer was read from. Null pointers resolve to `None`; an external file that is not
+    /// loaded into the [`Env`] is an error.
+    pub fn resolve(&self) -> UnityResult<Option<Object<'a>>> {
+        if self.is_null() {
+            return Ok(None);
+        }
+        let Some(source) = self.source else {
+            return Ok(self.env.serialized_files.iter().find_map(|asset| self.find_in(asset)));
+        };
+        if self.file_id == 0 {
+            return Ok(self.find_in(source));
+        }
+        let external = source.external(self.file_id).ok_or_else(|| UnityError::CustomError(format!("{} has no external file {}", source.path, self.file_id)))?;
+        let asset = self.env.find_serialized_file(&external.path_name).ok_or_else(|| UnityError::CustomError(format!("external file {} is not loaded", external.path_name)))?;
+        Ok(self.find_in(asset))
+    }
+
+    /// Resolves and reads the referenced object, checking that its class is `T`.
+    pub fn deref(&self) -> UnityResult<Option<T>> {
+        let Some(object) = self.resolve()? else {
+            return Ok(None);
+        };
+        if T::class() != ClassID::Object && object.class() != T::class() {
+            return Err(UnityError::CustomError(format!("{:?} points to a {:?}", self, object.class())));
         }
-        for asset in &self.env.serialized_files {
-            for info in &asset.objects_info {
-                if info.path_id != self.path_id {
-                    continue;
-                }
-                if info.class() != T::class() {
-                    continue;
-                }
-                let obj = Object {
-                    env: self.env,
-                    asset,
-                    info,
-                    cache: self.env.cache.clone(),
-                };
-                return Some(obj);
-            }
+        object.read().map(Some)
+    }
+
+    pub fn get_obj(&self) -> Option<Object<'a>> {
+        self.resolve().ok().flatten().filter(|x| T::class() == ClassID::Object || x.info.class() == T::class())
+    }
+
+    /// Resolves the pointer without checking the target's class, for pointers to base classes
+    /// such as `PPtr<Component>` whose targets are concrete subclasses.
+    pub fn get_obj_any(&self) -> Option<Object<'a>> {
+        self.resolve().ok().flatten()
+    }
+
+    fn find_in(&self, asset: &'a SerializedFile) -> Option<Object<'a>> {
+        let info: &ObjectInfo = asset.object(self.path_id)?;
+        Some(Object {
+            env: self.env,
+            asset,
+            info,
+            cache: self.env.cache.clone(),
+        })
+    }
+}
+
+/// A `PPtr` read through the type tree, before it is bound to the object it was read from.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RawPPtr {
+    #[serde(rename = "m_FileID")]
+    pub file_id: i32,
+    #[serde(rename = "m_PathID")]
+    pub path_id: i64,
+}
+
+impl RawPPtr {
+    /// Binds the pointer to `object`'s file, so `file_id` is resolved through its externals.
+    pub fn bind<'a, T: FromObject<'a>>(&self, object: &Object<'a>) -> PPtr<'a, T> {
+        PPtr {
+            env: object.env,
+            source: Some(object.asset),
+            file_id: self.file_id,
+            path_id: self.path_id,
+            target: PhantomData,
         }
-        None
     }
 }
 
@@ -0,0 +1,56 @@
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+use crate::math::{RectF32, Vector2};
+
+use super::transform::Transform;
+
+pub struct RectTransform<'a> {
+    pub transform: Transform<'a>,
+    pub anchor_min: Vector2,
+    pub anchor_max: Vector2,
+    pub anchored_position: Vector2,
+    pub size_delta: Vector2,
+    pub pivot: Vector2,
+}
+
+impl<'a> FromObject<'a> for RectTransform<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
+        let transform = Transform::from_reader(object, &mut r)?;
+        Ok(Self {
+            transform,
+            anchor_min: r.read_vector2()?,
+            anchor_max: r.read_vector2()?,
+            anchored_position: r.read_vector2()?,
+            size_delta: r.read_vector2()?,
+            pivot: r.read_vector2()?,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::RectTransform
+    }
+}
+
+impl RectTransform<'_> {
+    /// Resolves this transform's rect inside `parent`, in the parent's local space.
+    ///
+    /// `x`/`y` is the bottom left corner, matching Unity's `RectTransform.rect` offset by the pivot position.
+    pub fn rect_in(&self, parent: &RectF32) -> RectF32 {
+        let anchor_x = |t: f32| parent.x + parent.w * t;
+        let anchor_y = |t: f32| parent.y + parent.h * t;
+        let w = parent.w * (self.anchor_max.x - self.anchor_min.x) + self.size_delta.x;
+        let h = parent.h * (self.anchor_max.y - self.anchor_min.y) + self.size_delta.y;
+        let reference_x = anchor_x(self.anchor_min.x + (self.anchor_max.x - self.anchor_min.x) * self.pivot.x);
+        let reference_y = anchor_y(self.anchor_min.y + (self.anchor_max.y - self.anchor_min.y) * self.pivot.y);
+        let pivot_x = reference_x + self.anchored_position.x;
+        let pivot_y = reference_y + self.anchored_position.y;
+        RectF32 {
+            x: pivot_x - self.pivot.x * w,
+            y: pivot_y - self.pivot.y * h,
+            w,
+            h,
+        }
+    }
+}
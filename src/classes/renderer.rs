@@ -38,61 +38,71 @@ impl SubMeshInfo {
 
 pub struct Renderer<'a> {
     pub game_object: PPtr<'a, GameObject<'a>>,
+    pub enabled: bool,
+    /// `ShadowCastingMode`: 0 off, 1 on, 2 two sided, 3 shadows only.
+    pub cast_shadows: u8,
+    pub receive_shadows: bool,
+    /// 0xFFFF when the renderer is not lightmapped.
+    pub lightmap_index: u16,
     pub materials: Vec<PPtr<'a, Material<'a>>>,
     pub sub_mesh_info: Option<SubMeshInfo>,
 }
 
 impl<'a> FromObject<'a> for Renderer<'a> {
-    fn load(object: &'a crate::Object<'a>) -> UnityResult<Self> {
+    fn load(object: &crate::Object<'a>) -> UnityResult<Self> {
         let version = object.info.version;
-        let mut r = object.info.get_reader();
+        let mut r = object.info.get_reader()?;
         let game_object = PPtr::load(object, &mut r)?;
-        if version[0] < 5 {
-            let _enabled = r.read_bool()?;
-            let _cast_shadows = r.read_bool()?;
-            let _receive_shadows = r.read_bool()?;
-            let _lightmap_index = r.read_u8()?;
+        let enabled;
+        let cast_shadows;
+        let receive_shadows;
+        let lightmap_index;
+        if version.major < 5 {
+            enabled = r.read_bool()?;
+            cast_shadows = r.read_u8()?;
+            receive_shadows = r.read_bool()?;
+            lightmap_index = r.read_u8()? as u16;
         } else {
-            if version[0] > 5 || (version[0] == 5 && version[1] >= 4) {
-                let _enabled = r.read_bool()?;
-                let _cast_shadows = r.read_u8()?;
-                let _receive_shadows = r.read_u8()?;
-                if version[0] > 2017 || (version[0] == 2017 && version[1] >= 2) {
+            if version.is_at_least(5, 4) {
+                enabled = r.read_bool()?;
+                cast_shadows = r.read_u8()?;
+                receive_shadows = r.read_u8()? != 0;
+                if version.is_at_least(2017, 2) {
                     let _dynamic_occludee = r.read_u8()?;
                 }
-                if version[0] >= 2021 {
+                if version.major >= 2021 {
                     let _static_shadow_caster = r.read_u8()?;
                 }
                 let _motion_vectors = r.read_u8()?;
                 let _light_probe_usage = r.read_u8()?;
                 let _reflection_probe_usage = r.read_u8()?;
-                if version[0] > 2019 || (version[0] == 2019 && version[1] >= 3) {
+                if version.is_at_least(2019, 3) {
                     let _ray_tracing_mode = r.read_u8()?;
                 }
-                if version[0] >= 2020 {
+                if version.major >= 2020 {
                     let _ray_trace_procedural = r.read_u8()?;
                 }
                 r.align(4)?;
             } else {
-                let _enabled = r.read_bool()?;
+                enabled = r.read_bool()?;
                 r.align(4)?;
-                let _cast_shadows = r.read_u8()?;
-                let _receive_shadows = r.read_bool()?;
+                cast_shadows = r.read_u8()?;
+                receive_shadows = r.read_bool()?;
                 r.align(4)?;
             }
-            if version[0] >= 2018 {
+            if version.major >= 2018 {
                 let _rendering_layer_mask = r.read_u32()?;
             }
-            if version[0] > 2018 || (version[0] == 2018 && version[1] >= 3) {
+            if version.is_at_least(2018, 3) {
                 let _renderer_priority = r.read_i32()?;
             }
-            let _lightmap_index = r.read_u16()?;
+            lightmap_index = r.read_u16()?;
             let _lightmap_index_dynamic = r.read_u16()?;
         }
-        if version[0] >= 3 {
+        if version.major >= 3 {
             let _lightmap_tiling_offset = r.read_vector4()?;
         }
-        if version[0] >= 5 {
+        if version.major >= 5 {
             let _lightmap_tiling_offset_dynamic = r.read_vector4()?;
         }
         let materials_size = r.read_i32()?;
@@ -101,10 +111,10 @@ impl<'a> FromObject<'a> for Renderer<'a> {
             materials.push(PPtr::load(object, &mut r)?);
         }
         let mut sub_mesh_info = None;
-        if version[0] < 3 {
+        if version.major < 3 {
             let _lightmap_tiling_offset = r.read_vector4()?;
         } else {
-            if version[0] > 5 || (version[0] == 5 && version[1] >= 5) {
+            if version.is_at_least(5, 5) {
                 sub_mesh_info = Some(SubMeshInfo::StaticBatchInfo(StaticBatchInfo::load(object.info, &mut r)?))
             } else {
                 let size = r.read_i32()? as usize;
@@ -112,20 +122,20 @@ impl<'a> FromObject<'a> for Renderer<'a> {
             }
             let _static_batch_root = PPtr::<Transform>::load(object, &mut r)?;
         }
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 4) {
+        if version.is_at_least(5, 4) {
             let _probe_anchor = PPtr::<Transform>::load(object, &mut r)?;
             let _light_probe_volume_override = PPtr::<GameObject>::load(object, &mut r)?;
-        } else if version[0] > 3 || (version[0] == 3 && version[1] >= 5) {
+        } else if version.is_at_least(3, 5) {
             let _use_light_probes = r.read_bool()?;
             r.align(4)?;
-            if version[0] >= 5 {
+            if version.major >= 5 {
                 let _reflection_probe_usage = r.read_i32()?;
             }
             let _light_probe_anchor = PPtr::<Transform>::load(object, &mut r)?;
         }
 
-        if version[0] > 4 || (version[0] == 4 && version[1] >= 3) {
-            if version[0] == 4 && version[1] == 3 {
+        if version.is_at_least(4, 3) {
+            if version.major == 4 && version.minor == 3 {
                 let _sorting_layer = r.read_i16()?;
             } else {
                 let _sorting_layer_id = r.read_u32()?;
@@ -133,10 +143,25 @@ impl<'a> FromObject<'a> for Renderer<'a> {
             let _sorting_order = r.read_i16()?;
             r.align(4)?;
         }
-        Ok(Self { game_object, materials, sub_mesh_info })
+        Ok(Self {
+            game_object,
+            enabled,
+            cast_shadows,
+            receive_shadows,
+            lightmap_index,
+            materials,
+            sub_mesh_info,
+        })
     }
 
     fn class() -> super::ClassID {
         super::ClassID::Renderer
     }
 }
+
+impl<'a> Renderer<'a> {
+    /// Resolves `m_Materials`, keeping one entry per sub mesh slot; slots whose material is not loaded are `None`.
+    pub fn load_materials(&self) -> UnityResult<Vec<Option<Material<'a>>>> {
+        self.materials.iter().map(|x| x.get_obj().map(|x| x.read()).transpose()).collect()
+    }
+}
@@ -0,0 +1,44 @@
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::UnityResult;
+
+use super::pptr::PPtr;
+
+pub struct ResourceManagerDependency<'a> {
+    pub object: PPtr<'a, Object<'a>>,
+    pub dependencies: Vec<PPtr<'a, Object<'a>>>,
+}
+
+/// Maps `Resources.Load` paths to objects for player data folders (`resources.assets`).
+pub struct ResourceManager<'a> {
+    pub container: Vec<(String, PPtr<'a, Object<'a>>)>,
+    pub dependent_assets: Vec<ResourceManagerDependency<'a>>,
+}
+
+impl<'a> FromObject<'a> for ResourceManager<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        let mut container = Vec::new();
+        for _ in 0..r.read_i32()? {
+            let path = r.read_aligned_string()?;
+            container.push((path, PPtr::load(object, &mut r)?));
+        }
+        let mut dependent_assets = Vec::new();
+        if version.is_at_least(3, 5) {
+            for _ in 0..r.read_i32()? {
+                let object_ptr = PPtr::load(object, &mut r)?;
+                let mut dependencies = Vec::new();
+                for _ in 0..r.read_i32()? {
+                    dependencies.push(PPtr::load(object, &mut r)?);
+                }
+                dependent_assets.push(ResourceManagerDependency { object: object_ptr, dependencies });
+            }
+        }
+        Ok(Self { container, dependent_assets })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::ResourceManager
+    }
+}
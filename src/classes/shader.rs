@@ -1,15 +1,616 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::object::ObjectInfo;
+use crate::reader::Reader;
+use crate::version::UnityVersion;
 use crate::{Object, UnityError, UnityResult};
 
 use super::FromObject;
 
-pub struct Shader {}
+fn is_2020_3_2_and_up(version: UnityVersion) -> bool {
+    (version.is_at_least_patch(2020, 3, 2) && !version.is_at_least(2021, 0)) || version.is_at_least_patch(2021, 1, 4)
+}
+
+fn read_u32_list_list(r: &mut Reader) -> UnityResult<Vec<Vec<u32>>> {
+    let size = r.read_i32()?;
+    let mut result = Vec::new();
+    for _ in 0..size {
+        let length = r.read_i32()? as usize;
+        result.push(r.read_u32_list(length)?);
+    }
+    Ok(result)
+}
+
+fn read_u16_array(r: &mut Reader) -> UnityResult<Vec<u16>> {
+    let size = r.read_i32()? as usize;
+    let result = r.read_u16_list(size)?;
+    r.align(4)?;
+    Ok(result)
+}
+
+fn read_tag_map(r: &mut Reader) -> UnityResult<Vec<(String, String)>> {
+    let size = r.read_i32()?;
+    let mut tags = Vec::new();
+    for _ in 0..size {
+        tags.push((r.read_aligned_string()?, r.read_aligned_string()?));
+    }
+    Ok(tags)
+}
+
+fn skip_float_value(r: &mut Reader) -> UnityResult<()> {
+    let _val = r.read_f32()?;
+    let _name = r.read_aligned_string()?;
+    Ok(())
+}
+
+fn skip_float_values(r: &mut Reader, count: usize) -> UnityResult<()> {
+    for _ in 0..count {
+        skip_float_value(r)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct SerializedProperty {
+    pub name: String,
+    pub description: String,
+    pub attributes: Vec<String>,
+    pub type_: i32,
+    pub flags: u32,
+    pub def_value: [f32; 4],
+    pub def_texture_name: String,
+    pub def_texture_dim: i32,
+}
+
+impl SerializedProperty {
+    pub fn load(_object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        Ok(Self {
+            name: r.read_aligned_string()?,
+            description: r.read_aligned_string()?,
+            attributes: r.read_string_list()?,
+            type_: r.read_i32()?,
+            flags: r.read_u32()?,
+            def_value: r.read_f32_array::<4>()?,
+            def_texture_name: r.read_aligned_string()?,
+            def_texture_dim: r.read_i32()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializedShaderState {
+    pub name: String,
+    pub tags: Vec<(String, String)>,
+    pub lod: i32,
+    pub lighting: bool,
+}
+
+impl SerializedShaderState {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        let name = r.read_aligned_string()?;
+        // rtBlend[8]: srcBlend, destBlend, srcBlendAlpha, destBlendAlpha, blendOp, blendOpAlpha, colMask
+        skip_float_values(r, 8 * 7)?;
+        let _rt_separate_blend = r.read_bool()?;
+        r.align(4)?;
+        if version.is_at_least(2017, 2) {
+            skip_float_value(r)?;
+        }
+        // zTest, zWrite, culling
+        skip_float_values(r, 3)?;
+        if version.major >= 2020 {
+            skip_float_value(r)?;
+        }
+        // offsetFactor, offsetUnits, alphaToMask
+        skip_float_values(r, 3)?;
+        // stencilOp, stencilOpFront, stencilOpBack: pass, fail, zFail, comp
+        skip_float_values(r, 3 * 4)?;
+        // stencilReadMask, stencilWriteMask, stencilRef, fogStart, fogEnd, fogDensity
+        skip_float_values(r, 6)?;
+        // fogColor: x, y, z, w, name
+        skip_float_values(r, 4)?;
+        let _fog_color_name = r.read_aligned_string()?;
+        let _fog_mode = r.read_i32()?;
+        let _gpu_program_id = r.read_i32()?;
+        let tags = read_tag_map(r)?;
+        let lod = r.read_i32()?;
+        let lighting = r.read_bool()?;
+        r.align(4)?;
+        Ok(Self { name, tags, lod, lighting })
+    }
+}
+
+fn skip_vector_parameter(r: &mut Reader) -> UnityResult<()> {
+    let _name_index = r.read_i32()?;
+    let _index = r.read_i32()?;
+    let _array_size = r.read_i32()?;
+    let _type = r.read_i8()?;
+    let _dim = r.read_i8()?;
+    r.align(4)?;
+    Ok(())
+}
+
+fn skip_matrix_parameter(r: &mut Reader) -> UnityResult<()> {
+    let _name_index = r.read_i32()?;
+    let _index = r.read_i32()?;
+    let _array_size = r.read_i32()?;
+    let _type = r.read_i8()?;
+    let _row_count = r.read_i8()?;
+    r.align(4)?;
+    Ok(())
+}
+
+fn skip_texture_parameter(object: &ObjectInfo, r: &mut Reader) -> UnityResult<()> {
+    let version = object.version;
+    let _name_index = r.read_i32()?;
+    let _index = r.read_i32()?;
+    let _sampler_index = r.read_i32()?;
+    if version.is_at_least(2017, 3) {
+        let _multi_sampled = r.read_bool()?;
+    }
+    let _dim = r.read_i8()?;
+    r.align(4)?;
+    Ok(())
+}
+
+fn skip_buffer_binding(object: &ObjectInfo, r: &mut Reader) -> UnityResult<()> {
+    let _name_index = r.read_i32()?;
+    let _index = r.read_i32()?;
+    if object.version.major >= 2020 {
+        let _array_size = r.read_i32()?;
+    }
+    Ok(())
+}
+
+fn skip_struct_parameter(r: &mut Reader) -> UnityResult<()> {
+    let _name_index = r.read_i32()?;
+    let _index = r.read_i32()?;
+    let _array_size = r.read_i32()?;
+    let _struct_size = r.read_i32()?;
+    for _ in 0..r.read_i32()? {
+        skip_vector_parameter(r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_matrix_parameter(r)?;
+    }
+    Ok(())
+}
+
+fn skip_constant_buffer(object: &ObjectInfo, r: &mut Reader) -> UnityResult<()> {
+    let version = object.version;
+    let _name_index = r.read_i32()?;
+    for _ in 0..r.read_i32()? {
+        skip_matrix_parameter(r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_vector_parameter(r)?;
+    }
+    if version.is_at_least(2017, 3) {
+        for _ in 0..r.read_i32()? {
+            skip_struct_parameter(r)?;
+        }
+    }
+    let _size = r.read_i32()?;
+    if is_2020_3_2_and_up(version) {
+        let _is_partial_cb = r.read_bool()?;
+        r.align(4)?;
+    }
+    Ok(())
+}
+
+fn skip_program_parameters(object: &ObjectInfo, r: &mut Reader, with_samplers: bool) -> UnityResult<()> {
+    for _ in 0..r.read_i32()? {
+        skip_vector_parameter(r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_matrix_parameter(r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_texture_parameter(object, r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_buffer_binding(object, r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_constant_buffer(object, r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        skip_buffer_binding(object, r)?;
+    }
+    for _ in 0..r.read_i32()? {
+        let _name_index = r.read_i32()?;
+        let _index = r.read_i32()?;
+        let _original_index = r.read_i32()?;
+    }
+    if with_samplers {
+        for _ in 0..r.read_i32()? {
+            let _sampler = r.read_u32()?;
+            let _bind_point = r.read_i32()?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct SerializedSubProgram {
+    pub blob_index: u32,
+    pub keyword_indices: Vec<u16>,
+    pub local_keyword_indices: Vec<u16>,
+    pub shader_hardware_tier: i8,
+    pub gpu_program_type: i8,
+    pub shader_requirements: i64,
+}
+
+impl SerializedSubProgram {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        let blob_index = r.read_u32()?;
+        // ParserBindChannels
+        for _ in 0..r.read_i32()? {
+            let _source = r.read_i8()?;
+            let _target = r.read_i8()?;
+        }
+        r.align(4)?;
+        let _source_map = r.read_u32()?;
+        let mut local_keyword_indices = Vec::new();
+        let keyword_indices;
+        if version.is_at_least(2019, 0) && !version.is_at_least(2021, 2) {
+            keyword_indices = read_u16_array(r)?;
+            local_keyword_indices = read_u16_array(r)?;
+        } else {
+            let size = r.read_i32()? as usize;
+            keyword_indices = r.read_u16_list(size)?;
+            if version.major >= 2017 {
+                r.align(4)?;
+            }
+        }
+        let shader_hardware_tier = r.read_i8()?;
+        let gpu_program_type = r.read_i8()?;
+        r.align(4)?;
+        if is_2020_3_2_and_up(version) {
+            skip_program_parameters(object, r, true)?;
+        } else {
+            skip_program_parameters(object, r, version.major >= 2017)?;
+        }
+        let mut shader_requirements = 0;
+        if version.is_at_least(2017, 2) {
+            shader_requirements = if version.major >= 2021 { r.read_i64()? } else { r.read_i32()? as i64 };
+        }
+        Ok(Self {
+            blob_index,
+            keyword_indices,
+            local_keyword_indices,
+            shader_hardware_tier,
+            gpu_program_type,
+            shader_requirements,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializedPlayerSubProgram {
+    pub blob_index: u32,
+    pub keyword_indices: Vec<u16>,
+    pub shader_requirements: i64,
+    pub gpu_program_type: i8,
+}
+
+impl SerializedPlayerSubProgram {
+    pub fn load(_object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let blob_index = r.read_u32()?;
+        let keyword_indices = read_u16_array(r)?;
+        let shader_requirements = r.read_i64()?;
+        let gpu_program_type = r.read_i8()?;
+        r.align(4)?;
+        Ok(Self {
+            blob_index,
+            keyword_indices,
+            shader_requirements,
+            gpu_program_type,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SerializedProgram {
+    pub sub_programs: Vec<SerializedSubProgram>,
+    pub player_sub_programs: Vec<Vec<SerializedPlayerSubProgram>>,
+    pub parameter_blob_indices: Vec<Vec<u32>>,
+    pub serialized_keyword_state_mask: Vec<u16>,
+}
+
+impl SerializedProgram {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        let mut result = Self::default();
+        for _ in 0..r.read_i32()? {
+            result.sub_programs.push(SerializedSubProgram::load(object, r)?);
+        }
+        if (version.is_at_least_patch(2021, 3, 10) && !version.is_at_least(2022, 0)) || version.is_at_least_patch(2022, 1, 13) {
+            for _ in 0..r.read_i32()? {
+                let mut platform_programs = Vec::new();
+                for _ in 0..r.read_i32()? {
+                    platform_programs.push(SerializedPlayerSubProgram::load(object, r)?);
+                }
+                result.player_sub_programs.push(platform_programs);
+            }
+            result.parameter_blob_indices = read_u32_list_list(r)?;
+        }
+        if is_2020_3_2_and_up(version) {
+            skip_program_parameters(object, r, true)?;
+        }
+        if version.is_at_least(2022, 1) {
+            result.serialized_keyword_state_mask = read_u16_array(r)?;
+        }
+        Ok(result)
+    }
+
+    fn keyword_indices(&self) -> impl Iterator<Item = u16> + '_ {
+        let sub_programs = self.sub_programs.iter().flat_map(|x| x.keyword_indices.iter().chain(x.local_keyword_indices.iter()));
+        let player_sub_programs = self.player_sub_programs.iter().flatten().flat_map(|x| x.keyword_indices.iter());
+        sub_programs.chain(player_sub_programs).copied()
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializedPass {
+    pub name_indices: Vec<(String, i32)>,
+    pub type_: i32,
+    pub state: SerializedShaderState,
+    pub program_mask: u32,
+    pub programs: Vec<SerializedProgram>,
+    pub has_instancing_variant: bool,
+    pub use_name: String,
+    pub name: String,
+    pub texture_name: String,
+    pub tags: Vec<(String, String)>,
+}
+
+impl SerializedPass {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        if version.is_at_least(2020, 2) {
+            for _ in 0..r.read_i32()? {
+                let _editor_data_hash = r.read_u8_array::<16>()?;
+            }
+            r.align(4)?;
+            let size = r.read_i32()? as usize;
+            let _platforms = r.read_u8_slice(size)?;
+            r.align(4)?;
+            if !version.is_at_least(2021, 2) {
+                let _local_keyword_mask = read_u16_array(r)?;
+                let _global_keyword_mask = read_u16_array(r)?;
+            }
+        }
+        let mut name_indices = Vec::new();
+        for _ in 0..r.read_i32()? {
+            name_indices.push((r.read_aligned_string()?, r.read_i32()?));
+        }
+        let type_ = r.read_i32()?;
+        let state = SerializedShaderState::load(object, r)?;
+        let program_mask = r.read_u32()?;
+        // vertex, fragment, geometry, hull, domain and, since 2019.3, ray tracing
+        let program_count = if version.is_at_least(2019, 3) { 6 } else { 5 };
+        let mut programs = Vec::with_capacity(program_count);
+        for _ in 0..program_count {
+            programs.push(SerializedProgram::load(object, r)?);
+        }
+        let has_instancing_variant = r.read_bool()?;
+        if version.major >= 2018 {
+            let _has_procedural_instancing_variant = r.read_bool()?;
+        }
+        r.align(4)?;
+        let use_name = r.read_aligned_string()?;
+        let name = r.read_aligned_string()?;
+        let texture_name = r.read_aligned_string()?;
+        let tags = read_tag_map(r)?;
+        if version.major == 2021 && version.minor >= 2 {
+            let _serialized_keyword_state_mask = read_u16_array(r)?;
+        }
+        Ok(Self {
+            name_indices,
+            type_,
+            state,
+            program_mask,
+            programs,
+            has_instancing_variant,
+            use_name,
+            name,
+            texture_name,
+            tags,
+        })
+    }
+
+    /// Keyword names referenced by the pass's sub programs, resolved through `name_indices`.
+    pub fn keywords(&self) -> BTreeSet<String> {
+        let names: HashMap<i32, &str> = self.name_indices.iter().map(|(name, index)| (*index, name.as_str())).collect();
+        self.programs.iter().flat_map(|x| x.keyword_indices()).filter_map(|x| names.get(&(x as i32))).map(|x| x.to_string()).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializedSubShader {
+    pub passes: Vec<SerializedPass>,
+    pub tags: Vec<(String, String)>,
+    pub lod: i32,
+}
+
+impl SerializedSubShader {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let mut passes = Vec::new();
+        for _ in 0..r.read_i32()? {
+            passes.push(SerializedPass::load(object, r)?);
+        }
+        let tags = read_tag_map(r)?;
+        let lod = r.read_i32()?;
+        Ok(Self { passes, tags, lod })
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializedShader {
+    pub properties: Vec<SerializedProperty>,
+    pub sub_shaders: Vec<SerializedSubShader>,
+    pub keyword_names: Vec<String>,
+    pub keyword_flags: Vec<u8>,
+    pub name: String,
+    pub custom_editor_name: String,
+    pub fallback_name: String,
+    pub dependencies: Vec<(String, String)>,
+    pub disable_no_subshaders_message: bool,
+}
+
+impl SerializedShader {
+    pub fn load(object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
+        let version = object.version;
+        let mut properties = Vec::new();
+        for _ in 0..r.read_i32()? {
+            properties.push(SerializedProperty::load(object, r)?);
+        }
+        let mut sub_shaders = Vec::new();
+        for _ in 0..r.read_i32()? {
+            sub_shaders.push(SerializedSubShader::load(object, r)?);
+        }
+        let mut keyword_names = Vec::new();
+        let mut keyword_flags = Vec::new();
+        if version.is_at_least(2021, 2) {
+            keyword_names = r.read_string_list()?;
+            let size = r.read_i32()? as usize;
+            keyword_flags = r.read_u8_list(size)?;
+            r.align(4)?;
+        }
+        let name = r.read_aligned_string()?;
+        let custom_editor_name = r.read_aligned_string()?;
+        let fallback_name = r.read_aligned_string()?;
+        let mut dependencies = Vec::new();
+        for _ in 0..r.read_i32()? {
+            dependencies.push((r.read_aligned_string()?, r.read_aligned_string()?));
+        }
+        if version.major >= 2021 {
+            for _ in 0..r.read_i32()? {
+                let _custom_editor_name = r.read_aligned_string()?;
+                let _render_pipeline_type = r.read_aligned_string()?;
+            }
+        }
+        let disable_no_subshaders_message = r.read_bool()?;
+        r.align(4)?;
+        Ok(Self {
+            properties,
+            sub_shaders,
+            keyword_names,
+            keyword_flags,
+            name,
+            custom_editor_name,
+            fallback_name,
+            dependencies,
+            disable_no_subshaders_message,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ShaderPlatformBlob {
+    pub platform: u32,
+    pub offsets: Vec<u32>,
+    pub compressed_lengths: Vec<u32>,
+    pub decompressed_lengths: Vec<u32>,
+}
+
+pub struct Shader {
+    pub name: String,
+    pub parsed_form: Option<SerializedShader>,
+    pub platforms: Vec<ShaderPlatformBlob>,
+    pub compressed_blob: Vec<u8>,
+    pub script: Vec<u8>,
+}
 
 impl FromObject<'_> for Shader {
-    fn load(_object: &Object) -> UnityResult<Self> {
-        Err(UnityError::Unimplemented)
+    fn load(object: &Object) -> UnityResult<Self> {
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        let name = r.read_aligned_string()?;
+        let mut parsed_form = None;
+        let mut platforms = Vec::new();
+        let mut compressed_blob = Vec::new();
+        let mut script = Vec::new();
+        if version.is_at_least(5, 5) {
+            let form = SerializedShader::load(object.info, &mut r)?;
+            let size = r.read_i32()? as usize;
+            let platform_ids = r.read_u32_list(size)?;
+            let (offsets, compressed_lengths, decompressed_lengths) = if version.is_at_least(2019, 3) {
+                (read_u32_list_list(&mut r)?, read_u32_list_list(&mut r)?, read_u32_list_list(&mut r)?)
+            } else {
+                let mut read_single = || -> UnityResult<Vec<Vec<u32>>> {
+                    let size = r.read_i32()? as usize;
+                    Ok(r.read_u32_list(size)?.into_iter().map(|x| vec![x]).collect())
+                };
+                (read_single()?, read_single()?, read_single()?)
+            };
+            for (i, platform) in platform_ids.into_iter().enumerate() {
+                platforms.push(ShaderPlatformBlob {
+                    platform,
+                    offsets: offsets.get(i).cloned().unwrap_or_default(),
+                    compressed_lengths: compressed_lengths.get(i).cloned().unwrap_or_default(),
+                    decompressed_lengths: decompressed_lengths.get(i).cloned().unwrap_or_default(),
+                });
+            }
+            let size = r.read_i32()? as usize;
+            compressed_blob = r.read_u8_list(size)?;
+            r.align(4)?;
+            parsed_form = Some(form);
+        } else {
+            let size = r.read_i32()? as usize;
+            script = r.read_u8_list(size)?;
+            r.align(4)?;
+        }
+        Ok(Self {
+            name,
+            parsed_form,
+            platforms,
+            compressed_blob,
+            script,
+        })
     }
 
     fn class() -> super::ClassID {
         super::ClassID::Shader
     }
 }
+
+impl Shader {
+    /// The name declared in the shader source (`Shader "Name"`), falling back to the object name.
+    pub fn name(&self) -> &str {
+        match &self.parsed_form {
+            Some(form) if !form.name.is_empty() => &form.name,
+            _ => &self.name,
+        }
+    }
+
+    pub fn keywords(&self) -> Vec<String> {
+        let Some(form) = &self.parsed_form else {
+            return Vec::new();
+        };
+        if !form.keyword_names.is_empty() {
+            let indices: BTreeSet<u16> = form.sub_shaders.iter().flat_map(|x| &x.passes).flat_map(|x| &x.programs).flat_map(|x| x.keyword_indices()).collect();
+            return indices.into_iter().filter_map(|x| form.keyword_names.get(x as usize)).cloned().collect::<BTreeSet<_>>().into_iter().collect();
+        }
+        let keywords: BTreeSet<String> = form.sub_shaders.iter().flat_map(|x| &x.passes).flat_map(|x| x.keywords()).collect();
+        keywords.into_iter().collect()
+    }
+
+    /// Decompresses the sub program blob segments of every platform, in `platforms` order.
+    pub fn decompress_blobs(&self) -> UnityResult<Vec<Vec<Vec<u8>>>> {
+        let mut result = Vec::with_capacity(self.platforms.len());
+        for platform in &self.platforms {
+            let mut segments = Vec::with_capacity(platform.offsets.len());
+            for ((offset, compressed_length), decompressed_length) in platform.offsets.iter().zip(&platform.compressed_lengths).zip(&platform.decompressed_lengths) {
+                let start = *offset as usize;
+                let end = start + *compressed_length as usize;
+                let compressed = self.compressed_blob.get(start..end).ok_or(UnityError::Eof)?;
+                segments.push(lz4_flex::decompress(compressed, *decompressed_length as usize)?);
+            }
+            result.push(segments);
+        }
+        Ok(result)
+    }
+}
@@ -1,16 +1,18 @@
 use crate::classes::mesh::{SubMesh, VertexData};
 use crate::classes::pptr::PPtr;
 use crate::classes::sprite_atlas::SpriteAtlas;
-use crate::classes::{FromObject, Texture2D};
+use crate::classes::{ClassID, FromObject, Texture2D};
 use crate::env::Object;
 use crate::error::UnityResult;
 use crate::math::{Matrix4x4, RectF32, Vector2, Vector3, Vector4};
 use crate::object::ObjectInfo;
 use crate::reader::{ByteOrder, Reader};
+use crate::version::{ReleaseType, UnityVersion};
 use crate::UnityError;
 use image::imageops::FilterType;
-use image::{DynamicImage, RgbaImage};
+use image::{GrayImage, Luma, RgbaImage};
 use imageproc::point::Point;
+use serde_json::json;
 use std::borrow::Cow;
 
 use super::mesh::BoneWeights4;
@@ -22,7 +24,7 @@ pub struct SecondarySpriteTexture<'a> {
 }
 
 impl<'a> SecondarySpriteTexture<'a> {
-    pub fn load(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         let texture = PPtr::load(object, r)?;
         let name = r.read_string_util_null()?;
         Ok(Self { texture, name })
@@ -40,7 +42,7 @@ impl SpriteVertex {
         let mut result = Self::default();
         let version = object.version;
         result.pos = r.read_vector3()?;
-        if version[0] < 4 || (version[0] == 4 && version[1] <= 3) {
+        if !version.is_at_least(4, 4) {
             result.uv = r.read_vector2()?;
         }
         Ok(result)
@@ -65,10 +67,12 @@ pub struct SpriteRenderData<'a> {
     pub setting_raw: SpriteSettings,
     pub uv_transform: Vector4,
     pub downscale_multiplier: f32,
+    /// The order of the raw index and vertex buffers, which follow the serialized file.
+    pub byte_order: ByteOrder,
 }
 
 impl<'a> SpriteRenderData<'a> {
-    pub fn load(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         let version = object.info.version;
         let mut result = Self {
             texture: PPtr::load(object, r)?,
@@ -87,17 +91,18 @@ impl<'a> SpriteRenderData<'a> {
             setting_raw: SpriteSettings::default(),
             uv_transform: Vector4::default(),
             downscale_multiplier: 1.0,
+            byte_order: r.get_order(),
         };
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 2) {
+        if version.is_at_least(5, 2) {
             result.alpha_texture = Some(PPtr::load(object, r)?);
         }
-        if version[0] >= 2019 {
+        if version.major >= 2019 {
             let size = r.read_i32()?;
             for _ in 0..size {
                 result.secondary_textures.push(SecondarySpriteTexture::load(object, r)?)
             }
         }
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 6) {
+        if version.is_at_least(5, 6) {
             let size = r.read_i32()?;
             for _ in 0..size {
                 result.sub_meshes.push(SubMesh::load(object.info, r)?)
@@ -115,10 +120,10 @@ impl<'a> SpriteRenderData<'a> {
             result.indices = r.read_u16_list(size as usize)?;
             r.align(4)?;
         }
-        if version[0] > 2018 {
+        if version.major > 2018 {
             let size = r.read_i32()?;
             result.bindpose = r.read_matrix4x4_list(size as usize)?;
-            if version[0] == 2018 && version[1] < 2 {
+            if version.major == 2018 && version.minor < 2 {
                 let size = r.read_i32()? as usize;
                 result.source_skin = Vec::with_capacity(size);
                 for _ in 0..size {
@@ -128,14 +133,14 @@ impl<'a> SpriteRenderData<'a> {
         }
         result.texture_rect = r.read_rect_f32()?;
         result.texture_rect_offset = r.read_vector2()?;
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 6) {
+        if version.is_at_least(5, 6) {
             result.atlas_rect_offset = r.read_vector2()?;
         }
         result.setting_raw = SpriteSettings::load(object.info, r)?;
-        if version[0] > 4 || (version[0] == 4 && version[1] >= 5) {
+        if version.is_at_least(4, 5) {
             result.uv_transform = r.read_vector4()?;
         }
-        if version[0] >= 2017 {
+        if version.major >= 2017 {
             result.downscale_multiplier = r.read_f32()?;
         }
         Ok(result)
@@ -147,16 +152,17 @@ impl<'a> SpriteRenderData<'a> {
             let vertices: Vec<_> = self.vertices.iter().map(|i| Vector2 { x: i.pos.x, y: i.pos.y }).collect();
             let triangle_count = self.indices.len() / 3;
             for i in 0..triangle_count {
-                let first = self.indices[3 * i] as usize;
-                let second = self.indices[3 * i + 1] as usize;
-                let third = self.indices[3 * i + 2] as usize;
-                result.push([vertices[first], vertices[second], vertices[third]])
+                let mut triangle = [Vector2::default(); 3];
+                for (point, index) in triangle.iter_mut().zip(&self.indices[3 * i..3 * i + 3]) {
+                    *point = *vertices.get(*index as usize).ok_or_else(|| UnityError::CustomError("sprite index out of range".to_string()))?;
+                }
+                result.push(triangle)
             }
         } else {
             let channel = &self.vertex_data.channels[0];
             let stream = &self.vertex_data.streams[channel.stream as usize];
-            let mut vertex_r = Reader::new(&self.vertex_data.data_size, ByteOrder::Little);
-            let mut index_r = Reader::new(&self.index_buffer, ByteOrder::Little);
+            let mut vertex_r = Reader::new(&self.vertex_data.data_size, self.byte_order);
+            let mut index_r = Reader::new(&self.index_buffer, self.byte_order);
             for sub_mesh in &self.sub_meshes {
                 let mut offset = stream.offset as usize;
                 offset += sub_mesh.first_vertex as usize * stream.stride as usize;
@@ -172,10 +178,12 @@ impl<'a> SpriteRenderData<'a> {
                 index_r.set_offset(sub_mesh.first_bytes as usize)?;
                 let triangle_count = sub_mesh.index_count as usize / 3;
                 for _ in 0..triangle_count {
-                    let first = index_r.read_u16()? - sub_mesh.first_bytes as u16;
-                    let second = index_r.read_u16()? - sub_mesh.first_bytes as u16;
-                    let third = index_r.read_u16()? - sub_mesh.first_bytes as u16;
-                    result.push([vertices[first as usize], vertices[second as usize], vertices[third as usize]])
+                    let mut triangle = [Vector2::default(); 3];
+                    for point in &mut triangle {
+                        let index = (index_r.read_u16()? as usize).checked_sub(sub_mesh.first_vertex as usize);
+                        *point = *index.and_then(|i| vertices.get(i)).ok_or_else(|| UnityError::CustomError("sprite index out of range".to_string()))?;
+                    }
+                    result.push(triangle)
                 }
             }
         }
@@ -183,7 +191,7 @@ impl<'a> SpriteRenderData<'a> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SpritePackingMode {
     Tight = 0,
     Rectangle = 1,
@@ -195,7 +203,8 @@ impl Default for SpritePackingMode {
     }
 }
 
-#[derive(Clone, Debug)]
+/// How the atlas packer transformed the sprite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SpritePackingRotation {
     None = 0,
     FlipHorizontal = 1,
@@ -209,7 +218,21 @@ impl Default for SpritePackingRotation {
         Self::None
     }
 }
-#[derive(Clone, Debug)]
+
+impl SpritePackingRotation {
+    /// Undoes the packing transform on an image cut from the atlas, top row first.
+    pub fn unpack(self, image: RgbaImage) -> RgbaImage {
+        match self {
+            SpritePackingRotation::None => image,
+            SpritePackingRotation::FlipHorizontal => image::imageops::flip_horizontal(&image),
+            SpritePackingRotation::FlipVertical => image::imageops::flip_vertical(&image),
+            SpritePackingRotation::Rotate180 => image::imageops::rotate180(&image),
+            SpritePackingRotation::Rotate90 => image::imageops::rotate90(&image),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SpriteMeshType {
     FullRect = 0,
     Tight = 1,
@@ -221,55 +244,52 @@ impl Default for SpriteMeshType {
     }
 }
 
-#[allow(dead_code)]
+/// The `settingsRaw` bit field of sprite render data.
 #[derive(Clone, Debug, Default)]
 pub struct SpriteSettings {
-    raw: u32,
-    packed: bool,
-    packing_mode: SpritePackingMode,
-    packing_rotation: SpritePackingRotation,
-    mesh_type: SpriteMeshType,
+    pub raw: u32,
+    pub packed: bool,
+    pub packing_mode: SpritePackingMode,
+    pub packing_rotation: SpritePackingRotation,
+    pub mesh_type: SpriteMeshType,
 }
 
 impl SpriteSettings {
     pub fn load(_object: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
-        let raw = r.read_u32()?;
+        Ok(Self::from_raw(r.read_u32()?))
+    }
+
+    pub fn from_raw(raw: u32) -> Self {
         let packed = raw & 1 == 1;
-        let packing_mode = match (raw >> 1) & 1 {
-            0 => SpritePackingMode::Tight,
-            1 => SpritePackingMode::Rectangle,
-            _ => unreachable!(),
-        };
+        let packing_mode = if (raw >> 1) & 1 == 0 { SpritePackingMode::Tight } else { SpritePackingMode::Rectangle };
         let packing_rotation = match (raw >> 2) & 0xf {
-            0 => SpritePackingRotation::None,
             1 => SpritePackingRotation::FlipHorizontal,
             2 => SpritePackingRotation::FlipVertical,
             3 => SpritePackingRotation::Rotate180,
             4 => SpritePackingRotation::Rotate90,
-            _ => unreachable!(),
-        };
-        let mesh_type = match (raw >> 6) & 1 {
-            0 => SpriteMeshType::FullRect,
-            1 => SpriteMeshType::Tight,
-            _ => unreachable!(),
+            _ => SpritePackingRotation::None,
         };
-        Ok(Self {
+        let mesh_type = if (raw >> 6) & 1 == 0 { SpriteMeshType::FullRect } else { SpriteMeshType::Tight };
+        Self {
             raw,
             packed,
             packing_mode,
             packing_rotation,
             mesh_type,
-        })
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct Sprite<'a> {
     pub name: String,
+    /// The sprite's area in its source texture, in pixels from the bottom left corner.
     pub rect: RectF32,
     pub offset: Vector2,
+    /// 9-slice margins in pixels as `(left, bottom, right, top)`, serialized since 4.5.
     pub border: Option<Vector4>,
     pub pixels_to_units: f32,
+    /// Normalized within `rect`, `(0.5, 0.5)` being the center; always the center before 5.4.2.
     pub pivot: Vector2,
     pub extrude: u8,
     pub is_polygon: bool,
@@ -280,7 +300,7 @@ pub struct Sprite<'a> {
 }
 
 impl<'a> FromObject<'a> for Sprite<'a> {
-    fn load(object: &'a Object) -> UnityResult<Self> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
         let version = object.info.version;
 
         let mut border: Option<Vector4> = None;
@@ -292,23 +312,23 @@ impl<'a> FromObject<'a> for Sprite<'a> {
         let mut atlas_tags: Vec<String> = Vec::new();
         let mut sprite_atlas: Option<PPtr<SpriteAtlas>> = None;
 
-        let mut r = object.info.get_reader();
+        let mut r = object.info.get_reader()?;
         let name: String = r.read_aligned_string()?;
         let rect: RectF32 = r.read_rect_f32()?;
         let offset: Vector2 = r.read_vector2()?;
-        if version[0] > 4 || (version[0] == 4 && version[1] >= 5) {
+        if version.is_at_least(4, 5) {
             border = Some(r.read_vector4()?);
         }
         let pixels_to_units: f32 = r.read_f32()?;
-        if version[0] > 5 || (version[0] == 5 && version[1] > 4) || (version[0] == 5 && version[1] == 4 && version[2] >= 2) || (version[0] == 5 && version[1] == 4 && version[2] == 1 && object.info.build_type.is_patch() && version[3] >= 3) {
+        if version >= UnityVersion::new(5, 4, 1, ReleaseType::Patch, 3) {
             pivot = r.read_vector2()?;
         }
         let extrude: u8 = r.read_u32()? as u8;
-        if version[0] > 5 || (version[0] == 5 && version[1] >= 3) {
+        if version.is_at_least(5, 3) {
             is_polygon = r.read_bool()?;
             r.align(4)?;
         }
-        if version[0] >= 2017
+        if version.major >= 2017
         //2017 and up
         {
             let first = r.read_u8_array()?;
@@ -339,90 +359,148 @@ impl<'a> FromObject<'a> for Sprite<'a> {
     }
 }
 
-impl Sprite<'_> {
-    pub fn decode_image(&self) -> UnityResult<RgbaImage> {
+impl<'a> Sprite<'a> {
+    /// The atlas packing this sprite: `m_SpriteAtlas` when set, otherwise a loaded atlas whose
+    /// render data map contains this sprite's key.
+    pub fn atlas(&self) -> Option<Object<'a>> {
         if let Some(sprite_atlas) = self.sprite_atlas.as_ref().and_then(|x| x.get_obj()) {
-            if let Some(sprite_atlas_data) = sprite_atlas.read::<SpriteAtlas>()?.render_data_map.get(&self.render_data_key) {
-                if let Some(texture2d) = sprite_atlas_data.texture.get_obj() {
-                    let texture2d = texture2d.read()?;
-                    let rect = sprite_atlas_data.texture_rect;
-                    let offset = sprite_atlas_data.texture_rect_offset;
-                    let downscale_multiplier = sprite_atlas_data.downscale_multiplier;
-                    let setting = sprite_atlas_data.settings_raw.clone();
-                    return self.cut_image(&texture2d, rect, offset, downscale_multiplier, &setting);
-                }
-            }
+            return Some(sprite_atlas);
         }
-        if let Some(texture2d) = self.rd.texture.get_obj() {
-            let texture2d = texture2d.read()?;
-            return self.cut_image(&texture2d, self.rd.texture_rect, self.rd.texture_rect_offset, self.rd.downscale_multiplier, &self.rd.setting_raw);
+        if self.atlas_tags.is_empty() {
+            return None;
         }
-        Err(UnityError::Unimplemented)
+        self.rd.texture.env().objects().filter(|x| x.class() == ClassID::SpriteAtlas).find(|x| x.read::<SpriteAtlas>().is_ok_and(|atlas| atlas.render_data(&self.render_data_key).is_some()))
+    }
+
+    /// The metadata needed to rebuild the sprite in a UI, next to the file name `image` it was exported as.
+    pub fn to_metadata_json(&self, image: &str) -> String {
+        let border = self.border.map(|b| json!({ "left": b.x, "bottom": b.y, "right": b.z, "top": b.w }));
+        let metadata = json!({
+            "name": self.name,
+            "image": image,
+            "rect": { "x": self.rect.x, "y": self.rect.y, "width": self.rect.w, "height": self.rect.h },
+            "offset": { "x": self.offset.x, "y": self.offset.y },
+            "pivot": { "x": self.pivot.x, "y": self.pivot.y },
+            "border": border,
+            "pixels_per_unit": self.pixels_to_units,
+        });
+        metadata.to_string()
     }
-    fn cut_image(&self, texture2d: &Texture2D, rect: RectF32, _offset: Vector2, downscale_multiplier: f32, setting: &SpriteSettings) -> UnityResult<RgbaImage> {
-        let origin_image = texture2d.decode_image()?;
-        let mut origin_image = Cow::Borrowed(&*origin_image);
+
+    /// Cuts the sprite out of its texture, using the atlas render data for atlas-packed sprites.
+    ///
+    /// The result has the size of `m_Rect`; a `textureRect` trimmed smaller than that is placed at
+    /// `textureRectOffset` and the rest is left transparent.
+    ///
+    /// `m_Rect` is already measured in pixels of the source texture, so it is not scaled again by
+    /// `m_PixelsToUnits`, which only relates those pixels to world units for the mesh vertices.
+    /// `atlasRectOffset` is not needed either: it is where `m_Rect` would start in the atlas, which
+    /// is `textureRect`'s position less `textureRectOffset`.
+    pub fn decode_image(&self) -> UnityResult<RgbaImage> {
+        let atlas = self.atlas().map(|x| x.read::<SpriteAtlas>()).transpose()?;
+        let source = match atlas.as_ref().and_then(|x| x.render_data(&self.render_data_key)) {
+            Some(data) => CutSource {
+                texture: &data.texture,
+                alpha_texture: Some(&data.alpha_texture),
+                texture_rect: data.texture_rect,
+                texture_rect_offset: data.texture_rect_offset,
+                downscale_multiplier: data.downscale_multiplier,
+                settings: &data.settings_raw,
+            },
+            None => CutSource {
+                texture: &self.rd.texture,
+                alpha_texture: self.rd.alpha_texture.as_ref(),
+                texture_rect: self.rd.texture_rect,
+                texture_rect_offset: self.rd.texture_rect_offset,
+                downscale_multiplier: self.rd.downscale_multiplier,
+                settings: &self.rd.setting_raw,
+            },
+        };
+        let texture2d = source.texture.get_obj().ok_or_else(|| UnityError::CustomError(format!("can not find sprite texture {:?}", source.texture)))?;
+        self.cut_image(&texture2d.read()?, &source)
+    }
+
+    fn cut_image(&self, texture2d: &Texture2D, source: &CutSource) -> UnityResult<RgbaImage> {
+        let alpha_texture = source.alpha_texture.and_then(|x| x.get_obj()).map(|x| x.read::<Texture2D>()).transpose()?;
+        let merged = alpha_texture.map(|alpha| texture2d.merge_alpha(&alpha)).transpose()?;
+        let decoded;
+        let mut origin_image = match &merged {
+            Some(merged) => Cow::Borrowed(merged),
+            None => {
+                decoded = texture2d.decode_image()?;
+                Cow::Borrowed(&*decoded)
+            }
+        };
+        let downscale_multiplier = source.downscale_multiplier;
         if downscale_multiplier > 0.0 && downscale_multiplier != 1.0 {
-            let w = ((texture2d.width as f32) / downscale_multiplier) as u32;
-            let h = ((texture2d.height as f32) / downscale_multiplier) as u32;
+            let w = ((texture2d.width as f32) / downscale_multiplier).round() as u32;
+            let h = ((texture2d.height as f32) / downscale_multiplier).round() as u32;
             origin_image = Cow::Owned(image::imageops::resize(origin_image.as_ref(), w, h, FilterType::Nearest));
         }
-        let rect_x = rect.x.floor() as u32;
-        let rect_y = rect.y.floor() as u32;
-        let rect_w = rect.w.floor() as u32;
-        let rect_h = rect.h.floor() as u32;
-        let rect_w = rect_w.min(origin_image.width());
-        let rect_h = rect_h.min(origin_image.height());
-        let sprite_image = image::imageops::crop_imm(origin_image.as_ref(), rect_x, origin_image.height() - rect_y - rect_h, rect_w, rect_h).to_image();
-        let mut sprite_image = DynamicImage::ImageRgba8(sprite_image);
-        if setting.packed {
-            match setting.packing_rotation {
-                SpritePackingRotation::None => (),
-                SpritePackingRotation::FlipHorizontal => image::imageops::flip_horizontal_in_place(&mut sprite_image),
-                SpritePackingRotation::FlipVertical => image::imageops::flip_vertical_in_place(&mut sprite_image),
-                SpritePackingRotation::Rotate180 => image::imageops::rotate180_in_place(&mut sprite_image),
-                SpritePackingRotation::Rotate90 => sprite_image = sprite_image.rotate270(),
-            }
-        }
-        if let SpritePackingMode::Tight = setting.packing_mode {
-            let mut points = self.rd.get_triangles()?;
-            let mut min_x: Option<f32> = None;
-            let mut min_y: Option<f32> = None;
-            for i in &points {
-                for j in i {
-                    match min_x {
-                        Some(m) => min_x = Some(m.min(j.x)),
-                        None => min_x = Some(j.x),
-                    }
-                    match min_y {
-                        Some(m) => min_y = Some(m.min(j.y)),
-                        None => min_y = Some(j.y),
-                    }
-                }
-            }
-            let min_x = min_x.unwrap_or_default();
-            let min_y = min_y.unwrap_or_default();
-            for i in &mut points {
-                for j in i {
-                    j.x = (j.x - min_x) * self.pixels_to_units;
-                    j.y = (j.y - min_y) * self.pixels_to_units;
+        // `textureRect` is measured from the bottom left corner; widen it to whole pixels.
+        let rect = source.texture_rect;
+        let (width, height) = origin_image.dimensions();
+        let left = (rect.x.floor().max(0.0) as u32).min(width);
+        let bottom = (rect.y.floor().max(0.0) as u32).min(height);
+        let right = ((rect.x + rect.w).ceil().max(0.0) as u32).clamp(left, width);
+        let top = ((rect.y + rect.h).ceil().max(0.0) as u32).clamp(bottom, height);
+        let sprite_image = image::imageops::crop_imm(origin_image.as_ref(), left, height - top, right - left, top - bottom).to_image();
+        let setting = source.settings;
+        let sprite_image = if setting.packed { setting.packing_rotation.unpack(sprite_image) } else { sprite_image };
+        let mut sprite_image = self.place_in_rect(sprite_image, source.texture_rect_offset);
+        // Tight packed sprites overlap in the atlas; keep only the pixels covered by the sprite mesh.
+        if setting.packing_mode == SpritePackingMode::Tight {
+            let mask = self.mesh_mask(sprite_image.width(), sprite_image.height())?;
+            for (pixel, mask) in sprite_image.pixels_mut().zip(mask.pixels()) {
+                if mask.0[0] == 0 {
+                    *pixel = image::Rgba([0, 0, 0, 0]);
                 }
             }
-            let mut mask = image::GrayImage::from_pixel(sprite_image.width(), sprite_image.height(), image::Luma([0]));
-            for i in points {
-                let poly = [
-                    Point::new(i[0].x.round() as i32, rect_h as i32 - i[0].y.round() as i32),
-                    Point::new(i[1].x.round() as i32, rect_h as i32 - i[1].y.round() as i32),
-                    Point::new(i[2].x.round() as i32, rect_h as i32 - i[2].y.round() as i32),
-                ];
-                if poly[0] == poly[2] {
-                    continue;
-                }
-                imageproc::drawing::draw_polygon_mut(&mut mask, &poly, image::Luma([255]))
+        }
+        Ok(sprite_image)
+    }
+
+    /// Rasterizes the render data triangles into a `width` x `height` mask of the sprite rect.
+    ///
+    /// Vertices are in units relative to the pivot, so they are scaled by `m_PixelsToUnits` and moved
+    /// to the pivot's pixel position before the image's top down rows are flipped in.
+    fn mesh_mask(&self, width: u32, height: u32) -> UnityResult<GrayImage> {
+        let mut mask = GrayImage::new(width, height);
+        let pivot_x = self.rect.w * self.pivot.x;
+        let pivot_y = self.rect.h * self.pivot.y;
+        let to_point = |v: &Vector2| Point::new((v.x * self.pixels_to_units + pivot_x).round() as i32, height as i32 - (v.y * self.pixels_to_units + pivot_y).round() as i32);
+        for triangle in self.rd.get_triangles()? {
+            let poly = [to_point(&triangle[0]), to_point(&triangle[1]), to_point(&triangle[2])];
+            // `draw_polygon_mut` rejects polygons that are closed explicitly.
+            if poly[0] == poly[2] {
+                continue;
             }
-            let img = imageproc::map::map_colors2(&sprite_image, &mask, |a, b| if b.0[0] != 0 { image::Rgba([a.0[0], a.0[1], a.0[2], a.0[3]]) } else { image::Rgba([0, 0, 0, 0]) });
-            return Ok(img);
+            imageproc::drawing::draw_polygon_mut(&mut mask, &poly, Luma([255]));
         }
-        Ok(sprite_image.into_rgba8())
+        Ok(mask)
     }
+
+    /// Pads a trimmed cut out to the size of `m_Rect`, `offset` being measured from its bottom left corner.
+    fn place_in_rect(&self, image: RgbaImage, offset: Vector2) -> RgbaImage {
+        let width = (self.rect.w.round().max(0.0) as u32).max(image.width());
+        let height = (self.rect.h.round().max(0.0) as u32).max(image.height());
+        if (width, height) == image.dimensions() {
+            return image;
+        }
+        let x = (offset.x.round().max(0.0) as u32).min(width - image.width());
+        let y = (offset.y.round().max(0.0) as u32).min(height - image.height());
+        let mut result = RgbaImage::new(width, height);
+        image::imageops::replace(&mut result, &image, x as i64, (height - image.height() - y) as i64);
+        result
+    }
+}
+
+/// The render data an image is cut with: the atlas entry for atlas-packed sprites, otherwise `m_RD`.
+struct CutSource<'s, 'a> {
+    texture: &'s PPtr<'a, Texture2D>,
+    alpha_texture: Option<&'s PPtr<'a, Texture2D>>,
+    texture_rect: RectF32,
+    texture_rect_offset: Vector2,
+    downscale_multiplier: f32,
+    settings: &'s SpriteSettings,
 }
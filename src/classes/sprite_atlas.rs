@@ -20,18 +20,18 @@ pub struct SpriteAtlasData<'a> {
 }
 
 impl<'a> SpriteAtlasData<'a> {
-    pub fn load(object: &'a Object, r: &mut Reader) -> UnityResult<Self> {
+    pub fn load(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         let version = object.info.version;
         let texture = PPtr::load(object, r)?;
         let alpha_texture = PPtr::load(object, r)?;
         let texture_rect = r.read_rect_f32()?;
         let texture_rect_offset = r.read_vector2()?;
-        let atlas_rect_offset = if version[0] > 2017 || (version[0] == 2017 && version[1] >= 2) { r.read_vector2()? } else { Vector2::default() };
+        let atlas_rect_offset = if version.is_at_least(2017, 2) { r.read_vector2()? } else { Vector2::default() };
         let uv_transform = r.read_vector4()?;
         let downscale_multiplier = r.read_f32()?;
         let settings_raw = SpriteSettings::load(object.info, r)?;
         let mut secondary_textures = Vec::new();
-        if version[0] > 2020 || (version[0] == 2020 && version[1] >= 2) {
+        if version.is_at_least(2020, 2) {
             for _ in 0..r.read_i32()? {
                 secondary_textures.push(SecondarySpriteTexture::load(object, r)?)
             }
@@ -53,18 +53,21 @@ impl<'a> SpriteAtlasData<'a> {
 pub struct SpriteAtlas<'a> {
     pub name: String,
     pub packed_sprites: Vec<PPtr<'a, Sprite<'a>>>,
+    /// Sprite names, parallel to `packed_sprites`.
+    pub packed_sprite_names_to_index: Vec<String>,
     pub render_data_map: HashMap<([u8; 16], i64), SpriteAtlasData<'a>>,
+    pub tag: String,
     pub is_variant: bool,
 }
 impl<'a> FromObject<'a> for SpriteAtlas<'a> {
-    fn load(object: &'a Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
         let name = r.read_aligned_string()?;
         let mut packed_sprites = Vec::new();
         for _ in 0..r.read_i32()? {
             packed_sprites.push(PPtr::load(object, &mut r)?);
         }
-        let _packed_sprite_names_to_index = r.read_string_list()?;
+        let packed_sprite_names_to_index = r.read_string_list()?;
         let render_data_map_size = r.read_i32()?;
         let mut render_data_map = HashMap::new();
         for _ in 0..render_data_map_size {
@@ -73,12 +76,14 @@ impl<'a> FromObject<'a> for SpriteAtlas<'a> {
             let value = SpriteAtlasData::load(object, &mut r)?;
             render_data_map.insert((first, second), value);
         }
-        let _tag = r.read_aligned_string()?;
+        let tag = r.read_aligned_string()?;
         let is_variant = r.read_bool()?;
         Ok(Self {
             name,
             packed_sprites,
+            packed_sprite_names_to_index,
             render_data_map,
+            tag,
             is_variant,
         })
     }
@@ -87,3 +92,18 @@ impl<'a> FromObject<'a> for SpriteAtlas<'a> {
         super::ClassID::SpriteAtlas
     }
 }
+
+impl<'a> SpriteAtlas<'a> {
+    pub fn index_of(&self, sprite_name: &str) -> Option<usize> {
+        self.packed_sprite_names_to_index.iter().position(|name| name == sprite_name)
+    }
+
+    pub fn packed_sprite(&self, sprite_name: &str) -> Option<&PPtr<'a, Sprite<'a>>> {
+        self.packed_sprites.get(self.index_of(sprite_name)?)
+    }
+
+    /// Looks up the packing data of a sprite by its `render_data_key`.
+    pub fn render_data(&self, key: &([u8; 16], i64)) -> Option<&SpriteAtlasData<'a>> {
+        self.render_data_map.get(key)
+    }
+}
@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use image::{ImageBuffer, Luma};
+use serde::Deserialize;
+
+use crate::classes::FromObject;
+use crate::env::Object;
+use crate::error::{UnityError, UnityResult};
+use crate::math::Vector2;
+
+use super::math::RawVector2;
+use super::pptr::{PPtr, RawPPtr};
+use super::texture2d::Texture2D;
+
+/// Heights are stored as `0..=32766`, mapping to `0..=1` of the terrain height.
+pub const MAX_HEIGHT: u16 = 32766;
+
+#[derive(Deserialize)]
+struct RawSplatPrototype {
+    texture: RawPPtr,
+    #[serde(rename = "normalMap")]
+    normal_map: Option<RawPPtr>,
+    #[serde(rename = "tileSize")]
+    tile_size: RawVector2,
+    #[serde(rename = "tileOffset")]
+    tile_offset: Option<RawVector2>,
+}
+
+#[derive(Deserialize)]
+struct RawSplatDatabase {
+    #[serde(rename = "m_TerrainLayers", default)]
+    terrain_layers: Vec<RawPPtr>,
+    #[serde(rename = "m_Splats", default)]
+    splats: Vec<RawSplatPrototype>,
+    #[serde(rename = "m_AlphaTextures")]
+    alpha_textures: Vec<RawPPtr>,
+    #[serde(rename = "m_AlphamapResolution")]
+    alphamap_resolution: i32,
+}
+
+#[derive(Deserialize)]
+struct RawHeightmap {
+    #[serde(rename = "m_Heights")]
+    heights: Vec<i16>,
+    #[serde(rename = "m_Resolution")]
+    resolution: Option<i32>,
+    #[serde(rename = "m_Width")]
+    width: Option<i32>,
+    #[serde(rename = "m_Height")]
+    height: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct RawTerrainData {
+    #[serde(rename = "m_Name")]
+    name: String,
+    #[serde(rename = "m_SplatDatabase")]
+    splat_database: RawSplatDatabase,
+    #[serde(rename = "m_Heightmap")]
+    heightmap: RawHeightmap,
+}
+
+/// A pre-2018.3 splat definition; newer terrains reference TerrainLayer assets instead.
+pub struct SplatPrototype<'a> {
+    pub texture: PPtr<'a, Texture2D>,
+    pub normal_map: PPtr<'a, Texture2D>,
+    pub tile_size: Vector2,
+    pub tile_offset: Vector2,
+}
+
+/// The shape and paint of a terrain: its heightmap, and the splat maps blending the textures or
+/// TerrainLayers drawn on it.
+///
+/// There is no binary reader or [`builtin_nodes`](crate::typetree::builtin_nodes) template, so the
+/// object needs a type tree: asset bundles carry them unless built with `DisableWriteTypeTree`,
+/// while the level and sharedassets files of a player build do not and fail with
+/// [`MissingTypeTree`](crate::object::ReadTypeTreeError::MissingTypeTree).
+/// Heightmaps sized by `m_Width` and `m_Height` or by the square `m_Resolution` of 2019.3 on both
+/// read, as do `m_Splats` and the `m_TerrainLayers` that replace them in 2018.3.
+pub struct TerrainData<'a> {
+    pub name: String,
+    pub heightmap_width: u32,
+    pub heightmap_height: u32,
+    /// Row major from the terrain's back (z = 0) edge, in `0..=MAX_HEIGHT`.
+    pub heights: Vec<u16>,
+    pub alphamap_resolution: i32,
+    /// RGBA splat weights, four layers per texture.
+    pub alpha_textures: Vec<PPtr<'a, Texture2D>>,
+    /// TerrainLayer assets (2018.3+).
+    pub terrain_layers: Vec<PPtr<'a, Object<'a>>>,
+    pub splats: Vec<SplatPrototype<'a>>,
+}
+
+impl<'a> FromObject<'a> for TerrainData<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let raw: RawTerrainData = object.read_type_tree()?;
+        let heightmap = raw.heightmap;
+        let (width, height) = match (heightmap.resolution, heightmap.width, heightmap.height) {
+            (Some(resolution), _, _) => (resolution, resolution),
+            (None, Some(width), Some(height)) => (width, height),
+            _ => return Err(UnityError::CustomError("TerrainData heightmap has no size".to_string())),
+        };
+        let (width, height) = (width.max(0) as u32, height.max(0) as u32);
+        let count = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or_else(|| UnityError::CustomError(format!("TerrainData heightmap of {}x{} is too large", width, height)))?;
+        if heightmap.heights.len() != count {
+            return Err(UnityError::CustomError(format!("TerrainData heightmap has {} heights, expected {}x{}", heightmap.heights.len(), width, height)));
+        }
+        let heights = heightmap.heights.iter().map(|&x| x.clamp(0, MAX_HEIGHT as i16) as u16).collect();
+        let database = raw.splat_database;
+        Ok(Self {
+            name: raw.name,
+            heightmap_width: width,
+            heightmap_height: height,
+            heights,
+            alphamap_resolution: database.alphamap_resolution,
+            alpha_textures: database.alpha_textures.iter().map(|x| x.bind(object)).collect(),
+            terrain_layers: database.terrain_layers.iter().map(|x| x.bind(object)).collect(),
+            splats: database
+                .splats
+                .iter()
+                .map(|x| SplatPrototype {
+                    texture: x.texture.bind(object),
+                    normal_map: x.normal_map.as_ref().map_or(PPtr::new(object.env, 0, 0), |n| n.bind(object)),
+                    tile_size: x.tile_size.to_vector2(),
+                    tile_offset: x.tile_offset.as_ref().map(RawVector2::to_vector2).unwrap_or_default(),
+                })
+                .collect(),
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::TerrainData
+    }
+}
+
+impl TerrainData<'_> {
+    pub fn heightmap(&self) -> (u32, u32, Vec<u16>) {
+        (self.heightmap_width, self.heightmap_height, self.heights.clone())
+    }
+
+    /// The heightmap rescaled to the full 16 bit range, flipped so the terrain's far edge is at the top.
+    pub fn heightmap_image16(&self) -> ImageBuffer<Luma<u16>, Vec<u16>> {
+        let (width, height) = (self.heightmap_width, self.heightmap_height);
+        ImageBuffer::from_fn(width, height, |x, y| {
+            let value = self.heights[(height - 1 - y) as usize * width as usize + x as usize] as u32;
+            Luma([(value * u16::MAX as u32 / MAX_HEIGHT as u32) as u16])
+        })
+    }
+
+    pub fn export_heightmap_png16(&self, path: impl AsRef<Path>) -> UnityResult<()> {
+        self.heightmap_image16().save_with_format(path, image::ImageFormat::Png).map_err(|e| UnityError::CustomError(e.to_string()))
+    }
+}
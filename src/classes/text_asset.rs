@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::classes::FromObject;
 use crate::env::Object;
 use crate::error::UnityResult;
@@ -10,8 +12,8 @@ pub struct TextAsset {
 
 impl FromObject<'_> for TextAsset {
     fn load(object: &Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
-        let name = r.read_aligned_string()?;
+        let mut r = object.info.get_reader()?;
+        let name = object.env.decode_string(r.read_aligned_string_raw()?);
         let length = r.read_i32()?;
         let script = r.read_u8_list(length as usize)?;
         Ok(Self { name, script, path_id: object.info.path_id })
@@ -26,4 +28,9 @@ impl TextAsset {
     pub fn script_string(&self) -> UnityResult<&str> {
         Ok(std::str::from_utf8(&self.script)?)
     }
+
+    /// The script as text, replacing what is not UTF-8 instead of failing.
+    pub fn script_string_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.script)
+    }
 }
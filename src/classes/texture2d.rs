@@ -3,17 +3,25 @@ use crate::classes::FromObject;
 use crate::env::Object;
 use crate::error::{UnityError, UnityResult};
 use crate::object::ObjectInfo;
-use crate::reader::{ByteOrder, Reader};
+use crate::reader::Reader;
 use dashmap::mapref::one::Ref;
 use dashmap::DashMap;
+use image::imageops::FilterType;
 use image::{ImageBuffer, Rgba, RgbaImage};
 use num_enum::FromPrimitive;
 
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::sync::Arc;
 use texture_decoder::implements::{Alpha8, RFloat, RGB9e5Float, RGBAFloat, RGBAHalf, RGFloat, RGHalf, RHalf, ARGB32, ARGB4444, BGRA32, DXT1, DXT5, R16, R8, RG16, RGB24, RGB565, RGBA32, RGBA4444, YUY2};
 use texture_decoder::{ImageSize, Texture2DDecoder};
 
+/// The largest width and height Unity gives a texture.
+const MAX_TEXTURE_SIZE: i32 = 16384;
+/// The most pixels a byte of image data holds among the decoded formats, in ASTC 12x12 blocks
+/// of 16 bytes.
+const MAX_PIXELS_PER_BYTE: usize = 9;
+
 #[allow(non_camel_case_types, non_upper_case_globals)]
 #[derive(Debug, Eq, PartialEq, FromPrimitive, Clone, Copy, Default)]
 #[repr(i32)]
@@ -108,7 +116,7 @@ impl GLTextureSettings {
             mip_bias: r.read_f32()?,
             ..Self::default()
         };
-        if object_info.version[0] >= 2017 {
+        if object_info.version.major >= 2017 {
             result.wrap_mode = r.read_i32()?;
             let _wrap_w = r.read_i32()?;
             let _wrap_h = r.read_i32()?;
@@ -129,7 +137,7 @@ pub struct StreamingInfo {
 impl StreamingInfo {
     pub fn load(object_info: &ObjectInfo, r: &mut Reader) -> UnityResult<Self> {
         let mut result = Self::default();
-        if object_info.version[0] >= 2020 {
+        if object_info.version.major >= 2020 {
             result.offset = r.read_u64()?;
         } else {
             result.offset = r.read_u32()? as u64;
@@ -166,7 +174,7 @@ pub struct Texture2D {
 
 impl FromObject<'_> for Texture2D {
     fn load(object: &Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+        let mut r = object.info.get_reader()?;
         let mut result = Self {
             cache: object.cache.clone(),
             path_id: object.info.path_id,
@@ -175,10 +183,10 @@ impl FromObject<'_> for Texture2D {
             ..Self::default()
         };
         let version = &object.info.version;
-        if version[0] > 2017 || (version[0] == 2017 && version[1] >= 3) {
+        if version.is_at_least(2017, 3) {
             result.forced_fallback_format = r.read_i32()?;
             result.downscale_fallback = r.read_bool()?;
-            if version[0] > 2020 || (version[0] == 2020 && version[1] >= 2) {
+            if version.is_at_least(2020, 2) {
                 let _is_alpha_channel_optional = r.read_bool()?;
             }
             r.align(4)?;
@@ -186,55 +194,55 @@ impl FromObject<'_> for Texture2D {
         result.width = r.read_i32()?;
         result.height = r.read_i32()?;
         result.complete_image_size = r.read_i32()?;
-        if object.info.version[0] >= 2020 {
+        if object.info.version.major >= 2020 {
             let _mips_stripped = r.read_i32()?;
         }
         result.format = TextureFormat::from(r.read_i32()?);
         let mut _mip_map = false;
-        if object.info.version[0] < 5 || (object.info.version[0] == 5 && object.info.version[1] < 2) {
+        if !object.info.version.is_at_least(5, 2) {
             _mip_map = r.read_bool()?;
         } else {
             result.mip_count = r.read_i32()?;
         }
-        if version[0] > 2 || (version[0] == 2 && version[1] >= 6) {
+        if version.is_at_least(2, 6) {
             result.is_read_able = r.read_bool()?;
         }
-        if version[0] >= 2020 {
+        if version.major >= 2020 {
             let _is_pre_processed = r.read_bool()?;
         }
-        if version[0] > 2019 || (version[0] == 2019 && version[1] >= 3) {
+        if version.is_at_least(2019, 3) {
             let _is_ignore_master_texture_limit = r.read_bool()?;
         }
-        if version[0] >= 2022 && version[1] >= 2 {
+        if version.is_at_least(2022, 2) {
             r.align(4)?;
             let _mipmap_limit_group_name = r.read_aligned_string();
         }
-        if version[0] >= 3 && (version[0] < 5 || (version[0] == 5 && version[1] <= 4)) {
+        if version.is_at_least(3, 0) && !version.is_at_least(5, 5) {
             let _read_allowed = r.read_bool()?;
         }
-        if version[0] > 2018 || (version[0] == 2018 && version[1] >= 2) {
+        if version.is_at_least(2018, 2) {
             let _streaming_mip_maps = r.read_bool()?;
         }
         r.align(4)?;
-        if version[0] > 2018 || (version[0] == 2018 && version[1] >= 2) {
+        if version.is_at_least(2018, 2) {
             let _streaming_mip_maps_priority = r.read_i32()?;
         }
         result.image_count = r.read_i32()?;
         result.texture_dimension = r.read_i32()?;
         result.texture_setting = GLTextureSettings::load(object.info, &mut r)?;
-        if version[0] >= 3 {
+        if version.major >= 3 {
             result.light_map_format = r.read_i32()?;
         }
-        if version[0] > 3 || (version[0] == 3 && version[1] >= 5) {
+        if version.is_at_least(3, 5) {
             result.color_space = r.read_i32()?;
         }
-        if version[0] > 2020 || (version[0] == 2020 && version[1] >= 2) {
+        if version.is_at_least(2020, 2) {
             let length = r.read_i32()?;
             let _platform_blob = r.read_u8_slice(length as usize)?;
             r.align(4)?;
         }
         result.size = r.read_i32()?;
-        if result.size == 0 && ((version[0] == 5 && version[1] >= 3) || version[0] > 5) {
+        if result.size == 0 && version.is_at_least(5, 3) {
             result.stream_info = StreamingInfo::load(object.info, &mut r)?;
         }
         if result.stream_info.path.is_empty() {
@@ -242,9 +250,9 @@ impl FromObject<'_> for Texture2D {
         } else {
             let name = result.stream_info.path.split('/').next_back().ok_or(UnityError::InvalidValue)?;
             if let Some(buf) = object.env.get_loaded_file(name) {
-                let mut r = Reader::new(buf.as_slice(), ByteOrder::Big);
-                r.set_offset(result.stream_info.offset as usize)?;
-                result.data = r.read_u8_list(result.stream_info.size as usize)?;
+                let offset = usize::try_from(result.stream_info.offset).map_err(|_| UnityError::Eof)?;
+                let end = offset.checked_add(result.stream_info.size as usize).ok_or(UnityError::Eof)?;
+                result.data = buf.read(offset..end)?.to_vec();
             }
         }
         Ok(result)
@@ -263,12 +271,34 @@ impl Texture2D {
         Ok(self.cache.entry(self.path_id).insert(img).downgrade())
     }
 
+    /// Replaces this texture's alpha with a companion alpha texture, as shipped next to ETC1 textures.
+    ///
+    /// The alpha comes from `alpha`'s red channel, or its alpha channel for `Alpha8` textures, and is
+    /// scaled when the companion has a different resolution (commonly half size).
+    pub fn merge_alpha(&self, alpha: &Texture2D) -> UnityResult<RgbaImage> {
+        let mut color = self.decode_image()?.clone();
+        let alpha_image = alpha.decode_image()?;
+        let mut alpha_image = Cow::Borrowed(&*alpha_image);
+        if alpha_image.dimensions() != color.dimensions() {
+            alpha_image = Cow::Owned(image::imageops::resize(alpha_image.as_ref(), color.width(), color.height(), FilterType::Triangle));
+        }
+        let channel = if alpha.format == TextureFormat::Alpha8 { 3 } else { 0 };
+        for (pixel, source) in color.pixels_mut().zip(alpha_image.pixels()) {
+            pixel.0[3] = source.0[channel];
+        }
+        Ok(color)
+    }
+
     pub fn decode_image_without_cache(&self) -> UnityResult<RgbaImage> {
         let width = self.width;
         let height = self.height;
         if width <= 0 || height <= 0 {
             return Err(UnityError::ZeroSizeImage);
         }
+        // Checked before the image is allocated, so that a malformed size cannot exhaust memory.
+        if width > MAX_TEXTURE_SIZE || height > MAX_TEXTURE_SIZE || width as usize * height as usize > self.data.len().saturating_mul(MAX_PIXELS_PER_BYTE) {
+            return Err(UnityError::CustomError(format!("a {}x{} texture cannot be decoded from {} bytes", width, height, self.data.len())));
+        }
         let format = self.format;
         let size = ImageSize::new(width as usize, height as usize);
         let mut result: ImageBuffer<Rgba<u8>, Vec<u8>> = ImageBuffer::new(width as u32, height as u32);
@@ -398,7 +428,7 @@ impl Texture2D {
                 texture2ddecoder::decode_bc7(&self.data, width as usize, height as usize, image)?;
                 Ok(result)
             }
-            TextureFormat::DXT5 => DXT5::decode(&self.data, width as u32, height as u32).map_err(|_| UnityError::InvalidValue),
+            TextureFormat::DXT5 => DXT5::decode(&self.data, width as u32, height as u32).map_err(UnityError::DecodeImage),
             TextureFormat::DXT1 => DXT1::decode(&self.data, width as u32, height as u32).map_err(UnityError::DecodeImage),
             _ => Err(UnityError::Unimplemented),
         }
@@ -1,7 +1,8 @@
 use crate::classes::FromObject;
 use crate::env::Object;
-use crate::error::UnityResult;
-use crate::math::{Quaternion, Vector3};
+use crate::error::{UnityError, UnityResult};
+use crate::math::{Matrix4x4, Quaternion, Vector3};
+use crate::reader::Reader;
 
 use super::game_object::GameObject;
 use super::pptr::PPtr;
@@ -16,10 +17,23 @@ pub struct Transform<'a> {
 }
 
 impl<'a> FromObject<'a> for Transform<'a> {
-    fn load(object: &'a Object) -> UnityResult<Self> {
-        let mut r = object.info.get_reader();
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let mut r = object.info.get_reader()?;
+        Self::from_reader(object, &mut r)
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::Transform
+    }
+}
+
+/// Guards the parent walks against cyclic hierarchies in malformed files.
+const MAX_DEPTH: usize = 1024;
+
+impl<'a> Transform<'a> {
+    pub(crate) fn from_reader(object: &Object<'a>, r: &mut Reader) -> UnityResult<Self> {
         Ok(Self {
-            game_object: PPtr::<GameObject>::load(object, &mut r)?,
+            game_object: PPtr::<GameObject>::load(object, r)?,
             local_rotation: Quaternion::from_array(r.read_f32_array::<4>()?),
             local_position: r.read_vector3()?,
             local_scale: r.read_vector3()?,
@@ -27,15 +41,74 @@ impl<'a> FromObject<'a> for Transform<'a> {
                 let count = r.read_i32()? as usize;
                 let mut children = Vec::with_capacity(count);
                 for _ in 0..count {
-                    children.push(PPtr::load(object, &mut r)?);
+                    children.push(PPtr::load(object, r)?);
                 }
                 children
             },
-            father: PPtr::<Self>::load(object, &mut r)?,
+            father: PPtr::<Self>::load(object, r)?,
         })
     }
 
-    fn class() -> super::ClassID {
-        super::ClassID::Transform
+    pub fn local_matrix(&self) -> Matrix4x4 {
+        Matrix4x4::trs(&self.local_position, &self.local_rotation, &self.local_scale)
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.father.is_null()
+    }
+
+    pub fn game_object(&self) -> Option<Object<'a>> {
+        self.game_object.get_obj()
+    }
+
+    pub fn father(&self) -> Option<Object<'a>> {
+        self.father.get_obj_any()
+    }
+
+    /// Resolves the child transforms. Children may be `RectTransform`s, so the class is not checked.
+    pub fn children(&self) -> impl Iterator<Item = Object<'a>> + '_ {
+        self.children.iter().filter_map(|child| child.get_obj_any())
+    }
+
+    /// Composes the local TRS matrices up the parent chain.
+    pub fn world_matrix(&self) -> UnityResult<Matrix4x4> {
+        let mut matrix = self.local_matrix();
+        let mut father = self.father();
+        for _ in 0..MAX_DEPTH {
+            let Some(object) = father else {
+                return Ok(matrix);
+            };
+            let transform = object.read::<Transform>()?;
+            matrix = transform.local_matrix() * matrix;
+            father = transform.father();
+        }
+        Err(UnityError::CustomError(format!("transform hierarchy deeper than {} levels", MAX_DEPTH)))
+    }
+
+    pub fn world_position(&self) -> UnityResult<Vector3> {
+        Ok(self.world_matrix()?.translation())
+    }
+
+    /// The `/` separated GameObject names from the root down to this transform.
+    pub fn path(&self) -> UnityResult<String> {
+        let mut names = vec![game_object_name(&self.game_object)?];
+        let mut father = self.father();
+        for _ in 0..MAX_DEPTH {
+            let Some(object) = father else {
+                names.reverse();
+                return Ok(names.join("/"));
+            };
+            let transform = object.read::<Transform>()?;
+            names.push(game_object_name(&transform.game_object)?);
+            father = transform.father();
+        }
+        Err(UnityError::CustomError(format!("transform hierarchy deeper than {} levels", MAX_DEPTH)))
+    }
+}
+
+fn game_object_name(game_object: &PPtr<GameObject>) -> UnityResult<String> {
+    match game_object.get_obj() {
+        Some(object) => Ok(object.read::<GameObject>()?.name),
+        None => Ok(String::new()),
     }
 }
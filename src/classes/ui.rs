@@ -0,0 +1,140 @@
+use serde::Deserialize;
+
+use crate::error::UnityResult;
+use crate::math::{Color, RectF32};
+
+use super::font::Font;
+use super::material::Material;
+use super::math::{RawColor, RawRect};
+use super::mono_behaviour::MonoBehaviour;
+use super::pptr::{PPtr, RawPPtr};
+use super::sprite::Sprite;
+use super::texture2d::Texture2D;
+
+#[derive(Deserialize)]
+struct RawImage {
+    #[serde(rename = "m_Material")]
+    material: RawPPtr,
+    #[serde(rename = "m_Color")]
+    color: RawColor,
+    #[serde(rename = "m_Sprite")]
+    sprite: RawPPtr,
+    #[serde(rename = "m_Type")]
+    type_: i32,
+}
+
+#[derive(Deserialize)]
+struct RawRawImage {
+    #[serde(rename = "m_Material")]
+    material: RawPPtr,
+    #[serde(rename = "m_Color")]
+    color: RawColor,
+    #[serde(rename = "m_Texture")]
+    texture: RawPPtr,
+    #[serde(rename = "m_UVRect")]
+    uv_rect: RawRect,
+}
+
+#[derive(Deserialize)]
+struct RawFontData {
+    #[serde(rename = "m_Font")]
+    font: RawPPtr,
+    #[serde(rename = "m_FontSize")]
+    font_size: i32,
+    #[serde(rename = "m_FontStyle")]
+    font_style: i32,
+    #[serde(rename = "m_Alignment")]
+    alignment: i32,
+}
+
+#[derive(Deserialize)]
+struct RawText {
+    #[serde(rename = "m_Material")]
+    material: RawPPtr,
+    #[serde(rename = "m_Color")]
+    color: RawColor,
+    #[serde(rename = "m_FontData")]
+    font_data: RawFontData,
+    #[serde(rename = "m_Text")]
+    text: String,
+}
+
+/// `UnityEngine.UI.Image`.
+pub struct UiImage<'a> {
+    pub material: PPtr<'a, Material<'a>>,
+    pub color: Color,
+    pub sprite: PPtr<'a, Sprite<'a>>,
+    /// `Image.Type`: 0 simple, 1 sliced, 2 tiled, 3 filled.
+    pub type_: i32,
+}
+
+/// `UnityEngine.UI.RawImage`.
+pub struct UiRawImage<'a> {
+    pub material: PPtr<'a, Material<'a>>,
+    pub color: Color,
+    pub texture: PPtr<'a, Texture2D>,
+    pub uv_rect: RectF32,
+}
+
+/// `UnityEngine.UI.Text`.
+pub struct UiText<'a> {
+    pub material: PPtr<'a, Material<'a>>,
+    pub color: Color,
+    pub font: PPtr<'a, Font<'a>>,
+    pub font_size: i32,
+    pub font_style: i32,
+    pub alignment: i32,
+    pub text: String,
+}
+
+/// A typed view of the UGUI behaviours that reference sprites, textures and fonts.
+pub enum WellKnownBehaviour<'a> {
+    Image(UiImage<'a>),
+    RawImage(UiRawImage<'a>),
+    Text(UiText<'a>),
+}
+
+impl<'a> MonoBehaviour<'a> {
+    /// Classifies the behaviour by its MonoScript, returning `None` for scripts that are not
+    /// `UnityEngine.UI.Image`, `RawImage` or `Text`, or whose script can not be resolved.
+    pub fn well_known_behaviour(&self) -> UnityResult<Option<WellKnownBehaviour<'a>>> {
+        let Ok(script) = self.script() else {
+            return Ok(None);
+        };
+        let object = self.object();
+        let behaviour = match script.full_name().as_str() {
+            "UnityEngine.UI.Image" => {
+                let raw: RawImage = self.fields()?;
+                WellKnownBehaviour::Image(UiImage {
+                    material: raw.material.bind(&object),
+                    color: raw.color.to_color(),
+                    sprite: raw.sprite.bind(&object),
+                    type_: raw.type_,
+                })
+            }
+            "UnityEngine.UI.RawImage" => {
+                let raw: RawRawImage = self.fields()?;
+                WellKnownBehaviour::RawImage(UiRawImage {
+                    material: raw.material.bind(&object),
+                    color: raw.color.to_color(),
+                    texture: raw.texture.bind(&object),
+                    uv_rect: raw.uv_rect.to_rect(),
+                })
+            }
+            "UnityEngine.UI.Text" => {
+                let raw: RawText = self.fields()?;
+                WellKnownBehaviour::Text(UiText {
+                    material: raw.material.bind(&object),
+                    color: raw.color.to_color(),
+                    font: raw.font_data.font.bind(&object),
+                    font_size: raw.font_data.font_size,
+                    font_style: raw.font_data.font_style,
+                    alignment: raw.font_data.alignment,
+                    text: raw.text,
+                })
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(behaviour))
+    }
+}
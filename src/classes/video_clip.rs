@@ -0,0 +1,130 @@
+use crate::classes::FromObject;
+use crate::env::{Env, Object};
+use crate::error::{UnityError, UnityResult};
+use crate::reader::Reader;
+
+use super::pptr::PPtr;
+use super::shader::Shader;
+
+#[derive(Default, Debug, Clone)]
+pub struct StreamedResource {
+    pub source: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl StreamedResource {
+    pub fn load(r: &mut Reader) -> UnityResult<Self> {
+        Ok(Self {
+            source: r.read_aligned_string()?,
+            offset: r.read_u64()?,
+            size: r.read_u64()?,
+        })
+    }
+
+    /// Reads the referenced bytes out of the `.resource` file loaded alongside the bundle.
+    pub fn read_data(&self, env: &Env) -> UnityResult<Vec<u8>> {
+        let name = self.source.split('/').next_back().ok_or(UnityError::InvalidValue)?;
+        let buf = env.get_loaded_file(name).ok_or_else(|| UnityError::CustomError(format!("can not find resource {}", self.source)))?;
+        let offset = usize::try_from(self.offset).map_err(|_| UnityError::Eof)?;
+        let size = usize::try_from(self.size).map_err(|_| UnityError::Eof)?;
+        let end = offset.checked_add(size).ok_or(UnityError::Eof)?;
+        Ok(buf.read(offset..end)?.to_vec())
+    }
+}
+
+pub struct VideoClip<'a> {
+    pub name: String,
+    pub original_path: String,
+    pub proxy_width: u32,
+    pub proxy_height: u32,
+    pub width: u32,
+    pub height: u32,
+    pub pixel_aspect_ratio: (u32, u32),
+    pub frame_rate: f64,
+    pub frame_count: u64,
+    pub format: i32,
+    pub audio_channel_count: Vec<u16>,
+    pub audio_sample_rate: Vec<u32>,
+    pub audio_language: Vec<String>,
+    pub video_shaders: Vec<PPtr<'a, Shader>>,
+    pub external_resources: StreamedResource,
+    pub has_split_alpha: bool,
+    pub srgb: bool,
+}
+
+impl<'a> FromObject<'a> for VideoClip<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        let version = object.info.version;
+        let mut r = object.info.get_reader()?;
+        let name = r.read_aligned_string()?;
+        let original_path = r.read_aligned_string()?;
+        let proxy_width = r.read_u32()?;
+        let proxy_height = r.read_u32()?;
+        let width = r.read_u32()?;
+        let height = r.read_u32()?;
+        let mut pixel_aspect_ratio = (1, 1);
+        if version.is_at_least(2017, 2) {
+            pixel_aspect_ratio = (r.read_u32()?, r.read_u32()?);
+        }
+        let frame_rate = r.read_f64()?;
+        let frame_count = r.read_u64()?;
+        let format = r.read_i32()?;
+        let length = r.read_i32()? as usize;
+        let audio_channel_count = r.read_u16_list(length)?;
+        r.align(4)?;
+        let length = r.read_i32()? as usize;
+        let audio_sample_rate = r.read_u32_list(length)?;
+        let audio_language = r.read_string_list()?;
+        let mut video_shaders = Vec::new();
+        if version.major >= 2020 {
+            for _ in 0..r.read_i32()? {
+                video_shaders.push(PPtr::load(object, &mut r)?);
+            }
+        }
+        let external_resources = StreamedResource::load(&mut r)?;
+        let has_split_alpha = r.read_bool()?;
+        let mut srgb = false;
+        if version.major >= 2020 {
+            srgb = r.read_bool()?;
+        }
+        Ok(Self {
+            name,
+            original_path,
+            proxy_width,
+            proxy_height,
+            width,
+            height,
+            pixel_aspect_ratio,
+            frame_rate,
+            frame_count,
+            format,
+            audio_channel_count,
+            audio_sample_rate,
+            audio_language,
+            video_shaders,
+            external_resources,
+            has_split_alpha,
+            srgb,
+        })
+    }
+
+    fn class() -> super::ClassID {
+        super::ClassID::VideoClip
+    }
+}
+
+impl VideoClip<'_> {
+    /// The original container (MP4, WebM, ...), read from the bundle's resource node.
+    pub fn data(&self, env: &Env) -> UnityResult<Vec<u8>> {
+        if self.external_resources.source.is_empty() {
+            return Err(UnityError::CustomError(format!("VideoClip {} has no external resource", self.name)));
+        }
+        self.external_resources.read_data(env)
+    }
+
+    /// The file extension of `original_path`, e.g. `mp4`.
+    pub fn extension(&self) -> Option<&str> {
+        self.original_path.rsplit_once('.').map(|(_, extension)| extension)
+    }
+}
@@ -109,6 +109,7 @@ pub fn common_string(index: usize) -> Option<&'static str> {
         1138 => Some("m_PrefabAsset"),
         1152 => Some("FileSize"),
         1161 => Some("Hash128"),
+        1169 => Some("RenderingLayerMask"),
         _ => None,
     }
 }
@@ -1,15 +1,32 @@
-use crate::asset::SerializedFile;
-use crate::bundle::{BundleFileLoader, FileLoader};
-use crate::classes::{ClassID, FromObject};
-use crate::error::UnityResult;
+use crate::asset::{file_name, FileIdentifier, SerializedFile};
+use crate::bundle::{check_file_type, check_file_type_prefix, BundleFileLoader, FileData, FileLoader, FileSource, FileType, LoadOptions, LoadOutput, HEADER_PROBE_SIZE};
+use crate::classes::{AssetBundle, BuildSettings, ClassID, FromObject, MonoBehaviour, ResourceManager, Transform};
+use crate::error::{UnityError, UnityResult};
 use crate::object::{ObjectInfo, ReadTypeTreeError};
+use crate::reader::{RawString, SplitReader};
+use crate::typetree::{self, TypeTreeNode, TypeTreeProvider};
+use crate::warning::LoadWarning;
 use dashmap::DashMap;
 use image::RgbaImage;
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::fs::File;
+#[cfg(not(feature = "mmap"))]
+use std::io::BufReader;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
+mod owned;
+mod summary;
+pub use owned::OwnedObject;
+pub use summary::{ClassSummary, EnvSummary, MemoryUsage, ObjectSummary};
+
+/// The objects of an [`Env`], see [`Env::objects`].
 pub struct ObjectIter<'a> {
     env: &'a Env,
     asset_index: usize,
@@ -21,7 +38,7 @@ impl<'a> Iterator for ObjectIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let asset = self.env.serialized_files.get(self.asset_index)?;
-        let Some(info) = asset.objects_info.get(self.obj_index) else {
+        let Some(info) = asset.object_at(self.obj_index) else {
             self.obj_index = 0;
             self.asset_index += 1;
             return self.next();
@@ -38,17 +55,78 @@ impl<'a> Iterator for ObjectIter<'a> {
 
 pub struct Env {
     pub file_loaders: Vec<Box<dyn FileLoader>>,
+    /// The loaded serialized files in load order. Files added here directly rather than loaded
+    /// are only listed by [`Env::container`] and [`Env::warnings`] once another file is loaded.
     pub serialized_files: Vec<SerializedFile>,
     pub cache: Arc<DashMap<i64, RgbaImage>>,
-    pub loaded_files: Arc<DashMap<String, Arc<Vec<u8>>>>,
+    pub loaded_files: Arc<DashMap<String, FileSource>>,
+    pub typetree_provider: Option<Arc<dyn TypeTreeProvider>>,
+    /// What strings that are not valid UTF-8 are decoded with, see [`Env::decode_string`].
+    #[cfg(feature = "encoding")]
+    pub fallback_encoding: Option<&'static encoding_rs::Encoding>,
+    /// Container entries by lowercased path, built on first use and dropped when files are loaded.
+    /// Entries name their file by path, so [`Env::serialized_files`] changed in between never
+    /// resolves them to another file.
+    container_index: OnceLock<HashMap<String, Vec<ContainerEntry>>>,
+    /// Warnings found while decompressing bundles, which the loaded files do not keep.
+    load_warnings: Vec<LoadWarning>,
+    /// [`Env::warnings`], built on first use and dropped when files are loaded.
+    warnings: OnceLock<Vec<LoadWarning>>,
+}
+
+/// An object listed under `path` by an AssetBundle or ResourceManager.
+#[derive(PartialEq)]
+struct ContainerEntry {
+    path: String,
+    /// The path of the serialized file holding the object.
+    file: String,
+    path_id: i64,
 }
 
+/// The serialized files by path, the first one for a path loaded twice.
+type FilesByPath<'a> = HashMap<&'a str, &'a SerializedFile>;
+
 impl Default for Env {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// The GameObject of `object` if it is a transform without a father.
+fn root_game_object(object: Object<'_>) -> Option<Object<'_>> {
+    if !matches!(object.class(), ClassID::Transform | ClassID::RectTransform) {
+        return None;
+    }
+    let transform = object.read::<Transform>().ok()?;
+    if !transform.is_root() {
+        return None;
+    }
+    transform.game_object()
+}
+
+fn path_name(path: &Path) -> String {
+    path.file_name().map(|x| x.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Whether `path` holds streamed data, i.e. texture (`.resS`) or audio and video (`.resource`) bytes.
+fn is_resource_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|x| x.to_str()), Some("resS" | "resource"))
+}
+
+/// Reads `path` whole, memory mapped with the `mmap` feature.
+#[cfg(feature = "mmap")]
+fn open_data(path: &Path) -> UnityResult<FileData> {
+    let file = File::open(path)?;
+    // SAFETY: files are treated as read only input, see `Env::load_from_file`.
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    Ok(FileData::new(map))
+}
+
+#[cfg(not(feature = "mmap"))]
+fn open_data(path: &Path) -> UnityResult<FileData> {
+    Ok(FileData::from(std::fs::read(path)?))
+}
+
 impl Debug for Env {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Env")
@@ -58,51 +136,625 @@ impl Debug for Env {
 impl Env {
     pub fn new() -> Self {
         Self {
-            file_loaders: vec![Box::new(BundleFileLoader)],
+            file_loaders: vec![Box::new(BundleFileLoader::default())],
             serialized_files: Vec::new(),
             cache: Arc::new(DashMap::new()),
             loaded_files: Arc::new(DashMap::new()),
+            typetree_provider: None,
+            #[cfg(feature = "encoding")]
+            fallback_encoding: None,
+            container_index: OnceLock::new(),
+            load_warnings: Vec::new(),
+            warnings: OnceLock::new(),
+        }
+    }
+
+    /// Sets the provider consulted for MonoBehaviours whose serialized file has no type trees.
+    pub fn set_typetree_provider(&mut self, provider: impl TypeTreeProvider + 'static) {
+        self.typetree_provider = Some(Arc::new(provider));
+        self.warnings = OnceLock::new();
+    }
+
+    /// Sets the encoding, such as `encoding_rs::SHIFT_JIS` or `encoding_rs::GBK`, that names
+    /// which are not valid UTF-8 are decoded with.
+    #[cfg(feature = "encoding")]
+    pub fn set_fallback_encoding(&mut self, encoding: &'static encoding_rs::Encoding) {
+        self.fallback_encoding = Some(encoding);
+    }
+
+    /// The text of a string read with
+    /// [`read_aligned_string_raw`](crate::reader::Reader::read_aligned_string_raw): as is when it
+    /// is UTF-8, otherwise decoded with the fallback encoding if the `encoding` feature is on and
+    /// one is set, or lossily.
+    pub fn decode_string(&self, raw: RawString) -> String {
+        if raw.is_utf8() {
+            return raw.text;
+        }
+        self.decode_fallback(raw)
+    }
+
+    #[cfg(feature = "encoding")]
+    fn decode_fallback(&self, raw: RawString) -> String {
+        match self.fallback_encoding {
+            Some(encoding) => encoding.decode_without_bom_handling(&raw.bytes).0.into_owned(),
+            None => raw.text,
         }
     }
 
+    #[cfg(not(feature = "encoding"))]
+    fn decode_fallback(&self, raw: RawString) -> String {
+        raw.text
+    }
+
     pub fn add_loader(&mut self, loader: impl FileLoader + 'static) {
         self.file_loaders.push(Box::new(loader));
     }
 
+    /// Loads a bundle, or a bare serialized file such as `level0` if no loader accepts `src`.
     pub fn load_from_slice(&mut self, src: &[u8]) -> UnityResult<()> {
+        let mut loaded = false;
         for file_loader in &self.file_loaders {
             if !file_loader.check(src) {
                 continue;
             }
 
             let assets = file_loader.load(src)?;
-            self.serialized_files.extend(assets.serialized_files);
-            for loaded_file in assets.loaded_files {
-                self.loaded_files.insert(loaded_file.name, loaded_file.data);
+            self.add_output(assets);
+            loaded = true;
+        }
+        if !loaded && matches!(check_file_type(src), Ok(FileType::AssetsFile)) {
+            return self.load_serialized_file("", FileData::from(src.to_vec()));
+        }
+
+        Ok(())
+    }
+
+    /// Loads a bare serialized file under `name`, which externals of other files refer to it by.
+    pub fn load_serialized_file(&mut self, name: &str, data: FileData) -> UnityResult<()> {
+        let serialized_file = SerializedFile::new(data.into(), name)?;
+        self.serialized_files.push(serialized_file);
+        self.container_index = OnceLock::new();
+        self.warnings = OnceLock::new();
+        Ok(())
+    }
+
+    /// Loads a bundle that is hidden behind a prefix or a renamed signature, or is encrypted.
+    pub fn load_from_slice_with(&mut self, src: &[u8], options: &LoadOptions) -> UnityResult<()> {
+        let bundle = options.locate(src)?;
+        let assets = BundleFileLoader::new(options.clone()).load(&bundle)?;
+        self.add_output(assets);
+        Ok(())
+    }
+
+    /// Loads from a seekable stream.
+    ///
+    /// UnityFS bundles are decompressed block by block instead of being read into memory first;
+    /// anything else is read whole and passed to [`Env::load_from_slice`].
+    pub fn load_from_reader<R: Read + Seek>(&mut self, mut src: R) -> UnityResult<()> {
+        let start = src.stream_position()?;
+        let mut signature = Vec::new();
+        src.by_ref().take(8).read_to_end(&mut signature)?;
+        src.seek(SeekFrom::Start(start))?;
+        if signature == b"UnityFS\0" {
+            let assets = BundleFileLoader::default().load_from_reader(src)?;
+            self.add_output(assets);
+            return Ok(());
+        }
+
+        let mut data = Vec::new();
+        src.read_to_end(&mut data)?;
+        self.load_from_slice(&data)
+    }
+
+    /// Loads from shared file contents. UnityFS bundles are not copied when uncompressed and
+    /// otherwise decompressed block by block as objects are read, which also means blocks of the
+    /// wrong size are only fixed then, without a [`LoadWarning::BlockSize`].
+    pub fn load_from_data(&mut self, data: FileData) -> UnityResult<()> {
+        if data.starts_with(b"UnityFS\0") {
+            let assets = BundleFileLoader::default().load_from_data(data)?;
+            self.add_output(assets);
+            return Ok(());
+        }
+        self.load_from_slice(&data)
+    }
+
+    /// Loads `data` read from `path`. A bare serialized file is named after the file, and with
+    /// `resources` its `<name>.resS` and `<name>.resource` files next to it are registered for
+    /// streamed data. Those that cannot be read are left out, as if they were missing.
+    fn load_file_data(&mut self, path: &Path, data: FileData, resources: bool) -> UnityResult<()> {
+        if !matches!(check_file_type(&data), Ok(FileType::AssetsFile)) {
+            return self.load_from_data(data);
+        }
+        let name = path_name(path);
+        self.load_serialized_file(&name, data)?;
+        if !resources {
+            return Ok(());
+        }
+        for extension in ["resS", "resource"] {
+            let resource = format!("{}.{}", name, extension);
+            if self.loaded_files.contains_key(&resource) {
+                continue;
+            }
+            if let Ok(data) = open_data(&path.with_file_name(&resource)) {
+                self.loaded_files.insert(resource, data.into());
             }
         }
+        Ok(())
+    }
+
+    /// [`Env::load_from_data`] for bundles that [`Env::load_from_slice_with`] would need options for.
+    pub fn load_from_data_with(&mut self, data: FileData, options: &LoadOptions) -> UnityResult<()> {
+        let bundle = match options.locate(&data)? {
+            // The located bundle always runs to the end of `data`.
+            Cow::Borrowed(bundle) => data.slice(data.len() - bundle.len()..data.len())?,
+            Cow::Owned(bundle) => FileData::from(bundle),
+        };
+        let assets = BundleFileLoader::new(options.clone()).load_from_data(bundle)?;
+        self.add_output(assets);
+        Ok(())
+    }
+
+    /// Opens `path` and loads it with [`Env::load_from_reader`], or with the `mmap` feature memory
+    /// maps it and loads it with [`Env::load_from_data`]. Bare serialized files are named after
+    /// the file. Errors are wrapped in [`UnityError::LoadFile`] with the path.
+    ///
+    /// A memory mapped file must not be modified while the Env is alive.
+    pub fn load_from_file(&mut self, path: impl AsRef<Path>) -> UnityResult<()> {
+        let path = path.as_ref();
+        self.load_file(path, true).map_err(|e| UnityError::LoadFile { path: path.to_path_buf(), source: Box::new(e) })
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    fn load_file(&mut self, path: &Path, resources: bool) -> UnityResult<()> {
+        let mut src = BufReader::new(File::open(path)?);
+        let mut signature = Vec::new();
+        src.by_ref().take(8).read_to_end(&mut signature)?;
+        src.rewind()?;
+        if signature == b"UnityFS\0" {
+            return self.load_from_reader(src);
+        }
+        let mut data = Vec::new();
+        src.read_to_end(&mut data)?;
+        self.load_file_data(path, FileData::from(data), resources)
+    }
+
+    #[cfg(feature = "mmap")]
+    fn load_file(&mut self, path: &Path, resources: bool) -> UnityResult<()> {
+        self.load_file_data(path, open_data(path)?, resources)
+    }
 
+    /// Loads a game's `<Game>_Data` folder and its subfolders: bare serialized files such as
+    /// `globalgamemanagers`, `level0` and `sharedassets0.assets`, bundles, and the `.resS` and
+    /// `.resource` files holding streamed data. Since files are matched up by name, externals and
+    /// streamed data resolve across the folder. Anything else, such as `Managed/*.dll`, is skipped.
+    ///
+    /// Fails with the first file that does not load, after trying all of them; see
+    /// [`Env::load_from_dir`] to carry on past such files.
+    pub fn load_data_folder(&mut self, path: impl AsRef<Path>) -> UnityResult<()> {
+        for (path, result) in self.load_from_dir(path, true)? {
+            result.map_err(|e| UnityError::LoadFile { path, source: Box::new(e) })?;
+        }
         Ok(())
     }
 
+    /// Loads the bundles and serialized files in `path`, and in its subfolders if `recursive`,
+    /// and registers the `.resS` and `.resource` files for streamed data, like
+    /// [`Env::load_data_folder`]. Files are recognised by their signature whatever their
+    /// extension, and others are skipped.
+    ///
+    /// Game folders hold thousands of files, some of them truncated or not assets at all, so one
+    /// that fails to load does not stop the others: the result has each asset file in load order
+    /// with how loading it went, and the subfolders that could not be listed. Only failing to
+    /// list `path` itself is an error.
+    ///
+    /// Links to folders are not followed, so a link back up the tree does not recurse forever;
+    /// links to files are loaded like the files themselves.
+    pub fn load_from_dir(&mut self, path: impl AsRef<Path>, recursive: bool) -> UnityResult<Vec<(PathBuf, UnityResult<()>)>> {
+        let root = path.as_ref();
+        let mut result = Vec::new();
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let paths = std::fs::read_dir(&dir).and_then(|x| x.map(|x| x.map(|x| x.path())).collect::<Result<Vec<_>, _>>());
+            let mut paths = match paths {
+                Ok(paths) => paths,
+                Err(e) if dir == root => return Err(e.into()),
+                Err(e) => {
+                    result.push((dir, Err(e.into())));
+                    continue;
+                }
+            };
+            paths.sort();
+            for path in paths {
+                if path.is_dir() {
+                    if recursive && !path.is_symlink() {
+                        dirs.push(path);
+                    }
+                    continue;
+                }
+                match self.load_dir_entry(&path) {
+                    Ok(false) => {}
+                    Ok(true) => result.push((path, Ok(()))),
+                    Err(e) => result.push((path, Err(e))),
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Loads one file found by [`Env::load_from_dir`], returning whether it is an asset.
+    fn load_dir_entry(&mut self, path: &Path) -> UnityResult<bool> {
+        if is_resource_file(path) {
+            let name = path_name(path);
+            if !self.loaded_files.contains_key(&name) {
+                self.loaded_files.insert(name, open_data(path)?.into());
+            }
+            return Ok(true);
+        }
+        let mut head = Vec::new();
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        file.take(HEADER_PROBE_SIZE).read_to_end(&mut head)?;
+        if !matches!(check_file_type_prefix(&head, len), Ok(FileType::AssetsFile | FileType::BundleFile)) {
+            return Ok(false);
+        }
+        // The folder's resource files are registered as they are listed.
+        self.load_file(path, false)?;
+        Ok(true)
+    }
+
+    /// Loads a file that was split into chunks, such as `foo.ab.split0`, `foo.ab.split1`, ...,
+    /// from `paths` in order without joining them on disk.
+    pub fn load_split_files(&mut self, paths: &[PathBuf]) -> UnityResult<()> {
+        let parts = paths.iter().map(File::open).collect::<Result<Vec<_>, _>>()?;
+        self.load_from_reader(SplitReader::new(parts)?)
+    }
+
+    fn add_output(&mut self, assets: LoadOutput) {
+        self.serialized_files.extend(assets.serialized_files);
+        self.load_warnings.extend(assets.warnings);
+        self.container_index = OnceLock::new();
+        self.warnings = OnceLock::new();
+        for loaded_file in assets.loaded_files {
+            self.loaded_files.insert(loaded_file.name, loaded_file.data);
+        }
+    }
+
+    /// Every object, file by file in the order the files were loaded and within a file in the
+    /// order of its object table. The order only depends on what was loaded and in which order,
+    /// so loading the same files the same way always gives the same sequence.
     pub fn objects(&self) -> ObjectIter<'_> {
         ObjectIter { env: self, asset_index: 0, obj_index: 0 }
     }
 
-    pub fn get_loaded_file(&self, name: &str) -> Option<Arc<Vec<u8>>> {
+    /// [`Env::objects`] as a rayon parallel iterator, for reading and decoding objects on all cores.
+    /// Collecting it into a `Vec` keeps the order of [`Env::objects`].
+    #[cfg(feature = "rayon")]
+    pub fn par_objects(&self) -> impl rayon::iter::ParallelIterator<Item = Object<'_>> + '_ {
+        use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+        self.serialized_files.par_iter().flat_map(move |asset| {
+            (0..asset.object_count()).into_par_iter().filter_map(move |i| asset.object_at(i)).map(move |info| Object {
+                env: self,
+                asset,
+                info,
+                cache: self.cache.clone(),
+            })
+        })
+    }
+
+    /// The objects whose class is `class`, picked from the object tables so that no other object
+    /// is built. They come in the order of [`Env::objects`].
+    pub fn objects_with_class(&self, class: ClassID) -> impl Iterator<Item = Object<'_>> + '_ {
+        self.serialized_files.iter().flat_map(move |asset| {
+            let indices = asset.object_entries().iter().enumerate().filter(move |(_, x)| x.class() == class);
+            indices.filter_map(move |(i, _)| {
+                Some(Object {
+                    env: self,
+                    asset,
+                    info: asset.object_at(i)?,
+                    cache: self.cache.clone(),
+                })
+            })
+        })
+    }
+
+    /// The objects of class `T`, each with the result of reading it, so that one that fails to
+    /// parse does not hide the others.
+    pub fn objects_of<'a, T: FromObject<'a> + 'a>(&'a self) -> impl Iterator<Item = (Object<'a>, UnityResult<T>)> + 'a {
+        self.objects_with_class(T::class()).map(|object| {
+            let result = object.read();
+            (object, result)
+        })
+    }
+
+    pub fn get_loaded_file(&self, name: &str) -> Option<FileSource> {
         self.loaded_files.get(name).map(|x| x.value().clone())
     }
 
+    /// Finds a loaded serialized file by name, ignoring case and any directories or `archive:/`
+    /// prefix, so that entries of [`SerializedFile::externals`] can be looked up directly.
+    pub fn find_serialized_file(&self, name: &str) -> Option<&SerializedFile> {
+        let name = file_name(name);
+        self.serialized_files.iter().find(|x| file_name(&x.path).eq_ignore_ascii_case(name))
+    }
+
+    /// The externals of the loaded files that are not loaded themselves, such as the bundle
+    /// holding the textures of another one's sprites, each once in load order. Pointers into
+    /// them resolve once they are loaded, whether before or after the files pointing into them.
+    pub fn missing_dependencies(&self) -> Vec<&FileIdentifier> {
+        let mut result: Vec<&FileIdentifier> = Vec::new();
+        for external in self.serialized_files.iter().flat_map(|x| x.externals()) {
+            let name = external.file_name();
+            if name.is_empty() || self.find_serialized_file(name).is_some() || result.iter().any(|x| x.file_name().eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            result.push(external);
+        }
+        result
+    }
+
+    /// The first object with `path_id` in load order, see [`Env::object_by_path_id`].
     pub fn find_object(&self, path_id: i64) -> Option<Object<'_>> {
-        self.objects().find(|i| i.info.path_id == path_id)
+        self.object_by_path_id(path_id)
+    }
+
+    /// The object with `path_id`, for an Env holding one file. Path ids are only unique within
+    /// a file, so with several this is the first one in load order; see [`Env::object_in_file`].
+    pub fn object_by_path_id(&self, path_id: i64) -> Option<Object<'_>> {
+        self.serialized_files.iter().find_map(|asset| self.object_of(asset, path_id))
+    }
+
+    /// The object with `path_id` in the serialized file named `file_name`, looked up like
+    /// [`Env::find_serialized_file`].
+    pub fn object_in_file(&self, file_name: &str, path_id: i64) -> Option<Object<'_>> {
+        self.object_of(self.find_serialized_file(file_name)?, path_id)
+    }
+
+    fn object_of<'a>(&'a self, asset: &'a SerializedFile, path_id: i64) -> Option<Object<'a>> {
+        Some(Object {
+            env: self,
+            asset,
+            info: asset.object(path_id)?,
+            cache: self.cache.clone(),
+        })
+    }
+
+    /// GameObjects at the top of a hierarchy, i.e. whose transform has no father.
+    pub fn roots(&self) -> impl Iterator<Item = Object<'_>> + '_ {
+        self.objects().filter_map(root_game_object)
+    }
+
+    /// The root GameObjects of built scenes (see [`SerializedFile::is_scene`]), scene by scene in
+    /// load order. Builds do not keep the editor's root order, so within a scene they come in the
+    /// order the file stores their transforms.
+    pub fn scene_roots(&self) -> impl Iterator<Item = Object<'_>> + '_ {
+        self.serialized_files.iter().filter(|asset| asset.is_scene()).flat_map(move |asset| {
+            asset.objects().filter_map(move |info| {
+                root_game_object(Object {
+                    env: self,
+                    asset,
+                    info,
+                    cache: self.cache.clone(),
+                })
+            })
+        })
+    }
+
+    /// Maps original project paths (e.g. `assets/characters/char_1016/portrait.png`) to their objects,
+    /// merging AssetBundle containers with the ResourceManager of player data folders.
+    ///
+    /// A path can map to several objects, e.g. a texture and the sprite cut from it, or the same
+    /// path in bundles of different versions. The containers are read once and again only after
+    /// more files are loaded. The map's order is arbitrary, but each path's objects are in load
+    /// order.
+    pub fn container(&self) -> HashMap<String, Vec<Object<'_>>> {
+        let files = self.files_by_path();
+        let mut result: HashMap<String, Vec<Object<'_>>> = HashMap::new();
+        for entry in self.container_index().values().flatten() {
+            if let Some(object) = self.container_object(&files, entry) {
+                result.entry(entry.path.clone()).or_default().push(object);
+            }
+        }
+        result
+    }
+
+    /// Every object listed under `path` in [`Env::container`], ignoring case, each once and in
+    /// load order.
+    pub fn object_at_path(&self, path: &str) -> Vec<Object<'_>> {
+        let files = self.files_by_path();
+        let mut result: Vec<Object<'_>> = Vec::new();
+        for entry in self.container_index().get(&path.to_lowercase()).into_iter().flatten() {
+            if let Some(object) = self.container_object(&files, entry) {
+                if !result.iter().any(|x| std::ptr::eq(x.info, object.info)) {
+                    result.push(object);
+                }
+            }
+        }
+        result
+    }
+
+    fn files_by_path(&self) -> FilesByPath<'_> {
+        let mut result = HashMap::with_capacity(self.serialized_files.len());
+        for asset in &self.serialized_files {
+            result.entry(asset.path.as_str()).or_insert(asset);
+        }
+        result
+    }
+
+    fn container_object<'e>(&'e self, files: &FilesByPath<'e>, entry: &ContainerEntry) -> Option<Object<'e>> {
+        self.object_of(*files.get(entry.file.as_str())?, entry.path_id)
+    }
+
+    fn container_index(&self) -> &HashMap<String, Vec<ContainerEntry>> {
+        self.container_index.get_or_init(|| {
+            let mut result: HashMap<String, Vec<ContainerEntry>> = HashMap::new();
+            let mut add = |path: &str, object: Object<'_>| {
+                let entry = ContainerEntry {
+                    path: path.to_string(),
+                    file: object.asset.path.clone(),
+                    path_id: object.info.path_id,
+                };
+                let entries = result.entry(path.to_lowercase()).or_default();
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            };
+            for object in self.objects() {
+                match object.class() {
+                    ClassID::AssetBundle => {
+                        let Ok(asset_bundle) = object.read::<AssetBundle>() else {
+                            continue;
+                        };
+                        for (path, info) in &asset_bundle.container {
+                            if let Some(asset) = info.asset.get_obj() {
+                                add(path, asset);
+                            }
+                        }
+                    }
+                    ClassID::ResourceManager => {
+                        let Ok(resource_manager) = object.read::<ResourceManager>() else {
+                            continue;
+                        };
+                        for (path, asset) in &resource_manager.container {
+                            if let Some(asset) = asset.get_obj() {
+                                add(path, asset);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            result
+        })
+    }
+
+    /// Problems with the loaded files that did not stop them from loading, file by file in load
+    /// order, then the duplicate container paths by path. Built on first use, which reads the
+    /// containers like [`Env::container`], and again only after more files are loaded.
+    pub fn warnings(&self) -> &[LoadWarning] {
+        self.warnings.get_or_init(|| {
+            let mut result = self.load_warnings.clone();
+            for asset in &self.serialized_files {
+                result.extend(self.file_warnings(asset));
+            }
+            let files = self.files_by_path();
+            let mut duplicates: Vec<(&str, ClassID, Vec<(String, i64)>)> = Vec::new();
+            for entries in self.container_index().values() {
+                let mut classes: Vec<(ClassID, Vec<(String, i64)>)> = Vec::new();
+                for entry in entries {
+                    let Some(object) = self.container_object(&files, entry) else {
+                        continue;
+                    };
+                    let position = classes.iter().position(|x| x.0 == object.class()).unwrap_or_else(|| {
+                        classes.push((object.class(), Vec::new()));
+                        classes.len() - 1
+                    });
+                    let object = (object.asset.path.clone(), entry.path_id);
+                    if !classes[position].1.contains(&object) {
+                        classes[position].1.push(object);
+                    }
+                }
+                for (class, objects) in classes.into_iter().filter(|x| x.1.len() > 1) {
+                    duplicates.push((&entries[0].path, class, objects));
+                }
+            }
+            duplicates.sort_by(|a, b| a.0.cmp(b.0));
+            result.extend(duplicates.into_iter().map(|(path, class, objects)| LoadWarning::DuplicateContainerPath { path: path.to_string(), class, objects }));
+            result
+        })
+    }
+
+    /// The unknown classes and missing type trees of `asset`, by class id.
+    fn file_warnings(&self, asset: &SerializedFile) -> Vec<LoadWarning> {
+        let mut unknown: BTreeMap<i32, usize> = BTreeMap::new();
+        let mut missing: BTreeMap<i32, usize> = BTreeMap::new();
+        for entry in asset.object_entries() {
+            let class = ClassID::from(entry.class_id);
+            if class == ClassID::UnknownType {
+                *unknown.entry(entry.class_id).or_default() += 1;
+            }
+            let stripped = !asset.entry_type(entry).is_some_and(|x| !x.type_tree.nodes.is_empty());
+            if stripped && (class == ClassID::UnknownType || (class == ClassID::MonoBehaviour && self.typetree_provider.is_none())) {
+                *missing.entry(entry.class_id).or_default() += 1;
+            }
+        }
+        let file = || asset.path.clone();
+        let unknown = unknown.into_iter().map(|(class_id, objects)| LoadWarning::UnknownClass { file: file(), class_id, objects });
+        let missing = missing.into_iter().map(|(class_id, objects)| LoadWarning::MissingTypeTree { file: file(), class_id, objects });
+        unknown.chain(missing).collect()
+    }
+
+    /// The Unity version from BuildSettings, falling back to the first serialized file's header.
+    pub fn unity_version(&self) -> Option<String> {
+        let from_build_settings = self.objects_with_class(ClassID::BuildSettings).find_map(|x| x.read::<BuildSettings>().ok()?.version);
+        from_build_settings.or_else(|| self.serialized_files.iter().map(|x| x.unity_version.clone()).find(|x| !x.is_empty()))
     }
 
     pub fn find_object_with_class<'a, T: FromObject<'a>>(&'a self, path_id: i64) -> Option<Object<'a>> {
-        self.objects().find(|i| i.info.path_id == path_id && i.info.class() == T::class())
+        self.serialized_files.iter().find_map(|asset| self.object_of(asset, path_id).filter(|x| x.class() == T::class()))
+    }
+
+    /// Objects whose `m_Name` is `name`, found with [`Object::peek_name`].
+    pub fn find_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = Object<'a>> + 'a {
+        self.objects().filter(move |x| x.peek_name().ok().flatten().as_deref() == Some(name))
+    }
+
+    /// Objects whose name matches `pattern`, only those of `class` if given. A pattern with `*`
+    /// or `?` is a glob over the whole name, anything else a substring; both ignore case.
+    ///
+    /// Names come from [`Object::peek_name`]. Objects without one, such as Transforms, are matched
+    /// by their paths in [`Env::container`] instead, whole or just the file name.
+    pub fn find_objects(&self, pattern: &str, class: Option<ClassID>) -> Vec<Object<'_>> {
+        let pattern = pattern.to_lowercase();
+        if pattern.contains(['*', '?']) {
+            self.find_objects_by(class, |x| glob_match(&pattern, &x.to_lowercase()))
+        } else {
+            self.find_objects_by(class, |x| x.to_lowercase().contains(&pattern))
+        }
+    }
+
+    /// [`Env::find_objects`] with names matched by `regex`.
+    #[cfg(feature = "regex")]
+    pub fn find_objects_regex(&self, regex: &regex::Regex, class: Option<ClassID>) -> Vec<Object<'_>> {
+        self.find_objects_by(class, |x| regex.is_match(x))
+    }
+
+    fn find_objects_by(&self, class: Option<ClassID>, is_match: impl Fn(&str) -> bool) -> Vec<Object<'_>> {
+        // Reading the containers means reading every AssetBundle, so only done once it is needed.
+        let mut container = None;
+        let mut result = Vec::new();
+        for object in self.objects() {
+            if class.is_some_and(|x| x != object.class()) {
+                continue;
+            }
+            let found = match object.peek_name() {
+                Ok(Some(name)) if !name.is_empty() => is_match(&name),
+                _ => {
+                    let container = container.get_or_insert_with(|| self.container_paths());
+                    let paths = container.get(&(object.asset.path.as_str(), object.info.path_id));
+                    paths.is_some_and(|paths| paths.iter().any(|x| is_match(x) || is_match(file_name(x))))
+                }
+            };
+            if found {
+                result.push(object);
+            }
+        }
+        result
+    }
+
+    /// The container paths of each object, keyed by its file's path and its path id.
+    fn container_paths(&self) -> HashMap<(&str, i64), Vec<String>> {
+        let mut result: HashMap<(&str, i64), Vec<String>> = HashMap::new();
+        for (path, objects) in self.container() {
+            for object in objects {
+                result.entry((object.asset.path.as_str(), object.info.path_id)).or_default().push(path.clone());
+            }
+        }
+        result
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Object<'a> {
     pub env: &'a Env,
     pub asset: &'a SerializedFile,
@@ -110,16 +762,180 @@ pub struct Object<'a> {
     pub cache: Arc<DashMap<i64, RgbaImage>>,
 }
 
+/// Lets untyped pointers such as `PPtr<Object>` resolve to the object itself.
+impl<'a> FromObject<'a> for Object<'a> {
+    fn load(object: &Object<'a>) -> UnityResult<Self> {
+        Ok(object.clone())
+    }
+
+    fn class() -> ClassID {
+        ClassID::Object
+    }
+}
+
 impl<'a> Object<'a> {
-    pub fn read<T: FromObject<'a>>(&'a self) -> UnityResult<T> {
-        T::load(self)
+    /// Reads the object as `T`. Errors are wrapped in [`UnityError::ReadObject`] with the
+    /// object's file, class and path id.
+    pub fn read<T: FromObject<'a>>(&self) -> UnityResult<T> {
+        T::load(self).map_err(|e| UnityError::ReadObject {
+            file: self.asset.path.clone(),
+            class: self.class(),
+            path_id: self.info.path_id,
+            source: Box::new(e),
+        })
+    }
+
+    /// Like [`read`](Self::read), but parses each type at most once per object and shares the result.
+    ///
+    /// Only owned classes such as [`Texture2D`](crate::classes::Texture2D) can be cached; classes
+    /// holding [`PPtr`](crate::classes::PPtr)s borrow the [`Env`] and are not `'static`.
+    pub fn read_cached<T: FromObject<'a> + Send + Sync + 'static>(&self) -> UnityResult<Arc<T>> {
+        self.info.read_cache.get_or_try_insert(|| self.read::<T>())
     }
 
     pub fn class(&self) -> ClassID {
         ClassID::from(self.info.class_id)
     }
 
-    pub fn read_type_tree<T: DeserializeOwned>(&self) -> Result<T, ReadTypeTreeError> {
-        self.info.read_type_tree()
+    /// Reads just `m_Name`, skipping the header of GameObjects and MonoBehaviours, without parsing
+    /// the rest of the object. `None` for classes that have no leading name. Names that are not
+    /// UTF-8 are decoded with [`Env::decode_string`].
+    pub fn peek_name(&self) -> UnityResult<Option<String>> {
+        let mut r = self.info.get_reader()?;
+        let pptr_size = if self.info.asset_version < 14 { 8 } else { 12 };
+        match self.class() {
+            class if starts_with_name(class) => {}
+            ClassID::GameObject => {
+                let version = self.info.version;
+                let count = r.read_i32()?.max(0) as usize;
+                let class_size = if !version.is_at_least(5, 5) { 4 } else { 0 };
+                r.set_offset(r.get_offset() + count * (class_size + pptr_size))?;
+                let _layer = r.read_u32()?;
+            }
+            ClassID::MonoBehaviour => {
+                r.set_offset(pptr_size)?;
+                let _enabled = r.read_bool()?;
+                r.align(4)?;
+                r.set_offset(r.get_offset() + pptr_size)?;
+            }
+            _ => return Ok(None),
+        }
+        Ok(Some(self.env.decode_string(r.read_aligned_string_raw()?)))
+    }
+
+    /// Deserializes the object, see [`ObjectInfo::read_type_tree`]. Borrowed fields live as long
+    /// as the [`Env`]. Managed references of `[SerializeReference]` fields are read with the
+    /// file's [`ref_types`](SerializedFile::ref_types).
+    pub fn read_type_tree<T: Deserialize<'a>>(&self) -> Result<T, ReadTypeTreeError> {
+        let nodes = self.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.deserialize(&nodes, &self.asset.ref_types, false)
+    }
+
+    /// Deserializes the object, checking that it reads all of its bytes, see
+    /// [`ObjectInfo::read_type_tree_strict`].
+    pub fn read_type_tree_strict<T: Deserialize<'a>>(&self) -> Result<T, ReadTypeTreeError> {
+        let nodes = self.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.deserialize(&nodes, &self.asset.ref_types, true)
+    }
+
+    /// Reads the object into a generic value, see [`ObjectInfo::read_value`].
+    pub fn read_value(&self) -> Result<serde_json::Value, ReadTypeTreeError> {
+        self.read_type_tree()
+    }
+
+    /// The object as indented JSON, with fields in Unity's order.
+    pub fn to_json_string_pretty(&self) -> Result<String, ReadTypeTreeError> {
+        serde_json::to_string_pretty(&self.read_value()?).map_err(|e| ReadTypeTreeError::Custom(e.to_string()))
+    }
+
+    /// Deserializes the single field at `path`, see [`ObjectInfo::read_field`].
+    pub fn read_field<T: DeserializeOwned>(&self, path: &str) -> Result<T, ReadTypeTreeError> {
+        let nodes = self.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.read_field_with(&nodes, path)
+    }
+
+    /// The embedded type tree. Stripped MonoBehaviours fall back to the one supplied by
+    /// [`Env::typetree_provider`] and other classes to [`typetree::builtin_nodes`] for the file's version.
+    pub fn type_tree_nodes(&self) -> Option<Cow<'a, [TypeTreeNode]>> {
+        let nodes = self.info.serialized_type.type_tree.nodes.as_slice();
+        if !nodes.is_empty() {
+            return Some(Cow::Borrowed(nodes));
+        }
+        if self.class() != ClassID::MonoBehaviour {
+            return typetree::builtin_nodes(self.class(), self.info.version, self.info.asset_version).map(Cow::Owned);
+        }
+        self.external_type_tree_nodes().map(Cow::Owned)
+    }
+
+    fn external_type_tree_nodes(&self) -> Option<Vec<TypeTreeNode>> {
+        let provider = self.env.typetree_provider.as_ref()?;
+        let script = self.read::<MonoBehaviour>().ok()?.script().ok()?;
+        let nodes = provider.get_nodes(&script.assembly_name, &script.full_name(), &self.asset.unity_version)?;
+        Some(typetree::with_mono_behaviour_header(nodes, self.info.asset_version))
+    }
+}
+
+/// Classes deriving from Unity's `NamedObject`, whose payload starts with `m_Name`.
+fn starts_with_name(class: ClassID) -> bool {
+    matches!(
+        class,
+        ClassID::AnimationClip
+            | ClassID::AnimatorController
+            | ClassID::AnimatorOverrideController
+            | ClassID::AssetBundle
+            | ClassID::AssetBundleManifest
+            | ClassID::AudioClip
+            | ClassID::AudioMixer
+            | ClassID::Avatar
+            | ClassID::AvatarMask
+            | ClassID::ComputeShader
+            | ClassID::Cubemap
+            | ClassID::Flare
+            | ClassID::Font
+            | ClassID::LightingDataAsset
+            | ClassID::LightmapParameters
+            | ClassID::Material
+            | ClassID::Mesh
+            | ClassID::MonoScript
+            | ClassID::NavMeshData
+            | ClassID::PhysicMaterial
+            | ClassID::PhysicsMaterial2D
+            | ClassID::RenderTexture
+            | ClassID::Shader
+            | ClassID::ShaderVariantCollection
+            | ClassID::Sprite
+            | ClassID::SpriteAtlas
+            | ClassID::TerrainData
+            | ClassID::TerrainLayer
+            | ClassID::TextAsset
+            | ClassID::Texture2D
+            | ClassID::Texture2DArray
+            | ClassID::Texture3D
+            | ClassID::VideoClip
+    )
+}
+
+/// Whether all of `text` matches `pattern`, where `*` is any run of characters and `?` one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The last `*` and where in the text its run would end if the rest fails to match.
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            star = Some((star_p, star_t + 1));
+            p = star_p + 1;
+            t = star_t + 1;
+        } else {
+            return false;
+        }
     }
+    pattern[p..].iter().all(|x| *x == '*')
 }
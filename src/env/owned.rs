@@ -0,0 +1,99 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::Object;
+use crate::asset::SerializedType;
+use crate::classes::ClassID;
+use crate::object::{ObjectInfo, ReadTypeTreeError};
+use crate::typetree::TypeTreeNode;
+
+/// An object that no longer borrows its [`Env`](super::Env), see [`Object::to_owned`].
+///
+/// It keeps the file data the object is read from, so it can be sent to other threads and read
+/// after the `Env` is dropped. Only type tree reads are available, as classes read through
+/// [`FromObject`](crate::classes::FromObject) may need other objects of the `Env`.
+#[derive(Debug, Clone)]
+pub struct OwnedObject {
+    pub info: Arc<ObjectInfo>,
+    /// The path of the serialized file holding the object.
+    pub file: String,
+    pub unity_version: String,
+    /// The type tree found when detaching, if the object does not embed one.
+    nodes: Option<Arc<[TypeTreeNode]>>,
+    /// The file's managed reference types, kept when the type tree has a registry.
+    ref_types: Arc<[SerializedType]>,
+}
+
+impl Object<'_> {
+    /// Copies what reading the object's type tree needs into an [`OwnedObject`]. The file data
+    /// is shared, not copied. A type tree the object does not embed is looked up now, from the
+    /// `Env`'s type tree provider or the built-in ones.
+    pub fn to_owned(&self) -> OwnedObject {
+        let nodes = self.type_tree_nodes();
+        let ref_types: Arc<[SerializedType]> = match &nodes {
+            Some(nodes) if nodes.iter().any(|x| x.type_ == "ManagedReferencesRegistry") => Arc::from(self.asset.ref_types.as_slice()),
+            _ => Arc::from([]),
+        };
+        let nodes = match nodes {
+            Some(Cow::Owned(nodes)) => Some(Arc::from(nodes)),
+            _ => None,
+        };
+        OwnedObject {
+            info: Arc::new(self.info.clone()),
+            file: self.asset.path.clone(),
+            unity_version: self.asset.unity_version.clone(),
+            nodes,
+            ref_types,
+        }
+    }
+}
+
+impl OwnedObject {
+    pub fn class(&self) -> ClassID {
+        self.info.class()
+    }
+
+    pub fn path_id(&self) -> i64 {
+        self.info.path_id
+    }
+
+    /// The embedded type tree, or the one found by [`Object::to_owned`].
+    pub fn type_tree_nodes(&self) -> Option<&[TypeTreeNode]> {
+        let nodes = self.info.serialized_type.type_tree.nodes.as_slice();
+        if !nodes.is_empty() {
+            return Some(nodes);
+        }
+        self.nodes.as_deref()
+    }
+
+    /// Deserializes the object, see [`Object::read_type_tree`]. Borrowed fields live as long
+    /// as the handle.
+    pub fn read_type_tree<'a, T: Deserialize<'a>>(&'a self) -> Result<T, ReadTypeTreeError> {
+        let nodes = self.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.deserialize(nodes, &self.ref_types, false)
+    }
+
+    /// Deserializes the object, checking that it reads all of its bytes.
+    pub fn read_type_tree_strict<'a, T: Deserialize<'a>>(&'a self) -> Result<T, ReadTypeTreeError> {
+        let nodes = self.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.deserialize(nodes, &self.ref_types, true)
+    }
+
+    pub fn read_value(&self) -> Result<serde_json::Value, ReadTypeTreeError> {
+        self.read_type_tree()
+    }
+
+    /// The object as indented JSON, with fields in Unity's order.
+    pub fn to_json_string_pretty(&self) -> Result<String, ReadTypeTreeError> {
+        serde_json::to_string_pretty(&self.read_value()?).map_err(|e| ReadTypeTreeError::Custom(e.to_string()))
+    }
+
+    /// Deserializes the single field at `path`, see [`ObjectInfo::read_field`].
+    pub fn read_field<T: DeserializeOwned>(&self, path: &str) -> Result<T, ReadTypeTreeError> {
+        let nodes = self.type_tree_nodes().ok_or(ReadTypeTreeError::MissingTypeTree)?;
+        self.info.read_field_with(nodes, path)
+    }
+}
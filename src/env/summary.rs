@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use super::Env;
+use crate::bundle::{BufferCounter, CompressionType, FileData};
+use crate::classes::ClassID;
+
+/// How many of the largest objects [`Env::summary`] lists.
+const LARGEST_OBJECTS: usize = 10;
+
+/// An overview of what an [`Env`] holds, see [`Env::summary`].
+#[derive(Debug, Clone, Default)]
+pub struct EnvSummary {
+    pub serialized_files: usize,
+    pub objects: usize,
+    /// The sum of the objects' sizes.
+    pub bytes: usize,
+    /// By total size, largest first.
+    pub classes: Vec<ClassSummary>,
+    /// Largest first.
+    pub largest_objects: Vec<ObjectSummary>,
+    /// The Unity versions of the serialized files, each once in load order.
+    pub unity_versions: Vec<String>,
+    /// The compressions of the bundles the serialized files come from, each once in load order.
+    pub compressions: Vec<CompressionType>,
+}
+
+/// The objects of one class.
+#[derive(Debug, Clone)]
+pub struct ClassSummary {
+    /// As stored, since unknown classes are all [`ClassID::UnknownType`].
+    pub class_id: i32,
+    pub count: usize,
+    pub bytes: usize,
+}
+
+impl ClassSummary {
+    pub fn class(&self) -> ClassID {
+        ClassID::from(self.class_id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectSummary {
+    /// The path of the serialized file holding the object.
+    pub file: String,
+    pub path_id: i64,
+    pub class_id: i32,
+    pub bytes: usize,
+}
+
+impl Display for EnvSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} serialized files, {} objects, {} bytes", self.serialized_files, self.objects, self.bytes)?;
+        writeln!(f, "unity versions: {}", self.unity_versions.join(", "))?;
+        let compressions: Vec<String> = self.compressions.iter().map(|x| format!("{:?}", x)).collect();
+        writeln!(f, "compression: {}", compressions.join(", "))?;
+        writeln!(f)?;
+        writeln!(f, "{:<32} {:>10} {:>14}", "class", "count", "bytes")?;
+        for class in &self.classes {
+            writeln!(f, "{:<32} {:>10} {:>14}", ClassID::name_of(class.class_id), class.count, class.bytes)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<32} {:>20} {:>14}  file", "largest objects", "path id", "bytes")?;
+        for object in &self.largest_objects {
+            writeln!(f, "{:<32} {:>20} {:>14}  {}", ClassID::name_of(object.class_id), object.path_id, object.bytes, object.file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Memory held by an [`Env`] beyond its object tables, see [`Env::memory_usage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The loaded files and bundles, including memory mapped ones that are not on the heap.
+    pub file_data: usize,
+    /// Decompressed blocks cached by bundles that are decompressed on demand.
+    pub decompressed_blocks: usize,
+    /// Object data copied out of such bundles when read.
+    pub object_data: usize,
+    /// Images decoded into [`Env::cache`].
+    pub images: usize,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.file_data + self.decompressed_blocks + self.object_data + self.images
+    }
+}
+
+impl Env {
+    /// Counts objects and their sizes per class from the object tables, without reading any
+    /// object.
+    pub fn summary(&self) -> EnvSummary {
+        let mut result = EnvSummary {
+            serialized_files: self.serialized_files.len(),
+            ..EnvSummary::default()
+        };
+        let mut classes: HashMap<i32, ClassSummary> = HashMap::new();
+        let mut largest: Vec<ObjectSummary> = Vec::new();
+        for asset in &self.serialized_files {
+            if !asset.unity_version.is_empty() && !result.unity_versions.contains(&asset.unity_version) {
+                result.unity_versions.push(asset.unity_version.clone());
+            }
+            if let Some(compression) = asset.compression.filter(|x| !result.compressions.contains(x)) {
+                result.compressions.push(compression);
+            }
+            for entry in asset.object_entries() {
+                result.objects += 1;
+                result.bytes += entry.bytes_size;
+                let class = classes.entry(entry.class_id).or_insert(ClassSummary { class_id: entry.class_id, count: 0, bytes: 0 });
+                class.count += 1;
+                class.bytes += entry.bytes_size;
+                if largest.len() < LARGEST_OBJECTS || largest.last().is_some_and(|x| x.bytes < entry.bytes_size) {
+                    let object = ObjectSummary {
+                        file: asset.path.clone(),
+                        path_id: entry.path_id,
+                        class_id: entry.class_id,
+                        bytes: entry.bytes_size,
+                    };
+                    let position = largest.partition_point(|x| x.bytes >= entry.bytes_size);
+                    largest.insert(position, object);
+                    largest.truncate(LARGEST_OBJECTS);
+                }
+            }
+        }
+        result.classes = classes.into_values().collect();
+        result.classes.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.class_id.cmp(&b.class_id)));
+        result.largest_objects = largest;
+        result
+    }
+
+    /// Estimates the memory the loaded data, caches and decoded images take up. Buffers shared
+    /// by several files, such as a bundle's, are counted once.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut result = MemoryUsage::default();
+        let mut counter = BufferCounter::default();
+        let mut add = |data: &FileData, decompressed: bool| {
+            let size = counter.count(data);
+            if decompressed {
+                result.decompressed_blocks += size;
+            } else {
+                result.file_data += size;
+            }
+        };
+        for asset in &self.serialized_files {
+            asset.source().for_each_buffer(&mut add);
+        }
+        for file in self.loaded_files.iter() {
+            file.value().for_each_buffer(&mut add);
+        }
+        for asset in &self.serialized_files {
+            for info in asset.built_objects() {
+                if let Some(bytes) = info.bytes.get() {
+                    result.object_data += counter.count(bytes);
+                }
+            }
+        }
+        result.images = self.cache.iter().map(|x| x.value().as_raw().len()).sum();
+        result
+    }
+}
@@ -1,13 +1,19 @@
+use std::fmt::Display;
+use std::path::PathBuf;
 use std::string::FromUtf8Error;
 
 use thiserror::Error;
 
+use crate::classes::ClassID;
+use crate::object::{ReadTypeTreeError, WriteTypeTreeError};
 use crate::reader;
 
 #[derive(Error, Debug)]
 pub enum UnityError {
-    #[error("Eof")]
+    #[error("Unexpected end of data")]
     Eof,
+    #[error("{0}")]
+    BadLength(#[from] reader::BadLength),
     #[error("Utf8")]
     Utf8(#[from] std::str::Utf8Error),
     #[error("InvalidValue")]
@@ -32,10 +38,99 @@ pub enum UnityError {
     UnknownVersion,
     #[error("Except File type {0}")]
     FileTypeMissMatch(String),
+    #[error("Bundle signature not found: {0}")]
+    SignatureNotFound(String),
+    #[error("IoError: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("ReadTypeTreeError: {0}")]
+    ReadTypeTree(#[from] ReadTypeTreeError),
+    #[error("WriteTypeTreeError: {0}")]
+    WriteTypeTree(#[from] WriteTypeTreeError),
+    /// An error reading an object, with the object it happened in and the serialized file
+    /// holding it. Like the other context variants, its message leaves the cause to
+    /// [`std::error::Error::source`].
+    #[error("Reading {class:?} object {path_id} in {file}")]
+    ReadObject { file: String, class: ClassID, path_id: i64, source: Box<UnityError> },
+    /// An error loading a file from disk, with its path.
+    #[error("Loading {path:?}")]
+    LoadFile { path: PathBuf, source: Box<UnityError> },
+    /// An error parsing a bundle or serialized file, with where in the file it happened.
+    #[error("{context}")]
+    Parse { context: ParseContext, source: Box<UnityError> },
 }
 
 pub type UnityResult<T> = Result<T, UnityError>;
 
+/// Where in a file parsing failed, see [`UnityError::Parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseContext {
+    /// The file's name, such as the path of a bundle's node, or `bundle` for the header and
+    /// blocks of a bundle.
+    pub file: String,
+    /// The part of the file being read, such as `header` or `objects[3]`.
+    pub section: String,
+    /// Where the reader was in the file, or where the compressed data being read starts.
+    pub offset: usize,
+}
+
+impl Display for ParseContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} at offset {:#X}", self.file, self.section, self.offset)
+    }
+}
+
+/// The part of a file a parser is in. It only becomes a string once an error needs it, as
+/// parsers move through every entry of their tables.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Section {
+    name: &'static str,
+    index: Option<usize>,
+}
+
+impl Section {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self { name, index: None }
+    }
+
+    /// Entry `index` of the table `name`.
+    pub(crate) fn entry(name: &'static str, index: usize) -> Self {
+        Self { name, index: Some(index) }
+    }
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{}[{}]", self.name, index),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl UnityError {
+    /// Wraps the error with where in `file` it happened, unless it already says.
+    pub(crate) fn in_section(self, file: &str, section: Section, offset: usize) -> Self {
+        if matches!(self, Self::Parse { .. }) {
+            return self;
+        }
+        let context = ParseContext {
+            file: file.to_string(),
+            section: section.to_string(),
+            offset,
+        };
+        Self::Parse { context, source: Box::new(self) }
+    }
+
+    /// The error without the context [`UnityError::Parse`], [`UnityError::ReadObject`] and
+    /// [`UnityError::LoadFile`] add.
+    pub fn root_cause(&self) -> &UnityError {
+        match self {
+            Self::Parse { source, .. } | Self::ReadObject { source, .. } | Self::LoadFile { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
+}
+
 impl From<&'static str> for UnityError {
     fn from(value: &'static str) -> Self {
         Self::CustomError(value.to_string())
@@ -47,3 +142,12 @@ impl From<reader::Eof> for UnityError {
         Self::Eof
     }
 }
+
+impl From<reader::ReadError> for UnityError {
+    fn from(value: reader::ReadError) -> Self {
+        match value {
+            reader::ReadError::Eof => Self::Eof,
+            reader::ReadError::BadLength(e) => Self::BadLength(e),
+        }
+    }
+}
@@ -8,9 +8,15 @@ pub mod math;
 pub mod object;
 pub mod reader;
 pub mod typetree;
+mod unitycn;
+pub mod version;
+pub mod warning;
+pub mod writer;
 
 pub use crate::classes::{ClassID, Sprite};
-pub use crate::env::{Env, Object};
+pub use crate::env::{ClassSummary, Env, EnvSummary, MemoryUsage, Object, ObjectSummary, OwnedObject};
 pub use crate::error::UnityError;
 pub use crate::error::UnityResult;
+pub use crate::version::UnityVersion;
+pub use crate::warning::LoadWarning;
 pub use texture_decoder;
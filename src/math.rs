@@ -1,3 +1,8 @@
+mod de;
+
+use std::f32::consts::FRAC_PI_2;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
 #[derive(Default, Debug, Copy, Clone)]
 pub struct RectF32 {
     pub x: f32,
@@ -6,52 +11,178 @@ pub struct RectF32 {
     pub h: f32,
 }
 
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32,
 }
 
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct Vector4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Implements the constructors, component-wise operators and the methods shared by the vector
+/// types with the fields `$field`.
+macro_rules! vector {
+    ($type_:ident, [$($field:ident),+]) => {
+        impl $type_ {
+            pub const ZERO: Self = Self { $($field: 0.0),+ };
+            pub const ONE: Self = Self { $($field: 1.0),+ };
+
+            pub fn new($($field: f32),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            pub fn dot(&self, other: &Self) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            pub fn length(&self) -> f32 {
+                self.dot(self).sqrt()
+            }
+
+            /// Scales to a length of 1 in place. Vectors too short to have a direction, like
+            /// [`Self::ZERO`], become zero instead of NaN, as in Unity.
+            pub fn normalize(&mut self) {
+                *self = self.normalized();
+            }
+
+            /// A copy scaled to a length of 1, zero for vectors too short to have a direction.
+            pub fn normalized(&self) -> Self {
+                let length = self.length();
+                if length > 0.00001 {
+                    *self * (1.0 / length)
+                } else {
+                    Self::ZERO
+                }
+            }
+
+            /// The linear interpolation from `self` at `t = 0` to `other` at `t = 1`, `t` not
+            /// clamped.
+            pub fn lerp(&self, other: &Self, t: f32) -> Self {
+                Self { $($field: self.$field + (other.$field - self.$field) * t),+ }
+            }
+
+            /// The smallest of each component, such as the lower corner of a bounding box.
+            pub fn min(&self, other: &Self) -> Self {
+                Self { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            /// The largest of each component, such as the upper corner of a bounding box.
+            pub fn max(&self, other: &Self) -> Self {
+                Self { $($field: self.$field.max(other.$field)),+ }
+            }
+        }
+
+        impl Add for $type_ {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl Sub for $type_ {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $type_ {
+            type Output = Self;
+
+            fn mul(self, rhs: f32) -> Self::Output {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl Neg for $type_ {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $type_ {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $type_ {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl MulAssign<f32> for $type_ {
+            fn mul_assign(&mut self, rhs: f32) {
+                *self = *self * rhs;
+            }
+        }
+    };
+}
+
+vector!(Vector2, [x, y]);
+vector!(Vector3, [x, y, z]);
+vector!(Vector4, [x, y, z, w]);
+
 impl Vector3 {
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
-        Self { x, y, z }
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(self.y * other.z - self.z * other.y, self.z * other.x - self.x * other.z, self.x * other.y - self.y * other.x)
     }
+}
 
-    pub fn length(&self) -> f32 {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+/// Drops `z`.
+impl From<Vector3> for Vector2 {
+    fn from(value: Vector3) -> Self {
+        Self::new(value.x, value.y)
     }
+}
 
-    pub fn normalize(&mut self) {
-        let length = self.length();
-        if length > 0.00001 {
-            let inv_norm = 1.0 / length;
-            self.x *= inv_norm;
-            self.y *= inv_norm;
-            self.z *= inv_norm;
-        } else {
-            self.x = 0.0;
-            self.y = 0.0;
-            self.z = 0.0;
-        }
+/// With `z = 0`.
+impl From<Vector2> for Vector3 {
+    fn from(value: Vector2) -> Self {
+        Self::new(value.x, value.y, 0.0)
     }
 }
 
-#[derive(Default, Debug, Copy, Clone)]
-pub struct Vector4 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
-    pub w: f32,
+/// Drops `w`.
+impl From<Vector4> for Vector3 {
+    fn from(value: Vector4) -> Self {
+        Self::new(value.x, value.y, value.z)
+    }
 }
 
-#[derive(Default, Debug, Copy, Clone)]
+/// As a point, with `w = 1`.
+impl From<Vector3> for Vector4 {
+    fn from(value: Vector3) -> Self {
+        Self::new(value.x, value.y, value.z, 1.0)
+    }
+}
+
+/// A matrix for column vectors, `mRC` being row `R` and column `C`, so the translation is
+/// `m03`, `m13` and `m23` like Unity's `Matrix4x4`.
+///
+/// Unity stores the 16 floats column by column, `m00`, `m10`, `m20`, `m30`, `m01` and so on,
+/// which is the order of [`Self::to_array`] and [`Self::from_array`]. Its `Matrix4x4f` type
+/// tree names them `e00` to `e33` in that same order, column first: `e01` is `m10` and `e30`
+/// is `m03`.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub struct Matrix4x4 {
     pub m00: f32,
     pub m10: f32,
@@ -75,6 +206,57 @@ pub struct Matrix4x4 {
 }
 
 impl Matrix4x4 {
+    pub fn identity() -> Self {
+        Self {
+            m00: 1.0,
+            m11: 1.0,
+            m22: 1.0,
+            m33: 1.0,
+            ..Self::default()
+        }
+    }
+
+    /// Builds a translation * rotation * scale matrix, like Unity's `Matrix4x4.TRS`.
+    pub fn trs(translation: &Vector3, rotation: &Quaternion, scale: &Vector3) -> Self {
+        let Quaternion { x, y, z, w } = *rotation;
+        let (x2, y2, z2) = (x + x, y + y, z + z);
+        let (xx, yy, zz) = (x * x2, y * y2, z * z2);
+        let (xy, xz, yz) = (x * y2, x * z2, y * z2);
+        let (wx, wy, wz) = (w * x2, w * y2, w * z2);
+        Self {
+            m00: (1.0 - (yy + zz)) * scale.x,
+            m10: (xy + wz) * scale.x,
+            m20: (xz - wy) * scale.x,
+            m30: 0.0,
+            m01: (xy - wz) * scale.y,
+            m11: (1.0 - (xx + zz)) * scale.y,
+            m21: (yz + wx) * scale.y,
+            m31: 0.0,
+            m02: (xz + wy) * scale.z,
+            m12: (yz - wx) * scale.z,
+            m22: (1.0 - (xx + yy)) * scale.z,
+            m32: 0.0,
+            m03: translation.x,
+            m13: translation.y,
+            m23: translation.z,
+            m33: 1.0,
+        }
+    }
+
+    pub fn to_array(&self) -> [f32; 16] {
+        [
+            self.m00, self.m10, self.m20, self.m30, self.m01, self.m11, self.m21, self.m31, self.m02, self.m12, self.m22, self.m32, self.m03, self.m13, self.m23, self.m33,
+        ]
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> f32 {
+        self.to_array()[column * 4 + row]
+    }
+
+    pub fn translation(&self) -> Vector3 {
+        Vector3::new(self.m03, self.m13, self.m23)
+    }
+
     pub fn from_array(array: [f32; 16]) -> Self {
         Self {
             m00: array[0],
@@ -95,8 +277,121 @@ impl Matrix4x4 {
             m33: array[15],
         }
     }
+
+    fn column(&self, column: usize) -> Vector3 {
+        Vector3::new(self.get(0, column), self.get(1, column), self.get(2, column))
+    }
+
+    /// The inverse, `None` for a matrix that collapses a dimension, like one with a zero scale.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = self.to_array();
+        let mut inv = [0.0; 16];
+        inv[0] = m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15] + m[9] * m[7] * m[14] + m[13] * m[6] * m[11] - m[13] * m[7] * m[10];
+        inv[4] = -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15] - m[8] * m[7] * m[14] - m[12] * m[6] * m[11] + m[12] * m[7] * m[10];
+        inv[8] = m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15] + m[8] * m[7] * m[13] + m[12] * m[5] * m[11] - m[12] * m[7] * m[9];
+        inv[12] = -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14] - m[8] * m[6] * m[13] - m[12] * m[5] * m[10] + m[12] * m[6] * m[9];
+        inv[1] = -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15] - m[9] * m[3] * m[14] - m[13] * m[2] * m[11] + m[13] * m[3] * m[10];
+        inv[5] = m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15] + m[8] * m[3] * m[14] + m[12] * m[2] * m[11] - m[12] * m[3] * m[10];
+        inv[9] = -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15] - m[8] * m[3] * m[13] - m[12] * m[1] * m[11] + m[12] * m[3] * m[9];
+        inv[13] = m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14] + m[8] * m[2] * m[13] + m[12] * m[1] * m[10] - m[12] * m[2] * m[9];
+        inv[2] = m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15] + m[5] * m[3] * m[14] + m[13] * m[2] * m[7] - m[13] * m[3] * m[6];
+        inv[6] = -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15] - m[4] * m[3] * m[14] - m[12] * m[2] * m[7] + m[12] * m[3] * m[6];
+        inv[10] = m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15] + m[4] * m[3] * m[13] + m[12] * m[1] * m[7] - m[12] * m[3] * m[5];
+        inv[14] = -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14] - m[4] * m[2] * m[13] - m[12] * m[1] * m[6] + m[12] * m[2] * m[5];
+        inv[3] = -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11] - m[5] * m[3] * m[10] - m[9] * m[2] * m[7] + m[9] * m[3] * m[6];
+        inv[7] = m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11] + m[4] * m[3] * m[10] + m[8] * m[2] * m[7] - m[8] * m[3] * m[6];
+        inv[11] = -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11] - m[4] * m[3] * m[9] - m[8] * m[1] * m[7] + m[8] * m[3] * m[5];
+        inv[15] = m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10] + m[4] * m[2] * m[9] + m[8] * m[1] * m[6] - m[8] * m[2] * m[5];
+
+        let determinant = m[0] * inv[0] + m[1] * inv[4] + m[2] * inv[8] + m[3] * inv[12];
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        Some(Self::from_array(inv.map(|x| x / determinant)))
+    }
+
+    /// Transforms a position, like Unity's `MultiplyPoint3x4`: the bottom row is taken to be
+    /// `0, 0, 0, 1`, as it is for TRS matrices.
+    pub fn transform_point(&self, point: &Vector3) -> Vector3 {
+        self.transform_vector(point) + self.translation()
+    }
+
+    /// Transforms a direction or offset, which the translation does not apply to, like Unity's
+    /// `MultiplyVector`.
+    pub fn transform_vector(&self, vector: &Vector3) -> Vector3 {
+        self.column(0) * vector.x + self.column(1) * vector.y + self.column(2) * vector.z
+    }
+
+    /// Splits a matrix built by [`Self::trs`] back into its translation, rotation and scale.
+    ///
+    /// Mirroring matrices, with an odd number of negative scales, come back with only the x
+    /// scale negative, and the rotation that makes up for it; [`Self::trs`] of the result gives
+    /// the same matrix. An axis scaled to zero takes its direction from the other two; with more
+    /// than one, the rotation cannot be recovered. Shear, from scaling under a
+    /// rotated parent, is dropped.
+    pub fn decompose(&self) -> (Vector3, Quaternion, Vector3) {
+        let mut columns = [self.column(0), self.column(1), self.column(2)];
+        let mut scale = Vector3::new(columns[0].length(), columns[1].length(), columns[2].length());
+        if columns[0].cross(&columns[1]).dot(&columns[2]) < 0.0 {
+            scale.x = -scale.x;
+        }
+        let scales = [scale.x, scale.y, scale.z];
+        for (column, scale) in columns.iter_mut().zip(scales) {
+            if scale != 0.0 {
+                *column = *column * (1.0 / scale);
+            }
+        }
+        for (i, scale) in scales.into_iter().enumerate() {
+            if scale == 0.0 {
+                columns[i] = columns[(i + 1) % 3].cross(&columns[(i + 2) % 3]);
+            }
+        }
+        (self.translation(), rotation_from_columns(&columns), scale)
+    }
+}
+
+/// The rotation of an orthonormal basis, the axes' images as columns.
+fn rotation_from_columns([x, y, z]: &[Vector3; 3]) -> Quaternion {
+    let (m00, m10, m20) = (x.x, x.y, x.z);
+    let (m01, m11, m21) = (y.x, y.y, y.z);
+    let (m02, m12, m22) = (z.x, z.y, z.z);
+    // Divides by the largest of the four components, which is never close to zero.
+    let trace = m00 + m11 + m22;
+    let q = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        Quaternion::new((m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s, 0.25 * s)
+    } else if m00 > m11 && m00 > m22 {
+        let s = (1.0 + m00 - m11 - m22).sqrt() * 2.0;
+        Quaternion::new(0.25 * s, (m01 + m10) / s, (m02 + m20) / s, (m21 - m12) / s)
+    } else if m11 > m22 {
+        let s = (1.0 + m11 - m00 - m22).sqrt() * 2.0;
+        Quaternion::new((m01 + m10) / s, 0.25 * s, (m12 + m21) / s, (m02 - m20) / s)
+    } else {
+        let s = (1.0 + m22 - m00 - m11).sqrt() * 2.0;
+        Quaternion::new((m02 + m20) / s, (m12 + m21) / s, 0.25 * s, (m10 - m01) / s)
+    };
+    if q.x.is_finite() && q.y.is_finite() && q.z.is_finite() && q.w.is_finite() {
+        q.normalized()
+    } else {
+        Quaternion::IDENTITY
+    }
+}
+
+impl Mul for Matrix4x4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = [0.0; 16];
+        for column in 0..4 {
+            for row in 0..4 {
+                result[column * 4 + row] = (0..4).map(|i| self.get(row, i) * rhs.get(i, column)).sum();
+            }
+        }
+        Self::from_array(result)
+    }
 }
 
+#[derive(Debug, Copy, Clone)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -112,8 +407,29 @@ impl Color {
     pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
         Self { r, g, b, a }
     }
+
+    /// Unpacks a serialized `ColorRGBA32`, stored as little endian `r, g, b, a` bytes.
+    pub fn from_rgba32(value: u32) -> Self {
+        let [r, g, b, a] = value.to_le_bytes();
+        Self::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+    }
+
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let to_u8 = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+        [to_u8(self.r), to_u8(self.g), to_u8(self.b), to_u8(self.a)]
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Quaternion {
     pub x: f32,
     pub y: f32,
@@ -129,4 +445,120 @@ impl Quaternion {
     pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
         Self { x, y, z, w }
     }
+
+    pub const IDENTITY: Self = Self { x: 0.0, y: 0.0, z: 0.0, w: 1.0 };
+
+    /// The rotation by `euler` degrees around each axis, like Unity's `Quaternion.Euler`: around
+    /// z, then x, then y.
+    pub fn from_euler_degrees(euler: &Vector3) -> Self {
+        let around = |axis: Vector3, degrees: f32| {
+            let (sin, cos) = (degrees.to_radians() * 0.5).sin_cos();
+            Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+        };
+        around(Vector3::new(0.0, 1.0, 0.0), euler.y) * around(Vector3::new(1.0, 0.0, 0.0), euler.x) * around(Vector3::new(0.0, 0.0, 1.0), euler.z)
+    }
+
+    /// The angles in degrees that [`Self::from_euler_degrees`] turns back into this rotation,
+    /// each in `[0, 360)` like Unity's `Quaternion.eulerAngles`, which is what the inspector
+    /// shows for rotations not typed in there.
+    ///
+    /// When x is close to 90 or 270, y and z rotate around the same axis and only their
+    /// difference or sum is known, so all of it goes to y and z is 0.
+    pub fn to_euler_degrees(&self) -> Vector3 {
+        let m = self.to_matrix();
+        let sin_x = (-m.m12).clamp(-1.0, 1.0);
+        let (x, y, z) = if sin_x.abs() < 0.99999 {
+            (sin_x.asin(), m.m02.atan2(m.m22), m.m10.atan2(m.m11))
+        } else if sin_x > 0.0 {
+            (FRAC_PI_2, m.m01.atan2(m.m00), 0.0)
+        } else {
+            (-FRAC_PI_2, (-m.m01).atan2(m.m00), 0.0)
+        };
+        let wrap = |radians: f32| {
+            let degrees = radians.to_degrees().rem_euclid(360.0);
+            // Tiny negative angles round up to 360.
+            if degrees >= 360.0 {
+                0.0
+            } else {
+                degrees
+            }
+        };
+        Vector3::new(wrap(x), wrap(y), wrap(z))
+    }
+
+    /// The rotation matrix, like Unity's `Matrix4x4.Rotate`. The quaternion should be
+    /// normalized.
+    pub fn to_matrix(&self) -> Matrix4x4 {
+        Matrix4x4::trs(&Vector3::ZERO, self, &Vector3::ONE)
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// Scales to a length of 1 in place, see [`Self::normalized`].
+    pub fn normalize(&mut self) {
+        *self = self.normalized();
+    }
+
+    /// A copy scaled to a length of 1. Quaternions too short to be a rotation become
+    /// [`Self::IDENTITY`], as in Unity.
+    pub fn normalized(&self) -> Self {
+        let length = self.dot(self).sqrt();
+        if length > 0.00001 {
+            let inv_norm = 1.0 / length;
+            Self::new(self.x * inv_norm, self.y * inv_norm, self.z * inv_norm, self.w * inv_norm)
+        } else {
+            Self::IDENTITY
+        }
+    }
+
+    /// The spherical interpolation from `self` at `t = 0` to `other` at `t = 1` along the
+    /// shortest arc, `t` clamped to `[0, 1]` like Unity's `Quaternion.Slerp`. Both should be
+    /// normalized.
+    pub fn slerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mut dot = self.dot(other);
+        // `q` and `-q` are the same rotation; the one on the same side as `self` is closer.
+        let other = if dot < 0.0 {
+            dot = -dot;
+            Self::new(-other.x, -other.y, -other.z, -other.w)
+        } else {
+            *other
+        };
+        let (a, b) = if dot > 0.9995 {
+            // Nearly the same rotation, where the sine below would divide by almost zero.
+            (1.0 - t, t)
+        } else {
+            let angle = dot.acos();
+            let sin = angle.sin();
+            (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+        };
+        Self::new(self.x * a + other.x * b, self.y * a + other.y * b, self.z * a + other.z * b, self.w * a + other.w * b).normalized()
+    }
+}
+
+/// The rotation by `rhs`, then by `self`, as in Unity.
+impl Mul for Quaternion {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y + self.y * rhs.w + self.z * rhs.x - self.x * rhs.z,
+            self.w * rhs.z + self.z * rhs.w + self.x * rhs.y - self.y * rhs.x,
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        )
+    }
+}
+
+/// Rotates a vector. The quaternion should be normalized.
+impl Mul<Vector3> for Quaternion {
+    type Output = Vector3;
+
+    fn mul(self, rhs: Vector3) -> Self::Output {
+        let axis = Vector3::new(self.x, self.y, self.z);
+        let t = axis.cross(&rhs) * 2.0;
+        rhs + t * self.w + axis.cross(&t)
+    }
 }
@@ -0,0 +1,208 @@
+use std::fmt;
+
+use serde::de::{Error, Expected, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use super::{Color, Matrix4x4, Quaternion, RectF32, Vector2, Vector3, Vector4};
+
+/// The next float of a sequence, counting the ones read for the error.
+fn element<'de, A: SeqAccess<'de>, T: Deserialize<'de>>(seq: &mut A, count: &mut usize, expected: &dyn Expected) -> Result<T, A::Error> {
+    let result = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(*count, expected))?;
+    *count += 1;
+    Ok(result)
+}
+
+/// Implements `Deserialize` for a type of float fields, read from the type tree node `$name`
+/// with the fields keyed `$key` or from a sequence of the floats in order.
+macro_rules! floats {
+    ($type_:ident, $name:literal, [$($field:ident: $key:literal),+]) => {
+        impl<'de> Deserialize<'de> for $type_ {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct FloatsVisitor;
+
+                impl<'de> Visitor<'de> for FloatsVisitor {
+                    type Value = $type_;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "a {} struct or sequence of floats", $name)
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<$type_, A::Error> {
+                        let mut count = 0;
+                        $(let $field = element(&mut seq, &mut count, &self)?;)+
+                        Ok($type_ { $($field),+ })
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<$type_, A::Error> {
+                        $(let mut $field = None;)+
+                        while let Some(key) = map.next_key::<String>()? {
+                            match key.as_str() {
+                                $($key => $field = Some(map.next_value()?),)+
+                                _ => {
+                                    map.next_value::<IgnoredAny>()?;
+                                }
+                            }
+                        }
+                        Ok($type_ { $($field: $field.ok_or_else(|| A::Error::missing_field($key))?),+ })
+                    }
+                }
+
+                deserializer.deserialize_struct($name, &[$($key),+], FloatsVisitor)
+            }
+        }
+    };
+}
+
+floats!(Vector2, "Vector2f", [x: "x", y: "y"]);
+floats!(Vector3, "Vector3f", [x: "x", y: "y", z: "z"]);
+floats!(Vector4, "Vector4f", [x: "x", y: "y", z: "z", w: "w"]);
+floats!(Quaternion, "Quaternionf", [x: "x", y: "y", z: "z", w: "w"]);
+floats!(RectF32, "Rectf", [x: "x", y: "y", w: "width", h: "height"]);
+
+/// The 16 floats in the order they are stored, column by column, as
+/// [`Reader::read_matrix4x4`](crate::reader::Reader::read_matrix4x4) reads them. The node's
+/// `e00` to `e33` fields are in that order too, column first, so `e01` is `m10`.
+impl<'de> Deserialize<'de> for Matrix4x4 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        const FIELDS: &[&str] = &["e00", "e01", "e02", "e03", "e10", "e11", "e12", "e13", "e20", "e21", "e22", "e23", "e30", "e31", "e32", "e33"];
+
+        struct MatrixVisitor;
+
+        impl<'de> Visitor<'de> for MatrixVisitor {
+            type Value = Matrix4x4;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a Matrix4x4f struct or sequence of 16 floats")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Matrix4x4, A::Error> {
+                let mut array = [0.0; 16];
+                let mut count = 0;
+                for x in &mut array {
+                    *x = element(&mut seq, &mut count, &self)?;
+                }
+                Ok(Matrix4x4::from_array(array))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Matrix4x4, A::Error> {
+                let mut array = [None; 16];
+                while let Some(key) = map.next_key::<String>()? {
+                    match FIELDS.iter().position(|x| *x == key) {
+                        Some(i) => array[i] = Some(map.next_value()?),
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                let mut result = [0.0; 16];
+                for (i, x) in array.into_iter().enumerate() {
+                    result[i] = x.ok_or_else(|| A::Error::missing_field(FIELDS[i]))?;
+                }
+                Ok(Matrix4x4::from_array(result))
+            }
+        }
+
+        deserializer.deserialize_struct("Matrix4x4f", FIELDS, MatrixVisitor)
+    }
+}
+
+/// A color channel, or a whole color packed in an integer.
+enum Channel {
+    Float(f32),
+    Packed(u32),
+}
+
+impl Channel {
+    fn to_f32(&self) -> f32 {
+        match *self {
+            Channel::Float(x) => x,
+            Channel::Packed(x) => x as f32,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Channel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ChannelVisitor;
+
+        impl Visitor<'_> for ChannelVisitor {
+            type Value = Channel;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a float or an integer")
+            }
+
+            fn visit_f64<E: Error>(self, v: f64) -> Result<Channel, E> {
+                Ok(Channel::Float(v as f32))
+            }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<Channel, E> {
+                Ok(u32::try_from(v).map_or(Channel::Float(v as f32), Channel::Packed))
+            }
+
+            fn visit_i64<E: Error>(self, v: i64) -> Result<Channel, E> {
+                Ok(u32::try_from(v).map_or(Channel::Float(v as f32), Channel::Packed))
+            }
+        }
+
+        deserializer.deserialize_any(ChannelVisitor)
+    }
+}
+
+/// A `ColorRGBA` node, whose float `r`, `g`, `b` and `a` fields were a packed `rgba` before
+/// Unity 5.6, or a sequence of the 4 floats or of the one packed integer.
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ColorVisitor;
+
+        impl<'de> Visitor<'de> for ColorVisitor {
+            type Value = Color;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a ColorRGBA struct, a sequence of 4 floats or a packed integer")
+            }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<Color, E> {
+                let v = u32::try_from(v).map_err(|_| E::custom(format!("packed color {} out of range", v)))?;
+                Ok(Color::from_rgba32(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Color, A::Error> {
+                let mut count = 0;
+                let first: Channel = element(&mut seq, &mut count, &self)?;
+                let (r, g) = match (first, seq.next_element::<f32>()?) {
+                    (Channel::Packed(rgba), None) => return Ok(Color::from_rgba32(rgba)),
+                    (_, None) => return Err(A::Error::invalid_length(1, &self)),
+                    (r, Some(g)) => (r.to_f32(), g),
+                };
+                count += 1;
+                let b = element(&mut seq, &mut count, &self)?;
+                let a = element(&mut seq, &mut count, &self)?;
+                Ok(Color::new(r, g, b, a))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Color, A::Error> {
+                let (mut r, mut g, mut b, mut a, mut rgba) = (None, None, None, None, None);
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "r" => r = Some(map.next_value()?),
+                        "g" => g = Some(map.next_value()?),
+                        "b" => b = Some(map.next_value()?),
+                        "a" => a = Some(map.next_value()?),
+                        "rgba" => rgba = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                if let Some(rgba) = rgba {
+                    return Ok(Color::from_rgba32(rgba));
+                }
+                let channel = |x: Option<f32>, name| x.ok_or_else(|| A::Error::missing_field(name));
+                Ok(Color::new(channel(r, "r")?, channel(g, "g")?, channel(b, "b")?, channel(a, "a")?))
+            }
+        }
+
+        deserializer.deserialize_struct("ColorRGBA", &["r", "g", "b", "a"], ColorVisitor)
+    }
+}
@@ -1,10 +1,20 @@
 use crate::asset::{BuildType, SerializedType};
+use crate::bundle::{FileData, FileSource};
 use crate::classes::ClassID;
-use crate::reader::{ByteOrder, Eof, Reader};
-use crate::typetree::TypeTreeNode;
-use serde::de::DeserializeOwned;
-use std::fmt::Display;
-use std::sync::Arc;
+use crate::error::{UnityError, UnityResult};
+use crate::reader::{BadLength, ByteOrder, ByteSource, Eof, ReadError, Reader};
+use crate::typetree::{min_size, skip_subtree, TypeTreeNode, MAX_LEVEL};
+use crate::version::UnityVersion;
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Write};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+mod serializer;
+pub use serializer::{Serializer, WriteTypeTreeError};
 
 #[derive(Clone, Debug)]
 pub struct ObjectInfo {
@@ -12,31 +22,206 @@ pub struct ObjectInfo {
     pub asset_version: u32,
     pub bytes_start: usize,
     pub bytes_size: usize,
-    pub data: Arc<Vec<u8>>,
+    pub data: FileSource,
     pub bytes_order: ByteOrder,
     pub type_id: i32,
     pub class_id: i32,
     pub is_destroyed: u16,
     pub stripped: u8,
     pub path_id: i64,
-    pub serialized_type: SerializedType,
-    pub version: [i32; 4],
+    pub serialized_type: Arc<SerializedType>,
+    pub version: UnityVersion,
+    pub(crate) read_cache: ReadCache,
+    /// The object's bytes, once read from a file that is decompressed on demand.
+    pub(crate) bytes: OnceLock<FileData>,
+}
+
+type CacheSlot = Arc<Mutex<Option<Arc<dyn Any + Send + Sync>>>>;
+
+/// Typed reads of one object, filled by [`Object::read_cached`](crate::env::Object::read_cached).
+///
+/// Each type gets its own slot so a slow parse only blocks other readers of the same type.
+/// Cloning an [`ObjectInfo`] starts with an empty cache.
+#[derive(Default)]
+pub(crate) struct ReadCache(Mutex<HashMap<TypeId, CacheSlot>>);
+
+impl ReadCache {
+    pub(crate) fn get_or_try_insert<T: Any + Send + Sync>(&self, load: impl FnOnce() -> UnityResult<T>) -> UnityResult<Arc<T>> {
+        let slot = self.0.lock().unwrap_or_else(PoisonError::into_inner).entry(TypeId::of::<T>()).or_default().clone();
+        let mut slot = slot.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(value) = slot.as_ref() {
+            return value.clone().downcast().map_err(|_| UnityError::CustomError("read cache type mismatch".to_string()));
+        }
+        let value = Arc::new(load()?);
+        *slot = Some(value.clone() as Arc<dyn Any + Send + Sync>);
+        Ok(value)
+    }
+}
+
+impl Clone for ReadCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Debug for ReadCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.0.lock().unwrap_or_else(PoisonError::into_inner).len();
+        write!(f, "ReadCache {{ types: {} }}", len)
+    }
 }
 
 impl ObjectInfo {
-    pub fn get_reader(&'_ self) -> Reader<'_> {
-        Reader::new(&self.data[self.bytes_start..], self.bytes_order)
+    /// A reader positioned at the start of the object.
+    ///
+    /// For bundles loaded with [`Env::load_from_data`](crate::Env::load_from_data) this
+    /// decompresses the blocks the object spans, which can fail.
+    pub fn get_reader(&'_ self) -> UnityResult<Reader<'_>> {
+        Ok(Reader::new(self.reader_data()?, self.bytes_order))
+    }
+
+    /// The object's undecoded bytes, borrowed from the file data or the bytes cached for
+    /// objects of bundles decompressed on demand.
+    pub fn data_slice(&self) -> UnityResult<&[u8]> {
+        self.reader_data()?.get(..self.bytes_size).ok_or(UnityError::Eof)
+    }
+
+    /// The object's undecoded bytes as a view sharing the file's buffer, which can outlive the
+    /// object.
+    pub fn raw_data(&self) -> UnityResult<FileData> {
+        match &self.data {
+            FileSource::Data(data) => data.slice(self.bytes_start..self.bytes_start.checked_add(self.bytes_size).ok_or(UnityError::Eof)?),
+            FileSource::Blocks(_) => {
+                self.reader_data()?;
+                self.bytes.get().cloned().ok_or(UnityError::Eof)
+            }
+        }
+    }
+
+    /// The object's bytes, or with objects read from a whole file, everything from its start.
+    fn reader_data(&self) -> UnityResult<&[u8]> {
+        let data = match &self.data {
+            FileSource::Data(data) => data.get(self.bytes_start..).ok_or(UnityError::Eof)?,
+            FileSource::Blocks(_) => match self.bytes.get() {
+                Some(bytes) => bytes.as_slice(),
+                None => {
+                    let end = self.bytes_start.checked_add(self.bytes_size).ok_or(UnityError::Eof)?;
+                    let bytes = self.data.read(self.bytes_start..end)?;
+                    self.bytes.get_or_init(|| bytes).as_slice()
+                }
+            },
+        };
+        Ok(data)
     }
 
     pub fn class(&self) -> ClassID {
         ClassID::from(self.class_id)
     }
 
-    pub fn read_type_tree<T: DeserializeOwned>(&self) -> Result<T, ReadTypeTreeError> {
-        let mut reader = self.get_reader();
-        let nodes = &self.serialized_type.type_tree.nodes;
-        let mut de = Deserializer { nodes, index: 0, reader: &mut reader };
+    /// Whether the file embeds a type tree for this object. Without one,
+    /// [`Object::read_type_tree`](crate::Object::read_type_tree) falls back to a provided or
+    /// built-in tree.
+    pub fn has_typetree(&self) -> bool {
+        !self.serialized_type.type_tree.nodes.is_empty()
+    }
+
+    /// The embedded type tree as text, see [`typetree::dump_nodes`](crate::typetree::dump_nodes).
+    pub fn dump_typetree(&self) -> Option<String> {
+        self.has_typetree().then(|| self.serialized_type.type_tree.dump())
+    }
+
+    /// Deserializes the object with its embedded type tree.
+    ///
+    /// Strings and `TypelessData` can be borrowed from the object's bytes with `&str` and `&[u8]`
+    /// fields marked `#[serde(borrow)]`; strings that are not valid UTF-8 fail to borrow.
+    pub fn read_type_tree<'de, T: Deserialize<'de>>(&'de self) -> Result<T, ReadTypeTreeError> {
+        self.read_type_tree_with(&self.serialized_type.type_tree.nodes)
+    }
+
+    /// Deserializes the object with `nodes` instead of its embedded type tree.
+    pub fn read_type_tree_with<'de, T: Deserialize<'de>>(&'de self, nodes: &[TypeTreeNode]) -> Result<T, ReadTypeTreeError> {
+        self.deserialize(nodes, &[], false)
+    }
+
+    /// Like [`read_type_tree`](Self::read_type_tree), but fails with
+    /// [`ReadTypeTreeError::Trailing`] unless the tree read exactly the object's `bytes_size`
+    /// bytes, which catches a tree of the wrong version that happens to read without errors.
+    pub fn read_type_tree_strict<'de, T: Deserialize<'de>>(&'de self) -> Result<T, ReadTypeTreeError> {
+        self.read_type_tree_strict_with(&self.serialized_type.type_tree.nodes)
+    }
+
+    /// Like [`read_type_tree_strict`](Self::read_type_tree_strict) with `nodes`.
+    pub fn read_type_tree_strict_with<'de, T: Deserialize<'de>>(&'de self, nodes: &[TypeTreeNode]) -> Result<T, ReadTypeTreeError> {
+        self.deserialize(nodes, &[], true)
+    }
+
+    /// Deserializes the object with `nodes` and the type trees of its managed references, see
+    /// [`Deserializer::with_ref_types`], checking that it reads all of its bytes if `strict`.
+    pub(crate) fn deserialize<'de, T: Deserialize<'de>>(&'de self, nodes: &[TypeTreeNode], ref_types: &[SerializedType], strict: bool) -> Result<T, ReadTypeTreeError> {
+        if nodes.is_empty() {
+            return Err(ReadTypeTreeError::MissingTypeTree);
+        }
+        let data = self.reader_data().map_err(|e| ReadTypeTreeError::Data(Box::new(e)))?;
+        let data = if strict { data.get(..self.bytes_size).ok_or(ReadTypeTreeError::BufEof)? } else { data };
+        let mut reader = Reader::new(data, self.bytes_order);
+        let mut de = Deserializer::new(nodes, &mut reader).with_ref_types(ref_types);
+
+        let result = T::deserialize(&mut de)?;
+        if strict {
+            de.finish()?;
+        }
+        Ok(result)
+    }
+
+    /// Reads the object into a generic value with its embedded type tree. Fields keep Unity's
+    /// order, which matters when diffing dumps.
+    pub fn read_value(&self) -> Result<serde_json::Value, ReadTypeTreeError> {
+        self.read_type_tree()
+    }
+
+    /// Serializes `value` with the embedded type tree in the object's byte order, for
+    /// [`SerializedFile::replace_object`](crate::asset::SerializedFile::replace_object).
+    pub fn write_type_tree<T: Serialize + ?Sized>(&self, value: &T) -> Result<Vec<u8>, WriteTypeTreeError> {
+        Serializer::new(&self.serialized_type.type_tree.nodes, self.bytes_order).serialize(value)
+    }
+
+    /// Deserializes the single field at `path`, such as `m_Name`, `m_StreamData.offset` or
+    /// `m_Materials[2].m_PathID`, skipping the data before it instead of reading the whole object.
+    pub fn read_field<T: DeserializeOwned>(&self, path: &str) -> Result<T, ReadTypeTreeError> {
+        self.read_field_with(&self.serialized_type.type_tree.nodes, path)
+    }
+
+    /// [`ObjectInfo::read_field`] with `nodes` instead of the embedded type tree.
+    pub fn read_field_with<T: DeserializeOwned>(&self, nodes: &[TypeTreeNode], path: &str) -> Result<T, ReadTypeTreeError> {
+        if nodes.is_empty() {
+            return Err(ReadTypeTreeError::MissingTypeTree);
+        }
+        let mut reader = self.get_reader().map_err(|e| ReadTypeTreeError::Data(Box::new(e)))?;
+        let index = seek_field(&mut reader, nodes, path)?;
+        let mut de = Deserializer::new(nodes, &mut reader);
+        de.index = index;
+        let result = T::deserialize(&mut de)?;
+        Ok(result)
+    }
+
+    /// Deserializes the top level fields that follow `field`, with the reader starting at `offset`.
+    ///
+    /// Used by classes whose leading fields are parsed by hand, such as the MonoBehaviour header.
+    pub(crate) fn read_type_tree_after<T: DeserializeOwned>(&self, nodes: &[TypeTreeNode], field: &str, offset: usize) -> Result<T, ReadTypeTreeError> {
+        let Some(root) = nodes.first() else {
+            return Err(ReadTypeTreeError::MissingTypeTree);
+        };
+        let Some(field_index) = nodes.iter().position(|x| x.level == root.level + 1 && x.name == field) else {
+            return Err(ReadTypeTreeError::Custom(format!("field {} not found", field)));
+        };
+        let start = field_index + get_level_length(nodes, field_index);
+        let mut sub_nodes = Vec::with_capacity(nodes.len() - start + 1);
+        sub_nodes.push(root.clone());
+        sub_nodes.extend_from_slice(&nodes[start..]);
 
+        let mut reader = self.get_reader().map_err(|e| ReadTypeTreeError::Data(Box::new(e)))?;
+        reader.set_offset(offset)?;
+        let mut de = Deserializer::new(&sub_nodes, &mut reader);
         let result = T::deserialize(&mut de)?;
         Ok(result)
     }
@@ -46,7 +231,41 @@ impl ObjectInfo {
 pub enum ReadTypeTreeError {
     BufEof,
     NodeEof,
+    MissingTypeTree,
+    /// An array or map length over [`ReadLimits::max_elements`] or beyond the remaining data.
+    TooManyElements(i64),
+    /// A string or byte array length that is negative or beyond the remaining data, or a string
+    /// longer than [`Reader::max_string_length`].
+    BadLength(BadLength),
+    /// A type tree nested deeper than [`ReadLimits::max_depth`].
+    TooDeep(usize),
     Custom(String),
+    /// An error getting the object data to read, such as decompressing the bundle block holding
+    /// it.
+    Data(Box<UnityError>),
+    /// Data or nodes left after reading the root, see [`Deserializer::finish`].
+    Trailing {
+        bytes: usize,
+        nodes: usize,
+    },
+    /// An error from reading a node, with its field path such as `m_TexEnvs[3].second.m_Scale`,
+    /// its index in the type tree and the offset in the object data where it starts.
+    At {
+        path: String,
+        node: usize,
+        offset: usize,
+        source: Box<ReadTypeTreeError>,
+    },
+}
+
+impl ReadTypeTreeError {
+    /// The error without the position [`ReadTypeTreeError::At`] adds.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::At { source, .. } => source,
+            _ => self,
+        }
+    }
 }
 
 impl Display for ReadTypeTreeError {
@@ -54,7 +273,17 @@ impl Display for ReadTypeTreeError {
         match self {
             ReadTypeTreeError::BufEof => write!(f, "BufEof"),
             ReadTypeTreeError::NodeEof => write!(f, "NodeEof"),
+            ReadTypeTreeError::MissingTypeTree => write!(f, "MissingTypeTree: the serialized file was built without type trees"),
+            ReadTypeTreeError::TooManyElements(length) => write!(f, "TooManyElements: a length of {} exceeds the limit or the remaining data", length),
+            ReadTypeTreeError::BadLength(e) => write!(f, "{}", e),
+            ReadTypeTreeError::TooDeep(depth) => write!(f, "TooDeep: the type tree nests deeper than {} levels", depth),
             ReadTypeTreeError::Custom(custom) => write!(f, "Custom({})", custom),
+            ReadTypeTreeError::Data(e) => write!(f, "Data: {}", e),
+            ReadTypeTreeError::Trailing { bytes, nodes } => write!(f, "Trailing: {} bytes and {} nodes left after the root", bytes, nodes),
+            ReadTypeTreeError::At { path, node, offset, source } => {
+                let path = if path.is_empty() { "the root" } else { path };
+                write!(f, "{} at {} (node {}, offset {})", source, path, node, offset)
+            }
         }
     }
 }
@@ -65,9 +294,28 @@ impl From<Eof> for ReadTypeTreeError {
     }
 }
 
+impl From<BadLength> for ReadTypeTreeError {
+    fn from(value: BadLength) -> Self {
+        Self::BadLength(value)
+    }
+}
+
+impl From<ReadError> for ReadTypeTreeError {
+    fn from(value: ReadError) -> Self {
+        match value {
+            ReadError::Eof => Self::BufEof,
+            ReadError::BadLength(e) => Self::BadLength(e),
+        }
+    }
+}
+
 impl serde::de::StdError for ReadTypeTreeError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        None
+        match self {
+            Self::At { source, .. } => Some(source.as_ref()),
+            Self::Data(source) => Some(source.as_ref()),
+            _ => None,
+        }
     }
 
     fn description(&self) -> &str {
@@ -88,28 +336,155 @@ impl serde::de::Error for ReadTypeTreeError {
     }
 }
 
-pub struct Deserializer<'a> {
+/// Bounds on what the [`Deserializer`] accepts, so that malformed files fail to read instead of
+/// exhausting memory or the stack.
+#[derive(Clone, Copy, Debug)]
+pub struct ReadLimits {
+    /// The most elements of an array or map. Lengths are also checked against the bytes left,
+    /// counting the fewest bytes an element takes, and at least one.
+    pub max_elements: usize,
+    /// The most levels the type tree nests below its first node, and the most nodes read one
+    /// within the other.
+    pub max_depth: usize,
+}
+
+impl Default for ReadLimits {
+    fn default() -> Self {
+        Self {
+            max_elements: 1 << 28,
+            max_depth: MAX_LEVEL as usize,
+        }
+    }
+}
+
+/// Reads values laid out as `nodes` describe from a reader over bytes living for `'de`, which
+/// strings and byte arrays can borrow. Readers over other [`ByteSource`]s, such as a
+/// [`StreamReader`](crate::reader::StreamReader), work too but copy them.
+pub struct Deserializer<'a, 'de, S = &'de [u8]> {
     nodes: &'a [TypeTreeNode],
     index: usize,
-    reader: &'a mut Reader<'a>,
+    reader: &'a mut Reader<'de, S>,
+    limits: ReadLimits,
+    /// How many levels the nodes nest below the first one.
+    depth: usize,
+    /// How many nodes are being read, one within the other, counting those of the trees of
+    /// managed references this one is read for.
+    nested: usize,
+    /// Where the node being read is, for errors.
+    path: Vec<Segment<'a>>,
+    /// The type trees of the classes `[SerializeReference]` fields hold.
+    ref_types: &'a [SerializedType],
+    /// The class of the managed reference being read, noted when its `ReferencedManagedType`
+    /// node is reached.
+    managed_type: Option<ManagedType>,
 }
 
-impl<'a> Deserializer<'a> {
-    pub fn new(nodes: &'a [TypeTreeNode], reader: &'a mut Reader<'a>) -> Self {
-        Self { nodes, index: 0, reader }
+/// A `ReferencedManagedType`, the class the data of a managed reference is laid out as.
+struct ManagedType {
+    class: String,
+    namespace: String,
+    assembly: String,
+}
+
+impl ManagedType {
+    /// Null references have no class and hold no data, like the `Terminus`.
+    fn is_empty(&self) -> bool {
+        self.class.is_empty() || self.is_terminus()
+    }
+
+    /// The reference version 1 registries end with.
+    fn is_terminus(&self) -> bool {
+        self.class == "Terminus" && self.namespace == "UnityEngine.DMAT"
     }
 }
 
-impl<'de, 'a: 'de> serde::Deserializer<'de> for &mut Deserializer<'a> {
-    type Error = ReadTypeTreeError;
+/// What the visitor asked for, as vectors of pairs and maps read as either and structs as tuples.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Hint {
+    Any,
+    Seq,
+    Map,
+    Tuple,
+}
 
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+#[derive(Clone, Copy)]
+enum Segment<'a> {
+    Field(&'a str),
+    Index(usize),
+}
+
+impl<'a, 'de, S: ByteSource<'de>> Deserializer<'a, 'de, S> {
+    pub fn new(nodes: &'a [TypeTreeNode], reader: &'a mut Reader<'de, S>) -> Self {
+        let root = nodes.first().map_or(0, |x| x.level);
+        let deepest = nodes.iter().map(|x| x.level).max().unwrap_or(root);
+        let depth = usize::try_from(i64::from(deepest) - i64::from(root)).unwrap_or(0);
+        Self {
+            nodes,
+            index: 0,
+            reader,
+            limits: ReadLimits::default(),
+            depth,
+            nested: 0,
+            path: Vec::new(),
+            ref_types: &[],
+            managed_type: None,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: ReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Reads the managed references of `[SerializeReference]` fields, whose
+    /// `ReferencedObjectData` nodes are laid out as the one of `ref_types` their
+    /// `ReferencedManagedType` names. These are the file's
+    /// [`SerializedFile::ref_types`](crate::asset::SerializedFile::ref_types); without them
+    /// reading such data fails.
+    pub fn with_ref_types(mut self, ref_types: &'a [SerializedType]) -> Self {
+        self.ref_types = ref_types;
+        self
+    }
+
+    /// Checks that reading the root used every node and all the data up to its final 4 byte
+    /// alignment, returning [`ReadTypeTreeError::Trailing`] with what is left otherwise.
+    pub fn finish(&self) -> Result<(), ReadTypeTreeError> {
+        let nodes = self.nodes.len().saturating_sub(self.index + 1);
+        let len = self.reader.len();
+        let bytes = len - self.reader.get_offset().next_multiple_of(4).min(len);
+        if bytes > 0 || nodes > 0 {
+            return Err(ReadTypeTreeError::Trailing { bytes, nodes });
+        }
+        Ok(())
+    }
+
+    /// Reads the node, adding its position to errors.
+    fn read<V>(&mut self, visitor: V, hint: Hint) -> Result<V::Value, ReadTypeTreeError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let (index, offset) = (self.index, self.reader.get_offset());
+        // Malformed trees can nest arrays without nesting levels, which only this catches.
+        if self.nested > self.limits.max_depth {
+            return Err(self.at(index, offset, ReadTypeTreeError::TooDeep(self.limits.max_depth)));
+        }
+        self.nested += 1;
+        let result = self.read_node(visitor, hint).map_err(|e| self.at(index, offset, e));
+        self.nested -= 1;
+        result
+    }
+
+    fn read_node<V>(&mut self, visitor: V, hint: Hint) -> Result<V::Value, ReadTypeTreeError>
     where
         V: serde::de::Visitor<'de>,
     {
         let Some(node) = self.nodes.get(self.index) else {
             return Err(ReadTypeTreeError::NodeEof);
         };
+        if self.depth > self.limits.max_depth {
+            return Err(ReadTypeTreeError::TooDeep(self.limits.max_depth));
+        }
+        self.note_managed_type()?;
         let mut align = (node.meta_flag & 0x4000) != 0;
         let val = match node.type_.as_str() {
             "SInt8" => visitor.visit_i8(self.reader.read_i8()?),
@@ -125,15 +500,35 @@ impl<'de, 'a: 'de> serde::Deserializer<'de> for &mut Deserializer<'a> {
             "bool" => visitor.visit_bool(self.reader.read_bool()?),
             "string" => {
                 self.index += 3;
-                visitor.visit_string(self.reader.read_aligned_string()?)
+                let size = self.reader.read_string_length()?;
+                let bytes = self.reader.read_bytes(size)?;
+                self.reader.align(4)?;
+                match bytes {
+                    Cow::Borrowed(bytes) => match std::str::from_utf8(bytes) {
+                        Ok(v) => visitor.visit_borrowed_str(v),
+                        Err(_) => visitor.visit_string(String::from_utf8_lossy(bytes).into_owned()),
+                    },
+                    Cow::Owned(bytes) => match String::from_utf8(bytes) {
+                        Ok(v) => visitor.visit_string(v),
+                        Err(e) => visitor.visit_string(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+                    },
+                }
             }
             "TypelessData" => {
                 let size = self.reader.read_i32()?;
-                let v = self.reader.read_u8_list(size as usize)?;
+                let v = self.reader.read_bytes(self.reader.check_length(size.into(), 1)?)?;
                 self.index += 2;
-                visitor.visit_byte_buf(v)
+                match v {
+                    Cow::Borrowed(v) => visitor.visit_borrowed_bytes(v),
+                    Cow::Owned(v) => visitor.visit_byte_buf(v),
+                }
+            }
+            "ReferencedObjectData" => self.read_reference(visitor, hint),
+            "ManagedReferencesRegistry" if self.nodes.get(self.index + 2).is_some_and(|x| x.type_ == "ReferencedObject") => {
+                let registry = self.index;
+                visitor.visit_map(RegistryAccess { de: self, registry, field: 0 })
             }
-            "map" => {
+            _ if self.reads_as_map(hint) => {
                 if let Some(next_node) = self.nodes.get(self.index + 1) {
                     if next_node.meta_flag & 0x4000 != 0 {
                         align = true;
@@ -145,8 +540,8 @@ impl<'de, 'a: 'de> serde::Deserializer<'de> for &mut Deserializer<'a> {
                 let first = self.index + 4;
                 let second = get_level_length(self.nodes, self.index + 4) + first;
 
+                let size = self.read_length(self.index + 3)?;
                 self.index += map - 1;
-                let size = self.reader.read_i32()? as usize;
                 visitor.visit_map(MapAccess { de: self, first, second, index: 0, size })
             }
             _ => {
@@ -164,14 +559,19 @@ impl<'de, 'a: 'de> serde::Deserializer<'de> for &mut Deserializer<'a> {
                         let vector = get_level_length(self.nodes, self.index);
                         let offset = self.index + 3;
                         let end_offset = self.index + vector - 1;
-                        let size = self.reader.read_i32()? as usize;
-                        visitor.visit_seq(SeqAccess { de: self, offset, index: 0, size, end_offset })
+                        let size = self.read_length(offset)?;
+                        self.read_array(visitor, offset, end_offset, size)
                     }
                     None => {
                         let vector = get_level_length(self.nodes, self.index);
                         let end = self.index + vector - 1;
                         self.index += 1;
-                        visitor.visit_map(StructAccess { de: self, end, finish: false })
+                        let access = StructAccess { de: self, end, finish: false };
+                        if hint == Hint::Tuple {
+                            visitor.visit_seq(access)
+                        } else {
+                            visitor.visit_map(access)
+                        }
                     }
                 }
             }
@@ -184,22 +584,235 @@ impl<'de, 'a: 'de> serde::Deserializer<'de> for &mut Deserializer<'a> {
         val
     }
 
+    /// Reads an array of `size` elements of node `element`, the last of the array's nodes at
+    /// `end`. Numbers without alignment of their own, as in vertex data and key frames, are read
+    /// in one go rather than node by node.
+    fn read_array<V>(&mut self, visitor: V, element: usize, end: usize, size: usize) -> Result<V::Value, ReadTypeTreeError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let nodes = self.nodes;
+        let bulk = match nodes.get(element) {
+            Some(node) if element == end && node.meta_flag & 0x4000 == 0 => node.type_.as_str(),
+            _ => "",
+        };
+        let reader = &mut *self.reader;
+        let result = match bulk {
+            "SInt8" => visitor.visit_seq(NumberAccess::new(reader.read_i8_slice(size)?)),
+            "UInt8" | "char" => visitor.visit_seq(NumberAccess::new(reader.read_bytes(size)?.into_owned())),
+            "short" | "SInt16" => visitor.visit_seq(NumberAccess::new(reader.read_i16_slice(size)?)),
+            "UInt16" | "unsigned short" => visitor.visit_seq(NumberAccess::new(reader.read_u16_slice(size)?)),
+            "int" | "SInt32" => visitor.visit_seq(NumberAccess::new(reader.read_i32_slice(size)?)),
+            "UInt32" | "unsigned int" | "Type*" => visitor.visit_seq(NumberAccess::new(reader.read_u32_slice(size)?)),
+            "long long" | "SInt64" => visitor.visit_seq(NumberAccess::new(reader.read_i64_slice(size)?)),
+            "UInt64" | "unsigned long long" | "FileSize" => visitor.visit_seq(NumberAccess::new(reader.read_u64_slice(size)?)),
+            "float" => visitor.visit_seq(NumberAccess::new(reader.read_f32_slice(size)?)),
+            "double" => visitor.visit_seq(NumberAccess::new(reader.read_f64_slice(size)?)),
+            _ => {
+                return visitor.visit_seq(SeqAccess {
+                    de: self,
+                    offset: element,
+                    index: 0,
+                    size,
+                    end_offset: end,
+                })
+            }
+        };
+        self.index = end;
+        result
+    }
+
+    /// Notes the class of a managed reference at its `ReferencedManagedType` node, the three
+    /// strings of which come right before the data.
+    fn note_managed_type(&mut self) -> Result<(), ReadTypeTreeError> {
+        if self.nodes[self.index].type_ == "ReferencedManagedType" {
+            self.managed_type = Some(self.peek_managed_type()?);
+        }
+        Ok(())
+    }
+
+    fn peek_managed_type(&mut self) -> Result<ManagedType, ReadTypeTreeError> {
+        let mut reader = self.reader.save_position();
+        Ok(ManagedType {
+            class: reader.read_aligned_string()?,
+            namespace: reader.read_aligned_string()?,
+            assembly: reader.read_aligned_string()?,
+        })
+    }
+
+    /// Reads a `ReferencedObjectData` node, which has no children, with the type tree of the
+    /// class its `ReferencedManagedType` named. Null references read as unit.
+    fn read_reference<V>(&mut self, visitor: V, hint: Hint) -> Result<V::Value, ReadTypeTreeError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let Some(managed) = self.managed_type.take() else {
+            return Err(ReadTypeTreeError::Custom("ReferencedObjectData without a ReferencedManagedType".to_string()));
+        };
+        if managed.is_empty() {
+            return visitor.visit_unit();
+        }
+        let ref_types = self.ref_types;
+        let Some(ref_type) = ref_types.iter().find(|x| x.klass_name == managed.class && x.name_space == managed.namespace && x.asm_name == managed.assembly) else {
+            return Err(ReadTypeTreeError::Custom(format!("no type tree for the managed reference {}.{} in {}", managed.namespace, managed.class, managed.assembly)));
+        };
+        if ref_type.type_tree.nodes.is_empty() {
+            return Err(ReadTypeTreeError::MissingTypeTree);
+        }
+        let mut de = Deserializer::new(&ref_type.type_tree.nodes, &mut *self.reader).with_limits(self.limits).with_ref_types(ref_types);
+        de.path = self.path.clone();
+        de.nested = self.nested;
+        de.read(visitor, hint)
+    }
+
+    /// Whether the node is read as a map: a `map` unless a sequence is asked for, or a vector of
+    /// `pair` when a map is.
+    fn reads_as_map(&self, hint: Hint) -> bool {
+        let is_map = self.nodes[self.index].type_ == "map";
+        match hint {
+            Hint::Seq => false,
+            Hint::Map => is_map || (self.nodes.get(self.index + 1).is_some_and(|x| x.type_ == "Array") && self.nodes.get(self.index + 3).is_some_and(|x| x.type_ == "pair")),
+            _ => is_map,
+        }
+    }
+
+    /// Adds the position of node `index`, which started at `offset`, to an error from reading it.
+    fn at(&self, index: usize, offset: usize, e: ReadTypeTreeError) -> ReadTypeTreeError {
+        if matches!(e, ReadTypeTreeError::At { .. }) {
+            return e;
+        }
+        let mut path = String::new();
+        for segment in &self.path {
+            match segment {
+                Segment::Field(name) if path.is_empty() => path.push_str(name),
+                Segment::Field(name) => {
+                    path.push('.');
+                    path.push_str(name);
+                }
+                Segment::Index(i) => {
+                    let _ = write!(path, "[{}]", i);
+                }
+            }
+        }
+        ReadTypeTreeError::At {
+            path,
+            node: index,
+            offset,
+            source: Box::new(e),
+        }
+    }
+
+    /// Runs `read` with `segments` added to the path.
+    fn within<T>(&mut self, segments: &[Segment<'a>], read: impl FnOnce(&mut Self) -> T) -> T {
+        self.path.extend_from_slice(segments);
+        let result = read(self);
+        self.path.truncate(self.path.len() - segments.len());
+        result
+    }
+
+    /// Reads the length of an array or map whose elements are node `element`.
+    fn read_length(&mut self, element: usize) -> Result<usize, ReadTypeTreeError> {
+        let length = self.reader.read_i32()?;
+        let remaining = self.reader.len() - self.reader.get_offset();
+        // Elements taking no bytes still count one, so their number stays bounded by the data.
+        let element_size = if element < self.nodes.len() { min_size(self.nodes, element).max(1) } else { 1 };
+        match usize::try_from(length) {
+            Ok(size) if size <= self.limits.max_elements && size.saturating_mul(element_size) <= remaining => Ok(size),
+            _ => Err(ReadTypeTreeError::TooManyElements(length.into())),
+        }
+    }
+}
+
+impl<'de, S: ByteSource<'de>> serde::Deserializer<'de> for &mut Deserializer<'_, 'de, S> {
+    type Error = ReadTypeTreeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.read(visitor, Hint::Any)
+    }
+
+    /// Maps read as a sequence of their pairs, which deserialize as `(key, value)` tuples.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.read(visitor, Hint::Seq)
+    }
+
+    /// Vectors of `pair` read as a map of their `first` and `second` fields.
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.read(visitor, Hint::Map)
+    }
+
+    /// Structs read as a tuple of their fields, such as a `pair` as `(first, second)`.
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.read(visitor, Hint::Tuple)
+    }
+
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.read(visitor, Hint::Tuple)
+    }
+
+    /// Type trees have no optional values, so anything present is `Some`; fields absent from the
+    /// tree are defaulted to `None` by serde. As fields the struct lacks are skipped, one struct with
+    /// `Option` or `#[serde(default)]` fields reads the layouts of several Unity versions.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    /// Skips the node without materializing it, see [`skip_subtree`].
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        if self.index >= self.nodes.len() {
+            return Err(ReadTypeTreeError::NodeEof);
+        }
+        if self.depth > self.limits.max_depth {
+            return Err(ReadTypeTreeError::TooDeep(self.limits.max_depth));
+        }
+        // Managed references are laid out by other type trees, which only reading follows.
+        let end = self.index + get_level_length(self.nodes, self.index);
+        if self.nodes[self.index..end].iter().any(|x| x.type_ == "ReferencedObjectData") {
+            self.read(serde::de::IgnoredAny, Hint::Any)?;
+            return visitor.visit_unit();
+        }
+        let (index, offset) = (self.index, self.reader.get_offset());
+        self.note_managed_type().map_err(|e| self.at(index, offset, e))?;
+        skip_subtree(self.reader, self.nodes, self.index).map_err(|e| self.at(index, offset, e.into()))?;
+        self.index += get_level_length(self.nodes, self.index) - 1;
+        visitor.visit_unit()
+    }
+
     serde::forward_to_deserialize_any! {
         bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        bytes byte_buf unit unit_struct newtype_struct struct enum identifier
     }
 }
 
-struct MapAccess<'a, 'b: 'a> {
-    de: &'a mut Deserializer<'b>,
+struct MapAccess<'a, 'b, 'de, S> {
+    de: &'a mut Deserializer<'b, 'de, S>,
     first: usize,
     second: usize,
     index: usize,
     size: usize,
 }
 
-impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for MapAccess<'a, 'b> {
+impl<'de, S: ByteSource<'de>> serde::de::MapAccess<'de> for MapAccess<'_, '_, 'de, S> {
     type Error = ReadTypeTreeError;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
@@ -211,7 +824,8 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for MapAccess<'a, 'b> {
         }
         let index = self.de.index;
         self.de.index = self.first;
-        let val = seed.deserialize(&mut *self.de);
+        let nodes = self.de.nodes;
+        let val = self.de.within(&[Segment::Index(self.index), Segment::Field(nodes.get(self.first).map_or("first", |x| &x.name))], |de| seed.deserialize(de));
         self.de.index = index;
         Ok(Some(val?))
     }
@@ -222,22 +836,114 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for MapAccess<'a, 'b> {
     {
         let index = self.de.index;
         self.de.index = self.second;
-        let val = seed.deserialize(&mut *self.de);
+        let nodes = self.de.nodes;
+        let val = self.de.within(&[Segment::Index(self.index), Segment::Field(nodes.get(self.second).map_or("second", |x| &x.name))], |de| seed.deserialize(de))?;
         self.de.index = index;
         self.index += 1;
-        val
+        // The pair is never read as a node, so its own alignment is applied here.
+        if nodes.get(self.first - 1).is_some_and(|x| x.meta_flag & 0x4000 != 0) {
+            self.de.reader.align(4)?;
+        }
+        Ok(val)
+    }
+}
+
+/// A version 1 `ManagedReferencesRegistry`, whose references follow each other up to a
+/// `Terminus` one. It reads as its `version` and the references under `RefIds`, like the vector
+/// of version 2.
+struct RegistryAccess<'a, 'b, 'de, S> {
+    de: &'a mut Deserializer<'b, 'de, S>,
+    registry: usize,
+    field: usize,
+}
+
+impl<'de, S: ByteSource<'de>> serde::de::MapAccess<'de> for RegistryAccess<'_, '_, 'de, S> {
+    type Error = ReadTypeTreeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: serde::de::DeserializeSeed<'de>,
+    {
+        let key = match self.field {
+            0 => "version",
+            1 => "RefIds",
+            _ => return Ok(None),
+        };
+        Ok(Some(seed.deserialize(Field { key })?))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let nodes = self.de.nodes;
+        let val = if self.field == 0 {
+            self.de.index = self.registry + 1;
+            self.de.within(&[Segment::Field("version")], |de| seed.deserialize(de))?
+        } else {
+            let element = self.registry + 2;
+            let val = self.de.within(&[Segment::Field("RefIds")], |de| seed.deserialize(References { de, element, index: 0 }))?;
+            self.de.index = self.registry + get_level_length(nodes, self.registry) - 1;
+            val
+        };
+        self.field += 1;
+        Ok(val)
+    }
+}
+
+/// The references of a version 1 registry, each read as node `element`.
+struct References<'a, 'b, 'de, S> {
+    de: &'a mut Deserializer<'b, 'de, S>,
+    element: usize,
+    index: usize,
+}
+
+impl<'de, S: ByteSource<'de>> serde::de::Deserializer<'de> for References<'_, '_, 'de, S> {
+    type Error = ReadTypeTreeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, S: ByteSource<'de>> serde::de::SeqAccess<'de> for References<'_, '_, 'de, S> {
+    type Error = ReadTypeTreeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        self.de.index = self.element;
+        let (index, offset) = (self.de.index, self.de.reader.get_offset());
+        let managed = self.de.peek_managed_type().map_err(|e| self.de.at(index, offset, e))?;
+        if managed.is_terminus() {
+            serde::de::IgnoredAny::deserialize(&mut *self.de)?;
+            return Ok(None);
+        }
+        let val = self.de.within(&[Segment::Index(self.index)], |de| seed.deserialize(de))?;
+        self.index += 1;
+        Ok(Some(val))
     }
 }
 
-struct SeqAccess<'a, 'b: 'a> {
-    de: &'a mut Deserializer<'b>,
+struct SeqAccess<'a, 'b, 'de, S> {
+    de: &'a mut Deserializer<'b, 'de, S>,
     offset: usize,
     index: usize,
     size: usize,
     end_offset: usize,
 }
 
-impl<'de, 'a, 'b: 'a + 'de> serde::de::SeqAccess<'de> for SeqAccess<'a, 'b> {
+impl<'de, S: ByteSource<'de>> serde::de::SeqAccess<'de> for SeqAccess<'_, '_, 'de, S> {
     type Error = ReadTypeTreeError;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
@@ -251,7 +957,7 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::SeqAccess<'de> for SeqAccess<'a, 'b> {
 
         let offset = self.de.index;
         self.de.index = self.offset;
-        let val = seed.deserialize(&mut *self.de);
+        let val = self.de.within(&[Segment::Index(self.index)], |de| seed.deserialize(de));
         self.de.index = offset;
         self.index += 1;
 
@@ -259,13 +965,39 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::SeqAccess<'de> for SeqAccess<'a, 'b> {
     }
 }
 
-struct StructAccess<'a, 'b: 'a> {
-    de: &'a mut Deserializer<'b>,
+/// The numbers of an array read by [`Deserializer::read_array`] in one go.
+struct NumberAccess<T> {
+    values: std::vec::IntoIter<T>,
+}
+
+impl<T> NumberAccess<T> {
+    fn new(values: Vec<T>) -> Self {
+        Self { values: values.into_iter() }
+    }
+}
+
+impl<'de, T: IntoDeserializer<'de, ReadTypeTreeError>> serde::de::SeqAccess<'de> for NumberAccess<T> {
+    type Error = ReadTypeTreeError;
+
+    fn next_element_seed<E>(&mut self, seed: E) -> Result<Option<E::Value>, Self::Error>
+    where
+        E: serde::de::DeserializeSeed<'de>,
+    {
+        self.values.next().map(|x| seed.deserialize(x.into_deserializer())).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct StructAccess<'a, 'b, 'de, S> {
+    de: &'a mut Deserializer<'b, 'de, S>,
     end: usize,
     finish: bool,
 }
 
-impl<'a, 'b: 'a> StructAccess<'a, 'b> {
+impl<S> StructAccess<'_, '_, '_, S> {
     fn check_finish(&self) -> bool {
         if self.de.index >= self.de.nodes.len() {
             return true;
@@ -279,7 +1011,7 @@ impl<'a, 'b: 'a> StructAccess<'a, 'b> {
     }
 }
 
-impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for StructAccess<'a, 'b> {
+impl<'de, S: ByteSource<'de>> serde::de::MapAccess<'de> for StructAccess<'_, '_, 'de, S> {
     type Error = ReadTypeTreeError;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
@@ -289,6 +1021,12 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for StructAccess<'a, 'b> {
         if self.finish {
             return Ok(None);
         }
+        if self.de.index > self.end {
+            // A struct without fields, which ends at its own node.
+            self.de.index = self.end;
+            self.finish = true;
+            return Ok(None);
+        }
         let Some(node) = self.de.nodes.get(self.de.index) else {
             return Ok(None);
         };
@@ -299,7 +1037,9 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for StructAccess<'a, 'b> {
     where
         V: serde::de::DeserializeSeed<'de>,
     {
-        let val = seed.deserialize(&mut *self.de)?;
+        let nodes = self.de.nodes;
+        let name = &nodes[self.de.index].name;
+        let val = self.de.within(&[Segment::Field(name)], |de| seed.deserialize(de))?;
         if self.check_finish() {
             self.finish = true;
         } else {
@@ -309,11 +1049,31 @@ impl<'de, 'a, 'b: 'a + 'de> serde::de::MapAccess<'de> for StructAccess<'a, 'b> {
     }
 }
 
-struct Field<'de> {
-    key: &'de str,
+/// Reads the fields in order, for structs read as tuples.
+impl<'de, S: ByteSource<'de>> serde::de::SeqAccess<'de> for StructAccess<'_, '_, 'de, S> {
+    type Error = ReadTypeTreeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        if self.finish {
+            return Ok(None);
+        }
+        if self.de.index > self.end {
+            self.de.index = self.end;
+            self.finish = true;
+            return Ok(None);
+        }
+        serde::de::MapAccess::next_value_seed(self, seed).map(Some)
+    }
+}
+
+struct Field<'a> {
+    key: &'a str,
 }
 
-impl<'de> serde::de::Deserializer<'de> for Field<'de> {
+impl<'de> serde::de::Deserializer<'de> for Field<'_> {
     type Error = ReadTypeTreeError;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -330,6 +1090,47 @@ impl<'de> serde::de::Deserializer<'de> for Field<'de> {
     }
 }
 
+/// Moves `reader` to the field at the dotted `path` and returns its node, skipping the fields and
+/// array elements before it.
+fn seek_field(reader: &mut Reader, nodes: &[TypeTreeNode], path: &str) -> Result<usize, ReadTypeTreeError> {
+    let not_found = || ReadTypeTreeError::Custom(format!("field {} not found", path));
+    let mut index = 0;
+    for segment in path.split('.') {
+        let (name, indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        // Only the fields of classes and structs can be walked into.
+        let node = &nodes[index];
+        if matches!(node.type_.as_str(), "string" | "map" | "TypelessData") || nodes.get(index + 1).is_some_and(|x| x.type_ == "Array") {
+            return Err(not_found());
+        }
+        let end = index + get_level_length(nodes, index);
+        let mut child = index + 1;
+        while child < end && nodes[child].name != name {
+            skip_subtree(reader, nodes, child)?;
+            child += get_level_length(nodes, child);
+        }
+        if child >= end {
+            return Err(not_found());
+        }
+        index = child;
+
+        for element in indices.split_terminator(']') {
+            let element: usize = element.strip_prefix('[').and_then(|x| x.parse().ok()).ok_or_else(not_found)?;
+            if !nodes.get(index + 1).is_some_and(|x| x.type_ == "Array") || index + 3 >= nodes.len() {
+                return Err(not_found());
+            }
+            let size = reader.read_i32()?;
+            if element >= size.max(0) as usize {
+                return Err(ReadTypeTreeError::Custom(format!("index {} out of range for {} of size {}", element, path, size)));
+            }
+            for _ in 0..element {
+                skip_subtree(reader, nodes, index + 3)?;
+            }
+            index += 3;
+        }
+    }
+    Ok(index)
+}
+
 fn get_level_length(nodes: &[TypeTreeNode], idx: usize) -> usize {
     let Some(nodes) = nodes.get(idx..) else {
         return 0;
@@ -0,0 +1,210 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::reader::ByteOrder;
+use crate::typetree::{aligned, children, Kind, TypeTreeNode};
+use crate::writer::Writer;
+
+#[derive(Debug)]
+pub enum WriteTypeTreeError {
+    MissingTypeTree,
+    /// The value does not fit the node at the path.
+    Mismatch {
+        path: String,
+        message: String,
+    },
+    Custom(String),
+}
+
+impl Display for WriteTypeTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteTypeTreeError::MissingTypeTree => write!(f, "MissingTypeTree: the serialized file was built without type trees"),
+            WriteTypeTreeError::Mismatch { path, message } => write!(f, "Mismatch at {}: {}", path, message),
+            WriteTypeTreeError::Custom(custom) => write!(f, "Custom({})", custom),
+        }
+    }
+}
+
+impl std::error::Error for WriteTypeTreeError {}
+
+impl From<serde_json::Error> for WriteTypeTreeError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Custom(e.to_string())
+    }
+}
+
+/// Writes values in the binary layout a type tree describes, the counterpart of
+/// [`Deserializer`](super::Deserializer).
+///
+/// Values go through [`serde_json::Value`], so anything read with the deserializer into JSON or a
+/// `Serialize` struct can be written back. The value must match the tree: structs need exactly
+/// the fields of their node, integers must fit their type, and maps are written from objects
+/// (keys parsed for number typed keys) or from arrays of `[key, value]` pairs. Floats may be
+/// `null`, which is how `serde_json` holds NaN.
+///
+/// ```
+/// use unity_rs::object::Serializer;
+/// use unity_rs::reader::ByteOrder;
+/// use unity_rs::typetree::TypeTreeBuilder;
+///
+/// let nodes = TypeTreeBuilder::new("TextAsset").string("m_Name").build();
+/// let data = Serializer::new(&nodes, ByteOrder::Little).serialize(&serde_json::json!({ "m_Name": "a" })).unwrap();
+/// assert_eq!(data, [1, 0, 0, 0, b'a', 0, 0, 0]);
+/// ```
+pub struct Serializer<'a> {
+    nodes: &'a [TypeTreeNode],
+    writer: Writer,
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(nodes: &'a [TypeTreeNode], order: ByteOrder) -> Self {
+        Self { nodes, writer: Writer::new(order) }
+    }
+
+    /// Writes `value` as the root node and returns the bytes.
+    pub fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>, WriteTypeTreeError> {
+        self.write(&serde_json::to_value(value)?)
+    }
+
+    pub fn write(mut self, value: &Value) -> Result<Vec<u8>, WriteTypeTreeError> {
+        if self.nodes.is_empty() {
+            return Err(WriteTypeTreeError::MissingTypeTree);
+        }
+        self.write_node(0, value, "")?;
+        Ok(self.writer.into_inner())
+    }
+
+    /// Writes `value` as node `index`, whose parent is at `parent` in error messages.
+    fn write_node(&mut self, index: usize, value: &Value, parent: &str) -> Result<(), WriteTypeTreeError> {
+        let nodes = self.nodes;
+        let node = &nodes[index];
+        let path = match (index, parent) {
+            (0, _) => String::new(),
+            (_, "") => node.name.clone(),
+            _ => format!("{}.{}", parent, node.name),
+        };
+        let mismatch = |message: &str| WriteTypeTreeError::Mismatch {
+            path: if path.is_empty() { node.name.clone() } else { path.clone() },
+            message: message.to_string(),
+        };
+        match Kind::of(nodes, index) {
+            Kind::Primitive(_) => self.write_primitive(&node.type_, value).ok_or_else(|| mismatch(&format!("expected {}, found {}", node.type_, value)))?,
+            Kind::String => {
+                let s = value.as_str().ok_or_else(|| mismatch("expected a string"))?;
+                self.writer.write_aligned_string(s);
+            }
+            Kind::TypelessData => {
+                let bytes = value.as_array().ok_or_else(|| mismatch("expected an array of bytes"))?;
+                self.write_length(bytes.len());
+                for byte in bytes {
+                    let byte = byte.as_u64().and_then(|x| u8::try_from(x).ok()).ok_or_else(|| mismatch("expected an array of bytes"))?;
+                    self.writer.write_u8(byte);
+                }
+            }
+            Kind::Map => {
+                let pair = index + 3;
+                if pair >= nodes.len() {
+                    return Err(mismatch("malformed map node"));
+                }
+                let mut fields = children(nodes, pair);
+                let (Some(key), Some(field)) = (fields.next(), fields.next()) else {
+                    return Err(mismatch("malformed map node"));
+                };
+                match value {
+                    Value::Object(map) => {
+                        self.write_length(map.len());
+                        for (k, v) in map {
+                            let k: Value = match Kind::of(nodes, key) {
+                                Kind::Primitive(_) => serde_json::from_str(k).map_err(|_| mismatch(&format!("expected a {} key, found {:?}", nodes[key].type_, k)))?,
+                                _ => Value::String(k.clone()),
+                            };
+                            self.write_node(key, &k, &path)?;
+                            self.write_node(field, v, &path)?;
+                            self.align_pair(pair);
+                        }
+                    }
+                    Value::Array(pairs) => {
+                        self.write_length(pairs.len());
+                        for pair in pairs {
+                            let Some([k, v]) = pair.as_array().map(Vec::as_slice).and_then(|x| <&[Value; 2]>::try_from(x).ok()) else {
+                                return Err(mismatch("expected [key, value] pairs"));
+                            };
+                            self.write_node(key, k, &path)?;
+                            self.write_node(field, v, &path)?;
+                            self.align_pair(pair);
+                        }
+                    }
+                    _ => return Err(mismatch("expected an object or an array of pairs")),
+                }
+            }
+            Kind::Array => {
+                let elements = value.as_array().ok_or_else(|| mismatch("expected an array"))?;
+                if index + 3 >= nodes.len() {
+                    return Err(mismatch("malformed array node"));
+                }
+                self.write_length(elements.len());
+                for element in elements {
+                    self.write_node(index + 3, element, &path)?;
+                }
+            }
+            Kind::Struct => {
+                let map = value.as_object().ok_or_else(|| mismatch("expected an object"))?;
+                let mut fields = 0;
+                for child in children(nodes, index) {
+                    let name = &nodes[child].name;
+                    let v = map.get(name).ok_or_else(|| mismatch(&format!("missing field {}", name)))?;
+                    self.write_node(child, v, &path)?;
+                    fields += 1;
+                }
+                if map.len() != fields {
+                    let unknown = map.keys().find(|x| !children(nodes, index).any(|i| nodes[i].name == **x));
+                    return Err(mismatch(&format!("unknown field {}", unknown.map_or("", |x| x.as_str()))));
+                }
+            }
+        }
+        if aligned(nodes, index) {
+            self.align();
+        }
+        Ok(())
+    }
+
+    /// Writes a built-in value type, or `None` if `value` does not fit it.
+    fn write_primitive(&mut self, type_: &str, value: &Value) -> Option<()> {
+        let signed = || value.as_i64();
+        let unsigned = || value.as_u64();
+        let w = &mut self.writer;
+        match type_ {
+            "SInt8" => w.write_i8(i8::try_from(signed()?).ok()?),
+            "UInt8" | "char" => w.write_u8(u8::try_from(unsigned()?).ok()?),
+            "short" | "SInt16" => w.write_i16(i16::try_from(signed()?).ok()?),
+            "UInt16" | "unsigned short" => w.write_u16(u16::try_from(unsigned()?).ok()?),
+            "int" | "SInt32" => w.write_i32(i32::try_from(signed()?).ok()?),
+            "UInt32" | "unsigned int" | "Type*" => w.write_u32(u32::try_from(unsigned()?).ok()?),
+            "long long" | "SInt64" => w.write_i64(signed()?),
+            "UInt64" | "unsigned long long" | "FileSize" => w.write_u64(unsigned()?),
+            "float" => w.write_f32(if value.is_null() { f32::NAN } else { value.as_f64()? as f32 }),
+            "double" => w.write_f64(if value.is_null() { f64::NAN } else { value.as_f64()? }),
+            "bool" => w.write_bool(value.as_bool()?),
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn write_length(&mut self, length: usize) {
+        self.writer.write_i32(length as i32);
+    }
+
+    /// Aligns after a map entry if its `pair` node is aligned, as the deserializer does.
+    fn align_pair(&mut self, pair: usize) {
+        if aligned(self.nodes, pair) {
+            self.align();
+        }
+    }
+
+    fn align(&mut self) {
+        self.writer.align(4);
+    }
+}
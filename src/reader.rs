@@ -1,7 +1,12 @@
+use std::borrow::Cow;
 use std::fmt::Display;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
 
 use thiserror::Error;
 
+use crate::error::{UnityError, UnityResult};
 use crate::math::{Matrix4x4, RectF32, Vector2, Vector3, Vector4};
 
 #[derive(Error, Debug)]
@@ -15,22 +20,263 @@ impl Display for Eof {
 
 type Result<T> = std::result::Result<T, Eof>;
 
+/// The longest string [`Reader::read_aligned_string`] accepts by default, see
+/// [`Reader::set_max_string_length`].
+pub const DEFAULT_MAX_STRING_LENGTH: usize = 64 << 20;
+
+/// A length read from the data that is negative, longer than the data left or over the limit.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("BadLength: a length of {length} with {remaining} bytes left and a limit of {max}")]
+pub struct BadLength {
+    pub length: i64,
+    pub remaining: usize,
+    pub max: usize,
+}
+
+/// The error of reads that take their length from the data.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadError {
+    #[error("BufEof")]
+    Eof,
+    #[error(transparent)]
+    BadLength(#[from] BadLength),
+}
+
+impl From<Eof> for ReadError {
+    fn from(_: Eof) -> Self {
+        Self::Eof
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ByteOrder {
     Big,
     Little,
 }
 
+/// A string's bytes as stored, for names in encodings other than UTF-8, see
+/// [`Reader::read_aligned_string_raw`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawString {
+    pub bytes: Vec<u8>,
+    /// The bytes as UTF-8, with invalid sequences replaced by U+FFFD.
+    pub text: String,
+}
+
+impl RawString {
+    /// Whether the bytes are valid UTF-8, so that `text` holds them exactly.
+    pub fn is_utf8(&self) -> bool {
+        self.text.as_bytes() == self.bytes
+    }
+}
+
+/// A number [`Reader`] reads in bulk, see [`Reader::read_f32_slice`].
+trait Primitive: Sized {
+    const SIZE: usize;
+
+    fn from_le(bytes: &[u8]) -> Self;
+
+    fn from_be(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_primitive {
+    ($($t:ty),*) => {
+        $(impl Primitive for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+
+            #[inline]
+            fn from_le(bytes: &[u8]) -> Self {
+                <$t>::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            #[inline]
+            fn from_be(bytes: &[u8]) -> Self {
+                <$t>::from_be_bytes(bytes.try_into().unwrap())
+            }
+        })*
+    };
+}
+
+impl_primitive!(i8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+/// The bytes a [`Reader`] reads: a slice, which strings and byte arrays can borrow from for
+/// `'a`, or a [`StreamSource`].
+pub trait ByteSource<'a> {
+    fn len(&self) -> usize;
+
+    /// Fills `buf` with the bytes at `offset`.
+    fn read_exact_at(&mut self, offset: usize, buf: &mut [u8]) -> Result<()>;
+
+    /// The `length` bytes at `offset`, borrowed if the source can.
+    fn bytes_at(&mut self, offset: usize, length: usize) -> Result<Cow<'a, [u8]>>;
+}
+
+impl<'a> ByteSource<'a> for &'a [u8] {
+    #[inline]
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    #[inline]
+    fn read_exact_at(&mut self, offset: usize, buf: &mut [u8]) -> Result<()> {
+        let end = offset.checked_add(buf.len()).ok_or(Eof)?;
+        buf.copy_from_slice(self.get(offset..end).ok_or(Eof)?);
+        Ok(())
+    }
+
+    #[inline]
+    fn bytes_at(&mut self, offset: usize, length: usize) -> Result<Cow<'a, [u8]>> {
+        let data: &'a [u8] = *self;
+        let end = offset.checked_add(length).ok_or(Eof)?;
+        Ok(Cow::Borrowed(data.get(offset..end).ok_or(Eof)?))
+    }
+}
+
+/// How much a [`StreamSource`] reads ahead.
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A [`ByteSource`] over a `Read + Seek` stream, such as a file, read through a buffer so that
+/// small reads do not each hit the stream. Strings and byte arrays are copied out.
+///
+/// Reads past the end and I/O errors both fail with [`Eof`]; the last I/O error is kept for
+/// [`StreamSource::error`].
+pub struct StreamSource<R> {
+    inner: R,
+    len: usize,
+    buf: Vec<u8>,
+    /// Where `buf` starts in the stream.
+    buf_start: usize,
+    error: Option<io::Error>,
+}
+
+impl<R: Read + Seek> StreamSource<R> {
+    pub fn new(mut inner: R) -> UnityResult<Self> {
+        let len = usize::try_from(inner.seek(SeekFrom::End(0))?).map_err(|_| UnityError::CustomError("stream too long".to_string()))?;
+        Ok(Self {
+            inner,
+            len,
+            buf: Vec::new(),
+            buf_start: 0,
+            error: None,
+        })
+    }
+
+    /// The I/O error behind the last failed read, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn fill(&mut self, offset: usize, buf: &mut [u8]) -> io::Result<()> {
+        let end = offset + buf.len();
+        if offset >= self.buf_start && end <= self.buf_start + self.buf.len() {
+            buf.copy_from_slice(&self.buf[offset - self.buf_start..end - self.buf_start]);
+            return Ok(());
+        }
+        self.inner.seek(SeekFrom::Start(offset as u64))?;
+        if buf.len() >= STREAM_BUFFER_SIZE {
+            return self.inner.read_exact(buf);
+        }
+        self.buf.resize(STREAM_BUFFER_SIZE.min(self.len - offset), 0);
+        self.buf_start = offset;
+        if let Err(e) = self.inner.read_exact(&mut self.buf) {
+            self.buf.clear();
+            return Err(e);
+        }
+        buf.copy_from_slice(&self.buf[..buf.len()]);
+        Ok(())
+    }
+}
+
+impl<'a, R: Read + Seek> ByteSource<'a> for StreamSource<R> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn read_exact_at(&mut self, offset: usize, buf: &mut [u8]) -> Result<()> {
+        if !offset.checked_add(buf.len()).is_some_and(|end| end <= self.len) {
+            return Err(Eof);
+        }
+        self.fill(offset, buf).map_err(|e| {
+            self.error = Some(e);
+            Eof
+        })
+    }
+
+    fn bytes_at(&mut self, offset: usize, length: usize) -> Result<Cow<'a, [u8]>> {
+        if !offset.checked_add(length).is_some_and(|end| end <= self.len) {
+            return Err(Eof);
+        }
+        let mut result = vec![0; length];
+        self.read_exact_at(offset, &mut result)?;
+        Ok(Cow::Owned(result))
+    }
+}
+
+/// Reads Unity's primitives from a [`ByteSource`], a slice unless stated otherwise.
 #[derive(Clone)]
-pub struct Reader<'a> {
-    buf: &'a [u8],
+pub struct Reader<'a, S = &'a [u8]> {
+    source: S,
     offset: usize,
     order: ByteOrder,
+    max_string_length: usize,
+    bytes: PhantomData<&'a [u8]>,
 }
 
+/// A [`Reader`] over a `Read + Seek` stream, see [`Reader::from_stream`].
+pub type StreamReader<R> = Reader<'static, StreamSource<R>>;
+
 impl<'a> Reader<'a> {
     pub fn new(buf: &'a [u8], order: ByteOrder) -> Self {
-        Self { buf, offset: 0, order }
+        Self::with_source(buf, order)
+    }
+
+    /// Borrows the next `length` bytes from the underlying buffer, or the rest of it if shorter.
+    pub fn read_u8_slice(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.has_space(length).unwrap_or(self.len());
+        let buf = self.source;
+        let result = &buf[self.offset..end];
+        self.offset = end;
+        Ok(result)
+    }
+
+    /// An independent reader over the `length` bytes at `offset`, with the same byte order and
+    /// limits, whose offsets are relative to `offset`.
+    pub fn at(&self, offset: usize, length: usize) -> Result<Reader<'a>> {
+        let end = offset.checked_add(length).ok_or(Eof)?;
+        Ok(Reader {
+            source: self.source.get(offset..end).ok_or(Eof)?,
+            offset: 0,
+            order: self.order,
+            max_string_length: self.max_string_length,
+            bytes: PhantomData,
+        })
+    }
+}
+
+impl<R: Read + Seek> StreamReader<R> {
+    /// Reads `src` through a [`StreamSource`] instead of loading it into memory.
+    pub fn from_stream(src: R, order: ByteOrder) -> UnityResult<Self> {
+        Ok(Self::with_source(StreamSource::new(src)?, order))
+    }
+}
+
+impl<'a, S: ByteSource<'a>> Reader<'a, S> {
+    pub fn with_source(source: S, order: ByteOrder) -> Self {
+        Self {
+            source,
+            offset: 0,
+            order,
+            max_string_length: DEFAULT_MAX_STRING_LENGTH,
+            bytes: PhantomData,
+        }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
     }
 
     pub fn get_offset(&self) -> usize {
@@ -38,7 +284,7 @@ impl<'a> Reader<'a> {
     }
 
     pub fn set_offset(&mut self, offset: usize) -> Result<usize> {
-        if self.buf.len() < offset {
+        if self.len() < offset {
             return Err(Eof);
         }
         let result = self.offset;
@@ -46,6 +292,13 @@ impl<'a> Reader<'a> {
         Ok(result)
     }
 
+    /// A guard to read through that puts the offset back when dropped, whether the reads
+    /// succeeded or not.
+    pub fn save_position(&mut self) -> SavedPosition<'_, 'a, S> {
+        let offset = self.offset;
+        SavedPosition { reader: self, offset }
+    }
+
     pub fn set_little_order(&mut self) {
         self.order = ByteOrder::Little
     }
@@ -55,7 +308,7 @@ impl<'a> Reader<'a> {
     }
 
     pub fn len(&self) -> usize {
-        self.buf.len()
+        self.source.len()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -66,9 +319,39 @@ impl<'a> Reader<'a> {
         self.order
     }
 
+    pub fn max_string_length(&self) -> usize {
+        self.max_string_length
+    }
+
+    /// Sets the longest string, in bytes, that reads taking the length from the data accept.
+    pub fn set_max_string_length(&mut self, max: usize) {
+        self.max_string_length = max
+    }
+
+    /// Checks a `length` read from the data against the bytes left, counting `element_size`
+    /// bytes per element, before anything is allocated for it.
+    pub fn check_length(&self, length: i64, element_size: usize) -> std::result::Result<usize, BadLength> {
+        self.check_length_with_max(length, element_size, usize::MAX)
+    }
+
+    fn check_length_with_max(&self, length: i64, element_size: usize, max: usize) -> std::result::Result<usize, BadLength> {
+        let remaining = self.len().saturating_sub(self.offset);
+        match usize::try_from(length) {
+            Ok(size) if size <= max && size.checked_mul(element_size).is_some_and(|x| x <= remaining) => Ok(size),
+            _ => Err(BadLength { length, remaining, max }),
+        }
+    }
+
+    /// The `i32` length of a string, checked against the bytes left and
+    /// [`Reader::max_string_length`].
+    pub fn read_string_length(&mut self) -> std::result::Result<usize, ReadError> {
+        let length = self.read_i32()?;
+        Ok(self.check_length_with_max(length.into(), 1, self.max_string_length)?)
+    }
+
     pub fn has_space(&self, length: usize) -> Result<usize> {
-        let end = length + self.get_offset();
-        if self.buf.len() < end {
+        let end = length.checked_add(self.get_offset()).ok_or(Eof)?;
+        if self.len() < end {
             Err(Eof)
         } else {
             Ok(end)
@@ -82,25 +365,26 @@ impl<'a> Reader<'a> {
             0 => offset_before_align,
             _ => offset_before_align - remain + num,
         };
-        if offset_after_align > self.buf.len() {
+        if offset_after_align > self.len() {
             return Err(Eof);
         }
         self.set_offset(offset_after_align)?;
         Ok(offset_after_align)
     }
 
-    pub fn read_u8_slice(&mut self, length: usize) -> Result<&[u8]> {
+    /// The next `length` bytes, or the rest if shorter, borrowed when reading a slice.
+    pub fn read_bytes(&mut self, length: usize) -> Result<Cow<'a, [u8]>> {
         let end = self.has_space(length).unwrap_or(self.len());
-        let result = &self.buf[self.offset..end];
+        let result = self.source.bytes_at(self.offset, end - self.offset)?;
         self.offset = end;
         Ok(result)
     }
 
+    #[inline]
     pub fn read_u8_array<const N: usize>(&mut self) -> Result<[u8; N]> {
         let mut result = [0; N];
         let end = self.has_space(N)?;
-        let slice = &self.buf[self.get_offset()..end];
-        result.copy_from_slice(slice);
+        self.source.read_exact_at(self.offset, &mut result)?;
         self.offset = end;
         Ok(result)
     }
@@ -125,15 +409,14 @@ impl<'a> Reader<'a> {
         Ok(result)
     }
 
-    pub fn read_u8_list(&mut self, length: usize) -> Result<Vec<u8>> {
-        Ok(self.read_u8_slice(length)?.to_vec())
+    /// The next `length` bytes, failing without allocating if fewer are left.
+    pub fn read_u8_list(&mut self, length: usize) -> std::result::Result<Vec<u8>, ReadError> {
+        let length = self.check_length(i64::try_from(length).unwrap_or(i64::MAX), 1)?;
+        Ok(self.read_bytes(length)?.into_owned())
     }
 
     pub fn read_u8(&mut self) -> Result<u8> {
-        let end = self.has_space(1)?;
-        let result = self.buf[self.offset];
-        self.offset = end;
-        Ok(result)
+        Ok(self.read_u8_array::<1>()?[0])
     }
 
     pub fn read_bool(&mut self) -> Result<bool> {
@@ -209,6 +492,27 @@ impl<'a> Reader<'a> {
         }
     }
 
+    /// An IEEE 754 half precision float, widened exactly.
+    pub fn read_f16(&mut self) -> Result<f32> {
+        Ok(f16_to_f32(self.read_u16()?))
+    }
+
+    pub fn read_unorm8(&mut self) -> Result<f32> {
+        Ok(unorm8_to_f32(self.read_u8()?))
+    }
+
+    pub fn read_snorm8(&mut self) -> Result<f32> {
+        Ok(snorm8_to_f32(self.read_i8()?))
+    }
+
+    pub fn read_unorm16(&mut self) -> Result<f32> {
+        Ok(unorm16_to_f32(self.read_u16()?))
+    }
+
+    pub fn read_snorm16(&mut self) -> Result<f32> {
+        Ok(snorm16_to_f32(self.read_i16()?))
+    }
+
     pub fn read_7bit_u32(&mut self) -> Result<u32> {
         let mut out = 0u32;
         let mut shift = 0u32;
@@ -257,7 +561,7 @@ impl<'a> Reader<'a> {
     }
 
     pub fn read_string_with_length(&mut self, length: usize) -> Result<String> {
-        Ok(String::from_utf8_lossy(self.read_u8_slice(length)?).to_string())
+        Ok(String::from_utf8_lossy(&self.read_bytes(length)?).to_string())
     }
 
     pub fn read_string_with_7bit_length(&mut self) -> Result<String> {
@@ -265,20 +569,75 @@ impl<'a> Reader<'a> {
         self.read_string_with_length(length as usize)
     }
 
-    pub fn read_aligned_string(&mut self) -> Result<String> {
-        let length = self.read_i32()?;
-        let result = self.read_string_with_length(length as usize);
+    /// Reads like [`Reader::read_aligned_string`], keeping the bytes along with the text.
+    pub fn read_aligned_string_raw(&mut self) -> std::result::Result<RawString, ReadError> {
+        let length = self.read_string_length()?;
+        let bytes = self.read_bytes(length)?.into_owned();
         self.align(4)?;
-        result
+        let text = String::from_utf8_lossy(&bytes).into_owned();
+        Ok(RawString { bytes, text })
     }
 
-    pub fn read_i32_list(&mut self, length: usize) -> Result<Vec<i32>> {
+    /// A string after its `i32` length, see [`Reader::read_string_length`], and the padding to a
+    /// multiple of 4.
+    pub fn read_aligned_string(&mut self) -> std::result::Result<String, ReadError> {
+        let length = self.read_string_length()?;
+        let result = self.read_string_with_length(length)?;
+        self.align(4)?;
+        Ok(result)
+    }
+
+    /// Reads `size` numbers with one bounds check, converting the bytes in a loop the compiler
+    /// turns into a copy when the byte order is the native one.
+    fn read_primitives<T: Primitive>(&mut self, size: usize) -> Result<Vec<T>> {
+        let length = size.checked_mul(T::SIZE).ok_or(Eof)?;
         self.has_space(length)?;
-        let mut ret = Vec::with_capacity(length);
-        for _ in 0..length {
-            ret.push(self.read_i32()?)
-        }
-        Ok(ret)
+        let bytes = self.read_bytes(length)?;
+        let chunks = bytes.chunks_exact(T::SIZE);
+        Ok(match self.order {
+            ByteOrder::Little => chunks.map(T::from_le).collect(),
+            ByteOrder::Big => chunks.map(T::from_be).collect(),
+        })
+    }
+
+    pub fn read_i8_slice(&mut self, size: usize) -> Result<Vec<i8>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_i16_slice(&mut self, size: usize) -> Result<Vec<i16>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_u16_slice(&mut self, size: usize) -> Result<Vec<u16>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_i32_slice(&mut self, size: usize) -> Result<Vec<i32>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_u32_slice(&mut self, size: usize) -> Result<Vec<u32>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_i64_slice(&mut self, size: usize) -> Result<Vec<i64>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_u64_slice(&mut self, size: usize) -> Result<Vec<u64>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_f32_slice(&mut self, size: usize) -> Result<Vec<f32>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_f64_slice(&mut self, size: usize) -> Result<Vec<f64>> {
+        self.read_primitives(size)
+    }
+
+    pub fn read_i32_list(&mut self, length: usize) -> Result<Vec<i32>> {
+        self.read_i32_slice(length)
     }
 
     pub fn read_rect_f32(&mut self) -> Result<RectF32> {
@@ -311,6 +670,7 @@ impl<'a> Reader<'a> {
         })
     }
 
+    /// Column by column, see [`Matrix4x4`].
     pub fn read_matrix4x4(&mut self) -> Result<Matrix4x4> {
         Ok(Matrix4x4 {
             m00: self.read_f32()?,
@@ -333,26 +693,18 @@ impl<'a> Reader<'a> {
     }
 
     pub fn read_u16_list(&mut self, size: usize) -> Result<Vec<u16>> {
-        let _end = self.has_space(size)?;
-        let mut ret = Vec::with_capacity(size);
-        for _ in 0..size {
-            ret.push(self.read_u16()?)
-        }
-        Ok(ret)
+        self.read_u16_slice(size)
     }
 
     pub fn read_u32_list(&mut self, size: usize) -> Result<Vec<u32>> {
-        let _end = self.has_space(size)?;
-        let mut ret = Vec::with_capacity(size);
-        for _ in 0..size {
-            ret.push(self.read_u32()?)
-        }
-        Ok(ret)
+        self.read_u32_slice(size)
     }
 
-    pub fn read_string_list(&mut self) -> Result<Vec<String>> {
+    /// An `i32` count of aligned strings, each taking at least 4 bytes.
+    pub fn read_string_list(&mut self) -> std::result::Result<Vec<String>, ReadError> {
         let length = self.read_i32()?;
-        let mut result = Vec::with_capacity(length as usize);
+        let length = self.check_length(length.into(), 4)?;
+        let mut result = Vec::with_capacity(length);
         for _ in 0..length {
             result.push(self.read_aligned_string()?);
         }
@@ -360,16 +712,20 @@ impl<'a> Reader<'a> {
     }
 
     pub fn read_f32_list(&mut self, size: usize) -> Result<Vec<f32>> {
-        let _end = self.has_space(size)?;
+        self.read_f32_slice(size)
+    }
+
+    pub fn read_f16_list(&mut self, size: usize) -> Result<Vec<f32>> {
+        let _end = self.has_space(size.checked_mul(2).ok_or(Eof)?)?;
         let mut ret = Vec::with_capacity(size);
         for _ in 0..size {
-            ret.push(self.read_f32()?)
+            ret.push(self.read_f16()?)
         }
         Ok(ret)
     }
 
     pub fn read_matrix4x4_list(&mut self, size: usize) -> Result<Vec<Matrix4x4>> {
-        let _end = self.has_space(size)?;
+        let _end = self.has_space(size.checked_mul(64).ok_or(Eof)?)?;
         let mut ret = Vec::with_capacity(size);
         for _ in 0..size {
             ret.push(self.read_matrix4x4()?)
@@ -377,3 +733,107 @@ impl<'a> Reader<'a> {
         Ok(ret)
     }
 }
+
+/// A [`Reader`] that goes back to where it was when dropped, see [`Reader::save_position`].
+pub struct SavedPosition<'r, 'a, S> {
+    reader: &'r mut Reader<'a, S>,
+    offset: usize,
+}
+
+impl<'a, S> Deref for SavedPosition<'_, 'a, S> {
+    type Target = Reader<'a, S>;
+
+    fn deref(&self) -> &Self::Target {
+        self.reader
+    }
+}
+
+impl<S> DerefMut for SavedPosition<'_, '_, S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.reader
+    }
+}
+
+impl<S> Drop for SavedPosition<'_, '_, S> {
+    fn drop(&mut self) {
+        // The offset was valid when saved and the reader's length cannot change.
+        self.reader.offset = self.offset;
+    }
+}
+
+/// The bits of an IEEE 754 half precision float as an `f32`, keeping subnormals, infinities and
+/// NaN.
+pub fn f16_to_f32(bits: u16) -> f32 {
+    half::f16::from_bits(bits).to_f32()
+}
+
+/// An unsigned normalized 8 bit value, `0..=255` mapped to `0.0..=1.0`.
+pub fn unorm8_to_f32(x: u8) -> f32 {
+    x as f32 / 255.0
+}
+
+/// A signed normalized 8 bit value, `-127..=127` mapped to `-1.0..=1.0` with -128 clamped to -1.
+pub fn snorm8_to_f32(x: i8) -> f32 {
+    (x as f32 / 127.0).max(-1.0)
+}
+
+/// An unsigned normalized 16 bit value, `0..=65535` mapped to `0.0..=1.0`.
+pub fn unorm16_to_f32(x: u16) -> f32 {
+    x as f32 / 65535.0
+}
+
+/// A signed normalized 16 bit value, `-32767..=32767` mapped to `-1.0..=1.0` with -32768
+/// clamped to -1.
+pub fn snorm16_to_f32(x: i16) -> f32 {
+    (x as f32 / 32767.0).max(-1.0)
+}
+
+/// Reads a sequence of streams as if they were one, e.g. the `.split0`, `.split1`, ... chunks
+/// of a bundle. Reads and seeks may cross the boundaries between parts.
+pub struct SplitReader<R> {
+    parts: Vec<R>,
+    /// Where each part starts within the whole.
+    starts: Vec<u64>,
+    len: u64,
+    position: u64,
+}
+
+impl<R: Read + Seek> SplitReader<R> {
+    pub fn new(mut parts: Vec<R>) -> UnityResult<Self> {
+        let mut starts = Vec::with_capacity(parts.len());
+        let mut len = 0;
+        for part in &mut parts {
+            starts.push(len);
+            len += part.seek(SeekFrom::End(0))?;
+        }
+        Ok(Self { parts, starts, len, position: 0 })
+    }
+}
+
+impl<R: Read + Seek> Read for SplitReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+        let index = self.starts.partition_point(|x| *x <= self.position) - 1;
+        let part_end = self.starts.get(index + 1).copied().unwrap_or(self.len);
+        let part = &mut self.parts[index];
+        part.seek(SeekFrom::Start(self.position - self.starts[index]))?;
+        let size = buf.len().min((part_end - self.position) as usize);
+        let read = part.read(&mut buf[..size])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: Read + Seek> Seek for SplitReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek before the start of the split file"))?;
+        Ok(self.position)
+    }
+}
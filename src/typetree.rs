@@ -1,20 +1,226 @@
-#[derive(Default, Clone, Debug)]
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{UnityError, UnityResult};
+use crate::reader::Reader;
+
+mod builder;
+mod codegen;
+mod diff;
+mod size;
+mod templates;
+pub use builder::TypeTreeBuilder;
+pub use codegen::generate_rust;
+pub use diff::{diff, dump_changes, TypeTreeChange};
+pub use size::{skip_subtree, subtree_size};
+pub(crate) use size::{aligned, children, min_size, Kind};
+pub use templates::builtin_nodes;
+
+/// Set in [`TypeTreeNode::meta_flag`] when the reader aligns to 4 bytes after the node.
+pub(crate) const ALIGN_BYTES: i32 = 0x4000;
+
+/// Trees nested deeper are taken as malformed; Unity's own stay within a few dozen levels.
+pub(crate) const MAX_LEVEL: i32 = 128;
+
+/// A node of a type tree. It (de)serializes with the field names of TypeTreeGenerator dumps, in
+/// which only `m_Type`, `m_Name` and `m_Level` are required.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TypeTreeNode {
+    #[serde(rename = "m_Type")]
     pub type_: String,
+    #[serde(rename = "m_Name")]
     pub name: String,
+    #[serde(rename = "m_ByteSize", default)]
     pub size: i32,
+    #[serde(rename = "m_Index", default)]
     pub index: i32,
+    #[serde(rename = "m_TypeFlags", default)]
     pub type_flag: i32,
+    #[serde(rename = "m_Version", default)]
     pub version: i32,
+    #[serde(rename = "m_MetaFlag", default)]
     pub meta_flag: i32,
+    #[serde(rename = "m_Level")]
     pub level: i32,
+    #[serde(skip)]
     pub type_str_offset: usize,
+    #[serde(skip)]
     pub name_str_offset: usize,
+    #[serde(rename = "m_RefTypeHash", default)]
     pub ref_type_hash: u64,
 }
 
-#[derive(Default, Clone, Debug)]
+/// A type tree. It (de)serializes as its list of nodes.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct TypeTree {
     pub nodes: Vec<TypeTreeNode>,
+    #[serde(skip)]
     pub string_buffer: Vec<u8>,
 }
+
+/// Prints nodes the way AssetStudio dumps type trees, one line per node indented by its level:
+/// `type name // ByteSize{..}, Index{..}, Version{..}, IsArray{..}, MetaFlag{..}` with the size
+/// and flags in hex.
+pub fn dump_nodes(nodes: &[TypeTreeNode]) -> String {
+    nodes.iter().map(|x| format!("{}\n", x)).collect()
+}
+
+impl fmt::Display for TypeTreeNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = "\t".repeat(self.level.max(0) as usize);
+        write!(f, "{}{} {} // ByteSize{{{:x}}}, Index{{{}}}, Version{{{}}}, IsArray{{{}}}, MetaFlag{{{:x}}}", indent, self.type_, self.name, self.size, self.index, self.version, self.type_flag, self.meta_flag)
+    }
+}
+
+impl fmt::Display for TypeTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.dump())
+    }
+}
+
+impl TypeTree {
+    /// Reads a JSON array of nodes, as written by serializing a [`TypeTree`] or found per class
+    /// in TypeTreeGenerator dumps.
+    pub fn from_json_nodes(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn to_json_nodes(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// The tree as indented text, see [`dump_nodes`].
+    pub fn dump(&self) -> String {
+        dump_nodes(&self.nodes)
+    }
+
+    /// Reads a node and its children in the recursive layout of serialized file versions below 10
+    /// and version 11, where each node carries its own strings instead of offsets into a buffer.
+    pub fn read_legacy_node(&mut self, r: &mut Reader, format_version: u32, level: i32) -> UnityResult<()> {
+        let mut node = TypeTreeNode {
+            level,
+            type_: r.read_string_util_null()?,
+            name: r.read_string_util_null()?,
+            size: r.read_i32()?,
+            ..TypeTreeNode::default()
+        };
+        if format_version == 2 {
+            let _variable_count = r.read_i32()?;
+        }
+        if format_version != 3 {
+            node.index = r.read_i32()?;
+        }
+        node.type_flag = r.read_i32()?;
+        node.version = r.read_i32()?;
+        if format_version != 3 {
+            node.meta_flag = r.read_i32()?;
+        }
+        self.nodes.push(node);
+        if level >= MAX_LEVEL {
+            return Err(UnityError::CustomError(format!("type tree nested deeper than {} levels", MAX_LEVEL)));
+        }
+        for _ in 0..r.read_i32()? {
+            self.read_legacy_node(r, format_version, level + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Supplies type trees for objects whose serialized file was built without them.
+///
+/// `assembly_name` is the MonoScript's assembly (e.g. `Assembly-CSharp.dll`), `full_name` the
+/// namespace-qualified class name and `unity_version` the serialized file's version string.
+pub trait TypeTreeProvider: Send + Sync {
+    fn get_nodes(&self, assembly_name: &str, full_name: &str, unity_version: &str) -> Option<Vec<TypeTreeNode>>;
+}
+
+/// A [`TypeTreeProvider`] backed by a TypeTreeGenerator style JSON dump:
+/// `{ "Assembly-CSharp.dll": { "Namespace.Class": [ { "m_Type": .., "m_Name": .., "m_Level": .., "m_MetaFlag": .. }, .. ] } }`.
+#[derive(Default, Debug)]
+pub struct JsonTypeTreeProvider {
+    trees: HashMap<String, HashMap<String, Vec<TypeTreeNode>>>,
+}
+
+impl JsonTypeTreeProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut result = Self::new();
+        result.extend_from_json(json)?;
+        Ok(result)
+    }
+
+    pub fn extend_from_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let dump: HashMap<String, HashMap<String, Vec<TypeTreeNode>>> = serde_json::from_str(json)?;
+        for (assembly_name, classes) in dump {
+            for (full_name, nodes) in classes {
+                self.insert(&assembly_name, &full_name, nodes);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn insert(&mut self, assembly_name: &str, full_name: &str, nodes: Vec<TypeTreeNode>) {
+        let assembly_name = normalize_assembly_name(assembly_name);
+        self.trees.entry(assembly_name.to_string()).or_default().insert(full_name.to_string(), nodes);
+    }
+}
+
+impl TypeTreeProvider for JsonTypeTreeProvider {
+    fn get_nodes(&self, assembly_name: &str, full_name: &str, _unity_version: &str) -> Option<Vec<TypeTreeNode>> {
+        self.trees.get(normalize_assembly_name(assembly_name))?.get(full_name).cloned()
+    }
+}
+
+fn normalize_assembly_name(name: &str) -> &str {
+    name.strip_suffix(".dll").unwrap_or(name)
+}
+
+fn header_node(type_: &str, name: &str, level: i32, size: i32, meta_flag: i32) -> TypeTreeNode {
+    TypeTreeNode {
+        type_: type_.to_string(),
+        name: name.to_string(),
+        size,
+        meta_flag,
+        level,
+        ..TypeTreeNode::default()
+    }
+}
+
+/// Completes script field nodes from a [`TypeTreeProvider`] into a full MonoBehaviour type tree.
+///
+/// Dumps that already contain the `m_Script` header are returned unchanged.
+pub(crate) fn with_mono_behaviour_header(nodes: Vec<TypeTreeNode>, asset_version: u32) -> Vec<TypeTreeNode> {
+    if nodes.iter().any(|x| x.level == 1 && x.name == "m_Script") {
+        return nodes;
+    }
+    let (path_id_type, path_id_size) = if asset_version < 14 { ("int", 4) } else { ("SInt64", 8) };
+    let pptr = |type_: &str, name: &str| [header_node(type_, name, 1, 4 + path_id_size, 0), header_node("int", "m_FileID", 2, 4, 0), header_node(path_id_type, "m_PathID", 2, path_id_size, 0)];
+    let mut result = vec![header_node("MonoBehaviour", "Base", 0, -1, 0)];
+    result.extend(pptr("PPtr<GameObject>", "m_GameObject"));
+    result.push(header_node("UInt8", "m_Enabled", 1, 1, 0x4000));
+    result.extend(pptr("PPtr<MonoScript>", "m_Script"));
+    result.push(header_node("string", "m_Name", 1, -1, 0x8000));
+    result.push(header_node("Array", "Array", 2, -1, 0x4001));
+    result.push(header_node("int", "size", 3, 4, 0));
+    result.push(header_node("char", "data", 3, 1, 0));
+
+    let fields = match nodes.first() {
+        Some(first) if first.level == 0 => &nodes[1..],
+        _ => &nodes[..],
+    };
+    let shift = 1 - fields.iter().map(|x| x.level).min().unwrap_or(1);
+    result.extend(fields.iter().cloned().map(|mut x| {
+        x.level += shift;
+        x
+    }));
+    for (i, node) in result.iter_mut().enumerate() {
+        node.index = i as i32;
+    }
+    result
+}
@@ -0,0 +1,167 @@
+use super::size::primitive_size;
+use super::{header_node, TypeTreeNode, ALIGN_BYTES};
+
+/// Set on nodes with an aligned node below them.
+const ANY_CHILD_USES_ALIGN_BYTES: i32 = 0x8000;
+
+/// Builds a type tree in code, as Unity lays out the fields of a script.
+///
+/// ```
+/// use unity_rs::typetree::TypeTreeBuilder;
+///
+/// let nodes = TypeTreeBuilder::new("MonoBehaviour")
+///     .field("int", "m_Value")
+///     .string("m_Title")
+///     .array("vector", "m_Items", |b| b.field("float", "data"))
+///     .build();
+/// assert_eq!(nodes[1].name, "m_Value");
+/// ```
+///
+/// Levels, indices and sizes are filled in by [`TypeTreeBuilder::build`]. Fields smaller than 4
+/// bytes and arrays are aligned after, like Unity does for script fields, while the elements of an
+/// array are not. Built-in classes pack some small fields, which [`TypeTreeBuilder::packed`] adds.
+#[derive(Debug, Clone)]
+pub struct TypeTreeBuilder {
+    nodes: Vec<TypeTreeNode>,
+    level: i32,
+    /// Building the element of an array, which is packed.
+    element: bool,
+    /// The format of the serialized file the tree is for, which sizes path ids.
+    asset_version: u32,
+}
+
+impl TypeTreeBuilder {
+    /// Starts a tree whose root is a `type_` named `Base`.
+    pub fn new(type_: &str) -> Self {
+        let mut result = Self {
+            nodes: Vec::new(),
+            level: 1,
+            element: false,
+            asset_version: u32::MAX,
+        };
+        result.push(type_, "Base", 0, 0, 0);
+        result
+    }
+
+    /// Lays out pointers for a serialized file of format `asset_version`, whose path ids are 4
+    /// bytes before format 14. Trees are for the current format otherwise.
+    pub fn with_asset_version(mut self, asset_version: u32) -> Self {
+        self.asset_version = asset_version;
+        self
+    }
+
+    fn push(&mut self, type_: &str, name: &str, level: i32, size: i32, meta_flag: i32) {
+        self.nodes.push(TypeTreeNode {
+            version: 1,
+            ..header_node(type_, name, level, size, meta_flag)
+        });
+    }
+
+    /// A child builder for the fields one level below the last node.
+    fn child(&self, element: bool) -> Self {
+        Self {
+            nodes: Vec::new(),
+            level: self.level + 1,
+            element,
+            asset_version: self.asset_version,
+        }
+    }
+
+    /// A built-in value type such as `int`, `float`, `bool` or `UInt8`.
+    pub fn field(mut self, type_: &str, name: &str) -> Self {
+        let size = primitive_size(type_).map_or(-1, |x| x as i32);
+        let align = !self.element && (1..4).contains(&size);
+        self.push(type_, name, self.level, size, if align { ALIGN_BYTES } else { 0 });
+        self
+    }
+
+    /// A built-in value type that is never aligned after, for the small fields of built-in classes
+    /// that are followed by more small fields.
+    pub fn packed(mut self, type_: &str, name: &str) -> Self {
+        let size = primitive_size(type_).map_or(-1, |x| x as i32);
+        self.push(type_, name, self.level, size, 0);
+        self
+    }
+
+    /// A `string`, stored like an aligned array of `char`.
+    pub fn string(mut self, name: &str) -> Self {
+        self.push("string", name, self.level, -1, 0);
+        self.push("Array", "Array", self.level + 1, -1, ALIGN_BYTES | 1);
+        self.nodes.last_mut().expect("Missing node").type_flag = 1;
+        self.push("int", "size", self.level + 2, 4, 0);
+        self.push("char", "data", self.level + 2, 1, 0);
+        self
+    }
+
+    /// A vector or array field of `type_`, such as `vector`. `element` adds the one node of its
+    /// elements, by convention named `data`.
+    pub fn array(mut self, type_: &str, name: &str, element: impl FnOnce(Self) -> Self) -> Self {
+        self.push(type_, name, self.level, -1, 0);
+        self.push("Array", "Array", self.level + 1, -1, ALIGN_BYTES);
+        self.nodes.last_mut().expect("Missing node").type_flag = 1;
+        self.push("int", "size", self.level + 2, 4, 0);
+        let mut child = self.child(true);
+        child.level += 1;
+        self.nodes.extend(element(child).nodes);
+        self
+    }
+
+    /// A field of a serializable class or struct `type_`, whose fields `fields` adds.
+    pub fn nested(mut self, type_: &str, name: &str, fields: impl FnOnce(Self) -> Self) -> Self {
+        self.push(type_, name, self.level, 0, 0);
+        let child = self.child(false);
+        self.nodes.extend(fields(child).nodes);
+        self
+    }
+
+    /// A `PPtr<target>` to another object, whose path id is sized for the asset version.
+    pub fn pptr(self, target: &str, name: &str) -> Self {
+        let path_id = if self.asset_version < 14 { "int" } else { "SInt64" };
+        self.nested(&format!("PPtr<{}>", target), name, |b| b.field("int", "m_FileID").field(path_id, "m_PathID"))
+    }
+
+    /// A `pair` field, whose `fields` are named `first` and `second`.
+    pub fn pair(self, name: &str, fields: impl FnOnce(Self) -> Self) -> Self {
+        self.nested("pair", name, fields)
+    }
+
+    /// A `TypelessData` blob, a byte count and as many bytes, as built-in classes store image data.
+    pub fn typeless_data(mut self, name: &str) -> Self {
+        self.push("TypelessData", name, self.level, -1, 0);
+        self.push("int", "size", self.level + 1, 4, 0);
+        self.push("UInt8", "data", self.level + 1, 1, 0);
+        self
+    }
+
+    /// The flat nodes, with indices, the sizes of nested types and the flags of nodes with
+    /// aligned children.
+    pub fn build(mut self) -> Vec<TypeTreeNode> {
+        for i in (0..self.nodes.len()).rev() {
+            let level = self.nodes[i].level;
+            let end = i + 1 + self.nodes[i + 1..].iter().take_while(|x| x.level > level).count();
+            let children = &self.nodes[i + 1..end];
+            let aligned = children.iter().any(|x| x.meta_flag & ALIGN_BYTES != 0);
+            // Nested types are as big as their fields if those have fixed sizes and none is aligned.
+            let size = (self.nodes[i].size == 0).then(|| {
+                let fields: Vec<&TypeTreeNode> = children.iter().filter(|x| x.level == level + 1).collect();
+                let fixed = !fields.is_empty() && fields.iter().all(|x| x.size >= 0 && x.meta_flag & ALIGN_BYTES == 0);
+                if fixed {
+                    fields.iter().map(|x| x.size).sum()
+                } else {
+                    -1
+                }
+            });
+            let node = &mut self.nodes[i];
+            if aligned {
+                node.meta_flag |= ANY_CHILD_USES_ALIGN_BYTES;
+            }
+            if let Some(size) = size {
+                node.size = size;
+            }
+        }
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            node.index = i as i32;
+        }
+        self.nodes
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt::Write;
+
+use super::TypeTreeNode;
+
+/// Rust keywords that can be used as raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut",
+    "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+struct Field {
+    name: String,
+    ident: String,
+    type_: String,
+}
+
+struct Struct {
+    name: String,
+    fields: Vec<Field>,
+    /// Structs referenced by the fields, which must derive what this one derives.
+    uses: Vec<String>,
+    /// Used as a map key, so it also derives `Eq` and `Hash`.
+    hashable: bool,
+}
+
+/// A Rust type for a node, with the generated structs it refers to.
+struct RustType {
+    text: String,
+    uses: Vec<String>,
+}
+
+impl RustType {
+    fn plain(text: &str) -> Self {
+        Self { text: text.to_string(), uses: Vec::new() }
+    }
+}
+
+struct Generator<'a> {
+    nodes: &'a [TypeTreeNode],
+    structs: Vec<Struct>,
+}
+
+impl Generator<'_> {
+    fn children(&self, index: usize) -> Vec<usize> {
+        let level = self.nodes[index].level;
+        self.nodes[index + 1..].iter().take_while(|x| x.level > level).enumerate().filter(|(_, x)| x.level == level + 1).map(|(i, _)| index + 1 + i).collect()
+    }
+
+    /// The element node of a vector or map, the `data` child of its `Array` node.
+    fn array_data(&self, index: usize) -> Option<usize> {
+        let array = *self.children(index).first().filter(|&&x| self.nodes[x].type_ == "Array")?;
+        self.children(array).get(1).copied()
+    }
+
+    fn rust_type(&mut self, index: usize) -> RustType {
+        let nodes = self.nodes;
+        let type_ = nodes[index].type_.as_str();
+        if let Some(primitive) = primitive(type_) {
+            return RustType::plain(primitive);
+        }
+        match type_ {
+            "string" => return RustType::plain("String"),
+            "TypelessData" => return RustType::plain("Vec<u8>"),
+            _ => {}
+        }
+        if let Some(data) = self.array_data(index) {
+            let pair = self.children(data);
+            if type_ == "map" && pair.len() == 2 {
+                let key = self.rust_type(pair[0]);
+                for name in &key.uses {
+                    self.mark_hashable(name);
+                }
+                let value = self.rust_type(pair[1]);
+                return RustType {
+                    text: format!("std::collections::HashMap<{}, {}>", key.text, value.text),
+                    uses: key.uses.into_iter().chain(value.uses).collect(),
+                };
+            }
+            let element = self.rust_type(data);
+            return RustType {
+                text: format!("Vec<{}>", element.text),
+                uses: element.uses,
+            };
+        }
+        let name = self.struct_type(index, &type_ident(type_));
+        RustType { text: name.clone(), uses: vec![name] }
+    }
+
+    /// Generates the struct for the fields of `index`, returning its name. Structs with the same
+    /// name and fields are generated once; differing ones get a numbered name.
+    fn struct_type(&mut self, index: usize, name: &str) -> String {
+        let mut fields: Vec<Field> = Vec::new();
+        let mut uses = Vec::new();
+        for child in self.children(index) {
+            let type_ = self.rust_type(child);
+            let name = self.nodes[child].name.clone();
+            let mut ident = field_ident(&name);
+            let base = ident.clone();
+            for i in 2.. {
+                if !fields.iter().any(|x| x.ident == ident) {
+                    break;
+                }
+                ident = format!("{}_{}", base, i);
+            }
+            uses.extend(type_.uses);
+            fields.push(Field { name, ident, type_: type_.text });
+        }
+
+        for i in 1.. {
+            let candidate = if i == 1 { name.to_string() } else { format!("{}{}", name, i) };
+            match self.structs.iter().find(|x| x.name == candidate) {
+                Some(existing) if same_fields(&existing.fields, &fields) => return candidate,
+                Some(_) => continue,
+                None => {
+                    self.structs.push(Struct {
+                        name: candidate.clone(),
+                        fields,
+                        uses,
+                        hashable: false,
+                    });
+                    return candidate;
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    fn mark_hashable(&mut self, name: &str) {
+        let Some(s) = self.structs.iter_mut().find(|x| x.name == name) else {
+            return;
+        };
+        if s.hashable {
+            return;
+        }
+        s.hashable = true;
+        for name in s.uses.clone() {
+            self.mark_hashable(&name);
+        }
+    }
+}
+
+/// Generates Rust structs deserializing the object described by `nodes` with
+/// [`Object::read_type_tree`](crate::Object::read_type_tree), the root one named `type_name`.
+///
+/// Fields are snake cased without their `m_` prefix and renamed back with `#[serde(rename)]`.
+/// Nested types become structs named after their type, vectors `Vec` and maps `HashMap`, whose
+/// key structs also derive `Eq` and `Hash` (which fails to compile for keys holding floats).
+/// Paths are fully qualified, so the output compiles with only `serde` as a dependency.
+pub fn generate_rust(nodes: &[TypeTreeNode], type_name: &str) -> String {
+    if nodes.is_empty() {
+        return String::new();
+    }
+    let mut generator = Generator { nodes, structs: Vec::new() };
+    generator.struct_type(0, &type_ident(type_name));
+
+    let mut result = String::new();
+    // Nested structs are generated before their parents, so this writes the root first.
+    for (i, s) in generator.structs.iter().rev().enumerate() {
+        if i > 0 {
+            result.push('\n');
+        }
+        let derives = if s.hashable { "Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize" } else { "Debug, Clone, serde::Deserialize" };
+        let _ = writeln!(result, "#[derive({})]", derives);
+        let _ = writeln!(result, "pub struct {} {{", s.name);
+        for field in &s.fields {
+            if field.ident.trim_start_matches("r#") != field.name {
+                let _ = writeln!(result, "    #[serde(rename = {:?})]", field.name);
+            }
+            let _ = writeln!(result, "    pub {}: {},", field.ident, field.type_);
+        }
+        result.push_str("}\n");
+    }
+    result
+}
+
+/// The integer, float and bool types as the deserializer reads them.
+fn primitive(type_: &str) -> Option<&'static str> {
+    let result = match type_ {
+        "SInt8" => "i8",
+        "UInt8" | "char" => "u8",
+        "short" | "SInt16" => "i16",
+        "UInt16" | "unsigned short" => "u16",
+        "int" | "SInt32" => "i32",
+        "UInt32" | "unsigned int" | "Type*" => "u32",
+        "long long" | "SInt64" => "i64",
+        "UInt64" | "unsigned long long" | "FileSize" => "u64",
+        "float" => "f32",
+        "double" => "f64",
+        "bool" => "bool",
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn same_fields(a: &[Field], b: &[Field]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.name == b.name && a.type_ == b.type_)
+}
+
+/// A snake case identifier for a field name: `m_LocalPosition` becomes `local_position`,
+/// `m_PathID` `path_id` and `data[0]` `data_0`.
+fn field_ident(name: &str) -> String {
+    let name = name.strip_prefix("m_").filter(|x| !x.is_empty()).unwrap_or(name);
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !result.ends_with('_') {
+                result.push('_');
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|x| x.is_ascii_lowercase());
+            if (prev.is_ascii_lowercase() || prev.is_ascii_digit() || (prev.is_ascii_uppercase() && next_lower)) && !result.ends_with('_') {
+                result.push('_');
+            }
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    let result = result.trim_matches('_');
+    if result.is_empty() {
+        "field".to_string()
+    } else if result.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", result)
+    } else if matches!(result, "self" | "super" | "crate") {
+        format!("{}_", result)
+    } else if KEYWORDS.contains(&result) {
+        format!("r#{}", result)
+    } else {
+        result.to_string()
+    }
+}
+
+/// A camel case identifier for a type name: `PPtr<GameObject>` becomes `PPtrGameObject`.
+fn type_ident(type_: &str) -> String {
+    let mut result = String::new();
+    for part in type_.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            result.extend(chars);
+        }
+    }
+    if result.is_empty() {
+        "Unnamed".to_string()
+    } else if result.starts_with(|c: char| c.is_ascii_digit()) || result == "Self" {
+        format!("T{}", result)
+    } else {
+        result
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt;
+
+use super::TypeTreeNode;
+
+/// A difference between two type trees of a class, see [`diff`]. Paths are the field names from
+/// below the root joined by `.`, like `m_Items.Array.data.m_Count`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeTreeChange {
+    Added {
+        path: String,
+        type_: String,
+    },
+    Removed {
+        path: String,
+        type_: String,
+    },
+    /// A field kept its name but changed its type. Its children are compared as well.
+    Retyped {
+        path: String,
+        old_type: String,
+        new_type: String,
+    },
+    /// A field with the same type and children under a new name in the same parent.
+    Renamed {
+        old_path: String,
+        new_path: String,
+        type_: String,
+    },
+    /// A field with the same name, type and children under a different parent.
+    Moved {
+        old_path: String,
+        new_path: String,
+        type_: String,
+    },
+}
+
+/// Prints the change like a line of a unified diff: `- path: type` for removed fields,
+/// `+ path: type` for added ones, `~ path: old -> new` for retyped ones, `~ old -> new: type` for
+/// renamed ones and `> old -> new: type` for moved ones.
+impl fmt::Display for TypeTreeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { path, type_ } => write!(f, "+ {}: {}", path, type_),
+            Self::Removed { path, type_ } => write!(f, "- {}: {}", path, type_),
+            Self::Retyped { path, old_type, new_type } => write!(f, "~ {}: {} -> {}", path, old_type, new_type),
+            Self::Renamed { old_path, new_path, type_ } => write!(f, "~ {} -> {}: {}", old_path, new_path, type_),
+            Self::Moved { old_path, new_path, type_ } => write!(f, "> {} -> {}: {}", old_path, new_path, type_),
+        }
+    }
+}
+
+/// Prints one change per line, see [`TypeTreeChange`]'s `Display`.
+pub fn dump_changes(changes: &[TypeTreeChange]) -> String {
+    changes.iter().map(|x| format!("{}\n", x)).collect()
+}
+
+/// A node with its children, rebuilt from the flat levels.
+struct Field<'a> {
+    node: &'a TypeTreeNode,
+    children: Vec<Field<'a>>,
+}
+
+impl<'a> Field<'a> {
+    /// The tree from the first node, or `empty` without nodes.
+    fn root(nodes: &'a [TypeTreeNode], empty: &'a TypeTreeNode) -> Self {
+        if nodes.is_empty() {
+            Self { node: empty, children: Vec::new() }
+        } else {
+            Self::read(nodes, &mut 0)
+        }
+    }
+
+    fn read(nodes: &'a [TypeTreeNode], index: &mut usize) -> Self {
+        let node = &nodes[*index];
+        *index += 1;
+        let mut children = Vec::new();
+        while nodes.get(*index).is_some_and(|x| x.level > node.level) {
+            children.push(Self::read(nodes, index));
+        }
+        Self { node, children }
+    }
+
+    /// Whether both have the same type and the same children, ignoring their own names.
+    fn same_shape(&self, other: &Field) -> bool {
+        self.node.type_ == other.node.type_ && self.children.len() == other.children.len() && self.children.iter().zip(&other.children).all(|(a, b)| a.node.name == b.node.name && a.same_shape(b))
+    }
+}
+
+/// A field only found on one side.
+struct Entry<'a> {
+    path: String,
+    /// The path of the parent in the new tree.
+    parent: String,
+    field: &'a Field<'a>,
+}
+
+#[derive(Default)]
+struct Differ<'a> {
+    changes: Vec<TypeTreeChange>,
+    removed: Vec<Entry<'a>>,
+    added: Vec<Entry<'a>>,
+}
+
+impl<'a> Differ<'a> {
+    /// Pairs the children of `old` and `new` by name, collecting the unpaired ones.
+    fn compare(&mut self, old: &'a Field<'a>, new: &'a Field<'a>, old_path: &str, new_path: &str) {
+        let mut paired = vec![None; new.children.len()];
+        for o in &old.children {
+            match (0..new.children.len()).find(|&i| paired[i].is_none() && new.children[i].node.name == o.node.name) {
+                Some(i) => paired[i] = Some(o),
+                None => self.removed.push(Entry {
+                    path: join(old_path, &o.node.name),
+                    parent: new_path.to_string(),
+                    field: o,
+                }),
+            }
+        }
+        for (n, o) in new.children.iter().zip(paired) {
+            let path = join(new_path, &n.node.name);
+            let Some(o) = o else {
+                self.added.push(Entry { path, parent: new_path.to_string(), field: n });
+                continue;
+            };
+            if o.node.type_ != n.node.type_ {
+                self.changes.push(TypeTreeChange::Retyped {
+                    path: path.clone(),
+                    old_type: o.node.type_.clone(),
+                    new_type: n.node.type_.clone(),
+                });
+            }
+            self.compare(o, n, &join(old_path, &o.node.name), &path);
+        }
+    }
+}
+
+/// Compares two type trees of a class, such as the ones of two Unity versions.
+///
+/// Fields are paired by name within their parent. Unpaired fields are then matched up: one found
+/// with the same name, type and children under another parent, including inside an added or
+/// removed field, has moved, and one with the same type and children under another name in the
+/// same parent was renamed. The rest were added or removed. Retyped fields come first, then moved,
+/// renamed, removed and added ones.
+pub fn diff(old: &[TypeTreeNode], new: &[TypeTreeNode]) -> Vec<TypeTreeChange> {
+    let empty = TypeTreeNode::default();
+    let (old_root, new_root) = (Field::root(old, &empty), Field::root(new, &empty));
+    let mut differ = Differ::default();
+    differ.compare(&old_root, &new_root, "", "");
+    let Differ { mut changes, removed, added } = differ;
+
+    // Moves, where at least one side is a whole unpaired field rather than inside one.
+    let removed_all = descendants(&removed);
+    let added_all = descendants(&added);
+    let mut removed_taken = vec![false; removed.len()];
+    let mut added_taken = vec![false; added.len()];
+    let mut added_used = vec![false; added_all.len()];
+    for (r_top, r_path, r) in &removed_all {
+        if removed_taken[*r_top] {
+            continue;
+        }
+        let r_is_top = removed[*r_top].path == *r_path;
+        let Some(a) = (0..added_all.len()).find(|&i| {
+            let (a_top, a_path, a) = &added_all[i];
+            !added_used[i] && !added_taken[*a_top] && (r_is_top || added[*a_top].path == *a_path) && a.node.name == r.node.name && a.same_shape(r)
+        }) else {
+            continue;
+        };
+        added_used[a] = true;
+        let (a_top, a_path, _) = &added_all[a];
+        if r_is_top {
+            removed_taken[*r_top] = true;
+        }
+        if added[*a_top].path == *a_path {
+            added_taken[*a_top] = true;
+        }
+        changes.push(TypeTreeChange::Moved {
+            old_path: r_path.clone(),
+            new_path: a_path.clone(),
+            type_: r.node.type_.clone(),
+        });
+    }
+
+    for (r, taken) in removed.iter().zip(&mut removed_taken) {
+        if *taken {
+            continue;
+        }
+        let Some(a) = (0..added.len()).find(|&i| !added_taken[i] && added[i].parent == r.parent && added[i].field.same_shape(r.field)) else {
+            continue;
+        };
+        added_taken[a] = true;
+        *taken = true;
+        changes.push(TypeTreeChange::Renamed {
+            old_path: r.path.clone(),
+            new_path: added[a].path.clone(),
+            type_: r.field.node.type_.clone(),
+        });
+    }
+
+    for (r, _) in removed.iter().zip(removed_taken).filter(|x| !x.1) {
+        changes.push(TypeTreeChange::Removed {
+            path: r.path.clone(),
+            type_: r.field.node.type_.clone(),
+        });
+    }
+    for (a, _) in added.iter().zip(added_taken).filter(|x| !x.1) {
+        changes.push(TypeTreeChange::Added {
+            path: a.path.clone(),
+            type_: a.field.node.type_.clone(),
+        });
+    }
+    changes
+}
+
+/// Every entry's field and the fields below it, with the index of the entry and their path.
+fn descendants<'a>(entries: &[Entry<'a>]) -> Vec<(usize, String, &'a Field<'a>)> {
+    fn push<'a>(top: usize, path: String, field: &'a Field<'a>, result: &mut Vec<(usize, String, &'a Field<'a>)>) {
+        result.push((top, path.clone(), field));
+        for child in &field.children {
+            push(top, join(&path, &child.node.name), child, result);
+        }
+    }
+    let mut result = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        push(i, entry.path.clone(), entry.field, &mut result);
+    }
+    result
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
@@ -0,0 +1,171 @@
+use crate::reader::{ByteSource, Eof, Reader};
+
+use super::{TypeTreeNode, ALIGN_BYTES};
+
+/// How the deserializer reads a node.
+pub(crate) enum Kind {
+    Primitive(usize),
+    String,
+    TypelessData,
+    Map,
+    Array,
+    Struct,
+}
+
+impl Kind {
+    pub(crate) fn of(nodes: &[TypeTreeNode], index: usize) -> Self {
+        let type_ = nodes[index].type_.as_str();
+        if let Some(size) = primitive_size(type_) {
+            return Self::Primitive(size);
+        }
+        match type_ {
+            "string" => Self::String,
+            "TypelessData" => Self::TypelessData,
+            "map" => Self::Map,
+            _ if nodes.get(index + 1).is_some_and(|x| x.type_ == "Array") => Self::Array,
+            _ => Self::Struct,
+        }
+    }
+}
+
+/// The size of a built-in value type.
+pub(crate) fn primitive_size(type_: &str) -> Option<usize> {
+    let size = match type_ {
+        "bool" | "char" | "SInt8" | "UInt8" => 1,
+        "short" | "SInt16" | "UInt16" | "unsigned short" => 2,
+        "int" | "SInt32" | "UInt32" | "unsigned int" | "float" | "Type*" => 4,
+        "long long" | "SInt64" | "UInt64" | "unsigned long long" | "double" | "FileSize" => 8,
+        _ => return None,
+    };
+    Some(size)
+}
+
+pub(crate) fn children(nodes: &[TypeTreeNode], index: usize) -> impl Iterator<Item = usize> + '_ {
+    let level = nodes[index].level;
+    nodes[index + 1..].iter().take_while(move |x| x.level > level).enumerate().filter(move |(_, x)| x.level == level + 1).map(move |(i, _)| index + 1 + i)
+}
+
+/// Whether the reader aligns to 4 bytes after the node, which for vectors and maps is also set
+/// on their `Array` node.
+pub(crate) fn aligned(nodes: &[TypeTreeNode], index: usize) -> bool {
+    let array = matches!(Kind::of(nodes, index), Kind::Map | Kind::Array) && nodes.get(index + 1).is_some_and(|x| x.meta_flag & ALIGN_BYTES != 0);
+    nodes[index].meta_flag & ALIGN_BYTES != 0 || array
+}
+
+/// Where the node ends when it starts at `start`, if it has no strings, arrays or maps.
+fn fixed_end(nodes: &[TypeTreeNode], index: usize, start: usize) -> Option<usize> {
+    let end = match Kind::of(nodes, index) {
+        Kind::Primitive(size) => start + size,
+        Kind::Struct => {
+            let mut end = start;
+            for child in children(nodes, index) {
+                end = fixed_end(nodes, child, end)?;
+            }
+            end
+        }
+        _ => return None,
+    };
+    Some(if aligned(nodes, index) { end.next_multiple_of(4) } else { end })
+}
+
+/// The fewest bytes node `index` takes, as strings, arrays and maps take at least their length.
+/// Alignment padding is not counted.
+pub(crate) fn min_size(nodes: &[TypeTreeNode], index: usize) -> usize {
+    match Kind::of(nodes, index) {
+        Kind::Primitive(size) => size,
+        Kind::Struct => children(nodes, index).map(|x| min_size(nodes, x)).sum(),
+        _ => 4,
+    }
+}
+
+/// The serialized size of node `index` and its children when it is fixed, that is without
+/// strings, arrays or maps. Alignment padding is included, assuming the node starts 4 byte
+/// aligned.
+pub fn subtree_size(nodes: &[TypeTreeNode], index: usize) -> Option<usize> {
+    fixed_end(nodes, index, 0)
+}
+
+fn advance<'a, S: ByteSource<'a>>(reader: &mut Reader<'a, S>, length: usize) -> Result<(), Eof> {
+    let end = reader.has_space(length)?;
+    reader.set_offset(end)?;
+    Ok(())
+}
+
+fn read_length<'a, S: ByteSource<'a>>(reader: &mut Reader<'a, S>) -> Result<usize, Eof> {
+    usize::try_from(reader.read_i32()?).map_err(|_| Eof)
+}
+
+/// Reads the length of an array or map whose elements are node `element`, failing unless the
+/// elements fit in what is left, each taking at least a byte, and the element nests deeper than
+/// node `index`.
+fn read_count<'a, S: ByteSource<'a>>(reader: &mut Reader<'a, S>, nodes: &[TypeTreeNode], index: usize, element: usize) -> Result<usize, Eof> {
+    let length = read_length(reader)?;
+    if !nodes.get(element).is_some_and(|x| x.level > nodes[index].level) {
+        return Err(Eof);
+    }
+    let remaining = reader.len() - reader.get_offset();
+    if length.saturating_mul(min_size(nodes, element).max(1)) > remaining {
+        return Err(Eof);
+    }
+    Ok(length)
+}
+
+/// Moves `reader` past node `index` and its children, reading only the lengths of strings,
+/// arrays and maps. Arrays of fixed size elements are skipped at once.
+pub fn skip_subtree<'a, S: ByteSource<'a>>(reader: &mut Reader<'a, S>, nodes: &[TypeTreeNode], index: usize) -> Result<(), Eof> {
+    if index >= nodes.len() {
+        return Err(Eof);
+    }
+    if let Some(end) = fixed_end(nodes, index, reader.get_offset()) {
+        return advance(reader, end - reader.get_offset());
+    }
+    match Kind::of(nodes, index) {
+        Kind::String => {
+            let length = read_length(reader)?;
+            advance(reader, length)?;
+            reader.align(4)?;
+        }
+        Kind::TypelessData => {
+            let length = read_length(reader)?;
+            advance(reader, length)?;
+        }
+        Kind::Array => {
+            let element = index + 3;
+            let length = read_count(reader, nodes, index, element)?;
+            // Elements without aligned parts take the same space wherever they start.
+            match (fixed_end(nodes, element, 0), fixed_end(nodes, element, 1)) {
+                (Some(size), Some(end)) if end == size + 1 => advance(reader, size.checked_mul(length).ok_or(Eof)?)?,
+                _ => {
+                    for _ in 0..length {
+                        skip_subtree(reader, nodes, element)?;
+                    }
+                }
+            }
+        }
+        Kind::Map => {
+            let pair = index + 3;
+            let length = read_count(reader, nodes, index, pair)?;
+            let mut fields = children(nodes, pair);
+            let (Some(key), Some(value)) = (fields.next(), fields.next()) else {
+                return Err(Eof);
+            };
+            for _ in 0..length {
+                skip_subtree(reader, nodes, key)?;
+                skip_subtree(reader, nodes, value)?;
+                if aligned(nodes, pair) {
+                    reader.align(4)?;
+                }
+            }
+        }
+        Kind::Struct => {
+            for child in children(nodes, index) {
+                skip_subtree(reader, nodes, child)?;
+            }
+        }
+        Kind::Primitive(_) => unreachable!(),
+    }
+    if aligned(nodes, index) {
+        reader.align(4)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,522 @@
+use crate::classes::ClassID;
+use crate::version::{ReleaseType, UnityVersion};
+
+use super::{TypeTreeBuilder, TypeTreeNode};
+
+const XY: &[&str] = &["x", "y"];
+const XYZ: &[&str] = &["x", "y", "z"];
+const XYZW: &[&str] = &["x", "y", "z", "w"];
+const RECT: &[&str] = &["x", "y", "width", "height"];
+const RGBA: &[&str] = &["r", "g", "b", "a"];
+
+fn floats(b: TypeTreeBuilder, type_: &str, name: &str, fields: &[&str]) -> TypeTreeBuilder {
+    b.nested(type_, name, |b| fields.iter().fold(b, |b, x| b.field("float", x)))
+}
+
+/// Small fields followed by an alignment, which only the last of them carries.
+fn packed_then_aligned(b: TypeTreeBuilder, type_: &str, names: &[&str]) -> TypeTreeBuilder {
+    match names.split_last() {
+        Some((last, packed)) => packed.iter().fold(b, |b, x| b.packed(type_, x)).field(type_, last),
+        None => b,
+    }
+}
+
+fn aabb(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.nested("AABB", name, |b| floats(floats(b, "Vector3f", "m_Center", XYZ), "Vector3f", "m_Extent", XYZ))
+}
+
+fn matrix(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.nested("Matrix4x4f", name, |b| (0..16).fold(b, |b, i| b.field("float", &format!("e{}{}", i / 4, i % 4))))
+}
+
+fn streaming_info(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let offset = if version.major >= 2020 { "UInt64" } else { "unsigned int" };
+    b.nested("StreamingInfo", "m_StreamData", |b| b.field(offset, "offset").field("unsigned int", "size").string("path"))
+}
+
+/// The sub-meshes of Unity 5 and later.
+fn sub_meshes(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    b.array("vector", "m_SubMeshes", |b| {
+        b.nested("SubMesh", "data", |b| {
+            let b = b.field("unsigned int", "firstByte").field("unsigned int", "indexCount").field("int", "topology");
+            let b = if version.is_at_least(2017, 3) { b.field("unsigned int", "baseVertex") } else { b };
+            aabb(b.field("unsigned int", "firstVertex").field("unsigned int", "vertexCount"), "localAABB")
+        })
+    })
+}
+
+/// The vertex data of Unity 5 and later, whose streams follow from the channels.
+fn vertex_data(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    b.nested("VertexData", "m_VertexData", |b| {
+        let b = if version.major < 2018 { b.field("unsigned int", "m_CurrentChannels") } else { b };
+        b.field("unsigned int", "m_VertexCount")
+            .array("vector", "m_Channels", |b| {
+                b.nested("ChannelInfo", "data", |b| ["stream", "offset", "format", "dimension"].iter().fold(b, |b, x| b.packed("UInt8", x)))
+            })
+            .typeless_data("m_DataSize")
+    })
+}
+
+fn packed_float_vector(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.nested("PackedBitVector", name, |b| {
+        b.field("unsigned int", "m_NumItems")
+            .field("float", "m_Range")
+            .field("float", "m_Start")
+            .array("vector", "m_Data", |b| b.field("UInt8", "data"))
+            .field("UInt8", "m_BitSize")
+    })
+}
+
+fn packed_int_vector(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.nested("PackedBitVector", name, |b| b.field("unsigned int", "m_NumItems").array("vector", "m_Data", |b| b.field("UInt8", "data")).field("UInt8", "m_BitSize"))
+}
+
+fn texture2d(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let mut b = b.string("m_Name");
+    if version.is_at_least(2017, 3) {
+        b = b.field("int", "m_ForcedFallbackFormat");
+        let flags: &[&str] = if version.is_at_least(2020, 2) { &["m_DownscaleFallback", "m_IsAlphaChannelOptional"] } else { &["m_DownscaleFallback"] };
+        b = packed_then_aligned(b, "bool", flags);
+    }
+    b = b.field("int", "m_Width").field("int", "m_Height").field("int", "m_CompleteImageSize");
+    if version.major >= 2020 {
+        b = b.field("int", "m_MipsStripped");
+    }
+    b = b.field("int", "m_TextureFormat");
+
+    // The flags that share the aligned block after the format.
+    let mut flags = Vec::new();
+    if version.is_at_least(5, 2) {
+        b = b.field("int", "m_MipCount");
+    } else {
+        flags.push("m_MipMap");
+    }
+    if version.is_at_least(2, 6) {
+        flags.push("m_IsReadable");
+    }
+    if version.major >= 2020 {
+        flags.push("m_IsPreProcessed");
+    }
+    if version.is_at_least(2019, 3) {
+        flags.push("m_IgnoreMasterTextureLimit");
+    }
+    if version.is_at_least(2022, 2) {
+        b = packed_then_aligned(b, "bool", &flags).string("m_MipmapLimitGroupName");
+        flags.clear();
+    }
+    if version.is_at_least(3, 0) && !version.is_at_least(5, 5) {
+        flags.push("m_ReadAllowed");
+    }
+    if version.is_at_least(2018, 2) {
+        flags.push("m_StreamingMipmaps");
+    }
+    b = packed_then_aligned(b, "bool", &flags);
+    if version.is_at_least(2018, 2) {
+        b = b.field("int", "m_StreamingMipmapsPriority");
+    }
+
+    b = b.field("int", "m_ImageCount").field("int", "m_TextureDimension");
+    b = b.nested("GLTextureSettings", "m_TextureSettings", |b| {
+        let b = b.field("int", "m_FilterMode").field("int", "m_Aniso").field("float", "m_MipBias");
+        if version.major >= 2017 {
+            b.field("int", "m_WrapU").field("int", "m_WrapV").field("int", "m_WrapW")
+        } else {
+            b.field("int", "m_WrapMode")
+        }
+    });
+    if version.major >= 3 {
+        b = b.field("int", "m_LightmapFormat");
+    }
+    if version.is_at_least(3, 5) {
+        b = b.field("int", "m_ColorSpace");
+    }
+    if version.is_at_least(2020, 2) {
+        b = b.array("vector", "m_PlatformBlob", |b| b.field("UInt8", "data"));
+    }
+    b = b.typeless_data("image data");
+    if version.is_at_least(5, 3) {
+        b = streaming_info(b, version);
+    }
+    b
+}
+
+fn sprite_render_data(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let mut b = b.pptr("Texture2D", "texture");
+    if version.is_at_least(5, 2) {
+        b = b.pptr("Texture2D", "alphaTexture");
+    }
+    if version.major >= 2019 {
+        b = b.array("vector", "secondaryTextures", |b| b.nested("SecondarySpriteTexture", "data", |b| b.pptr("Texture2D", "texture").string("name")));
+    }
+    if version.is_at_least(5, 6) {
+        b = sub_meshes(b, version).array("vector", "m_IndexBuffer", |b| b.field("UInt8", "data"));
+        b = vertex_data(b, version);
+    } else {
+        b = b.array("vector", "vertices", |b| {
+            b.nested("SpriteVertex", "data", |b| {
+                let b = floats(b, "Vector3f", "pos", XYZ);
+                if version.is_at_least(4, 4) {
+                    b
+                } else {
+                    floats(b, "Vector2f", "uv", XY)
+                }
+            })
+        });
+        b = b.array("vector", "indices", |b| b.field("UInt16", "data"));
+    }
+    if version.major >= 2018 {
+        b = b.array("vector", "m_Bindpose", |b| matrix(b, "data"));
+        if version.major == 2018 && version.minor < 2 {
+            b = b.array("vector", "m_SourceSkin", |b| {
+                b.nested("BoneWeights4", "data", |b| {
+                    let b = (0..4).fold(b, |b, i| b.field("float", &format!("weight[{}]", i)));
+                    (0..4).fold(b, |b, i| b.field("int", &format!("boneIndex[{}]", i)))
+                })
+            });
+        }
+    }
+    b = floats(b, "Rectf", "textureRect", RECT);
+    b = floats(b, "Vector2f", "textureRectOffset", XY);
+    if version.is_at_least(5, 6) {
+        b = floats(b, "Vector2f", "atlasRectOffset", XY);
+    }
+    b = b.field("unsigned int", "settingsRaw");
+    if version.is_at_least(4, 5) {
+        b = floats(b, "Vector4f", "uvTransform", XYZW);
+    }
+    if version.major >= 2017 {
+        b = b.field("float", "downscaleMultiplier");
+    }
+    b
+}
+
+fn sprite(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let mut b = floats(floats(b.string("m_Name"), "Rectf", "m_Rect", RECT), "Vector2f", "m_Offset", XY);
+    if version.is_at_least(4, 5) {
+        b = floats(b, "Vector4f", "m_Border", XYZW);
+    }
+    b = b.field("float", "m_PixelsToUnits");
+    if version >= UnityVersion::new(5, 4, 1, ReleaseType::Patch, 3) {
+        b = floats(b, "Vector2f", "m_Pivot", XY);
+    }
+    b = b.field("unsigned int", "m_Extrude");
+    if version.is_at_least(5, 3) {
+        b = b.field("bool", "m_IsPolygon");
+    }
+    if version.major >= 2017 {
+        b = b.pair("m_RenderDataKey", |b| {
+            b.nested("GUID", "first", |b| (0..4).fold(b, |b, i| b.field("unsigned int", &format!("data[{}]", i)))).field("SInt64", "second")
+        });
+        b = b.array("vector", "m_AtlasTags", |b| b.string("data")).pptr("SpriteAtlas", "m_SpriteAtlas");
+    }
+    b.nested("SpriteRenderData", "m_RD", |b| sprite_render_data(b, version))
+}
+
+/// The layout of Unity 2018.3 and later, the first to stream vertex data.
+fn mesh(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let mut b = sub_meshes(b.string("m_Name"), version);
+    b = b.nested("BlendShapeData", "m_Shapes", |b| {
+        b.array("vector", "vertices", |b| {
+            b.nested("BlendShapeVertex", "data", |b| {
+                floats(floats(floats(b, "Vector3f", "vertex", XYZ), "Vector3f", "normal", XYZ), "Vector3f", "tangent", XYZ).field("unsigned int", "index")
+            })
+        })
+        .array("vector", "shapes", |b| {
+            b.nested("MeshBlendShape", "data", |b| {
+                b.field("unsigned int", "firstVertex").field("unsigned int", "vertexCount").packed("bool", "hasNormals").field("bool", "hasTangents")
+            })
+        })
+        .array("vector", "channels", |b| {
+            b.nested("MeshBlendShapeChannel", "data", |b| b.string("name").field("unsigned int", "nameHash").field("int", "frameIndex").field("int", "frameCount"))
+        })
+        .array("vector", "fullWeights", |b| b.field("float", "data"))
+    });
+    b = b.array("vector", "m_BindPose", |b| matrix(b, "data"));
+    b = b.array("vector", "m_BoneNameHashes", |b| b.field("unsigned int", "data")).field("unsigned int", "m_RootBoneNameHash");
+    if version.major >= 2019 {
+        b = b.array("vector", "m_BonesAABB", |b| b.nested("MinMaxAABB", "data", |b| floats(floats(b, "Vector3f", "m_Min", XYZ), "Vector3f", "m_Max", XYZ)));
+        b = b.nested("VariableBoneCountWeights", "m_VariableBoneCountWeights", |b| b.array("vector", "m_Data", |b| b.field("unsigned int", "data")));
+    }
+    b = packed_then_aligned(b.packed("UInt8", "m_MeshCompression"), "bool", &["m_IsReadable", "m_KeepVertices", "m_KeepIndices"]);
+    b = b.field("int", "m_IndexFormat").array("vector", "m_IndexBuffer", |b| b.field("UInt8", "data"));
+    b = vertex_data(b, version);
+    b = b.nested("CompressedMesh", "m_CompressedMesh", |b| {
+        let b = ["m_Vertices", "m_UV", "m_Normals", "m_Tangents"].iter().fold(b, |b, x| packed_float_vector(b, x));
+        let b = ["m_Weights", "m_NormalSigns", "m_TangentSigns"].iter().fold(b, |b, x| packed_int_vector(b, x));
+        let b = packed_float_vector(b, "m_FloatColors");
+        let b = ["m_BoneIndices", "m_Triangles"].iter().fold(b, |b, x| packed_int_vector(b, x));
+        b.field("unsigned int", "m_UVInfo")
+    });
+    b = aabb(b, "m_LocalAABB").field("int", "m_MeshUsageFlags");
+    if version.is_at_least(2022, 1) {
+        b = b.field("int", "m_CookingOptions");
+    }
+    b = b.array("vector", "m_BakedConvexCollisionMesh", |b| b.field("UInt8", "data"));
+    b = b.array("vector", "m_BakedTriangleCollisionMesh", |b| b.field("UInt8", "data"));
+    b = b.field("float", "m_MeshMetrics[0]").field("float", "m_MeshMetrics[1]");
+    streaming_info(b, version)
+}
+
+fn bit_field(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.nested("BitField", name, |b| b.field("unsigned int", "m_Bits"))
+}
+
+/// The layout of Unity 2017 and 2018 up to the target display, with the physical camera of 2018.2.
+fn camera(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let mut b = floats(b.pptr("GameObject", "m_GameObject").field("UInt8", "m_Enabled").field("unsigned int", "m_ClearFlags"), "ColorRGBA", "m_BackGroundColor", RGBA);
+    if version.is_at_least(2018, 2) {
+        b = b.field("int", "m_projectionMatrixMode");
+        if version.is_at_least(2018, 3) {
+            b = b.field("int", "m_GateFitMode");
+        }
+        b = floats(floats(b, "Vector2f", "m_SensorSize", XY), "Vector2f", "m_LensShift", XY).field("float", "m_FocalLength");
+    }
+    b = floats(b, "Rectf", "m_NormalizedViewPortRect", RECT);
+    b = ["near clip plane", "far clip plane", "field of view"].iter().fold(b, |b, x| b.field("float", x));
+    b = b.field("bool", "orthographic").field("float", "orthographic size").field("float", "m_Depth");
+    bit_field(b, "m_CullingMask").field("int", "m_RenderingPath").pptr("RenderTexture", "m_TargetTexture").field("int", "m_TargetDisplay")
+}
+
+/// The layout of Unity 2017 and 2018 up to the culling mask. The baking fields after it moved
+/// between versions and are left out.
+fn light(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    let b = floats(b.pptr("GameObject", "m_GameObject").field("UInt8", "m_Enabled").field("int", "m_Type"), "ColorRGBA", "m_Color", RGBA);
+    let b = ["m_Intensity", "m_Range", "m_SpotAngle", "m_CookieSize"].iter().fold(b, |b, x| b.field("float", x));
+    let b = b.nested("ShadowSettings", "m_Shadows", |b| {
+        let b = b.field("int", "m_Type").field("int", "m_Resolution").field("int", "m_CustomResolution");
+        ["m_Strength", "m_Bias", "m_NormalBias", "m_NearPlane"].iter().fold(b, |b, x| b.field("float", x))
+    });
+    let b = b.pptr("Texture", "m_Cookie").field("bool", "m_DrawHalo").pptr("Flare", "m_Flare").field("int", "m_RenderMode");
+    bit_field(b, "m_CullingMask")
+}
+
+/// The playback settings of Unity 2017.2 to 2017.4, which come before the modules. The modules
+/// are left out, so they read as `None`.
+fn particle_system(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    let b = b.pptr("GameObject", "m_GameObject").field("float", "lengthInSec").field("float", "simulationSpeed").field("int", "stopAction");
+    packed_then_aligned(b, "bool", &["looping", "prewarm", "playOnAwake"])
+}
+
+/// An `OffsetPtr` to a `type_`, which Mecanim wraps around the constants it nests.
+fn offset_ptr(b: TypeTreeBuilder, type_: &str, name: &str, fields: impl FnOnce(TypeTreeBuilder) -> TypeTreeBuilder) -> TypeTreeBuilder {
+    b.nested("OffsetPtr", name, |b| b.nested(type_, "data", fields))
+}
+
+fn vector(b: TypeTreeBuilder, type_: &str, name: &str) -> TypeTreeBuilder {
+    b.array("vector", name, |b| b.field(type_, "data"))
+}
+
+/// The `m_TOS` table of Mecanim classes, from path or name hashes to the strings.
+fn tos(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    b.array("map", "m_TOS", |b| b.pair("data", |b| b.field("unsigned int", "first").string("second")))
+}
+
+fn condition_constants(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    b.array("vector", "m_ConditionConstantArray", |b| {
+        offset_ptr(b, "ConditionConstant", "data", |b| {
+            b.field("unsigned int", "m_ConditionMode").field("unsigned int", "m_EventID").field("float", "m_EventThreshold").field("float", "m_ExitTime")
+        })
+    })
+}
+
+fn transition_constants(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.array("vector", name, |b| {
+        offset_ptr(b, "TransitionConstant", "data", |b| {
+            let b = ["m_DestinationState", "m_FullPathID", "m_ID", "m_UserID"].iter().fold(condition_constants(b), |b, x| b.field("unsigned int", x));
+            let b = ["m_TransitionDuration", "m_TransitionOffset", "m_ExitTime"].iter().fold(b, |b, x| b.field("float", x));
+            let b = packed_then_aligned(b, "bool", &["m_HasExitTime", "m_HasFixedDuration"]).field("int", "m_InterruptionSource");
+            packed_then_aligned(b, "bool", &["m_OrderedInterruption", "m_CanTransitionToSelf"])
+        })
+    })
+}
+
+fn blend_tree_node(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    let b = ["m_BlendType", "m_BlendEventID", "m_BlendEventYID"].iter().fold(b, |b, x| b.field("unsigned int", x));
+    let b = offset_ptr(vector(b, "unsigned int", "m_ChildIndices"), "Blend1dDataConstant", "m_Blend1dData", |b| vector(b, "float", "m_ChildThresholdArray"));
+    let b = offset_ptr(b, "Blend2dDataConstant", "m_Blend2dData", |b| {
+        let b = vector(b.array("vector", "m_ChildPositionArray", |b| floats(b, "Vector2f", "data", XY)), "float", "m_ChildMagnitudeArray");
+        let b = vector(b.array("vector", "m_ChildPairVectorArray", |b| floats(b, "Vector2f", "data", XY)), "float", "m_ChildPairAvgMagInvArray");
+        b.array("vector", "m_ChildNeighborListArray", |b| b.nested("MotionNeighborList", "data", |b| vector(b, "unsigned int", "m_NeighborArray")))
+    });
+    let b = offset_ptr(b, "BlendDirectDataConstant", "m_BlendDirectData", |b| vector(b, "unsigned int", "m_ChildBlendEventIDArray").field("bool", "m_NormalizedBlendValues"));
+    b.field("unsigned int", "m_ClipID").field("float", "m_Duration").field("float", "m_CycleOffset").field("bool", "m_Mirror")
+}
+
+fn state(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let b = vector(transition_constants(b, "m_TransitionConstantArray"), "int", "m_BlendTreeConstantIndexArray");
+    let b = b.array("vector", "m_BlendTreeConstantArray", |b| {
+        offset_ptr(b, "BlendTreeConstant", "data", |b| b.array("vector", "m_NodeArray", |b| offset_ptr(b, "BlendTreeNodeConstant", "data", blend_tree_node)))
+    });
+    let mut ids = vec!["m_NameID", "m_PathID", "m_FullPathID", "m_TagID", "m_SpeedParamID", "m_MirrorParamID", "m_CycleOffsetParamID"];
+    if version.is_at_least(2017, 2) {
+        ids.push("m_TimeParamID");
+    }
+    let b = ids.iter().fold(b, |b, x| b.field("unsigned int", x)).field("float", "m_Speed").field("float", "m_CycleOffset");
+    packed_then_aligned(b, "bool", &["m_IKOnFeet", "m_WriteDefaultValues", "m_Loop", "m_Mirror"])
+}
+
+fn state_machine(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let b = b.array("vector", "m_StateConstantArray", |b| offset_ptr(b, "StateConstant", "data", |b| state(b, version)));
+    let b = transition_constants(b, "m_AnyStateTransitionConstantArray").array("vector", "m_SelectorStateConstantArray", |b| {
+        offset_ptr(b, "SelectorStateConstant", "data", |b| {
+            let b = b.array("vector", "m_TransitionConstantArray", |b| {
+                offset_ptr(b, "SelectorTransitionConstant", "data", |b| condition_constants(b.field("unsigned int", "m_Destination")))
+            });
+            b.field("unsigned int", "m_FullPathID").field("bool", "m_isEntry")
+        })
+    });
+    b.field("unsigned int", "m_DefaultState").field("unsigned int", "m_MotionSetCount")
+}
+
+fn layer(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    let b = b.field("unsigned int", "m_StateMachineIndex").field("unsigned int", "m_StateMachineMotionSetIndex");
+    let b = b.nested("HumanPoseMask", "m_BodyMask", |b| ["word0", "word1", "word2"].iter().fold(b, |b, x| b.field("unsigned int", x)));
+    let b = offset_ptr(b, "SkeletonMask", "m_SkeletonMask", |b| {
+        b.array("vector", "m_Data", |b| b.nested("SkeletonMaskElement", "data", |b| b.field("unsigned int", "m_PathHash").field("float", "m_Weight")))
+    });
+    let b = b.field("unsigned int", "m_Binding").field("int", "m_LayerBlendingMode").field("float", "m_DefaultWeight");
+    packed_then_aligned(b, "bool", &["m_IKPass", "m_SyncedLayerAffectsTiming"])
+}
+
+/// The layout of Unity 5.5 and later, whose value arrays are no longer tagged with a type id, up
+/// to the clips.
+fn animator_controller(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let b = b.string("m_Name").field("unsigned int", "m_ControllerSize");
+    let b = b.nested("ControllerConstant", "m_Controller", |b| {
+        let b = b.array("vector", "m_LayerArray", |b| offset_ptr(b, "LayerConstant", "data", layer));
+        let b = b.array("vector", "m_StateMachineArray", |b| offset_ptr(b, "StateMachineConstant", "data", |b| state_machine(b, version)));
+        let b = offset_ptr(b, "ValueArrayConstant", "m_Values", |b| {
+            b.array("vector", "m_ValueArray", |b| b.nested("ValueConstant", "data", |b| ["m_ID", "m_Type", "m_Index"].iter().fold(b, |b, x| b.field("unsigned int", x))))
+        });
+        offset_ptr(b, "ValueArray", "m_DefaultValues", |b| {
+            let b = b.array("vector", "m_PositionValues", |b| floats(b, "float3", "data", XYZ));
+            let b = b.array("vector", "m_QuaternionValues", |b| floats(b, "float4", "data", XYZW));
+            let b = b.array("vector", "m_ScaleValues", |b| floats(b, "float3", "data", XYZ));
+            vector(vector(vector(b, "float", "m_FloatValues"), "int", "m_IntValues"), "bool", "m_BoolValues")
+        })
+    });
+    tos(b).array("vector", "m_AnimationClips", |b| b.pptr("AnimationClip", "data"))
+}
+
+fn xform(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    b.nested("xform", name, |b| floats(floats(floats(b, "float3", "t", XYZ), "float4", "q", XYZW), "float3", "s", XYZ))
+}
+
+fn skeleton(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    offset_ptr(b, "Skeleton", name, |b| {
+        let b = b.array("vector", "m_Node", |b| b.nested("Node", "data", |b| b.field("int", "m_ParentId").field("int", "m_AxesId")));
+        vector(b, "unsigned int", "m_ID").array("vector", "m_AxesArray", |b| {
+            b.nested("Axes", "data", |b| {
+                let b = floats(floats(floats(b, "float4", "m_PreQ", XYZW), "float4", "m_PostQ", XYZW), "float3", "m_Sgn", XYZ);
+                let b = b.nested("Limit", "m_Limit", |b| floats(floats(b, "float3", "m_Min", XYZ), "float3", "m_Max", XYZ));
+                b.field("float", "m_Length").field("unsigned int", "m_Type")
+            })
+        })
+    })
+}
+
+fn skeleton_pose(b: TypeTreeBuilder, name: &str) -> TypeTreeBuilder {
+    offset_ptr(b, "SkeletonPose", name, |b| b.array("vector", "m_X", |b| xform(b, "data")))
+}
+
+fn human(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let b = skeleton_pose(skeleton(xform(b, "m_RootX"), "m_Skeleton"), "m_SkeletonPose");
+    let mut b = ["m_LeftHand", "m_RightHand"].iter().fold(b, |b, x| offset_ptr(b, "Hand", x, |b| vector(b, "int", "m_HandBoneIndex")));
+    // Handles and colliders were dropped in 2018.2.
+    let colliders = !version.is_at_least(2018, 2);
+    if colliders {
+        b = b.array("vector", "m_Handles", |b| b.nested("Handle", "data", |b| xform(b, "m_X").field("unsigned int", "m_ParentHumanIndex").field("unsigned int", "m_ID")));
+        b = b.array("vector", "m_ColliderArray", |b| {
+            b.nested("Collider", "data", |b| {
+                let b = ["m_Type", "m_XMotionType", "m_YMotionType", "m_ZMotionType"].iter().fold(xform(b, "m_X"), |b, x| b.field("unsigned int", x));
+                ["m_MinLimitX", "m_MaxLimitX", "m_MaxLimitY", "m_MaxLimitZ"].iter().fold(b, |b, x| b.field("float", x))
+            })
+        });
+    }
+    b = vector(vector(b, "int", "m_HumanBoneIndex"), "float", "m_HumanBoneMass");
+    if colliders {
+        b = vector(b, "int", "m_ColliderIndex");
+    }
+    let stretch = ["m_Scale", "m_ArmTwist", "m_ForeArmTwist", "m_UpperLegTwist", "m_LegTwist", "m_ArmStretch", "m_LegStretch", "m_FeetSpacing"];
+    packed_then_aligned(stretch.iter().fold(b, |b, x| b.field("float", x)), "bool", &["m_HasLeftHand", "m_HasRightHand", "m_HasTDoF"])
+}
+
+/// The layout of Unity 5.4 and later, whose transforms hold three floats rather than four, up to
+/// the `m_TOS` table.
+fn avatar(b: TypeTreeBuilder, version: UnityVersion) -> TypeTreeBuilder {
+    let b = b.string("m_Name").field("unsigned int", "m_AvatarSize");
+    let b = b.nested("AvatarConstant", "m_Avatar", |b| {
+        let b = skeleton_pose(skeleton_pose(skeleton(b, "m_AvatarSkeleton"), "m_AvatarSkeletonPose"), "m_DefaultPose");
+        let b = offset_ptr(vector(b, "unsigned int", "m_SkeletonNameIDArray"), "Human", "m_Human", |b| human(b, version));
+        let b = vector(vector(b, "int", "m_HumanSkeletonIndexArray"), "int", "m_HumanSkeletonReverseIndexArray");
+        let b = xform(b.field("int", "m_RootMotionBoneIndex"), "m_RootMotionBoneX");
+        vector(skeleton_pose(skeleton(b, "m_RootMotionSkeleton"), "m_RootMotionSkeletonPose"), "int", "m_RootMotionSkeletonIndexArray")
+    });
+    tos(b)
+}
+
+/// A type tree for a built-in class whose layout is known, for files built with stripped type trees.
+///
+/// `version` is the serialized file's Unity version and `asset_version` its format version. The
+/// covered classes are `TextAsset`, `GameObject`, `Transform`, `MonoScript`, `Texture2D` and
+/// `Sprite` for the versions their readers handle, `Mesh` from Unity 2018.3 on, `Camera` and
+/// `Light` in Unity 2017 and 2018, `ParticleSystem` from 2017.2 to 2017.4, `Avatar` from 5.4 on and
+/// `AnimatorController` from 5.5 on.
+///
+/// `Canvas`, `BuildSettings` and `PlayerSettings` have binary readers of their own. `TerrainData`
+/// and other classes have no template yet, nor do the UI scripts, whose trees come from
+/// [`Env::typetree_provider`](crate::Env::typetree_provider).
+pub fn builtin_nodes(class: ClassID, version: UnityVersion, asset_version: u32) -> Option<Vec<TypeTreeNode>> {
+    let b = TypeTreeBuilder::new(&format!("{:?}", class)).with_asset_version(asset_version);
+    let b = match class {
+        ClassID::TextAsset => b.string("m_Name").string("m_Script"),
+        ClassID::GameObject => {
+            let b = b.array("vector", "m_Component", |b| {
+                if version.is_at_least(5, 5) {
+                    b.nested("ComponentPair", "data", |b| b.pptr("Component", "component"))
+                } else {
+                    b.pair("data", |b| b.field("int", "first").pptr("Component", "second"))
+                }
+            });
+            b.field("unsigned int", "m_Layer").string("m_Name").packed("UInt16", "m_Tag").field("bool", "m_IsActive")
+        }
+        ClassID::Transform => {
+            let b = floats(b.pptr("GameObject", "m_GameObject"), "Quaternionf", "m_LocalRotation", XYZW);
+            let b = floats(floats(b, "Vector3f", "m_LocalPosition", XYZ), "Vector3f", "m_LocalScale", XYZ);
+            b.array("vector", "m_Children", |b| b.pptr("Transform", "data")).pptr("Transform", "m_Father")
+        }
+        ClassID::MonoScript => {
+            let mut b = b.string("m_Name");
+            if version.is_at_least(3, 4) {
+                b = b.field("int", "m_ExecutionOrder");
+            }
+            if version.major >= 5 {
+                b = b.nested("Hash128", "m_PropertiesHash", |b| (0..16).fold(b, |b, i| b.packed("UInt8", &format!("bytes[{}]", i))));
+            } else {
+                b = b.field("UInt32", "m_PropertiesHash");
+            }
+            if version.major < 3 {
+                b = b.string("m_PathName");
+            }
+            b = b.string("m_ClassName");
+            if version.major >= 3 {
+                b = b.string("m_Namespace");
+            }
+            b = b.string("m_AssemblyName");
+            if !version.is_at_least(2018, 2) {
+                b = b.field("bool", "m_IsEditorScript");
+            }
+            b
+        }
+        ClassID::Texture2D => texture2d(b, version),
+        ClassID::Sprite => sprite(b, version),
+        ClassID::Mesh if version.is_at_least(2018, 3) => mesh(b, version),
+        ClassID::Camera if matches!(version.major, 2017 | 2018) => camera(b, version),
+        ClassID::Light if matches!(version.major, 2017 | 2018) => light(b),
+        ClassID::ParticleSystem if version.major == 2017 && version.minor >= 2 => particle_system(b),
+        ClassID::AnimatorController if version.is_at_least(5, 5) => animator_controller(b, version),
+        ClassID::Avatar if version.is_at_least(5, 4) => avatar(b, version),
+        _ => return None,
+    };
+    Some(b.build())
+}
@@ -0,0 +1,125 @@
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes128;
+
+use crate::error::{UnityError, UnityResult};
+use crate::reader::Reader;
+
+const SIGNATURE: &[u8; 16] = b"#$unity3dchina!@";
+
+/// The UnityCN encryption of Unity's China builds: an info block after the bundle header and
+/// LZ4 blocks whose control bytes are substituted, with tables derived from a per game key.
+#[derive(Clone)]
+pub(crate) struct UnityCN {
+    index: [u8; 16],
+    sub: [u8; 16],
+}
+
+impl UnityCN {
+    pub(crate) fn read(r: &mut Reader, key: &[u8; 16]) -> UnityResult<Self> {
+        let cipher = Aes128::new(GenericArray::from_slice(key));
+        let _flags = r.read_u32()?;
+        let mut info_bytes = r.read_u8_array::<16>()?;
+        let info_key = r.read_u8_array::<16>()?;
+        r.read_u8()?;
+        let mut signature_bytes = r.read_u8_array::<16>()?;
+        let signature_key = r.read_u8_array::<16>()?;
+        r.read_u8()?;
+
+        decrypt_key(&cipher, signature_key, &mut signature_bytes);
+        if &signature_bytes != SIGNATURE {
+            return Err(UnityError::CustomError(format!("UnityCN signature mismatch, the key is likely wrong: {:?}", String::from_utf8_lossy(&signature_bytes))));
+        }
+        decrypt_key(&cipher, info_key, &mut info_bytes);
+
+        let nibbles: Vec<u8> = info_bytes.iter().flat_map(|x| [x >> 4, x & 0xf]).collect();
+        let mut index = [0; 16];
+        index.copy_from_slice(&nibbles[..16]);
+        let mut sub = [0; 16];
+        for (i, x) in nibbles[16..].iter().enumerate() {
+            sub[i % 4 * 4 + i / 4] = *x;
+        }
+        Ok(Self { index, sub })
+    }
+
+    /// Decrypts the data of block `index` in place, before it is decompressed.
+    pub(crate) fn decrypt_block(&self, data: &mut [u8], mut index: usize) {
+        let mut offset = 0;
+        while offset < data.len() {
+            offset += self.decrypt_sequence(&mut data[offset..], index);
+            index += 1;
+        }
+    }
+
+    /// Decrypts the token, length and offset bytes of one LZ4 sequence, returning its length.
+    fn decrypt_sequence(&self, data: &mut [u8], mut index: usize) -> usize {
+        let mut offset = 0;
+        let Some(token) = self.decrypt_byte(data, &mut offset, &mut index) else {
+            return data.len();
+        };
+        let mut literal_len = (token >> 4) as usize;
+        if literal_len == 0xf {
+            loop {
+                let Some(x) = self.decrypt_byte(data, &mut offset, &mut index) else {
+                    return data.len();
+                };
+                literal_len += x as usize;
+                if x != 0xff {
+                    break;
+                }
+            }
+        }
+        offset += literal_len;
+
+        if offset < data.len() {
+            self.decrypt_byte(data, &mut offset, &mut index);
+            self.decrypt_byte(data, &mut offset, &mut index);
+            if token & 0xf == 0xf {
+                while let Some(0xff) = self.decrypt_byte(data, &mut offset, &mut index) {}
+            }
+        }
+        offset
+    }
+
+    fn decrypt_byte(&self, data: &mut [u8], offset: &mut usize, index: &mut usize) -> Option<u8> {
+        let x = *data.get(*offset)?;
+        let i = *index;
+        let sub = |n: usize| self.sub[n] as i32;
+        let b = sub(((i >> 2) & 3) + 4) + sub(i & 3) + sub(((i >> 4) & 3) + 8) + sub((i as u8 >> 6) as usize + 12);
+        let low = (self.index[(x & 0xf) as usize] as i32 - b) & 0xf;
+        let high = 0x10 * (self.index[(x >> 4) as usize] as i32 - b);
+        let result = (low | high) as u8;
+        data[*offset] = result;
+        *offset += 1;
+        *index += 1;
+        Some(result)
+    }
+}
+
+/// The archive flag marking UnityCN bundles, which moved from the padding flag in later releases.
+pub(crate) fn encryption_flag(unity_revision: &str) -> u32 {
+    let mut version = [0; 3];
+    for (slot, part) in version.iter_mut().zip(unity_revision.split(|c: char| !c.is_ascii_digit()).filter_map(|x| x.parse::<u32>().ok())) {
+        *slot = part;
+    }
+    let old = match version {
+        [major, ..] if major < 2020 => true,
+        [2020, 3, patch] => patch <= 34,
+        [2021, 3, patch] => patch <= 2,
+        [2022, 3, patch] => patch <= 1,
+        _ => false,
+    };
+    if old {
+        0x200
+    } else {
+        0x400
+    }
+}
+
+fn decrypt_key(cipher: &Aes128, key: [u8; 16], data: &mut [u8; 16]) {
+    let mut block = GenericArray::from(key);
+    cipher.encrypt_block(&mut block);
+    for (x, k) in data.iter_mut().zip(block.iter()) {
+        *x ^= k;
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::UnityError;
+
+/// The release stage of a Unity version, in the order Unity ships them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ReleaseType {
+    Experimental,
+    Alpha,
+    Beta,
+    #[default]
+    Final,
+    Patch,
+}
+
+impl ReleaseType {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'x' => Some(Self::Experimental),
+            'a' => Some(Self::Alpha),
+            'b' => Some(Self::Beta),
+            'f' => Some(Self::Final),
+            'p' => Some(Self::Patch),
+            _ => None,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Experimental => 'x',
+            Self::Alpha => 'a',
+            Self::Beta => 'b',
+            Self::Final => 'f',
+            Self::Patch => 'p',
+        }
+    }
+}
+
+/// A Unity version such as `2019.4.40f1`, ordered by release.
+///
+/// Parsing is lenient: missing parts are 0 and anything after the build number, like the `c1` of
+/// China builds (`2019.4.40f1c1`), is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct UnityVersion {
+    pub major: i32,
+    pub minor: i32,
+    pub patch: i32,
+    pub release_type: ReleaseType,
+    pub build: i32,
+}
+
+impl UnityVersion {
+    pub const fn new(major: i32, minor: i32, patch: i32, release_type: ReleaseType, build: i32) -> Self {
+        Self { major, minor, patch, release_type, build }
+    }
+
+    /// Whether this is `major.minor` or later.
+    pub fn is_at_least(&self, major: i32, minor: i32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+
+    /// Whether this is `major.minor.patch` or later.
+    pub fn is_at_least_patch(&self, major: i32, minor: i32, patch: i32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+}
+
+impl FromStr for UnityVersion {
+    type Err = UnityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut numbers = [0; 4];
+        let mut count = 0;
+        let mut release_type = ReleaseType::default();
+        let mut rest = s;
+        while count < numbers.len() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            if digits == 0 {
+                break;
+            }
+            numbers[count] = rest[..digits].parse().map_err(|_| UnityError::CustomError(format!("invalid Unity version {:?}", s)))?;
+            count += 1;
+            rest = &rest[digits..];
+            let Some(c) = rest.chars().next() else {
+                break;
+            };
+            if count < 3 && c == '.' {
+                rest = &rest[1..];
+            } else if let Some(x) = ReleaseType::from_char(c).filter(|_| count == 3) {
+                release_type = x;
+                rest = &rest[1..];
+            } else {
+                break;
+            }
+        }
+        if count == 0 {
+            return Err(UnityError::CustomError(format!("invalid Unity version {:?}", s)));
+        }
+        let [major, minor, patch, build] = numbers;
+        Ok(Self::new(major, minor, patch, release_type, build))
+    }
+}
+
+impl fmt::Display for UnityVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}{}{}", self.major, self.minor, self.patch, self.release_type.as_char(), self.build)
+    }
+}
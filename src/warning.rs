@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+use crate::classes::ClassID;
+
+/// Something found in the loaded files that does not stop them from loading, see
+/// [`Env::warnings`](crate::Env::warnings).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadWarning {
+    /// Objects of a class the crate does not know, which can only be read through their type
+    /// trees. `class_id` is as stored.
+    UnknownClass { file: String, class_id: i32, objects: usize },
+    /// Objects whose type tree is stripped and cannot be found elsewhere, so their fields cannot
+    /// be read: MonoBehaviours when the [`Env`](crate::Env) has no type tree provider, and
+    /// classes the crate does not know.
+    MissingTypeTree { file: String, class_id: i32, objects: usize },
+    /// A bundle block that decompressed to another size than the blocks info gives, and was
+    /// padded with zeros or cut to that size. `file` is the bundle's file the block starts in.
+    ///
+    /// Only bundles decompressed while loading are reported; bundles decompressed as objects are
+    /// read fix such blocks the same way when they are read, after loading, see
+    /// [`BundleFileLoader::load_from_data`](crate::bundle::BundleFileLoader::load_from_data).
+    BlockSize { file: String, block: usize, expected: usize, actual: usize },
+    /// A container path listed for several objects of the same class, such as the same asset in
+    /// two bundles, so looking it up is ambiguous. `objects` are the files and path ids, in load
+    /// order.
+    DuplicateContainerPath { path: String, class: ClassID, objects: Vec<(String, i64)> },
+}
+
+impl Display for LoadWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadWarning::UnknownClass { file, class_id, objects } => write!(f, "{}: {} objects of unknown class {}", file, objects, class_id),
+            LoadWarning::MissingTypeTree { file, class_id, objects } => write!(f, "{}: {} objects of class {} have no type tree", file, objects, ClassID::name_of(*class_id)),
+            LoadWarning::BlockSize { file, block, expected, actual } => write!(f, "{}: block {} decompressed to {} bytes instead of {}", file, block, actual, expected),
+            LoadWarning::DuplicateContainerPath { path, class, objects } => {
+                let objects: Vec<String> = objects.iter().map(|(file, path_id)| format!("{}:{}", file, path_id)).collect();
+                write!(f, "{} is listed for {} {:?} objects: {}", path, objects.len(), class, objects.join(", "))
+            }
+        }
+    }
+}
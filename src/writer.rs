@@ -0,0 +1,242 @@
+use crate::math::{Matrix4x4, RectF32, Vector2, Vector3, Vector4};
+use crate::reader::{ByteOrder, Eof};
+
+type Result<T> = std::result::Result<T, Eof>;
+
+/// Writes Unity's primitives into a buffer, the mirror of [`Reader`](crate::reader::Reader).
+///
+/// Writes go to the current offset, overwriting what is there and growing the buffer past its
+/// end, so a length can be patched in after its data by seeking back with
+/// [`Writer::set_offset`].
+#[derive(Clone, Debug)]
+pub struct Writer {
+    buf: Vec<u8>,
+    offset: usize,
+    order: ByteOrder,
+}
+
+impl Writer {
+    pub fn new(order: ByteOrder) -> Self {
+        Self { buf: Vec::new(), offset: 0, order }
+    }
+
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Moves to `offset` within what was written, returning the previous offset.
+    pub fn set_offset(&mut self, offset: usize) -> Result<usize> {
+        if self.buf.len() < offset {
+            return Err(Eof);
+        }
+        let result = self.offset;
+        self.offset = offset;
+        Ok(result)
+    }
+
+    pub fn set_little_order(&mut self) {
+        self.order = ByteOrder::Little
+    }
+
+    pub fn set_big_order(&mut self) {
+        self.order = ByteOrder::Big
+    }
+
+    pub fn get_order(&self) -> ByteOrder {
+        self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// Pads with zeros up to the next multiple of `num`, returning the new offset.
+    pub fn align(&mut self, num: usize) -> usize {
+        let padding = self.offset.next_multiple_of(num) - self.offset;
+        self.write_zeros(padding);
+        self.offset
+    }
+
+    pub fn write_zeros(&mut self, length: usize) {
+        let end = self.offset + length;
+        if self.buf.len() < end {
+            self.buf.resize(end, 0);
+        }
+        self.buf[self.offset..end].fill(0);
+        self.offset = end;
+    }
+
+    pub fn write_u8_slice(&mut self, data: &[u8]) {
+        let end = self.offset + data.len();
+        if self.buf.len() < end {
+            self.buf.resize(end, 0);
+        }
+        self.buf[self.offset..end].copy_from_slice(data);
+        self.offset = end;
+    }
+
+    /// Writes `data` as is, without a length.
+    pub fn write_u8_list(&mut self, data: &[u8]) {
+        self.write_u8_slice(data)
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.write_u8_slice(&[value])
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8)
+    }
+
+    pub fn write_i8(&mut self, value: i8) {
+        self.write_u8(value as u8)
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        match self.order {
+            ByteOrder::Big => self.write_u8_slice(&value.to_be_bytes()),
+            ByteOrder::Little => self.write_u8_slice(&value.to_le_bytes()),
+        }
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        match self.order {
+            ByteOrder::Big => self.write_u8_slice(&value.to_be_bytes()),
+            ByteOrder::Little => self.write_u8_slice(&value.to_le_bytes()),
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        match self.order {
+            ByteOrder::Big => self.write_u8_slice(&value.to_be_bytes()),
+            ByteOrder::Little => self.write_u8_slice(&value.to_le_bytes()),
+        }
+    }
+
+    pub fn write_i16(&mut self, value: i16) {
+        self.write_u16(value as u16)
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_u32(value as u32)
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_u64(value as u64)
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits())
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.write_u64(value.to_bits())
+    }
+
+    /// An IEEE 754 half precision float, rounded to nearest.
+    pub fn write_f16(&mut self, value: f32) {
+        self.write_u16(half::f16::from_f32(value).to_bits())
+    }
+
+    pub fn write_7bit_u32(&mut self, mut value: u32) {
+        while value >= 0x80 {
+            self.write_u8((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.write_u8(value as u8)
+    }
+
+    pub fn write_string_util_null(&mut self, value: &str) {
+        self.write_u8_slice(value.as_bytes());
+        self.write_u8(0)
+    }
+
+    pub fn write_string_with_7bit_length(&mut self, value: &str) {
+        self.write_7bit_u32(value.len() as u32);
+        self.write_u8_slice(value.as_bytes())
+    }
+
+    /// The length as an `i32`, the bytes and zeros up to a multiple of 4.
+    pub fn write_aligned_string(&mut self, value: &str) {
+        self.write_i32(value.len() as i32);
+        self.write_u8_slice(value.as_bytes());
+        self.align(4);
+    }
+
+    /// Overwrites the `i32` at `offset`, such as a length only known once its data is written,
+    /// and comes back to the current offset.
+    pub fn patch_i32(&mut self, offset: usize, value: i32) -> Result<()> {
+        if !offset.checked_add(4).is_some_and(|end| end <= self.buf.len()) {
+            return Err(Eof);
+        }
+        let current = self.set_offset(offset)?;
+        self.write_i32(value);
+        self.offset = current;
+        Ok(())
+    }
+
+    pub fn write_i32_list(&mut self, values: &[i32]) {
+        for value in values {
+            self.write_i32(*value)
+        }
+    }
+
+    pub fn write_u16_list(&mut self, values: &[u16]) {
+        for value in values {
+            self.write_u16(*value)
+        }
+    }
+
+    pub fn write_u32_list(&mut self, values: &[u32]) {
+        for value in values {
+            self.write_u32(*value)
+        }
+    }
+
+    pub fn write_f32_list(&mut self, values: &[f32]) {
+        for value in values {
+            self.write_f32(*value)
+        }
+    }
+
+    /// The count as an `i32`, then each string aligned.
+    pub fn write_string_list(&mut self, values: &[String]) {
+        self.write_i32(values.len() as i32);
+        for value in values {
+            self.write_aligned_string(value)
+        }
+    }
+
+    pub fn write_rect_f32(&mut self, value: &RectF32) {
+        self.write_f32_list(&[value.x, value.y, value.w, value.h])
+    }
+
+    pub fn write_vector2(&mut self, value: &Vector2) {
+        self.write_f32_list(&[value.x, value.y])
+    }
+
+    pub fn write_vector3(&mut self, value: &Vector3) {
+        self.write_f32_list(&[value.x, value.y, value.z])
+    }
+
+    pub fn write_vector4(&mut self, value: &Vector4) {
+        self.write_f32_list(&[value.x, value.y, value.z, value.w])
+    }
+
+    /// Column by column, as [`Reader::read_matrix4x4`](crate::reader::Reader::read_matrix4x4) reads it.
+    pub fn write_matrix4x4(&mut self, m: &Matrix4x4) {
+        self.write_f32_list(&[m.m00, m.m10, m.m20, m.m30, m.m01, m.m11, m.m21, m.m31, m.m02, m.m12, m.m22, m.m32, m.m03, m.m13, m.m23, m.m33])
+    }
+}
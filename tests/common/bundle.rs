@@ -0,0 +1,134 @@
+use lzma_rs::compress::{Options, UnpackedSize};
+use std::io::Cursor;
+
+/// LZMA as written into UnityFS blocks: the 5 byte properties, but no uncompressed size.
+pub fn lzma_block(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    let options = Options {
+        unpacked_size: UnpackedSize::SkipWritingToHeader,
+    };
+    lzma_rs::lzma_compress_with_options(&mut Cursor::new(data), &mut result, &options).expect("Compress Failure");
+    result
+}
+
+/// A UnityFS bundle of `file` as one data block, LZMA compressed or stored as is.
+pub fn unity_fs_bundle(file: &[u8], lzma: bool) -> Vec<u8> {
+    let block = if lzma { lzma_block(file) } else { file.to_vec() };
+    UnityFsBundle::new().block(&block, lzma as u16, file.len()).build()
+}
+
+/// A version 6 UnityFS bundle with LZMA blocks info, whose data blocks together make up the one
+/// file `CAB-test`. Tests state what differs from the default: revision 2017.4.39f1, flags 1 and
+/// nothing between the header and the blocks info.
+pub struct UnityFsBundle<'a> {
+    revision: &'a str,
+    flags: u32,
+    extra_header: &'a [u8],
+    blocks: Vec<(&'a [u8], u16, usize)>,
+    node_size: Option<i64>,
+}
+
+impl<'a> UnityFsBundle<'a> {
+    pub fn new() -> Self {
+        Self {
+            revision: "2017.4.39f1",
+            flags: 1,
+            extra_header: &[],
+            blocks: Vec::new(),
+            node_size: None,
+        }
+    }
+
+    pub fn revision(mut self, revision: &'a str) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    pub fn flags(mut self, flags: u32) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Bytes written right after the header, such as UnityCN's encryption info.
+    pub fn extra_header(mut self, extra_header: &'a [u8]) -> Self {
+        self.extra_header = extra_header;
+        self
+    }
+
+    /// Appends a data block stored as `data`, which the blocks info claims decompresses to
+    /// `uncompressed_size` bytes.
+    pub fn block(mut self, data: &'a [u8], flags: u16, uncompressed_size: usize) -> Self {
+        self.blocks.push((data, flags, uncompressed_size));
+        self
+    }
+
+    /// Makes the directory claim `size` bytes for the file instead of what the blocks add up to.
+    pub fn node_size(mut self, size: i64) -> Self {
+        self.node_size = Some(size);
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let file_len = self.node_size.unwrap_or_else(|| self.blocks.iter().map(|x| x.2 as i64).sum());
+        let mut blocks_info = vec![0; 16];
+        blocks_info.extend_from_slice(&(self.blocks.len() as i32).to_be_bytes());
+        for (block, block_flags, uncompressed_size) in &self.blocks {
+            blocks_info.extend_from_slice(&(*uncompressed_size as u32).to_be_bytes());
+            blocks_info.extend_from_slice(&(block.len() as u32).to_be_bytes());
+            blocks_info.extend_from_slice(&block_flags.to_be_bytes());
+        }
+        blocks_info.extend_from_slice(&1i32.to_be_bytes());
+        blocks_info.extend_from_slice(&0i64.to_be_bytes());
+        blocks_info.extend_from_slice(&file_len.to_be_bytes());
+        blocks_info.extend_from_slice(&4u32.to_be_bytes());
+        blocks_info.extend_from_slice(b"CAB-test\0");
+        let compressed_blocks_info = lzma_block(&blocks_info);
+
+        let mut bundle = Vec::new();
+        bundle.extend_from_slice(b"UnityFS\0");
+        bundle.extend_from_slice(&6u32.to_be_bytes());
+        bundle.extend_from_slice(b"5.x.x\0");
+        bundle.extend_from_slice(self.revision.as_bytes());
+        bundle.push(0);
+        let size = bundle.len() + 20 + self.extra_header.len() + compressed_blocks_info.len() + self.blocks.iter().map(|x| x.0.len()).sum::<usize>();
+        bundle.extend_from_slice(&(size as i64).to_be_bytes());
+        bundle.extend_from_slice(&(compressed_blocks_info.len() as u32).to_be_bytes());
+        bundle.extend_from_slice(&(blocks_info.len() as u32).to_be_bytes());
+        bundle.extend_from_slice(&self.flags.to_be_bytes());
+        bundle.extend_from_slice(self.extra_header);
+        bundle.extend_from_slice(&compressed_blocks_info);
+        for (block, _, _) in &self.blocks {
+            bundle.extend_from_slice(block);
+        }
+        bundle
+    }
+}
+
+pub fn unity_web_bundle(file: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&1i32.to_be_bytes());
+    payload.extend_from_slice(b"CAB-test\0");
+    let offset = payload.len() + 8;
+    payload.extend_from_slice(&(offset as u32).to_be_bytes());
+    payload.extend_from_slice(&(file.len() as u32).to_be_bytes());
+    payload.extend_from_slice(file);
+    let mut compressed = Vec::new();
+    lzma_rs::lzma_compress(&mut Cursor::new(&payload), &mut compressed).expect("Compress Failure");
+
+    let mut bundle = Vec::new();
+    bundle.extend_from_slice(b"UnityWeb\0");
+    bundle.extend_from_slice(&3u32.to_be_bytes());
+    bundle.extend_from_slice(b"3.x.x\0");
+    bundle.extend_from_slice(b"5.6.0f1\0");
+    let header_size = bundle.len() + 8 * 4;
+    bundle.extend_from_slice(&0u32.to_be_bytes());
+    bundle.extend_from_slice(&(header_size as u32).to_be_bytes());
+    bundle.extend_from_slice(&1u32.to_be_bytes());
+    bundle.extend_from_slice(&1i32.to_be_bytes());
+    bundle.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    bundle.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    bundle.extend_from_slice(&((header_size + compressed.len()) as u32).to_be_bytes());
+    bundle.extend_from_slice(&0u32.to_be_bytes());
+    bundle.extend_from_slice(&compressed);
+    bundle
+}
@@ -0,0 +1,46 @@
+//! Fixtures shared by the integration tests: serialized files, bundles and the object data
+//! that goes into them, written byte by byte the way Unity lays them out.
+
+#![allow(dead_code)]
+
+use unity_rs::classes::TextAsset;
+use unity_rs::{ClassID, Env};
+
+mod bundle;
+mod objects;
+mod serialized_file;
+
+pub use bundle::*;
+pub use objects::*;
+pub use serialized_file::*;
+
+pub const TEXT: &str = "hello from an lzma bundle";
+
+pub fn align(data: &mut Vec<u8>, n: usize) {
+    while data.len() % n != 0 {
+        data.push(0);
+    }
+}
+
+pub fn aligned_string(data: &mut Vec<u8>, s: &str) {
+    data.extend_from_slice(&(s.len() as i32).to_le_bytes());
+    data.extend_from_slice(s.as_bytes());
+    align(data, 4);
+}
+
+pub fn floats(data: &mut Vec<u8>, values: &[f32]) {
+    for x in values {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+}
+
+pub fn assert_text_asset(env: &Env) {
+    assert_text_asset_is(env, TEXT);
+}
+
+pub fn assert_text_asset_is(env: &Env, text: &str) {
+    let object = env.objects().find(|x| x.class() == ClassID::TextAsset).expect("Missing TextAsset");
+    let text_asset: TextAsset = object.read().expect("Read Failure");
+    assert_eq!(text_asset.name, "greeting");
+    assert_eq!(text_asset.script_string().expect("Utf8 Failure"), text);
+}
@@ -0,0 +1,485 @@
+use super::{align, aligned_string, floats};
+
+pub fn pptr(data: &mut Vec<u8>, path_id: i64) {
+    data.extend_from_slice(&0i32.to_le_bytes());
+    data.extend_from_slice(&path_id.to_le_bytes());
+}
+
+/// A GameObject with a single component, `transform`.
+pub fn game_object(name: &str, transform: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&1i32.to_le_bytes());
+    pptr(&mut data, transform);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    aligned_string(&mut data, name);
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.push(1);
+    data
+}
+
+/// An AssetBundle named `name` whose container maps paths to path ids in its own file.
+pub fn asset_bundle(name: &str, container: &[(&str, i64)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, name);
+    data.extend_from_slice(&0i32.to_le_bytes());
+    data.extend_from_slice(&(container.len() as i32).to_le_bytes());
+    for (path, path_id) in container {
+        aligned_string(&mut data, path);
+        data.extend_from_slice(&[0; 8]);
+        pptr(&mut data, *path_id);
+    }
+    data.extend_from_slice(&[0; 8]);
+    pptr(&mut data, 0);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    aligned_string(&mut data, name);
+    data.extend_from_slice(&[0; 8]);
+    data
+}
+
+/// A MonoBehaviour named `name` on `game_object`, running `script`.
+pub fn mono_behaviour(name: &str, game_object: i64, script: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    pptr(&mut data, game_object);
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    pptr(&mut data, script);
+    aligned_string(&mut data, name);
+    data
+}
+
+/// An identity Transform of `game_object` under `father`, 0 for none.
+pub fn transform(game_object: i64, children: &[i64], father: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    pptr(&mut data, game_object);
+    for x in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&(children.len() as i32).to_le_bytes());
+    for child in children {
+        pptr(&mut data, *child);
+    }
+    pptr(&mut data, father);
+    data
+}
+
+/// An enabled, screen space overlay Canvas on `game_object`.
+pub fn canvas(game_object: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    pptr(&mut data, game_object);
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    data.extend_from_slice(&0i32.to_le_bytes());
+    pptr(&mut data, 0);
+    data.extend_from_slice(&100.0f32.to_le_bytes());
+    data.push(0);
+    data
+}
+
+/// A perspective Camera on `game_object` as 2017.4 lays it out, clearing to a solid color and
+/// rendering every layer to the screen.
+pub fn camera(game_object: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    pptr(&mut data, game_object);
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    data.extend_from_slice(&2u32.to_le_bytes());
+    floats(&mut data, &[0.25, 0.5, 0.75, 1.0, 0.0, 0.0, 1.0, 1.0, 0.3, 1000.0, 60.0]);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    floats(&mut data, &[5.0, -1.0]);
+    data.extend_from_slice(&u32::MAX.to_le_bytes());
+    data.extend_from_slice(&(-1i32).to_le_bytes());
+    pptr(&mut data, 0);
+    data.extend_from_slice(&0i32.to_le_bytes());
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(&[1, 1, 0, 0, 1, 0, 0, 0]);
+    floats(&mut data, &[10.0, 0.022]);
+    data
+}
+
+/// A readable RGBA32 Texture2D, `pixels` starting at the bottom row.
+pub fn texture2d(name: &str, width: i32, height: i32, pixels: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, name);
+    data.extend_from_slice(&[0; 8]);
+    for x in [width, height, pixels.len() as i32, 4, 1] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    for x in [1, 2, 0, 1, 0, 0, 0, 0, 0, 0, pixels.len() as i32] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(pixels);
+    data
+}
+
+pub const SPRITE_KEY: [u8; 16] = [7; 16];
+
+/// A 3x3 pixel Sprite of `texture`, packed in `atlas` under [`SPRITE_KEY`]. Its own render data
+/// points at the bottom left of the texture.
+pub fn sprite(name: &str, texture: i64, atlas: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, name);
+    floats(&mut data, &[0.0, 0.0, 3.0, 3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 100.0, 0.5, 0.5]);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data.extend_from_slice(&SPRITE_KEY);
+    data.extend_from_slice(&21i64.to_le_bytes());
+    data.extend_from_slice(&1i32.to_le_bytes());
+    aligned_string(&mut data, "ui");
+    pptr(&mut data, atlas);
+    pptr(&mut data, texture);
+    pptr(&mut data, 0);
+    data.extend_from_slice(&[0; 20]);
+    floats(&mut data, &[0.0, 0.0, 2.0, 2.0, 0.0, 0.0, -1.0, -1.0]);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    floats(&mut data, &[0.0, 0.0, 0.0, 0.0, 1.0]);
+    data
+}
+
+/// A SpriteAtlas packing `sprite` into `texture` at `texture_rect`, trimmed by `texture_rect_offset`.
+pub fn sprite_atlas(sprite: (i64, &str), texture: i64, texture_rect: [f32; 4], texture_rect_offset: [f32; 2]) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, "atlas");
+    data.extend_from_slice(&1i32.to_le_bytes());
+    pptr(&mut data, sprite.0);
+    data.extend_from_slice(&1i32.to_le_bytes());
+    aligned_string(&mut data, sprite.1);
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&SPRITE_KEY);
+    data.extend_from_slice(&21i64.to_le_bytes());
+    pptr(&mut data, texture);
+    pptr(&mut data, 0);
+    floats(&mut data, &texture_rect);
+    floats(&mut data, &texture_rect_offset);
+    floats(&mut data, &[texture_rect[0] - texture_rect_offset[0], texture_rect[1] - texture_rect_offset[1], 0.0, 0.0, 0.0, 0.0, 1.0]);
+    // Packed in a rectangle, so that no mesh masks the cut.
+    data.extend_from_slice(&3u32.to_le_bytes());
+    aligned_string(&mut data, "ui");
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data
+}
+
+/// A 2017.4 Mesh without geometry whose blend shape `channels` each have a name and the weights of
+/// their frames. Frame `i`, counting across the channels, moves vertex `i` by `(i + 1, 0, 0)`.
+pub fn blend_shape_mesh(name: &str, channels: &[(&str, &[f32])]) -> Vec<u8> {
+    let frames: usize = channels.iter().map(|x| x.1.len()).sum();
+    let mut data = Vec::new();
+    aligned_string(&mut data, name);
+    // No sub meshes.
+    data.extend_from_slice(&0i32.to_le_bytes());
+
+    data.extend_from_slice(&(frames as i32).to_le_bytes());
+    for i in 0..frames {
+        floats(&mut data, &[i as f32 + 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        data.extend_from_slice(&(i as u32).to_le_bytes());
+    }
+    data.extend_from_slice(&(frames as i32).to_le_bytes());
+    for i in 0..frames {
+        data.extend_from_slice(&(i as u32).to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0]);
+    }
+    data.extend_from_slice(&(channels.len() as i32).to_le_bytes());
+    let mut frame_index = 0;
+    for (i, (name, weights)) in channels.iter().enumerate() {
+        aligned_string(&mut data, name);
+        data.extend_from_slice(&(i as u32).to_le_bytes());
+        data.extend_from_slice(&(frame_index as i32).to_le_bytes());
+        data.extend_from_slice(&(weights.len() as i32).to_le_bytes());
+        frame_index += weights.len();
+    }
+    data.extend_from_slice(&(frames as i32).to_le_bytes());
+    for (_, weights) in channels {
+        floats(&mut data, weights);
+    }
+
+    // No bind poses or bones, then the compression and flags, 16 bit indices and no index buffer,
+    // skin or vertex data.
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&[0; 16]);
+    // The compressed mesh, its packed vectors all empty, ending with the float colors, bone
+    // indices, triangles and uv info.
+    let packed_float = [0; 20];
+    let packed_int = [0; 12];
+    for _ in 0..4 {
+        data.extend_from_slice(&packed_float);
+    }
+    for _ in 0..3 {
+        data.extend_from_slice(&packed_int);
+    }
+    data.extend_from_slice(&packed_float);
+    for _ in 0..2 {
+        data.extend_from_slice(&packed_int);
+    }
+    data.extend_from_slice(&[0; 4]);
+    // The local AABB, the usage flags and no baked collision meshes.
+    data.extend_from_slice(&[0; 24]);
+    data.extend_from_slice(&[0; 12]);
+    data
+}
+
+/// A 2017.4 Shader with one pass, which names `keywords` by their index and whose vertex program
+/// has `sub_programs`, each a blob index, a GPU program type and keyword indices. Each of `blobs`
+/// is a platform and its code, LZ4 compressed one after the other into the shared blob.
+pub fn shader(name: &str, keywords: &[&str], sub_programs: &[(u32, i8, &[u16])], blobs: &[(u32, &[u8])]) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, "shader");
+    // No properties, one sub shader with one pass.
+    data.extend_from_slice(&0i32.to_le_bytes());
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&(keywords.len() as i32).to_le_bytes());
+    for (i, keyword) in keywords.iter().enumerate() {
+        aligned_string(&mut data, keyword);
+        data.extend_from_slice(&(i as i32).to_le_bytes());
+    }
+    data.extend_from_slice(&0i32.to_le_bytes());
+    // The state: its 85 float values, fog, tags, LOD and lighting all zero.
+    aligned_string(&mut data, "state");
+    data.extend_from_slice(&[0; 708]);
+    data.extend_from_slice(&1u32.to_le_bytes());
+
+    data.extend_from_slice(&(sub_programs.len() as i32).to_le_bytes());
+    for (blob_index, gpu_program_type, keyword_indices) in sub_programs {
+        data.extend_from_slice(&blob_index.to_le_bytes());
+        // No bind channels, and the source map.
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&(keyword_indices.len() as i32).to_le_bytes());
+        for x in *keyword_indices {
+            data.extend_from_slice(&x.to_le_bytes());
+        }
+        align(&mut data, 4);
+        data.extend_from_slice(&[0, *gpu_program_type as u8, 0, 0]);
+        // No parameters or samplers, and the shader requirements.
+        data.extend_from_slice(&[0; 36]);
+    }
+    // The fragment, geometry, hull and domain programs have no sub programs.
+    data.extend_from_slice(&[0; 16]);
+    // No instancing variant, then the use name, pass name, texture name and tags.
+    data.extend_from_slice(&[0; 4]);
+    aligned_string(&mut data, "");
+    aligned_string(&mut data, "FORWARD");
+    aligned_string(&mut data, "");
+    data.extend_from_slice(&0i32.to_le_bytes());
+    // The sub shader's tags and LOD.
+    data.extend_from_slice(&[0; 8]);
+
+    aligned_string(&mut data, name);
+    aligned_string(&mut data, "");
+    aligned_string(&mut data, "Diffuse");
+    // No dependencies, and the subshaders message flag.
+    data.extend_from_slice(&[0; 8]);
+
+    let mut blob = Vec::new();
+    let mut columns = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+    for (platform, code) in blobs {
+        let compressed = lz4_flex::compress(code);
+        columns[0].push(*platform);
+        columns[1].push(blob.len() as u32);
+        columns[2].push(compressed.len() as u32);
+        columns[3].push(code.len() as u32);
+        blob.extend_from_slice(&compressed);
+    }
+    for column in columns {
+        data.extend_from_slice(&(column.len() as i32).to_le_bytes());
+        for x in column {
+            data.extend_from_slice(&x.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(&(blob.len() as i32).to_le_bytes());
+    data.extend_from_slice(&blob);
+    align(&mut data, 4);
+    data
+}
+
+/// A 2017.4 AnimatorController with one layer, bound to the hash `layer`, whose `states` are each
+/// a name hash, a full path hash and the clips they play: one clip on its own, several through a
+/// blend tree. Every state transitions to the next on one float parameter. `tos` names the hashes.
+pub fn animator_controller(name: &str, layer: u32, states: &[(u32, u32, &[u32])], tos: &[(u32, &str)], clips: &[i64]) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, name);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    // The layer plays state machine 0 with motion set 0 and masks nothing, then its binding,
+    // blending mode, weight and flags.
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&0i32.to_le_bytes());
+    data.extend_from_slice(&layer.to_le_bytes());
+    data.extend_from_slice(&0i32.to_le_bytes());
+    floats(&mut data, &[1.0]);
+    data.extend_from_slice(&[0; 4]);
+
+    data.extend_from_slice(&1i32.to_le_bytes());
+    data.extend_from_slice(&(states.len() as i32).to_le_bytes());
+    for (i, (name_id, full_path_id, clip_indices)) in states.iter().enumerate() {
+        // The transition and its condition on parameter 5, then the destination, ids, timing and
+        // flags.
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&5u32.to_le_bytes());
+        floats(&mut data, &[0.5, 0.0]);
+        data.extend_from_slice(&(((i + 1) % states.len()) as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 12]);
+        floats(&mut data, &[0.25, 0.0, 0.9]);
+        data.extend_from_slice(&[1, 1, 0, 0]);
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&[0, 1, 0, 0]);
+        // Motion set 0 plays the one blend tree.
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&1i32.to_le_bytes());
+        if let [clip] = clip_indices {
+            data.extend_from_slice(&1i32.to_le_bytes());
+            blend_tree_node(&mut data, &[], *clip);
+        } else {
+            data.extend_from_slice(&(clip_indices.len() as i32 + 1).to_le_bytes());
+            blend_tree_node(&mut data, &(1..=clip_indices.len() as u32).collect::<Vec<_>>(), u32::MAX);
+            for clip in *clip_indices {
+                blend_tree_node(&mut data, &[], *clip);
+            }
+        }
+        // The name, path and full path ids, the tag and parameter ids, speed, cycle offset and
+        // flags.
+        data.extend_from_slice(&name_id.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&full_path_id.to_le_bytes());
+        data.extend_from_slice(&[0; 20]);
+        floats(&mut data, &[1.0, 0.0]);
+        data.extend_from_slice(&[0, 1, 0, 0]);
+    }
+    // No any state transitions or selectors, the default state and the motion set count.
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    // Parameter 5 is the first float, which defaults to 0.
+    data.extend_from_slice(&1i32.to_le_bytes());
+    for x in [5u32, 1, 0] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&1i32.to_le_bytes());
+    floats(&mut data, &[0.0]);
+    data.extend_from_slice(&[0; 8]);
+
+    data.extend_from_slice(&(tos.len() as i32).to_le_bytes());
+    for (hash, path) in tos {
+        data.extend_from_slice(&hash.to_le_bytes());
+        aligned_string(&mut data, path);
+    }
+    data.extend_from_slice(&(clips.len() as i32).to_le_bytes());
+    for clip in clips {
+        pptr(&mut data, *clip);
+    }
+    data
+}
+
+/// A blend tree node playing `clip`, or blending `children` with empty blend data.
+fn blend_tree_node(data: &mut Vec<u8>, children: &[u32], clip: u32) {
+    // The blend type and parameters.
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&(children.len() as i32).to_le_bytes());
+    for x in children {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    // The 1D thresholds, five 2D arrays and the direct blend data with its flag.
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&[0; 20]);
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&clip.to_le_bytes());
+    floats(data, &[1.0, 0.0]);
+    data.extend_from_slice(&[0; 4]);
+}
+
+/// A 2017.4 Avatar whose avatar and human skeletons are chains of the path hashes `skeleton` and
+/// `human_skeleton` in their rest pose, with `human_bone_index` into the human one. `tos` names
+/// the hashes.
+pub fn avatar(name: &str, skeleton: &[u32], human_skeleton: &[u32], human_bone_index: &[i32], tos: &[(u32, &str)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    aligned_string(&mut data, name);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    avatar_skeleton(&mut data, skeleton);
+    // The skeleton pose, default pose and name ids.
+    skeleton_pose(&mut data, skeleton.len());
+    skeleton_pose(&mut data, skeleton.len());
+    data.extend_from_slice(&(skeleton.len() as i32).to_le_bytes());
+    for x in skeleton {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+
+    identity_xform(&mut data);
+    avatar_skeleton(&mut data, human_skeleton);
+    skeleton_pose(&mut data, human_skeleton.len());
+    // No hand bones, handles or colliders.
+    data.extend_from_slice(&[0; 16]);
+    data.extend_from_slice(&(human_bone_index.len() as i32).to_le_bytes());
+    for x in human_bone_index {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&(human_bone_index.len() as i32).to_le_bytes());
+    for _ in human_bone_index {
+        floats(&mut data, &[1.0]);
+    }
+    data.extend_from_slice(&0i32.to_le_bytes());
+    // The scale, twists, stretches and feet spacing, then the hand and degree of freedom flags.
+    floats(&mut data, &[1.0, 0.5, 0.5, 0.5, 0.5, 0.05, 0.05, 0.0]);
+    data.extend_from_slice(&[0, 0, 1, 0]);
+
+    // No skeleton index maps, no root motion bone and an empty root motion skeleton.
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&(-1i32).to_le_bytes());
+    identity_xform(&mut data);
+    data.extend_from_slice(&[0; 20]);
+
+    data.extend_from_slice(&(tos.len() as i32).to_le_bytes());
+    for (hash, path) in tos {
+        data.extend_from_slice(&hash.to_le_bytes());
+        aligned_string(&mut data, path);
+    }
+    data
+}
+
+/// A skeleton of nodes `ids`, each the parent of the next, with no axes.
+fn avatar_skeleton(data: &mut Vec<u8>, ids: &[u32]) {
+    data.extend_from_slice(&(ids.len() as i32).to_le_bytes());
+    for i in 0..ids.len() {
+        data.extend_from_slice(&(i as i32 - 1).to_le_bytes());
+        data.extend_from_slice(&(-1i32).to_le_bytes());
+    }
+    data.extend_from_slice(&(ids.len() as i32).to_le_bytes());
+    for x in ids {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&0i32.to_le_bytes());
+}
+
+fn skeleton_pose(data: &mut Vec<u8>, nodes: usize) {
+    data.extend_from_slice(&(nodes as i32).to_le_bytes());
+    for _ in 0..nodes {
+        identity_xform(data);
+    }
+}
+
+fn identity_xform(data: &mut Vec<u8>) {
+    floats(data, &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+}
+
+/// BuildSettings as 2017.4 lays them out without a type tree: `scenes`, the preloaded plugins, VR
+/// devices, build tags and GUID, eleven flags starting with `flags`, and `version`.
+pub fn build_settings(scenes: &[&str], flags: [bool; 4], version: &str) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(scenes.len() as i32).to_le_bytes());
+    for scene in scenes {
+        aligned_string(&mut data, scene);
+    }
+    data.extend_from_slice(&1i32.to_le_bytes());
+    aligned_string(&mut data, "Plugins/native.dll");
+    data.extend_from_slice(&[0; 8]);
+    aligned_string(&mut data, "0123456789abcdef0123456789abcdef");
+    data.extend(flags.map(u8::from));
+    data.extend_from_slice(&[1, 0, 1, 0, 0, 1, 1]);
+    align(&mut data, 4);
+    aligned_string(&mut data, version);
+    aligned_string(&mut data, "token");
+    data
+}
@@ -0,0 +1,382 @@
+use unity_rs::ClassID;
+
+use super::{align, aligned_string, TEXT};
+
+/// Writes numbers in the byte order of the file under construction.
+struct Endian {
+    big: bool,
+    data: Vec<u8>,
+}
+
+impl Endian {
+    fn bytes<const N: usize>(&mut self, mut x: [u8; N]) {
+        if self.big {
+            x.reverse();
+        }
+        self.data.extend_from_slice(&x);
+    }
+
+    fn i32(&mut self, x: i32) {
+        self.bytes(x.to_le_bytes());
+    }
+
+    fn aligned_string(&mut self, s: &str) {
+        self.i32(s.len() as i32);
+        self.data.extend_from_slice(s.as_bytes());
+        align(&mut self.data, 4);
+    }
+}
+
+/// The default [`TextAssetFile`].
+pub fn serialized_file() -> Vec<u8> {
+    TextAssetFile::new().build()
+}
+
+/// A version 17 serialized file of TextAssets with path ids from 1, named `greeting`,
+/// `greeting1`, `greeting2` and so on. Tests state what differs from the default: one little
+/// endian TextAsset holding [`TEXT`], without a type tree or externals.
+pub struct TextAssetFile<'a> {
+    texts: Vec<&'a str>,
+    externals: Vec<&'a str>,
+    type_tree: bool,
+    big_endian: bool,
+    common_strings: bool,
+}
+
+impl<'a> TextAssetFile<'a> {
+    pub fn new() -> Self {
+        Self {
+            texts: vec![TEXT],
+            externals: Vec::new(),
+            type_tree: false,
+            big_endian: false,
+            common_strings: false,
+        }
+    }
+
+    pub fn text(self, text: &'a str) -> Self {
+        self.texts(&[text])
+    }
+
+    pub fn texts(mut self, texts: &[&'a str]) -> Self {
+        self.texts = texts.to_vec();
+        self
+    }
+
+    /// Files referenced by path, with the guid of the n-th filled with n.
+    pub fn externals(mut self, externals: &[&'a str]) -> Self {
+        self.externals = externals.to_vec();
+        self
+    }
+
+    /// Adds a TextAsset type tree blob, its strings in a local string buffer.
+    pub fn type_tree(mut self) -> Self {
+        self.type_tree = true;
+        self
+    }
+
+    pub fn big_endian(mut self, big_endian: bool) -> Self {
+        self.big_endian = big_endian;
+        self
+    }
+
+    /// Makes the type tree's nodes refer to Unity's common string table instead of a local
+    /// string buffer.
+    pub fn common_strings(mut self) -> Self {
+        self.common_strings = true;
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let big = self.big_endian;
+        let mut objects = Vec::new();
+        for (i, text) in self.texts.iter().enumerate() {
+            let mut object = Endian { big, data: Vec::new() };
+            object.aligned_string(&if i == 0 { "greeting".to_string() } else { format!("greeting{}", i) });
+            object.aligned_string(text);
+            objects.push(object.data);
+        }
+
+        let local_strings = b"TextAsset\0Base\0string\0m_Name\0Array\0int\0size\0char\0data\0m_Script\0";
+        let common = [
+            ("TextAsset", 847),
+            ("Base", 55),
+            ("string", 840),
+            ("m_Name", 427),
+            ("Array", 49),
+            ("int", 222),
+            ("size", 795),
+            ("char", 81),
+            ("data", 106),
+            ("m_Script", 490),
+        ];
+        let strings: &[u8] = if self.common_strings { &[] } else { local_strings };
+        let offset = |s: &str| {
+            if self.common_strings {
+                return 0x80000000 | common.iter().find(|x| x.0 == s).expect("Missing string").1;
+            }
+            local_strings.windows(s.len() + 1).position(|x| &x[..s.len()] == s.as_bytes() && x[s.len()] == 0).expect("Missing string") as u32
+        };
+        let mut nodes = vec![("TextAsset", "Base", 0u8, 0u8, -1, 0x8000)];
+        for name in ["m_Name", "m_Script"] {
+            nodes.extend([("string", name, 1, 0, -1, 0x8000), ("Array", "Array", 2, 1, -1, 0x4000), ("int", "size", 3, 0, 4, 0), ("char", "data", 3, 0, 1, 0)]);
+        }
+
+        let mut m = Endian { big, data: Vec::new() };
+        m.data.extend_from_slice(b"2017.4.39f1\0");
+        m.i32(5);
+        m.data.push(self.type_tree as u8);
+        m.i32(1);
+        m.i32(ClassID::TextAsset as i32);
+        m.data.push(0);
+        m.bytes((-1i16).to_le_bytes());
+        m.data.extend_from_slice(&[0; 16]);
+        if self.type_tree {
+            m.i32(nodes.len() as i32);
+            m.i32(strings.len() as i32);
+            for (index, (type_, name, level, type_flag, size, meta_flag)) in nodes.into_iter().enumerate() {
+                m.bytes(1u16.to_le_bytes());
+                m.data.extend_from_slice(&[level, type_flag]);
+                m.bytes(offset(type_).to_le_bytes());
+                m.bytes(offset(name).to_le_bytes());
+                m.i32(size);
+                m.i32(index as i32);
+                m.i32(meta_flag);
+            }
+            m.data.extend_from_slice(strings);
+        }
+        m.i32(objects.len() as i32);
+        let mut start = 0;
+        for (i, object) in objects.iter().enumerate() {
+            // Object entries are aligned relative to the start of the file, after the 20 byte header.
+            while (20 + m.data.len()) % 4 != 0 {
+                m.data.push(0);
+            }
+            m.bytes((i as i64 + 1).to_le_bytes());
+            m.bytes((start as u32).to_le_bytes());
+            m.bytes((object.len() as u32).to_le_bytes());
+            m.i32(0);
+            start += object.len().next_multiple_of(8);
+        }
+        m.i32(0);
+        m.i32(self.externals.len() as i32);
+        for (i, path) in self.externals.iter().enumerate() {
+            m.data.push(0);
+            m.data.extend_from_slice(&[i as u8 + 1; 16]);
+            m.i32(0);
+            m.data.extend_from_slice(path.as_bytes());
+            m.data.push(0);
+        }
+        m.data.push(0);
+
+        let metadata_size = m.data.len();
+        let data_offset = (20 + metadata_size).next_multiple_of(16);
+        let mut file = Vec::new();
+        file.extend_from_slice(&(metadata_size as u32).to_be_bytes());
+        file.extend_from_slice(&((data_offset + start) as u32).to_be_bytes());
+        file.extend_from_slice(&17u32.to_be_bytes());
+        file.extend_from_slice(&(data_offset as u32).to_be_bytes());
+        file.extend_from_slice(&[big as u8, 0, 0, 0]);
+        file.extend_from_slice(&m.data);
+        file.resize(data_offset, 0);
+        for object in objects {
+            file.extend_from_slice(&object);
+            align(&mut file, 8);
+        }
+        file
+    }
+}
+
+/// A version 22 serialized file with the 48 byte large file header, an empty type tree blob and
+/// one referenced type, holding the same TextAsset as [`serialized_file`].
+pub fn large_serialized_file() -> Vec<u8> {
+    let mut object = Vec::new();
+    aligned_string(&mut object, "greeting");
+    aligned_string(&mut object, TEXT);
+
+    let mut metadata = Vec::new();
+    metadata.extend_from_slice(b"2021.3.5f1\0");
+    metadata.extend_from_slice(&5i32.to_le_bytes());
+    metadata.push(1);
+    metadata.extend_from_slice(&1i32.to_le_bytes());
+    metadata.extend_from_slice(&(ClassID::TextAsset as i32).to_le_bytes());
+    metadata.push(0);
+    metadata.extend_from_slice(&(-1i16).to_le_bytes());
+    metadata.extend_from_slice(&[0; 16]);
+    metadata.extend_from_slice(&[0; 8]);
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.extend_from_slice(&1i32.to_le_bytes());
+    while (48 + metadata.len()) % 4 != 0 {
+        metadata.push(0);
+    }
+    metadata.extend_from_slice(&1i64.to_le_bytes());
+    metadata.extend_from_slice(&0i64.to_le_bytes());
+    metadata.extend_from_slice(&(object.len() as u32).to_le_bytes());
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    // A referenced type without a script index, so no script id precedes its hash.
+    metadata.extend_from_slice(&1i32.to_le_bytes());
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.push(0);
+    metadata.extend_from_slice(&(-1i16).to_le_bytes());
+    metadata.extend_from_slice(&[0; 16]);
+    metadata.extend_from_slice(&[0; 8]);
+    metadata.extend_from_slice(b"Payload\0Game\0Assembly-CSharp\0");
+    metadata.push(0);
+
+    let metadata_size = metadata.len();
+    let mut data_offset = 48 + metadata_size;
+    data_offset += (16 - data_offset % 16) % 16;
+    let file_size = data_offset + object.len();
+
+    let mut file = Vec::new();
+    file.extend_from_slice(&0u32.to_be_bytes());
+    file.extend_from_slice(&0u32.to_be_bytes());
+    file.extend_from_slice(&22u32.to_be_bytes());
+    file.extend_from_slice(&0u32.to_be_bytes());
+    file.extend_from_slice(&[0, 0, 0, 0]);
+    file.extend_from_slice(&(metadata_size as u32).to_be_bytes());
+    file.extend_from_slice(&(file_size as i64).to_be_bytes());
+    file.extend_from_slice(&(data_offset as i64).to_be_bytes());
+    file.extend_from_slice(&0i64.to_be_bytes());
+    file.extend_from_slice(&metadata);
+    file.resize(data_offset, 0);
+    file.extend_from_slice(&object);
+    file
+}
+
+/// A version 17, little endian serialized file without type trees holding `objects` as
+/// `(path id, class, data)`.
+pub fn serialized_file_with_objects(objects: &[(i64, ClassID, Vec<u8>)]) -> Vec<u8> {
+    let mut classes: Vec<ClassID> = Vec::new();
+    for (_, class, _) in objects {
+        if !classes.contains(class) {
+            classes.push(*class);
+        }
+    }
+
+    let mut metadata = Vec::new();
+    metadata.extend_from_slice(b"2017.4.39f1\0");
+    metadata.extend_from_slice(&5i32.to_le_bytes());
+    metadata.push(0);
+    metadata.extend_from_slice(&(classes.len() as i32).to_le_bytes());
+    for class in &classes {
+        metadata.extend_from_slice(&(*class as i32).to_le_bytes());
+        metadata.push(0);
+        metadata.extend_from_slice(&(-1i16).to_le_bytes());
+        metadata.extend_from_slice(&[0; 16]);
+    }
+    metadata.extend_from_slice(&(objects.len() as i32).to_le_bytes());
+    let mut start = 0;
+    for (path_id, class, data) in objects {
+        while (20 + metadata.len()) % 4 != 0 {
+            metadata.push(0);
+        }
+        metadata.extend_from_slice(&path_id.to_le_bytes());
+        metadata.extend_from_slice(&(start as u32).to_le_bytes());
+        metadata.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        let type_index = classes.iter().position(|x| x == class).expect("Missing class");
+        metadata.extend_from_slice(&(type_index as i32).to_le_bytes());
+        start += data.len().next_multiple_of(8);
+    }
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.extend_from_slice(&0i32.to_le_bytes());
+    metadata.push(0);
+
+    let data_offset = (20 + metadata.len()).next_multiple_of(16);
+    let mut file = Vec::new();
+    file.extend_from_slice(&(metadata.len() as u32).to_be_bytes());
+    file.extend_from_slice(&((data_offset + start) as u32).to_be_bytes());
+    file.extend_from_slice(&17u32.to_be_bytes());
+    file.extend_from_slice(&(data_offset as u32).to_be_bytes());
+    file.extend_from_slice(&[0, 0, 0, 0]);
+    file.extend_from_slice(&metadata);
+    file.resize(data_offset, 0);
+    for (_, _, data) in objects {
+        file.extend_from_slice(data);
+        align(&mut file, 8);
+    }
+    file
+}
+
+/// One node of a recursive type tree, whose `children` have to be written right after it.
+pub fn legacy_node(data: &mut Vec<u8>, type_: &str, name: &str, size: i32, type_flag: i32, meta_flag: i32, children: i32) {
+    data.extend_from_slice(type_.as_bytes());
+    data.push(0);
+    data.extend_from_slice(name.as_bytes());
+    data.push(0);
+    data.extend_from_slice(&size.to_be_bytes());
+    data.extend_from_slice(&0i32.to_be_bytes());
+    data.extend_from_slice(&type_flag.to_be_bytes());
+    data.extend_from_slice(&1i32.to_be_bytes());
+    data.extend_from_slice(&meta_flag.to_be_bytes());
+    data.extend_from_slice(&children.to_be_bytes());
+}
+
+pub fn legacy_string_node(data: &mut Vec<u8>, name: &str) {
+    legacy_node(data, "string", name, -1, 0, 0x8000, 1);
+    legacy_node(data, "Array", "Array", -1, 1, 0x4000, 2);
+    legacy_node(data, "int", "size", 4, 0, 0, 0);
+    legacy_node(data, "char", "data", 1, 0, 0, 0);
+}
+
+/// A version 9, big endian serialized file as Unity 4 writes them, holding one TextAsset with
+/// path id 1 and a recursive type tree.
+pub fn unity4_serialized_file() -> Vec<u8> {
+    legacy_serialized_file(9)
+}
+
+/// A big endian serialized file of `version` 9, as Unity 4 writes them, or 11, as Unity 5.0 does:
+/// the versions whose type trees are recursive nodes with inline strings. It holds one TextAsset
+/// with path id 1.
+pub fn legacy_serialized_file(version: u32) -> Vec<u8> {
+    let mut object = Vec::new();
+    for s in ["greeting", TEXT] {
+        object.extend_from_slice(&(s.len() as i32).to_be_bytes());
+        object.extend_from_slice(s.as_bytes());
+        align(&mut object, 4);
+    }
+
+    let mut metadata = Vec::new();
+    metadata.extend_from_slice(if version < 11 { b"4.7.2f1\0" } else { b"5.0.4f1\0" });
+    metadata.extend_from_slice(&5i32.to_be_bytes());
+    metadata.extend_from_slice(&1i32.to_be_bytes());
+    metadata.extend_from_slice(&(ClassID::TextAsset as i32).to_be_bytes());
+    legacy_node(&mut metadata, "TextAsset", "Base", -1, 0, 0x8000, 2);
+    legacy_string_node(&mut metadata, "m_Name");
+    legacy_string_node(&mut metadata, "m_Script");
+    metadata.extend_from_slice(&0i32.to_be_bytes());
+    metadata.extend_from_slice(&1i32.to_be_bytes());
+    metadata.extend_from_slice(&1i32.to_be_bytes());
+    metadata.extend_from_slice(&0u32.to_be_bytes());
+    metadata.extend_from_slice(&(object.len() as u32).to_be_bytes());
+    metadata.extend_from_slice(&(ClassID::TextAsset as i32).to_be_bytes());
+    metadata.extend_from_slice(&(ClassID::TextAsset as u16).to_be_bytes());
+    if version < 11 {
+        // Not destroyed.
+        metadata.extend_from_slice(&0u16.to_be_bytes());
+    } else {
+        // No script type, and no script types in the file.
+        metadata.extend_from_slice(&(-1i16).to_be_bytes());
+        metadata.extend_from_slice(&0i32.to_be_bytes());
+    }
+    metadata.extend_from_slice(&0i32.to_be_bytes());
+    metadata.push(0);
+
+    let metadata_size = metadata.len();
+    let mut data_offset = 20 + metadata_size;
+    data_offset += (16 - data_offset % 16) % 16;
+    let file_size = data_offset + object.len();
+
+    let mut file = Vec::new();
+    file.extend_from_slice(&(metadata_size as u32).to_be_bytes());
+    file.extend_from_slice(&(file_size as u32).to_be_bytes());
+    file.extend_from_slice(&version.to_be_bytes());
+    file.extend_from_slice(&(data_offset as u32).to_be_bytes());
+    file.extend_from_slice(&[1, 0, 0, 0]);
+    file.extend_from_slice(&metadata);
+    file.resize(data_offset, 0);
+    file.extend_from_slice(&object);
+    file
+}
@@ -0,0 +1,68 @@
+use unity_rs::classes::{AnimationCurve, Keyframe};
+
+fn key(time: f32, value: f32, in_slope: f32, out_slope: f32) -> Keyframe<f32> {
+    Keyframe {
+        time,
+        value,
+        in_slope,
+        out_slope,
+        weighted_mode: 0,
+        in_weight: 1.0 / 3.0,
+        out_weight: 1.0 / 3.0,
+    }
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "expected {expected}, got {actual}");
+}
+
+#[test]
+fn test_linear_curve() {
+    // AnimationCurve.Linear(0, 0, 1, 1)
+    let curve = AnimationCurve::new(vec![key(0.0, 0.0, 1.0, 1.0), key(1.0, 1.0, 1.0, 1.0)]);
+    assert_close(curve.evaluate(0.25), 0.25);
+    assert_close(curve.evaluate(0.5), 0.5);
+    assert_close(curve.evaluate(1.0), 1.0);
+}
+
+#[test]
+fn test_ease_in_out_curve() {
+    // AnimationCurve.EaseInOut(0, 0, 1, 1)
+    let curve = AnimationCurve::new(vec![key(0.0, 0.0, 0.0, 0.0), key(1.0, 1.0, 0.0, 0.0)]);
+    assert_close(curve.evaluate(0.25), 0.15625);
+    assert_close(curve.evaluate(0.5), 0.5);
+    assert_close(curve.evaluate(0.75), 0.84375);
+}
+
+#[test]
+fn test_weighted_default_weights_match_hermite() {
+    let mut lhs = key(0.0, 0.0, 0.0, 0.0);
+    let mut rhs = key(1.0, 1.0, 0.0, 0.0);
+    lhs.weighted_mode = 3;
+    rhs.weighted_mode = 3;
+    let curve = AnimationCurve::new(vec![lhs, rhs]);
+    assert_close(curve.evaluate(0.25), 0.15625);
+    assert_close(curve.evaluate(0.5), 0.5);
+}
+
+#[test]
+fn test_stepped_curve() {
+    let curve = AnimationCurve::new(vec![key(0.0, 0.0, 0.0, f32::INFINITY), key(1.0, 1.0, f32::INFINITY, 0.0)]);
+    assert_close(curve.evaluate(0.99), 0.0);
+    assert_close(curve.evaluate(1.0), 1.0);
+}
+
+#[test]
+fn test_wrap_modes() {
+    let mut curve = AnimationCurve::new(vec![key(0.0, 0.0, 1.0, 1.0), key(1.0, 1.0, 1.0, 1.0)]);
+    assert_close(curve.evaluate(-1.0), 0.0);
+    assert_close(curve.evaluate(2.0), 1.0);
+
+    curve.post_infinity = 1;
+    assert_close(curve.evaluate(1.25), 0.25);
+
+    curve.post_infinity = 0;
+    curve.pre_infinity = 0;
+    assert_close(curve.evaluate(1.25), 0.75);
+    assert_close(curve.evaluate(-0.25), 0.25);
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::{AnimatorController, StateMotion};
+use unity_rs::{ClassID, Env};
+
+use common::*;
+
+#[test]
+fn test_animator_controller_without_type_tree() {
+    let tos = [(1, "Base Layer"), (2, "Idle"), (3, "Run"), (4, "Base Layer.Idle")];
+    let data = animator_controller("Player", 1, &[(2, 4, &[0]), (3, 0, &[0, 1])], &tos, &[10, 11]);
+    let file = serialized_file_with_objects(&[(7, ClassID::AnimatorController, data)]);
+    let mut env = Env::new();
+    env.load_serialized_file("sharedassets0.assets", FileData::from(file)).expect("Load failure");
+    let object = env.objects_with_class(ClassID::AnimatorController).next().expect("Missing controller");
+    assert!(!object.asset.enable_type_tree);
+    let controller = object.read::<AnimatorController>().expect("Read Failure");
+    assert_eq!(controller.name, "Player");
+    assert_eq!(controller.tos.len(), 4);
+    let [layer] = controller.layers.as_slice() else {
+        panic!("Expected one layer");
+    };
+    assert_eq!(layer.name, "Base Layer");
+    let states: Vec<(&str, &str, &StateMotion)> = layer.states.iter().map(|x| (x.name.as_str(), x.full_path.as_str(), &x.motion)).collect();
+    // Run has no full path in the table and falls back to the layer and state names.
+    assert_eq!(states, [("Idle", "Base Layer.Idle", &StateMotion::Clip(0)), ("Run", "Base Layer.Run", &StateMotion::BlendTree(vec![0, 1]))]);
+    assert_eq!(controller.animation_clips.iter().map(|x| x.path_id).collect::<Vec<_>>(), [10, 11]);
+    assert_eq!(controller.state_clips().into_iter().collect::<Vec<_>>(), [("Base Layer.Idle".to_string(), 10)]);
+}
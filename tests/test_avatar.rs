@@ -0,0 +1,24 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::Avatar;
+use unity_rs::{ClassID, Env};
+
+use common::*;
+
+#[test]
+fn test_avatar_without_type_tree() {
+    let tos = [(100, "Armature"), (101, "Armature/Hips"), (102, "Armature/Hips/Spine")];
+    let file = serialized_file_with_objects(&[(3, ClassID::Avatar, avatar("RobotAvatar", &[100, 101, 102], &[100, 102], &[0, -1, 1], &tos))]);
+    let mut env = Env::new();
+    env.load_serialized_file("sharedassets0.assets", FileData::from(file)).expect("Load failure");
+    let object = env.objects_with_class(ClassID::Avatar).next().expect("Missing avatar");
+    assert!(!object.asset.enable_type_tree);
+    let avatar = object.read::<Avatar>().expect("Read Failure");
+    assert_eq!(avatar.name, "RobotAvatar");
+    assert_eq!(avatar.skeleton, [100, 101, 102]);
+    assert_eq!(avatar.human_skeleton, [100, 102]);
+    assert_eq!(avatar.path(101), Some("Armature/Hips"));
+    // The second bone is missing from the rig.
+    assert_eq!(avatar.human_bone_paths(), [Some("Armature"), None, Some("Armature/Hips/Spine")]);
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::BuildSettings;
+use unity_rs::{ClassID, Env};
+
+use common::*;
+
+fn load(data: Vec<u8>) -> Env {
+    let file = serialized_file_with_objects(&[(11, ClassID::BuildSettings, data)]);
+    let mut env = Env::new();
+    env.load_serialized_file("globalgamemanagers", FileData::from(file)).expect("Load failure");
+    env
+}
+
+#[test]
+fn test_build_settings_without_type_tree() {
+    let env = load(build_settings(&["Assets/Scenes/Boot.unity", "Assets/Scenes/Main.unity"], [true, false, true, true], "2017.4.40f1"));
+    let object = env.objects_with_class(ClassID::BuildSettings).next().expect("Missing BuildSettings");
+    assert!(!object.asset.enable_type_tree);
+    let settings = object.read::<BuildSettings>().expect("Read Failure");
+    assert_eq!(settings.scenes, ["Assets/Scenes/Boot.unity", "Assets/Scenes/Main.unity"]);
+    assert_eq!(
+        (settings.has_render_texture, settings.has_pro_version, settings.has_publishing_rights, settings.has_shadows),
+        (Some(true), Some(false), Some(true), Some(true))
+    );
+    assert_eq!(settings.version.as_deref(), Some("2017.4.40f1"));
+    // The build's version wins over the file header's 2017.4.39f1.
+    assert_eq!(env.unity_version().as_deref(), Some("2017.4.40f1"));
+}
+
+#[test]
+fn test_build_settings_without_version() {
+    let env = load(build_settings(&[], [false; 4], "not a version"));
+    let settings = env.objects_with_class(ClassID::BuildSettings).next().expect("Missing BuildSettings").read::<BuildSettings>().expect("Read Failure");
+    assert!(settings.scenes.is_empty());
+    assert_eq!(settings.version, None);
+    assert_eq!(env.unity_version().as_deref(), Some("2017.4.39f1"));
+}
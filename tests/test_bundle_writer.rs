@@ -0,0 +1,130 @@
+mod common;
+
+use std::io::Cursor;
+use unity_rs::bundle::{Bundle, BundleNode, CompressionType};
+use unity_rs::Env;
+
+use common::*;
+
+#[test]
+fn test_bundle_round_trip() {
+    let original = unity_fs_bundle(&serialized_file(), true);
+    let bundle = Bundle::from_reader(Cursor::new(&original)).expect("Read failure");
+    assert_eq!(bundle.nodes.len(), 1);
+    assert_eq!(bundle.node("CAB-test").expect("Missing node").data, serialized_file());
+    assert_eq!(bundle.compression, CompressionType::Lzma);
+
+    for compression in [CompressionType::None, CompressionType::Lz4, CompressionType::Lzma] {
+        let written = Bundle { compression, ..bundle.clone() }.to_bytes().expect("Write failure");
+        let reread = Bundle::from_reader(Cursor::new(&written)).expect("Read failure");
+        assert_eq!(reread.nodes[0].path, "CAB-test");
+        assert_eq!(reread.nodes[0].flags, 4);
+        assert_eq!(reread.nodes[0].data, bundle.nodes[0].data);
+        assert_eq!(reread.compression, compression);
+
+        let mut env = Env::new();
+        env.load_from_slice(&written).expect("Load failure");
+        assert_text_asset(&env);
+    }
+}
+
+#[test]
+fn test_bundle_replace_node() {
+    let mut bundle = Bundle::from_reader(Cursor::new(unity_fs_bundle(&serialized_file(), true))).expect("Read failure");
+    bundle.replace_node("CAB-test", TextAssetFile::new().text("patched text").build()).expect("Replace failure");
+    assert!(bundle.replace_node("CAB-missing", Vec::new()).is_err());
+
+    let mut env = Env::new();
+    env.load_from_slice(&bundle.to_bytes().expect("Write failure")).expect("Load failure");
+    assert_text_asset_is(&env, "patched text");
+}
+
+#[test]
+fn test_repack_text_asset() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&serialized_file(), true)).expect("Load failure");
+    let asset = &mut env.serialized_files[0];
+    let mut object = Vec::new();
+    aligned_string(&mut object, "greeting");
+    aligned_string(&mut object, "patched");
+    asset.replace_object(1, object).expect("Replace failure");
+
+    let mut bundle = Bundle::from_reader(Cursor::new(unity_fs_bundle(&serialized_file(), true))).expect("Read failure");
+    bundle.replace_node("CAB-test", asset.to_bytes().expect("Write failure")).expect("Replace failure");
+    let mut env = Env::new();
+    env.load_from_slice(&bundle.to_bytes().expect("Write failure")).expect("Load failure");
+    assert_text_asset_is(&env, "patched");
+}
+
+#[test]
+fn test_bundle_directory_and_extract() {
+    let bundle = Bundle {
+        version: 6,
+        unity_version: "5.x.x".to_string(),
+        unity_revision: "2017.4.39f1".to_string(),
+        compression: CompressionType::Lz4,
+        nodes: vec![
+            BundleNode {
+                path: "CAB-test".to_string(),
+                flags: 4,
+                offset: 0,
+                data: serialized_file(),
+            },
+            BundleNode {
+                path: "CAB-test.resS".to_string(),
+                flags: 0,
+                offset: 0,
+                data: vec![1, 2, 3, 4, 5],
+            },
+        ],
+    };
+    let mut bundle = Bundle::from_reader(Cursor::new(bundle.to_bytes().expect("Write failure"))).expect("Read failure");
+    bundle.replace_node("CAB-test", vec![0; 3]).expect("Replace failure");
+    let directory = bundle.directory();
+    assert_eq!(directory.len(), 2);
+    assert_eq!(directory[1].path, "CAB-test.resS");
+    assert_eq!(directory[1].offset, serialized_file().len() as i64);
+    assert_eq!(directory[1].size, 5);
+    assert_eq!(bundle.node_data("CAB-test.resS"), Some(&[1, 2, 3, 4, 5][..]));
+
+    let dir = std::path::Path::new("./target/tests/extract");
+    bundle.extract_to(dir).expect("Extract failure");
+    assert_eq!(std::fs::read(dir.join("CAB-test")).expect("Read failure"), [0; 3]);
+    assert_eq!(std::fs::read(dir.join("CAB-test.resS")).expect("Read failure"), [1, 2, 3, 4, 5]);
+}
+
+fn bundle_of(paths: &[&str]) -> Bundle {
+    Bundle {
+        version: 6,
+        unity_version: "5.x.x".to_string(),
+        unity_revision: "2017.4.39f1".to_string(),
+        compression: CompressionType::None,
+        nodes: paths
+            .iter()
+            .enumerate()
+            .map(|(i, path)| BundleNode {
+                path: path.to_string(),
+                flags: 0,
+                offset: 0,
+                data: vec![i as u8],
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_bundle_extract_keeps_node_paths() {
+    let dir = std::path::Path::new("./target/tests/extract_paths");
+    bundle_of(&["a/CAB-test", "b/CAB-test"]).extract_to(dir).expect("Extract failure");
+    assert_eq!(std::fs::read(dir.join("a/CAB-test")).expect("Read failure"), [0]);
+    assert_eq!(std::fs::read(dir.join("b/CAB-test")).expect("Read failure"), [1]);
+}
+
+#[test]
+fn test_bundle_extract_rejects_escaping_paths() {
+    let dir = std::path::Path::new("./target/tests/extract_escape");
+    for path in ["../CAB-test", "a/../../CAB-test", "/tmp/CAB-test", "."] {
+        assert!(bundle_of(&["CAB-first", path]).extract_to(dir).is_err(), "{} was extracted", path);
+    }
+    assert!(!dir.join("CAB-first").exists());
+}
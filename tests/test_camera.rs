@@ -0,0 +1,28 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::Camera;
+use unity_rs::{ClassID, Env};
+
+use common::*;
+
+#[test]
+fn test_camera_without_type_tree() {
+    let file = serialized_file_with_objects(&[(2, ClassID::Camera, camera(1))]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(file)).expect("Load failure");
+    let object = env.objects_with_class(ClassID::Camera).next().expect("Missing camera");
+    assert!(!object.asset.enable_type_tree);
+    let camera = object.read::<Camera>().expect("Read Failure");
+    assert_eq!((camera.game_object.path_id, camera.enabled, camera.clear_flags), (1, true, 2));
+    let color = camera.background_color;
+    assert_eq!((color.r, color.g, color.b, color.a), (0.25, 0.5, 0.75, 1.0));
+    // 2017.4 predates the physical camera.
+    assert_eq!(camera.projection_matrix_mode, None);
+    assert!(camera.physical.is_none());
+    let rect = camera.viewport_rect;
+    assert_eq!((rect.x, rect.y, rect.w, rect.h), (0.0, 0.0, 1.0, 1.0));
+    assert_eq!((camera.near_clip_plane, camera.far_clip_plane, camera.field_of_view), (0.3, 1000.0, 60.0));
+    assert_eq!((camera.orthographic, camera.orthographic_size, camera.depth), (false, 5.0, -1.0));
+    assert_eq!((camera.culling_mask, camera.target_texture.path_id, camera.target_display), (u32::MAX, 0, Some(0)));
+}
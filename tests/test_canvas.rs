@@ -0,0 +1,23 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::Canvas;
+use unity_rs::{ClassID, Env};
+
+use common::*;
+
+#[test]
+fn test_canvas_elements_cycle() {
+    // The child lists its parent as a child of its own.
+    let file = serialized_file_with_objects(&[
+        (1, ClassID::GameObject, game_object("Canvas", 2)),
+        (2, ClassID::Transform, transform(1, &[4], 0)),
+        (3, ClassID::GameObject, game_object("Panel", 4)),
+        (4, ClassID::Transform, transform(3, &[2], 2)),
+        (5, ClassID::Canvas, canvas(1)),
+    ]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(file)).expect("Load failure");
+    let canvas = env.objects_with_class(ClassID::Canvas).next().expect("Missing canvas").read::<Canvas>().expect("Read Failure");
+    assert!(canvas.elements().expect("Read Failure").is_empty());
+}
@@ -0,0 +1,390 @@
+mod common;
+
+use unity_rs::bundle::{CompressionType, FileData};
+use unity_rs::classes::{GameObject, PPtr, TextAsset};
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::{ClassID, Env, LoadWarning, Object, OwnedObject, UnityError, UnityResult};
+
+use common::*;
+
+#[test]
+fn test_load_file_errors_carry_path() {
+    let path = std::env::temp_dir().join("unity_rs_missing_file.ab");
+    let error = Env::new().load_from_file(&path).expect_err("Loaded a missing file");
+    match &error {
+        UnityError::LoadFile { path: error_path, source } => {
+            assert_eq!(error_path, &path);
+            assert!(matches!(source.as_ref(), UnityError::Io(_)));
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+    assert!(error.to_string().contains("unity_rs_missing_file.ab"));
+}
+
+#[test]
+fn test_missing_dependencies() {
+    let externals = ["archive:/CAB-textures/CAB-textures", "Library/unity default resources"];
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-char", FileData::from(TextAssetFile::new().externals(&externals).build())).expect("Load failure");
+    env.load_serialized_file("CAB-other", FileData::from(TextAssetFile::new().externals(&externals[1..]).build())).expect("Load failure");
+    let missing: Vec<&str> = env.missing_dependencies().iter().map(|x| x.path_name.as_str()).collect();
+    assert_eq!(missing, externals);
+
+    let mut data = 1i32.to_le_bytes().to_vec();
+    data.extend_from_slice(&1i64.to_le_bytes());
+    let pointer = |env: &Env| -> UnityResult<Option<Vec<u8>>> {
+        let object = env.objects().find(|x| x.asset.path == "CAB-char").expect("Missing object");
+        let pptr = PPtr::<TextAsset>::load(&object, &mut Reader::new(&data, ByteOrder::Little))?;
+        Ok(pptr.deref()?.map(|x| x.script))
+    };
+    assert!(pointer(&env).is_err());
+
+    // The file pointed into can be loaded after the one pointing.
+    env.load_serialized_file("CAB-textures", FileData::from(TextAssetFile::new().text("texture text").build())).expect("Load failure");
+    let missing: Vec<&str> = env.missing_dependencies().iter().map(|x| x.path_name.as_str()).collect();
+    assert_eq!(missing, &externals[1..]);
+    assert_eq!(pointer(&env).expect("Resolve failure"), Some(b"texture text".to_vec()));
+}
+
+#[test]
+fn test_object_by_path_id() {
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-first", FileData::from(TextAssetFile::new().text("first").build())).expect("Load failure");
+    env.load_serialized_file("CAB-second", FileData::from(TextAssetFile::new().text("second").build())).expect("Load failure");
+    let script = |object: Object| object.read::<TextAsset>().expect("Read Failure").script;
+
+    // Both files hold path id 1.
+    assert_eq!(script(env.object_by_path_id(1).expect("Missing object")), b"first");
+    assert_eq!(script(env.object_in_file("CAB-first", 1).expect("Missing object")), b"first");
+    assert_eq!(script(env.object_in_file("archive:/cab-second/CAB-second", 1).expect("Missing object")), b"second");
+    assert!(env.object_by_path_id(2).is_none());
+    assert!(env.object_in_file("CAB-second", 2).is_none());
+    assert!(env.object_in_file("CAB-third", 1).is_none());
+}
+
+#[test]
+fn test_load_data_folder() {
+    let dir = std::path::Path::new("./target/tests/data_folder");
+    let _ = std::fs::remove_dir_all(dir);
+    std::fs::create_dir_all(dir.join("Managed")).expect("CreateError");
+    std::fs::write(dir.join("sharedassets0.assets"), serialized_file()).expect("Write failure");
+    std::fs::write(dir.join("sharedassets0.assets.resS"), b"streamed").expect("Write failure");
+    std::fs::write(dir.join("data.unity3d"), unity_fs_bundle(&TextAssetFile::new().text("bundled").build(), true)).expect("Write failure");
+    std::fs::write(dir.join("Managed/Assembly-CSharp.dll"), b"MZ not an asset").expect("Write failure");
+
+    let mut env = Env::new();
+    env.load_from_file(dir.join("sharedassets0.assets")).expect("Load failure");
+    assert_text_asset(&env);
+    assert_eq!(env.serialized_files[0].path, "sharedassets0.assets");
+    let streamed = env.get_loaded_file("sharedassets0.assets.resS").expect("Missing resS");
+    assert_eq!(streamed.read(0..streamed.len()).expect("Read failure").as_slice(), b"streamed");
+
+    // Only the file's own resources are registered, and one that cannot be read is skipped.
+    let other = std::path::Path::new("./target/tests/data_folder_resources");
+    let _ = std::fs::remove_dir_all(other);
+    std::fs::create_dir_all(other.join("level1.resource")).expect("CreateError");
+    std::fs::write(other.join("level1"), serialized_file()).expect("Write failure");
+    std::fs::write(other.join("level10.resS"), b"streamed").expect("Write failure");
+    let mut env = Env::new();
+    env.load_from_file(other.join("level1")).expect("Load failure");
+    assert!(env.get_loaded_file("level10.resS").is_none());
+    assert!(env.get_loaded_file("level1.resource").is_none());
+
+    let mut env = Env::new();
+    env.load_data_folder(dir).expect("Load failure");
+    assert_eq!(env.serialized_files.len(), 2);
+    assert!(env.find_serialized_file("sharedassets0.assets").is_some());
+    assert!(env.find_serialized_file("CAB-test").is_some());
+    assert!(env.get_loaded_file("sharedassets0.assets.resS").is_some());
+}
+
+#[test]
+fn test_load_from_dir() {
+    let dir = std::path::Path::new("./target/tests/load_from_dir");
+    let _ = std::fs::remove_dir_all(dir);
+    std::fs::create_dir_all(dir.join("sub")).expect("CreateError");
+    let bundle = unity_fs_bundle(&TextAssetFile::new().text("bundled").build(), true);
+    std::fs::write(dir.join("a.ab"), &bundle).expect("Write failure");
+    std::fs::write(dir.join("b.bundle"), &bundle[..bundle.len() - 10]).expect("Write failure");
+    std::fs::write(dir.join("c.bin"), bundle).expect("Write failure");
+    std::fs::write(dir.join("readme.txt"), b"not an asset").expect("Write failure");
+    std::fs::write(dir.join("sub/level0"), serialized_file()).expect("Write failure");
+    std::fs::write(dir.join("sub/level0.resS"), b"streamed").expect("Write failure");
+
+    let mut env = Env::new();
+    let results = env.load_from_dir(dir, false).expect("Load failure");
+    let names: Vec<(&str, bool)> = results.iter().map(|(path, result)| (path.file_name().and_then(|x| x.to_str()).unwrap_or_default(), result.is_ok())).collect();
+    assert_eq!(names, [("a.ab", true), ("b.bundle", false), ("c.bin", true)]);
+    assert_eq!(env.serialized_files.len(), 2);
+
+    let mut env = Env::new();
+    let results = env.load_from_dir(dir, true).expect("Load failure");
+    assert_eq!(results.len(), 5);
+    assert!(env.find_serialized_file("level0").is_some());
+    assert!(env.get_loaded_file("level0.resS").is_some());
+    assert!(Env::new().load_data_folder(dir).is_err());
+    assert!(Env::new().load_from_dir(dir.join("missing"), true).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_load_from_dir_skips_linked_folders() {
+    let dir = std::path::Path::new("./target/tests/load_from_dir_links");
+    let _ = std::fs::remove_dir_all(dir);
+    std::fs::create_dir_all(dir.join("sub")).expect("CreateError");
+    std::fs::write(dir.join("sub/level0"), serialized_file()).expect("Write failure");
+    std::os::unix::fs::symlink("..", dir.join("sub/parent")).expect("Link failure");
+    std::os::unix::fs::symlink("level0", dir.join("sub/level1")).expect("Link failure");
+
+    let mut env = Env::new();
+    let results = env.load_from_dir(dir, true).expect("Load failure");
+    let names: Vec<&str> = results.iter().map(|(path, _)| path.file_name().and_then(|x| x.to_str()).unwrap_or_default()).collect();
+    assert_eq!(names, ["level0", "level1"]);
+}
+
+#[test]
+fn test_scene_roots() {
+    let scene = serialized_file_with_objects(&[
+        (1, ClassID::OcclusionCullingSettings, vec![0; 4]),
+        (2, ClassID::RenderSettings, vec![0; 4]),
+        (10, ClassID::GameObject, game_object("Camera", 11)),
+        (11, ClassID::Transform, transform(10, &[], 0)),
+        (3, ClassID::GameObject, game_object("World", 4)),
+        (4, ClassID::Transform, transform(3, &[6], 0)),
+        (5, ClassID::GameObject, game_object("Ground", 6)),
+        (6, ClassID::Transform, transform(5, &[], 4)),
+    ]);
+    let prefab = serialized_file_with_objects(&[(3, ClassID::GameObject, game_object("Prefab", 4)), (4, ClassID::Transform, transform(3, &[], 0))]);
+
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(scene)).expect("Load failure");
+    env.load_serialized_file("sharedassets0.assets", FileData::from(prefab)).expect("Load failure");
+    assert!(env.serialized_files[0].is_scene());
+    assert!(!env.serialized_files[1].is_scene());
+    assert_eq!(env.roots().count(), 3);
+
+    let names: Vec<String> = env.scene_roots().map(|x| x.read::<GameObject>().expect("Read Failure").name).collect();
+    assert_eq!(names, ["Camera", "World"]);
+}
+
+#[test]
+fn test_objects_of_class() {
+    let file = serialized_file_with_objects(&[
+        (1, ClassID::GameObject, game_object("Camera", 2)),
+        (2, ClassID::Transform, transform(1, &[], 0)),
+        (5, ClassID::GameObject, game_object("Ground", 6)),
+        (6, ClassID::Transform, transform(5, &[], 0)),
+        (3, ClassID::GameObject, vec![0; 4]),
+    ]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(file)).expect("Load failure");
+    assert_eq!(env.objects_with_class(ClassID::Transform).map(|x| x.info.path_id).collect::<Vec<_>>(), [2, 6]);
+
+    let game_objects: Vec<(i64, Option<String>)> = env.objects_of::<GameObject>().map(|(object, result)| (object.info.path_id, result.ok().map(|x| x.name))).collect();
+    assert_eq!(game_objects, [(1, Some("Camera".to_string())), (5, Some("Ground".to_string())), (3, None)]);
+}
+
+#[test]
+fn test_objects_order() {
+    let texts = ["first", "second", "third"];
+    let load = || {
+        let mut env = Env::new();
+        env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().texts(&texts).type_tree().build(), true)).expect("Load failure");
+        let objects = [(9, ClassID::TextAsset, Vec::new()), (3, ClassID::GameObject, Vec::new())];
+        env.load_from_slice(&unity_fs_bundle(&serialized_file_with_objects(&objects), false)).expect("Load failure");
+        env
+    };
+    let order = |env: &Env| env.objects().map(|x| (x.asset.path.clone(), x.info.path_id)).collect::<Vec<_>>();
+    let (first, second) = (load(), load());
+    assert_eq!(order(&first), order(&second));
+    let path_ids: Vec<i64> = order(&first).into_iter().map(|(_, path_id)| path_id).collect();
+    assert_eq!(path_ids, [1, 2, 3, 9, 3]);
+    let text_assets: Vec<i64> = first.objects_with_class(ClassID::TextAsset).map(|x| x.info.path_id).collect();
+    assert_eq!(text_assets, [1, 2, 3, 9]);
+}
+
+#[test]
+fn test_find_objects() {
+    let file = serialized_file_with_objects(&[
+        (1, ClassID::GameObject, game_object("Portrait", 2)),
+        (2, ClassID::Transform, transform(1, &[], 0)),
+        (3, ClassID::GameObject, game_object("Camera", 4)),
+        (4, ClassID::Transform, transform(3, &[], 0)),
+        (5, ClassID::AssetBundle, asset_bundle("ui", &[("assets/ui/portrait_frame.prefab", 2)])),
+    ]);
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-ui", FileData::from(file)).expect("Load failure");
+    let find = |pattern: &str, class: Option<ClassID>| env.find_objects(pattern, class).iter().map(|x| x.info.path_id).collect::<Vec<_>>();
+
+    // The Transform has no name of its own and is found by its container path.
+    assert_eq!(find("portrait*", None), [1, 2]);
+    assert_eq!(find("portrait*", Some(ClassID::GameObject)), [1]);
+    assert_eq!(find("TRAIT", None), [1, 2]);
+    assert_eq!(find("assets/ui/*.prefab", None), [2]);
+    assert_eq!(find("?amera", None), [3]);
+    assert_eq!(find("u", None), [2, 5]);
+    assert!(find("trait*", None).is_empty());
+}
+
+#[test]
+fn test_peek_name() {
+    let mut text = Vec::new();
+    aligned_string(&mut text, "Player");
+    aligned_string(&mut text, "hello");
+    let file = serialized_file_with_objects(&[
+        (1, ClassID::GameObject, game_object("Player", 2)),
+        (2, ClassID::Transform, transform(1, &[], 0)),
+        (3, ClassID::MonoBehaviour, mono_behaviour("PlayerController", 1, 9)),
+        (4, ClassID::TextAsset, text),
+    ]);
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-player", FileData::from(file)).expect("Load failure");
+    let name = |path_id: i64| env.objects().find(|x| x.info.path_id == path_id).expect("Missing object").peek_name().expect("Read failure");
+
+    // The GameObject's components and layer, and the MonoBehaviour's GameObject, enabled flag and
+    // script come before the name.
+    assert_eq!(name(1).as_deref(), Some("Player"));
+    assert_eq!(name(2), None);
+    assert_eq!(name(3).as_deref(), Some("PlayerController"));
+    assert_eq!(name(4).as_deref(), Some("Player"));
+
+    let found: Vec<(i64, ClassID)> = env.find_by_name("Player").map(|x| (x.info.path_id, x.class())).collect();
+    assert_eq!(found, [(1, ClassID::GameObject), (4, ClassID::TextAsset)]);
+    assert_eq!(env.find_by_name("PlayerController").count(), 1);
+    assert_eq!(env.find_by_name("player").count(), 0);
+}
+
+#[test]
+fn test_object_at_path() {
+    let bundle = |text: &str| {
+        serialized_file_with_objects(&[
+            (1, ClassID::GameObject, game_object(text, 2)),
+            (2, ClassID::Transform, transform(1, &[], 0)),
+            (3, ClassID::AssetBundle, asset_bundle("ui", &[("Assets/UI/Portrait.prefab", 1), ("assets/ui/portrait.prefab", 1)])),
+        ])
+    };
+    let names = |objects: Vec<Object>| objects.iter().map(|x| x.read::<GameObject>().expect("Read Failure").name).collect::<Vec<_>>();
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-old", FileData::from(bundle("Old"))).expect("Load failure");
+    assert_eq!(names(env.object_at_path("assets/ui/PORTRAIT.prefab")), ["Old"]);
+    assert_eq!(env.container().len(), 2);
+
+    // Both bundles keep their candidates for the path, in load order.
+    env.load_serialized_file("CAB-new", FileData::from(bundle("New"))).expect("Load failure");
+    assert_eq!(names(env.object_at_path("ASSETS/UI/PORTRAIT.PREFAB")), ["Old", "New"]);
+    assert_eq!(names(env.container().remove("Assets/UI/Portrait.prefab").expect("Missing path")), ["Old", "New"]);
+    assert!(env.object_at_path("assets/ui/missing.prefab").is_empty());
+
+    // Entries follow their files when the list is changed directly.
+    env.serialized_files.swap(0, 1);
+    assert_eq!(names(env.object_at_path("assets/ui/portrait.prefab")), ["Old", "New"]);
+    env.serialized_files.remove(0);
+    assert_eq!(names(env.object_at_path("assets/ui/portrait.prefab")), ["Old"]);
+}
+
+#[test]
+fn test_warnings() {
+    // The TextAsset's class id follows the 20 byte header, the version, the platform, the type
+    // tree flag and the type count.
+    let mut file = serialized_file();
+    let class = 20 + 12 + 4 + 1 + 4;
+    file[class..class + 4].copy_from_slice(&99999i32.to_le_bytes());
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-unknown", FileData::from(file)).expect("Load failure");
+    let expected = [
+        LoadWarning::UnknownClass {
+            file: "CAB-unknown".to_string(),
+            class_id: 99999,
+            objects: 1,
+        },
+        LoadWarning::MissingTypeTree {
+            file: "CAB-unknown".to_string(),
+            class_id: 99999,
+            objects: 1,
+        },
+    ];
+    assert_eq!(env.warnings(), expected);
+
+    let bundle = |text: &str| {
+        serialized_file_with_objects(&[
+            (1, ClassID::GameObject, game_object(text, 2)),
+            (2, ClassID::Transform, transform(1, &[], 0)),
+            (3, ClassID::AssetBundle, asset_bundle("ui", &[("Assets/UI/Portrait.prefab", 1), ("assets/ui/portrait.prefab", 1), ("assets/ui/frame.prefab", 2)])),
+        ])
+    };
+    let mut env = Env::new();
+    env.load_serialized_file("CAB-old", FileData::from(bundle("Old"))).expect("Load failure");
+    assert!(env.warnings().is_empty());
+    env.load_serialized_file("CAB-new", FileData::from(bundle("New"))).expect("Load failure");
+    let objects = |path_id: i64| vec![("CAB-old".to_string(), path_id), ("CAB-new".to_string(), path_id)];
+    let expected = [
+        LoadWarning::DuplicateContainerPath {
+            path: "Assets/UI/Portrait.prefab".to_string(),
+            class: ClassID::GameObject,
+            objects: objects(1),
+        },
+        LoadWarning::DuplicateContainerPath {
+            path: "assets/ui/frame.prefab".to_string(),
+            class: ClassID::Transform,
+            objects: objects(2),
+        },
+    ];
+    assert_eq!(env.warnings(), expected);
+}
+
+#[test]
+fn test_summary() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().texts(&["first", "second"]).type_tree().build(), true)).expect("Load failure");
+    env.load_from_slice(&unity_fs_bundle(&serialized_file(), false)).expect("Load failure");
+    let summary = env.summary();
+    assert_eq!((summary.serialized_files, summary.objects), (2, 3));
+    assert_eq!(summary.compressions, [CompressionType::Lzma, CompressionType::None]);
+    assert_eq!(summary.classes.len(), 1);
+    assert_eq!((summary.classes[0].class(), summary.classes[0].count, summary.classes[0].bytes), (ClassID::TextAsset, 3, summary.bytes));
+    let sizes: Vec<usize> = summary.largest_objects.iter().map(|x| x.bytes).collect();
+    assert!(sizes.windows(2).all(|x| x[0] >= x[1]), "{:?}", sizes);
+    assert!(summary.to_string().contains("TextAsset"), "{}", summary);
+
+    let usage = env.memory_usage();
+    assert!(usage.file_data > 0);
+    assert_eq!(usage.images, 0);
+    assert_eq!(usage.total(), usage.file_data + usage.decompressed_blocks + usage.object_data);
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_read_objects_across_threads() {
+    assert_send_sync::<Env>();
+    assert_send_sync::<Object>();
+    assert_send_sync::<Reader>();
+    assert_send_sync::<TextAsset>();
+    assert_send_sync::<GameObject>();
+
+    // Blocks of a bundle loaded from data are decompressed as the threads read them.
+    let texts: Vec<String> = (0..8).map(|i| format!("text {}", i)).collect();
+    let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+    let mut env = Env::new();
+    env.load_from_data(FileData::from(unity_fs_bundle(&TextAssetFile::new().texts(&texts).type_tree().build(), true))).expect("Load failure");
+    let objects: Vec<Object> = env.objects().collect();
+    let read: Vec<Vec<u8>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = objects.iter().map(|object| scope.spawn(move || object.read::<TextAsset>().expect("Read Failure").script)).collect();
+        handles.into_iter().map(|x| x.join().expect("Thread panicked")).collect()
+    });
+    assert_eq!(read, texts.iter().map(|x| x.as_bytes()).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_owned_objects_outlive_env() {
+    let mut env = Env::new();
+    let file = TextAssetFile::new().texts(&["first", "second"]).type_tree().build();
+    env.load_from_data(FileData::from(unity_fs_bundle(&file, true))).expect("Load failure");
+    env.load_from_slice(&unity_fs_bundle(&serialized_file(), true)).expect("Load failure");
+    let owned: Vec<OwnedObject> = env.objects().filter(|x| x.class() == ClassID::TextAsset).map(|x| x.to_owned()).collect();
+    drop(env);
+
+    assert_send_sync::<OwnedObject>();
+    let read = std::thread::spawn(move || owned.iter().map(|x| x.read_field::<String>("m_Script").expect("Read Failure")).collect::<Vec<_>>());
+    assert_eq!(read.join().expect("Thread panicked"), ["first", "second", TEXT]);
+}
@@ -1,4 +1,5 @@
 use unity_rs::classes::Texture2D;
+use unity_rs::texture_decoder::implements::{DXT1, DXT5};
 use unity_rs::{object, Env};
 
 #[test]
@@ -15,10 +16,22 @@ fn test_load_texture2d() {
         let s: Texture2D = obj.read().expect("Read Failure");
         s.decode_image().expect("Decode Failure").save(format!("./target/tests/Texture2D {}.png", s.name)).expect("Save Failure");
         let nodes = &obj.info.serialized_type.type_tree.nodes;
-        let mut reader = obj.info.get_reader();
+        let mut reader = obj.info.get_reader().expect("Reader Failure");
         let mut deserializer = object::Deserializer::new(nodes, &mut reader);
         let file = std::fs::File::create(format!("./target/tests/Texture2D {}.json", s.name)).expect("Open Json Failure");
         let mut serializer = serde_json::Serializer::pretty(file);
         serde_transcode::transcode(&mut deserializer, &mut serializer).expect("Transcode Failure");
     }
 }
+
+#[test]
+fn test_decode_dxt_bad_size() {
+    let data = [0u8; 64];
+    assert!(DXT1::decode(&data, 8, 8).is_ok());
+    assert!(DXT5::decode(&data, 8, 8).is_ok());
+    assert!(DXT1::decode(&data, 0, 64).is_ok());
+    for (width, height) in [(u32::MAX, u32::MAX), (1 << 20, 1 << 20), (64, 64)] {
+        assert!(DXT1::decode(&data, width, height).is_err(), "{}x{}", width, height);
+        assert!(DXT5::decode(&data, width, height).is_err(), "{}x{}", width, height);
+    }
+}
@@ -0,0 +1,314 @@
+mod common;
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes128;
+use std::io::Cursor;
+use std::sync::Arc;
+use unity_rs::bundle::{BlockDecryptor, CompressionType, FileData, LoadOptions};
+use unity_rs::{ClassID, Env, LoadWarning, UnityError};
+
+use common::*;
+
+#[test]
+fn test_load_unity_fs_lzma() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&serialized_file(), true)).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_load_unity_web() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_web_bundle(&serialized_file())).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_load_unity_fs_from_reader() {
+    let mut env = Env::new();
+    env.load_from_reader(Cursor::new(unity_fs_bundle(&serialized_file(), true))).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_load_uncompressed_unity_fs_from_data() {
+    let mut env = Env::new();
+    env.load_from_data(FileData::from(unity_fs_bundle(&serialized_file(), false))).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_uncompressed_blocks_share_file_data() {
+    let data = FileData::from(unity_fs_bundle(&serialized_file(), false));
+    let mut env = Env::new();
+    env.load_from_data(data.clone()).expect("Load failure");
+    let object = env.objects().find(|x| x.class() == ClassID::TextAsset).expect("Missing TextAsset");
+    let raw = object.info.raw_data().expect("Read Failure");
+    assert!(data.as_ptr_range().contains(&raw.as_ptr()));
+
+    // The bundle's buffer is the only one, counted once and never copied into blocks.
+    let usage = env.memory_usage();
+    assert_eq!((usage.file_data, usage.decompressed_blocks), (data.len(), 0));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_load_mapped_file_shares_map() {
+    let bundle = unity_fs_bundle(&serialized_file(), false);
+    let path = std::env::temp_dir().join("unity_rs_mapped.ab");
+    std::fs::write(&path, &bundle).expect("Write failure");
+    let mut env = Env::new();
+    env.load_from_file(&path).expect("Load failure");
+    assert_text_asset(&env);
+    // The serialized file reads from the map of the whole bundle rather than a copy of its node.
+    let usage = env.memory_usage();
+    assert_eq!((usage.file_data, usage.decompressed_blocks), (bundle.len(), 0));
+    drop(env);
+    std::fs::remove_file(&path).expect("Remove failure");
+}
+
+#[test]
+fn test_load_lzma_unity_fs_from_data_lazily() {
+    let mut env = Env::new();
+    env.load_from_data(FileData::from(unity_fs_bundle(&serialized_file(), true))).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_load_short_block() {
+    // The first block decompresses to 8 bytes less than it claims. Either way of loading pads it
+    // with zeros, which keeps the second block where the blocks info puts it.
+    let file = serialized_file();
+    let half = file.len() / 2;
+    let first = lz4_flex::compress(&file[..half]);
+    let bundle = UnityFsBundle::new().block(&first, 2, half + 8).block(&file[half..], 0, file.len() - half).build();
+    let mut lazy = Env::new();
+    lazy.load_from_data(FileData::from(bundle.clone())).expect("Load failure");
+    let mut streamed = Env::new();
+    streamed.load_from_slice(&bundle).expect("Load failure");
+    for env in [&lazy, &streamed] {
+        let loaded = env.get_loaded_file("CAB-test").expect("Missing file");
+        let data = loaded.read(0..loaded.len()).expect("Read failure");
+        assert_eq!(data.len(), file.len() + 8);
+        assert_eq!(&data[..half], &file[..half]);
+        assert_eq!(&data[half..half + 8], &[0; 8]);
+        assert_eq!(&data[half + 8..], &file[half..]);
+    }
+    assert!(lazy.warnings().is_empty());
+    assert_eq!(streamed.warnings().len(), 1);
+}
+
+#[test]
+fn test_block_size_warning() {
+    // The first block decompresses to 8 bytes less than it claims.
+    let first = lz4_flex::compress(&[0; 64]);
+    let bundle = UnityFsBundle::new().block(&first, 2, 72).block(&[0; 64], 0, 64).build();
+    let mut env = Env::new();
+    env.load_from_slice(&bundle).expect("Load failure");
+    let expected = LoadWarning::BlockSize {
+        file: "CAB-test".to_string(),
+        block: 0,
+        expected: 72,
+        actual: 64,
+    };
+    assert_eq!(env.warnings(), [expected]);
+}
+
+#[test]
+fn test_load_node_outside_blocks() {
+    // Directory sizes are untrusted, a negative or too large one fails instead of wrapping.
+    let file = serialized_file();
+    let compressed = lzma_block(&file);
+    for (block, flags) in [(&file, 0), (&compressed, 1)] {
+        for size in [-1, file.len() as i64 + 1, i64::MAX] {
+            let bundle = UnityFsBundle::new().block(block, flags, file.len()).node_size(size).build();
+            let error = Env::new().load_from_data(FileData::from(bundle.clone())).expect_err("Loaded a node outside the blocks");
+            assert!(error.root_cause().to_string().contains("lies outside"), "{}", error);
+            assert!(Env::new().load_from_slice(&bundle).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_bundle_errors_carry_section() {
+    let bundle = UnityFsBundle::new().block(&[0xff; 32], 2, 100).build();
+    let data_start = bundle.len() - 32;
+    let error = Env::new().load_from_slice(&bundle).expect_err("Loaded a bad block");
+    let UnityError::Parse { context, .. } = &error else { panic!("unexpected error {:?}", error) };
+    assert_eq!((context.file.as_str(), context.section.as_str(), context.offset), ("bundle", "blocks[0]", data_start));
+
+    let mut env = Env::new();
+    env.load_from_data(FileData::from(bundle)).expect("Load failure");
+    let error = env.get_loaded_file("CAB-test").expect("Missing file").read(0..100).expect_err("Read a bad block");
+    let UnityError::Parse { context, .. } = &error else { panic!("unexpected error {:?}", error) };
+    assert_eq!((context.file.as_str(), context.section.as_str(), context.offset), ("bundle", "blocks[0]", data_start));
+}
+
+#[test]
+fn test_load_obfuscated_unity_fs() {
+    let bundle = unity_fs_bundle(&serialized_file(), true);
+    let mut prefixed = b"junk prefix".to_vec();
+    prefixed.extend_from_slice(&bundle);
+
+    let mut env = Env::new();
+    env.load_from_slice_with(&prefixed, &LoadOptions { scan_limit: Some(1024), ..Default::default() }).expect("Load failure");
+    assert_text_asset(&env);
+
+    let mut renamed = prefixed.clone();
+    renamed[11..18].copy_from_slice(b"Abc1234");
+    let options = LoadOptions {
+        offset: 11,
+        signature: Some(b"Abc1234".to_vec()),
+        ..Default::default()
+    };
+    let mut env = Env::new();
+    env.load_from_slice_with(&renamed, &options).expect("Load failure");
+    assert_text_asset(&env);
+
+    let error = Env::new().load_from_slice_with(&renamed, &LoadOptions::default()).expect_err("Loaded without signature");
+    assert!(error.root_cause().to_string().contains("saw \"junk prefixAbc1234"), "{}", error);
+}
+
+#[test]
+fn test_load_malformed_unity_fs() {
+    // An LZ4 block claiming to decompress to 1 GiB.
+    let file = serialized_file();
+    let bundle = UnityFsBundle::new().block(&file, CompressionType::Lz4 as u16, 1 << 30).build();
+    let error = Env::new().load_from_slice_with(&bundle, &LoadOptions::default()).expect_err("Loaded a malformed block");
+    assert!(error.root_cause().to_string().contains("cannot decompress"), "{}", error);
+
+    let bundle = unity_fs_bundle(&file, true);
+    let options = LoadOptions {
+        max_block_size: Some(64),
+        ..Default::default()
+    };
+    let error = Env::new().load_from_slice_with(&bundle, &options).expect_err("Loaded a block over the limit");
+    assert!(error.root_cause().to_string().contains("cannot decompress"), "{}", error);
+}
+
+/// Encrypts `plain` the way UnityCN info and signature blocks are stored.
+fn unity_cn_field(cipher: &Aes128, key: [u8; 16], plain: &[u8; 16]) -> Vec<u8> {
+    let mut block = GenericArray::from(key);
+    cipher.encrypt_block(&mut block);
+    let mut result: Vec<u8> = plain.iter().zip(block.iter()).map(|(x, k)| x ^ k).collect();
+    result.extend_from_slice(&key);
+    result
+}
+
+/// Applies UnityCN to an LZ4 block for tables where every substituted nibble is shifted by 4.
+fn unity_cn_encrypt(data: &mut [u8]) {
+    let shift = |x: u8| ((((x >> 4) + 4) & 0xf) << 4) | (((x & 0xf) + 4) & 0xf);
+    let mut offset = 0;
+    let encrypt = |data: &mut [u8], offset: &mut usize| {
+        let x = data[*offset];
+        data[*offset] = shift(x);
+        *offset += 1;
+        x
+    };
+    while offset < data.len() {
+        let token = encrypt(data, &mut offset);
+        let mut literal_len = (token >> 4) as usize;
+        if literal_len == 0xf {
+            loop {
+                let x = encrypt(data, &mut offset);
+                literal_len += x as usize;
+                if x != 0xff {
+                    break;
+                }
+            }
+        }
+        offset += literal_len;
+        if offset < data.len() {
+            encrypt(data, &mut offset);
+            encrypt(data, &mut offset);
+            if token & 0xf == 0xf {
+                while encrypt(data, &mut offset) == 0xff {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_load_unity_cn() {
+    let key = [7; 16];
+    let cipher = Aes128::new(GenericArray::from_slice(&key));
+    // Index is the identity and every Sub nibble is 1, so decryption subtracts 4 from each nibble.
+    let info = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11];
+    let mut unity_cn = 0u32.to_be_bytes().to_vec();
+    unity_cn.extend(unity_cn_field(&cipher, [1; 16], &info));
+    unity_cn.push(0);
+    unity_cn.extend(unity_cn_field(&cipher, [2; 16], b"#$unity3dchina!@"));
+    unity_cn.push(0);
+
+    let file = serialized_file();
+    let mut block = lz4_flex::compress(&file);
+    unity_cn_encrypt(&mut block);
+    let bundle = UnityFsBundle::new().revision("2021.3.10f1").flags(1 | 0x400).extra_header(&unity_cn).block(&block, 2 | 0x100, file.len()).build();
+
+    let options = LoadOptions { unitycn_key: Some(key), ..Default::default() };
+    let mut env = Env::new();
+    env.load_from_slice_with(&bundle, &options).expect("Load failure");
+    assert_text_asset(&env);
+
+    let wrong_key = LoadOptions {
+        unitycn_key: Some([8; 16]),
+        ..Default::default()
+    };
+    assert!(Env::new().load_from_slice_with(&bundle, &wrong_key).is_err());
+}
+
+/// XORs blocks with their index and the blocks info with a fixed byte.
+struct XorDecryptor;
+
+impl BlockDecryptor for XorDecryptor {
+    fn decrypt(&self, block_index: usize, _flags: u16, data: &mut [u8]) {
+        data.iter_mut().for_each(|x| *x ^= 0x5a ^ block_index as u8);
+    }
+
+    fn decrypt_blocks_info(&self, _flags: u32, data: &mut [u8]) {
+        data.iter_mut().for_each(|x| *x ^= 0xa5);
+    }
+}
+
+#[test]
+fn test_load_with_block_decryptor() {
+    let file = serialized_file();
+    let mut block = lzma_block(&file);
+    block.iter_mut().for_each(|x| *x ^= 0x5a);
+    let mut bundle = UnityFsBundle::new().block(&block, 1, file.len()).build();
+    let blocks_info_start = b"UnityFS\0".len() + 4 + b"5.x.x\0".len() + b"2017.4.39f1\0".len() + 20;
+    let blocks_info_size = u32::from_be_bytes(bundle[blocks_info_start - 12..blocks_info_start - 8].try_into().unwrap()) as usize;
+    bundle[blocks_info_start..blocks_info_start + blocks_info_size].iter_mut().for_each(|x| *x ^= 0xa5);
+
+    let options = LoadOptions {
+        decryptor: Some(Arc::new(XorDecryptor)),
+        ..Default::default()
+    };
+    let mut env = Env::new();
+    env.load_from_slice_with(&bundle, &options).expect("Load failure");
+    assert_text_asset(&env);
+
+    let mut env = Env::new();
+    env.load_from_data_with(FileData::from(bundle), &options).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_load_split_files() {
+    let bundle = unity_fs_bundle(&serialized_file(), true);
+    // Split inside the header, inside the blocks info and inside the data block.
+    let cuts = [0, 5, 60, bundle.len() - 20, bundle.len()];
+    let dir = std::path::Path::new("./target/tests/split");
+    std::fs::create_dir_all(dir).expect("CreateError");
+    let mut paths = Vec::new();
+    for (index, range) in cuts.windows(2).enumerate() {
+        let path = dir.join(format!("test.ab.split{}", index));
+        std::fs::write(&path, &bundle[range[0]..range[1]]).expect("Write failure");
+        paths.push(path);
+    }
+
+    let mut env = Env::new();
+    env.load_split_files(&paths).expect("Load failure");
+    assert_text_asset(&env);
+}
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use serde_json::json;
+use unity_rs::asset::SerializedType;
+use unity_rs::object::Deserializer;
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::typetree::{TypeTree, TypeTreeBuilder, TypeTreeNode};
+
+fn aligned_string(data: &mut Vec<u8>, s: &str) {
+    data.extend_from_slice(&(s.len() as i32).to_le_bytes());
+    data.extend_from_slice(s.as_bytes());
+    data.resize(data.len().next_multiple_of(4), 0);
+}
+
+fn managed_type(data: &mut Vec<u8>, class: &str, namespace: &str, assembly: &str) {
+    for s in [class, namespace, assembly] {
+        aligned_string(data, s);
+    }
+}
+
+fn ref_types() -> Vec<SerializedType> {
+    let nodes = TypeTreeBuilder::new("Weapon").field("int", "m_Damage").string("m_Label").build();
+    vec![SerializedType {
+        klass_name: "Weapon".to_string(),
+        name_space: "Game".to_string(),
+        asm_name: "Assembly-CSharp".to_string(),
+        type_tree: TypeTree { nodes, ..TypeTree::default() },
+        ..SerializedType::default()
+    }]
+}
+
+/// A MonoBehaviour with a `[SerializeReference]` field, a registry of `version` and a field after.
+fn nodes(registry: impl FnOnce(TypeTreeBuilder) -> TypeTreeBuilder) -> Vec<TypeTreeNode> {
+    TypeTreeBuilder::new("MonoBehaviour")
+        .nested("managedReference", "m_Item", |b| b.field("SInt64", "rid"))
+        .nested("ManagedReferencesRegistry", "references", |b| registry(b.field("int", "version")))
+        .field("int", "m_Last")
+        .build()
+}
+
+fn referenced_type(b: TypeTreeBuilder) -> TypeTreeBuilder {
+    b.nested("ReferencedManagedType", "type", |b| b.string("class").string("ns").string("asm")).nested("ReferencedObjectData", "data", |b| b)
+}
+
+#[derive(Deserialize)]
+struct Last {
+    #[serde(rename = "m_Last")]
+    last: i32,
+}
+
+fn read<'de, T: Deserialize<'de>>(nodes: &[TypeTreeNode], ref_types: &[SerializedType], data: &'de [u8]) -> T {
+    let mut reader = Reader::new(data, ByteOrder::Little);
+    let mut de = Deserializer::new(nodes, &mut reader).with_ref_types(ref_types);
+    let result = T::deserialize(&mut de).expect("Read Failure");
+    de.finish().expect("Data left");
+    result
+}
+
+#[test]
+fn test_read_managed_references() {
+    let nodes = nodes(|b| b.array("vector", "RefIds", |b| b.nested("ReferencedObject", "data", |b| referenced_type(b.field("SInt64", "rid")))));
+    let mut data = Vec::new();
+    data.extend_from_slice(&1000i64.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(&1000i64.to_le_bytes());
+    managed_type(&mut data, "Weapon", "Game", "Assembly-CSharp");
+    data.extend_from_slice(&5i32.to_le_bytes());
+    aligned_string(&mut data, "axe");
+    data.extend_from_slice(&(-2i64).to_le_bytes());
+    managed_type(&mut data, "", "", "");
+    data.extend_from_slice(&42i32.to_le_bytes());
+
+    let value: serde_json::Value = read(&nodes, &ref_types(), &data);
+    assert_eq!(value["m_Item"], json!({ "rid": 1000 }));
+    let references = &value["references"]["RefIds"];
+    assert_eq!(references[0]["rid"], 1000);
+    assert_eq!(references[0]["type"]["class"], "Weapon");
+    assert_eq!(references[0]["data"], json!({ "m_Damage": 5, "m_Label": "axe" }));
+    assert_eq!(references[1]["rid"], -2);
+    assert_eq!(references[1]["data"], serde_json::Value::Null);
+    assert_eq!(value["m_Last"], 42);
+
+    // Skipping the registry still follows the references' own type trees.
+    assert_eq!(read::<Last>(&nodes, &ref_types(), &data).last, 42);
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let error = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read without the type trees");
+    assert!(error.to_string().contains("Game.Weapon"), "{}", error);
+}
+
+#[test]
+fn test_read_managed_references_version_1() {
+    let nodes = nodes(|b| b.nested("ReferencedObject", "00000000", referenced_type));
+    let mut data = Vec::new();
+    data.extend_from_slice(&0i64.to_le_bytes());
+    data.extend_from_slice(&1i32.to_le_bytes());
+    managed_type(&mut data, "Weapon", "Game", "Assembly-CSharp");
+    data.extend_from_slice(&7i32.to_le_bytes());
+    aligned_string(&mut data, "bow");
+    managed_type(&mut data, "Terminus", "UnityEngine.DMAT", "FAKE_ASM");
+    data.extend_from_slice(&42i32.to_le_bytes());
+
+    let value: serde_json::Value = read(&nodes, &ref_types(), &data);
+    assert_eq!(value["references"]["version"], 1);
+    let references = value["references"]["RefIds"].as_array().expect("Not an array");
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0]["data"], json!({ "m_Damage": 7, "m_Label": "bow" }));
+    assert_eq!(value["m_Last"], 42);
+    assert_eq!(read::<Last>(&nodes, &ref_types(), &data).last, 42);
+}
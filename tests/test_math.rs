@@ -0,0 +1,299 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use serde::Deserialize;
+use unity_rs::math::{Matrix4x4, Quaternion, Vector2, Vector3, Vector4};
+use unity_rs::object::Deserializer;
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::typetree::TypeTreeBuilder;
+
+fn assert_close(a: Vector2, b: Vector2) {
+    assert!((a - b).length() < 1e-6, "{:?} != {:?}", a, b);
+}
+
+fn assert_close3(a: Vector3, b: Vector3, tolerance: f32) {
+    assert!((a - b).length() < tolerance, "{:?} != {:?}", a, b);
+}
+
+fn assert_close_matrix(a: &Matrix4x4, b: &Matrix4x4) {
+    let close = a.to_array().iter().zip(b.to_array()).all(|(a, b)| (a - b).abs() < 1e-5);
+    assert!(close, "{:?} != {:?}", a, b);
+}
+
+fn assert_same_rotation(a: Quaternion, b: Quaternion) {
+    // `q` and `-q` are the same rotation.
+    assert!(1.0 - a.dot(&b).abs() < 1e-5, "{:?} != {:?}", a, b);
+}
+
+/// Rotations as `(euler angles, quaternion)`, as the Unity editor shows them in the inspector
+/// and for `Quaternion.Euler(x, y, z)`.
+const EDITOR_ROTATIONS: [([f32; 3], [f32; 4]); 6] = [
+    ([0.0, 90.0, 0.0], [0.0, FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2]),
+    ([45.0, 45.0, 0.0], [0.353553, 0.353553, -0.146447, 0.853553]),
+    ([30.0, 45.0, 60.0], [0.391904, 0.200562, 0.360423, 0.822363]),
+    ([10.0, 20.0, 30.0], [0.127679, 0.144878, 0.239298, 0.951549]),
+    ([330.0, 200.0, 10.0], [-0.127679, -0.943714, -0.239298, 0.189308]),
+    ([90.0, 45.0, 0.0], [0.653281, 0.270598, -0.270598, 0.653281]),
+];
+
+#[test]
+fn test_vector_operators() {
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(4.0, -5.0, 6.0);
+    assert_eq!(a + b, Vector3::new(5.0, -3.0, 9.0));
+    assert_eq!(a - b, Vector3::new(-3.0, 7.0, -3.0));
+    assert_eq!(a * 2.0, Vector3::new(2.0, 4.0, 6.0));
+    assert_eq!(-a, Vector3::new(-1.0, -2.0, -3.0));
+
+    let mut c = a;
+    c += b;
+    c -= Vector3::ONE;
+    c *= 0.5;
+    assert_eq!(c, Vector3::new(2.0, -2.0, 4.0));
+
+    assert_eq!(Vector2::new(1.0, 2.0) + Vector2::ONE, Vector2::new(2.0, 3.0));
+    assert_eq!(Vector4::new(1.0, 2.0, 3.0, 4.0) - Vector4::ONE, Vector4::new(0.0, 1.0, 2.0, 3.0));
+    assert_eq!(Vector4::default(), Vector4::ZERO);
+}
+
+#[test]
+fn test_vector_products() {
+    let a = Vector3::new(1.0, 2.0, 3.0);
+    let b = Vector3::new(4.0, -5.0, 6.0);
+    assert_eq!(a.dot(&b), 12.0);
+    assert_eq!(Vector2::new(3.0, 4.0).dot(&Vector2::new(2.0, 1.0)), 10.0);
+
+    let x = Vector3::new(1.0, 0.0, 0.0);
+    let y = Vector3::new(0.0, 1.0, 0.0);
+    assert_eq!(x.cross(&y), Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(y.cross(&x), Vector3::new(0.0, 0.0, -1.0));
+    assert_eq!(a.cross(&a), Vector3::ZERO);
+    // The cross product is perpendicular to both sides.
+    assert_eq!(a.cross(&b).dot(&a), 0.0);
+    assert_eq!(a.cross(&b).dot(&b), 0.0);
+}
+
+#[test]
+fn test_vector_normalize() {
+    assert_eq!(Vector2::new(3.0, 4.0).length(), 5.0);
+    assert_close(Vector2::new(3.0, 4.0).normalized(), Vector2::new(0.6, 0.8));
+    assert_eq!(Vector4::new(0.0, 0.0, 0.0, 2.0).normalized(), Vector4::new(0.0, 0.0, 0.0, 1.0));
+
+    let mut v = Vector3::new(0.0, -10.0, 0.0);
+    v.normalize();
+    assert_eq!(v, Vector3::new(0.0, -1.0, 0.0));
+
+    // Vectors without a direction normalize to zero rather than NaN.
+    assert_eq!(Vector3::ZERO.normalized(), Vector3::ZERO);
+    assert_eq!(Vector3::new(1e-6, 0.0, -1e-6).normalized(), Vector3::ZERO);
+    let mut zero = Vector2::ZERO;
+    zero.normalize();
+    assert_eq!(zero, Vector2::ZERO);
+    assert_eq!(Vector4::ZERO.normalized().length(), 0.0);
+}
+
+#[test]
+fn test_vector_lerp_min_max() {
+    let a = Vector3::new(0.0, 10.0, -2.0);
+    let b = Vector3::new(4.0, 0.0, 2.0);
+    assert_eq!(a.lerp(&b, 0.0), a);
+    assert_eq!(a.lerp(&b, 1.0), b);
+    assert_eq!(a.lerp(&b, 0.25), Vector3::new(1.0, 7.5, -1.0));
+    // `t` is not clamped.
+    assert_eq!(a.lerp(&b, 2.0), Vector3::new(8.0, -10.0, 6.0));
+
+    assert_eq!(a.min(&b), Vector3::new(0.0, 0.0, -2.0));
+    assert_eq!(a.max(&b), Vector3::new(4.0, 10.0, 2.0));
+
+    // The bounding box of some points.
+    let points = [Vector2::new(1.0, -1.0), Vector2::new(-3.0, 2.0), Vector2::new(0.5, 4.0)];
+    let lower = points.iter().fold(points[0], |lower, p| lower.min(p));
+    let upper = points.iter().fold(points[0], |upper, p| upper.max(p));
+    assert_eq!(lower, Vector2::new(-3.0, -1.0));
+    assert_eq!(upper, Vector2::new(1.0, 4.0));
+}
+
+#[test]
+fn test_vector_conversions() {
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    assert_eq!(Vector4::from(v), Vector4::new(1.0, 2.0, 3.0, 1.0));
+    assert_eq!(Vector3::from(Vector4::new(1.0, 2.0, 3.0, 0.0)), v);
+    assert_eq!(Vector2::from(v), Vector2::new(1.0, 2.0));
+    assert_eq!(Vector3::from(Vector2::new(1.0, 2.0)), Vector3::new(1.0, 2.0, 0.0));
+}
+
+#[test]
+fn test_quaternion_euler() {
+    for (euler, quaternion) in EDITOR_ROTATIONS {
+        let euler = Vector3::new(euler[0], euler[1], euler[2]);
+        let quaternion = Quaternion::from_array(quaternion);
+        assert_same_rotation(Quaternion::from_euler_degrees(&euler), quaternion);
+        assert_close3(quaternion.to_euler_degrees(), euler, 1e-3);
+    }
+    assert_eq!(Quaternion::IDENTITY.to_euler_degrees(), Vector3::ZERO);
+
+    // Angles come back in `[0, 360)`, with x in `[0, 90]` or `[270, 360)`.
+    let q = Quaternion::from_euler_degrees(&Vector3::new(-30.0, -90.0, 0.0));
+    assert_close3(q.to_euler_degrees(), Vector3::new(330.0, 270.0, 0.0), 1e-3);
+    let q = Quaternion::from_euler_degrees(&Vector3::new(120.0, 0.0, 0.0));
+    assert_close3(q.to_euler_degrees(), Vector3::new(60.0, 180.0, 180.0), 1e-3);
+
+    // Pointing straight up or down, y and z turn around the same axis and z is folded into y.
+    let q = Quaternion::from_euler_degrees(&Vector3::new(90.0, 45.0, 30.0));
+    assert_close3(q.to_euler_degrees(), Vector3::new(90.0, 15.0, 0.0), 1e-3);
+    let q = Quaternion::from_euler_degrees(&Vector3::new(270.0, 30.0, 0.0));
+    assert_close3(q.to_euler_degrees(), Vector3::new(270.0, 30.0, 0.0), 1e-3);
+}
+
+#[test]
+fn test_quaternion_rotate() {
+    let yaw = Quaternion::from_euler_degrees(&Vector3::new(0.0, 90.0, 0.0));
+    let pitch = Quaternion::from_euler_degrees(&Vector3::new(90.0, 0.0, 0.0));
+    // Unity's axes are left handed: turning right moves forward to the right.
+    assert_close3(yaw * Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, 0.0), 1e-6);
+    assert_close3(yaw * Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0), 1e-6);
+    assert_close3(pitch * Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0), 1e-6);
+
+    // The right side applies first.
+    let q = yaw * pitch;
+    assert_same_rotation(q, Quaternion::from_euler_degrees(&Vector3::new(90.0, 90.0, 0.0)));
+    assert_close3(q * Vector3::new(0.0, 0.0, 1.0), yaw * (pitch * Vector3::new(0.0, 0.0, 1.0)), 1e-6);
+    assert_same_rotation(q * Quaternion::IDENTITY, q);
+
+    let m = q.to_matrix();
+    let v = Vector3::new(1.0, 2.0, 3.0);
+    let rotated = q * v;
+    let column = |i: usize| Vector3::new(m.get(0, i), m.get(1, i), m.get(2, i));
+    assert_close3(column(0) * v.x + column(1) * v.y + column(2) * v.z, rotated, 1e-5);
+    assert_eq!(m.translation(), Vector3::ZERO);
+    assert_eq!((m.get(3, 0), m.get(3, 1), m.get(3, 2), m.get(3, 3)), (0.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+fn test_quaternion_normalize() {
+    let mut q = Quaternion::new(0.0, 2.0, 0.0, 2.0);
+    q.normalize();
+    assert_same_rotation(q, Quaternion::from_euler_degrees(&Vector3::new(0.0, 90.0, 0.0)));
+    assert!((q.dot(&q) - 1.0).abs() < 1e-6);
+
+    // Too short to be a rotation, like a zero filled field.
+    assert_eq!(Quaternion::new(0.0, 0.0, 0.0, 0.0).normalized(), Quaternion::IDENTITY);
+}
+
+#[test]
+fn test_quaternion_slerp() {
+    let from = Quaternion::IDENTITY;
+    let to = Quaternion::from_euler_degrees(&Vector3::new(0.0, 90.0, 0.0));
+    let halfway = Quaternion::from_euler_degrees(&Vector3::new(0.0, 45.0, 0.0));
+    assert_same_rotation(from.slerp(&to, 0.0), from);
+    assert_same_rotation(from.slerp(&to, 1.0), to);
+    assert_same_rotation(from.slerp(&to, 0.5), halfway);
+    assert_close3(from.slerp(&to, 0.25).to_euler_degrees(), Vector3::new(0.0, 22.5, 0.0), 1e-3);
+
+    // The same rotation with the opposite sign still takes the short way.
+    let negated = Quaternion::new(-to.x, -to.y, -to.z, -to.w);
+    assert_same_rotation(from.slerp(&negated, 0.5), halfway);
+
+    // `t` is clamped.
+    assert_same_rotation(from.slerp(&to, 2.0), to);
+    assert_same_rotation(from.slerp(&to, -1.0), from);
+    assert_same_rotation(to.slerp(&to, 0.5), to);
+}
+
+#[test]
+fn test_matrix_transform() {
+    let rotation = Quaternion::from_euler_degrees(&Vector3::new(0.0, 90.0, 0.0));
+    let m = Matrix4x4::trs(&Vector3::new(1.0, 2.0, 3.0), &rotation, &Vector3::new(2.0, 2.0, 2.0));
+    assert_close3(m.transform_point(&Vector3::new(0.0, 0.0, 1.0)), Vector3::new(3.0, 2.0, 3.0), 1e-6);
+    assert_close3(m.transform_vector(&Vector3::new(0.0, 0.0, 1.0)), Vector3::new(2.0, 0.0, 0.0), 1e-6);
+    assert_eq!(m.transform_point(&Vector3::ZERO), m.translation());
+
+    // The right side applies first, as for a parent's matrix times a child's.
+    let parent = Matrix4x4::trs(&Vector3::new(0.0, 10.0, 0.0), &Quaternion::IDENTITY, &Vector3::new(1.0, 3.0, 1.0));
+    let point = Vector3::new(1.0, 1.0, 1.0);
+    assert_close3((parent * m).transform_point(&point), parent.transform_point(&m.transform_point(&point)), 1e-5);
+    assert_close_matrix(&(Matrix4x4::identity() * m), &m);
+}
+
+#[test]
+fn test_matrix_inverse() {
+    let rotation = Quaternion::from_euler_degrees(&Vector3::new(30.0, 45.0, 60.0));
+    let m = Matrix4x4::trs(&Vector3::new(1.0, -2.0, 3.0), &rotation, &Vector3::new(0.5, 2.0, -1.0));
+    let inverse = m.inverse().expect("invertible");
+    assert_close_matrix(&(m * inverse), &Matrix4x4::identity());
+    assert_close_matrix(&(inverse * m), &Matrix4x4::identity());
+    let point = Vector3::new(4.0, 5.0, 6.0);
+    assert_close3(inverse.transform_point(&m.transform_point(&point)), point, 1e-5);
+
+    // A zero scale flattens everything onto a plane, which cannot be undone.
+    let flat = Matrix4x4::trs(&Vector3::ZERO, &rotation, &Vector3::new(1.0, 0.0, 1.0));
+    assert!(flat.inverse().is_none());
+    assert!(Matrix4x4::default().inverse().is_none());
+}
+
+#[test]
+fn test_matrix_decompose() {
+    let translation = Vector3::new(1.0, -2.0, 3.0);
+    let rotation = Quaternion::from_euler_degrees(&Vector3::new(30.0, 45.0, 60.0));
+    for scale in [Vector3::ONE, Vector3::new(0.5, 2.0, 4.0), Vector3::new(-1.0, 2.0, 3.0)] {
+        let (t, r, s) = Matrix4x4::trs(&translation, &rotation, &scale).decompose();
+        assert_close3(t, translation, 1e-6);
+        assert_same_rotation(r, rotation);
+        assert_close3(s, scale, 1e-5);
+    }
+
+    // Mirrored along another axis, the sign moves to x but the matrix stays the same.
+    let m = Matrix4x4::trs(&translation, &rotation, &Vector3::new(1.0, -2.0, 3.0));
+    let (t, r, s) = m.decompose();
+    assert_close3(s, Vector3::new(-1.0, 2.0, 3.0), 1e-5);
+    assert_close_matrix(&Matrix4x4::trs(&t, &r, &s), &m);
+    let m = Matrix4x4::trs(&translation, &rotation, &Vector3::new(-1.0, -1.0, -1.0));
+    let (t, r, s) = m.decompose();
+    assert_close3(s, Vector3::new(-1.0, 1.0, 1.0), 1e-5);
+    assert_close_matrix(&Matrix4x4::trs(&t, &r, &s), &m);
+
+    // A flattened axis still has a direction, from the others.
+    let (_, r, s) = Matrix4x4::trs(&translation, &rotation, &Vector3::new(2.0, 0.0, 1.0)).decompose();
+    assert_same_rotation(r, rotation);
+    assert_close3(s, Vector3::new(2.0, 0.0, 1.0), 1e-5);
+
+    let (t, r, s) = Matrix4x4::default().decompose();
+    assert_eq!((t, s), (Vector3::ZERO, Vector3::ZERO));
+    assert!(r.x.is_finite() && r.y.is_finite() && r.z.is_finite() && r.w.is_finite());
+}
+
+#[derive(Deserialize)]
+struct BindPoses {
+    #[serde(rename = "m_BindPose")]
+    bind_pose: Vec<Matrix4x4>,
+}
+
+#[test]
+fn test_bind_pose() {
+    // The bind pose of a bone at `(0, 1.5, 0.2)` turned 90 degrees around z, the inverse of its
+    // world matrix, as a mesh's `m_BindPose` stores it: column by column, the translation last.
+    let stored = [0.0, -1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.5, 0.0, -0.2, 1.0];
+    let mut data = 1i32.to_le_bytes().to_vec();
+    data.extend(stored.iter().flat_map(|x: &f32| x.to_le_bytes()));
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    reader.read_i32().expect("Read Failure");
+    let bind_pose = reader.read_matrix4x4_list(1).expect("Read Failure")[0];
+    assert_eq!(bind_pose.translation(), Vector3::new(-1.5, 0.0, -0.2));
+
+    // Read through the type tree, the matrix comes out the same.
+    let nodes = TypeTreeBuilder::new("Mesh")
+        .array("vector", "m_BindPose", |b| b.nested("Matrix4x4f", "data", |b| (0..16).fold(b, |b, i| b.field("float", &format!("e{}{}", i / 4, i % 4)))))
+        .build();
+    let mesh = BindPoses::deserialize(&mut Deserializer::new(&nodes, &mut Reader::new(&data, ByteOrder::Little))).expect("Read Failure");
+    assert_eq!(mesh.bind_pose, [bind_pose]);
+
+    // It takes the bone to the origin, and one unit above it onto the bone's x axis.
+    assert_close3(bind_pose.transform_point(&Vector3::new(0.0, 1.5, 0.2)), Vector3::ZERO, 1e-6);
+    assert_close3(bind_pose.transform_point(&Vector3::new(0.0, 2.5, 0.2)), Vector3::new(1.0, 0.0, 0.0), 1e-6);
+
+    let bone = bind_pose.inverse().expect("invertible");
+    let (t, r, s) = bone.decompose();
+    assert_close3(t, Vector3::new(0.0, 1.5, 0.2), 1e-6);
+    assert_same_rotation(r, Quaternion::from_euler_degrees(&Vector3::new(0.0, 0.0, 90.0)));
+    assert_close3(s, Vector3::ONE, 1e-6);
+    assert_close_matrix(&Matrix4x4::trs(&t, &r, &s), &bone);
+}
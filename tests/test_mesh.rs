@@ -0,0 +1,27 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::Mesh;
+use unity_rs::{ClassID, Env};
+
+use common::*;
+
+#[test]
+fn test_mesh_blend_shapes() {
+    let file = serialized_file_with_objects(&[(1, ClassID::Mesh, blend_shape_mesh("face", &[("smile", &[50.0, 100.0]), ("blink", &[100.0])]))]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(file)).expect("Load failure");
+    let mesh = env.objects_with_class(ClassID::Mesh).next().expect("Missing mesh").read::<Mesh>().expect("Read Failure");
+    assert_eq!(mesh.name, "face");
+
+    let shapes = mesh.blend_shapes();
+    let names: Vec<(&str, u32)> = shapes.iter().map(|x| (x.name.as_str(), x.name_hash)).collect();
+    assert_eq!(names, [("smile", 0), ("blink", 1)]);
+    let weights: Vec<Vec<f32>> = shapes.iter().map(|x| x.frames.iter().map(|x| x.weight).collect()).collect();
+    assert_eq!(weights, [vec![50.0, 100.0], vec![100.0]]);
+
+    // Each frame moves its own vertex, the blink's the third.
+    let deltas: Vec<Vec<(u32, f32)>> = shapes.iter().map(|x| x.frames.iter().flat_map(|x| x.deltas.iter().map(|x| (x.index, x.vertex.x))).collect()).collect();
+    assert_eq!(deltas, [vec![(0, 1.0), (1, 2.0)], vec![(2, 3.0)]]);
+    assert!(shapes.iter().flat_map(|x| &x.frames).all(|x| !x.has_normals && !x.has_tangents));
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use unity_rs::classes::TextAsset;
+use unity_rs::object::ReadTypeTreeError;
+use unity_rs::typetree::TypeTreeBuilder;
+use unity_rs::{ClassID, Env, UnityError};
+
+use common::*;
+
+#[test]
+fn test_read_stripped_type_tree_from_template() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&serialized_file(), true)).expect("Load failure");
+    let object = env.objects().find(|x| x.class() == ClassID::TextAsset).expect("Missing TextAsset");
+    assert!(!object.info.has_typetree());
+
+    let value: serde_json::Value = object.read_type_tree().expect("Read Failure");
+    assert_eq!(value["m_Name"], "greeting");
+    assert_eq!(value["m_Script"], TEXT);
+
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().type_tree().build(), true)).expect("Load failure");
+    assert!(env.serialized_files[1].object_at(0).expect("Missing object").has_typetree());
+}
+
+#[test]
+fn test_dump_typetree() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().type_tree().build(), true)).expect("Load failure");
+    let object = env.objects().next().expect("Missing object");
+    let dump = object.info.dump_typetree().expect("Missing type tree");
+    let lines: Vec<&str> = dump.lines().collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(lines[0], "TextAsset Base // ByteSize{ffffffff}, Index{0}, Version{1}, IsArray{0}, MetaFlag{8000}");
+    assert_eq!(lines[2], "\t\tArray Array // ByteSize{ffffffff}, Index{2}, Version{1}, IsArray{1}, MetaFlag{4000}");
+    assert_eq!(lines[3], "\t\t\tint size // ByteSize{4}, Index{3}, Version{1}, IsArray{0}, MetaFlag{0}");
+
+    env.load_from_slice(&unity_fs_bundle(&serialized_file(), true)).expect("Load failure");
+    assert!(env.serialized_files[1].object_at(0).expect("Missing object").dump_typetree().is_none());
+}
+
+#[test]
+fn test_read_value() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().type_tree().build(), true)).expect("Load failure");
+    let object = env.find_object(1).expect("Missing object");
+    let expected = serde_json::json!({ "m_Name": "greeting", "m_Script": TEXT });
+    assert_eq!(object.info.read_value().expect("Read Failure"), expected);
+    assert_eq!(object.read_value().expect("Read Failure"), expected);
+    assert_eq!(object.to_json_string_pretty().expect("Read Failure"), serde_json::to_string_pretty(&expected).expect("Write failure"));
+}
+
+#[test]
+fn test_read_field() {
+    let mut env = Env::new();
+    let file = TextAssetFile::new().texts(&["first text", TEXT]).type_tree().big_endian(true).build();
+    env.load_from_slice(&unity_fs_bundle(&file, true)).expect("Load failure");
+    let object = env.find_object(2).expect("Missing object");
+    assert_eq!(object.read_field::<String>("m_Script").expect("Read Failure"), TEXT);
+    assert_eq!(object.info.read_field::<String>("m_Name").expect("Read Failure"), "greeting1");
+    assert!(object.read_field::<String>("m_Missing").is_err());
+    assert!(object.read_field::<String>("m_Name.data").is_err());
+
+    let nodes = TypeTreeBuilder::new("TextAsset").string("m_Name").array("vector", "m_Script", |b| b.field("char", "data")).build();
+    assert_eq!(object.info.read_field_with::<u8>(&nodes, "m_Script[4]").expect("Read Failure"), TEXT.as_bytes()[4]);
+    assert!(object.info.read_field_with::<u8>(&nodes, &format!("m_Script[{}]", TEXT.len())).is_err());
+}
+
+#[test]
+fn test_read_type_tree_strict() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().texts(&[TEXT, "second text"]).type_tree().build(), true)).expect("Load failure");
+    let object = env.find_object(1).expect("Missing object");
+    assert_eq!(object.read_type_tree_strict::<serde_json::Value>().expect("Read Failure")["m_Script"], TEXT);
+
+    let name = TypeTreeBuilder::new("TextAsset").string("m_Name").build();
+    assert!(object.info.read_type_tree_with::<serde_json::Value>(&name).is_ok());
+    let error = object.info.read_type_tree_strict_with::<serde_json::Value>(&name).expect_err("Read with bytes left");
+    assert!(matches!(error, ReadTypeTreeError::Trailing { bytes: 32, nodes: 0 }), "{}", error);
+
+    // Without the check, a field past the object reads the next one.
+    let extra = TypeTreeBuilder::new("TextAsset").string("m_Name").string("m_Script").field("int", "m_Extra").build();
+    assert!(object.info.read_type_tree_with::<serde_json::Value>(&extra).is_ok());
+    let error = object.info.read_type_tree_strict_with::<serde_json::Value>(&extra).expect_err("Read past the object");
+    assert!(matches!(error.inner(), ReadTypeTreeError::BufEof), "{}", error);
+}
+
+#[test]
+fn test_read_errors_carry_object() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().type_tree().build(), true)).expect("Load failure");
+    let object = env.find_object(1).expect("Missing object");
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Mismatched {
+        #[serde(rename = "m_Name")]
+        _name: i32,
+    }
+    assert!(object.read_type_tree::<Mismatched>().is_err());
+
+    let mut env = Env::new();
+    let mut text = Vec::new();
+    text.extend_from_slice(&100i32.to_le_bytes());
+    text.extend_from_slice(b"short");
+    env.load_from_slice(&unity_fs_bundle(&serialized_file_with_objects(&[(7, ClassID::TextAsset, text)]), true)).expect("Load failure");
+    let object = env.find_object(7).expect("Missing object");
+    assert!(object.read_type_tree::<serde_json::Value>().is_err());
+    let error = match object.read::<TextAsset>() {
+        Err(error) => error,
+        Ok(text) => panic!("unexpected result {:?}", text.name),
+    };
+    match &error {
+        UnityError::ReadObject { file, class, path_id, .. } => assert_eq!((file.as_str(), *class, *path_id), (object.asset.path.as_str(), ClassID::TextAsset, 7)),
+        other => panic!("unexpected error {:?}", other),
+    }
+    // The cause is left to `source`, so that printing the chain shows it once.
+    assert_eq!(error.to_string(), format!("Reading TextAsset object 7 in {}", object.asset.path));
+    let source = std::error::Error::source(&error).expect("Missing source");
+    assert!(!error.to_string().contains(&source.to_string()));
+}
+
+#[test]
+fn test_write_edited_type_tree() {
+    let mut env = Env::new();
+    env.load_from_slice(&TextAssetFile::new().texts(&["first text", TEXT]).type_tree().big_endian(true).build()).expect("Load failure");
+    let object = env.find_object(2).expect("Missing object");
+    let mut value: serde_json::Value = object.read_type_tree().expect("Read Failure");
+    let data = object.info.write_type_tree(&value).expect("Write Failure");
+    assert_eq!(data, object.info.get_reader().expect("Reader Failure").read_u8_slice(object.info.bytes_size).expect("Read Failure"));
+
+    value["m_Script"] = "an edited text".into();
+    let data = object.info.write_type_tree(&value).expect("Write Failure");
+    value["m_Extra"] = 1.into();
+    assert!(object.info.write_type_tree(&value).is_err());
+    assert!(object.info.write_type_tree(&serde_json::json!({ "m_Name": 1, "m_Script": "" })).is_err());
+    assert!(object.info.write_type_tree(&serde_json::json!({ "m_Name": "" })).is_err());
+
+    env.serialized_files[0].replace_object(2, data).expect("Replace failure");
+    let file = env.serialized_files[0].to_bytes().expect("Write failure");
+    let mut env = Env::new();
+    env.load_from_slice(&file).expect("Load failure");
+    let text: TextAsset = env.find_object(2).expect("Missing object").read().expect("Read Failure");
+    assert_eq!((text.name.as_str(), text.script_string().expect("Utf8 Failure")), ("greeting1", "an edited text"));
+}
+
+#[test]
+fn test_type_tree_common_strings() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().type_tree().common_strings().build(), true)).expect("Load failure");
+    let object = env.objects().next().expect("Missing object");
+    let names: Vec<(&str, &str)> = object.info.serialized_type.type_tree.nodes.iter().map(|x| (x.type_.as_str(), x.name.as_str())).collect();
+    assert_eq!(&names[..5], [("TextAsset", "Base"), ("string", "m_Name"), ("Array", "Array"), ("int", "size"), ("char", "data")]);
+    assert_eq!(names[5], ("string", "m_Script"));
+
+    let value: serde_json::Value = object.read_type_tree().expect("Read Failure");
+    assert_eq!(value["m_Script"], TEXT);
+}
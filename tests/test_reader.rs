@@ -0,0 +1,166 @@
+use unity_rs::reader::{f16_to_f32, snorm16_to_f32, snorm8_to_f32, unorm16_to_f32, unorm8_to_f32, BadLength, ByteOrder, ReadError, Reader, DEFAULT_MAX_STRING_LENGTH};
+
+#[test]
+fn test_f16_to_f32() {
+    assert_eq!(f16_to_f32(0x0000), 0.0);
+    assert!(f16_to_f32(0x8000).is_sign_negative());
+    assert_eq!(f16_to_f32(0x3c00), 1.0);
+    assert_eq!(f16_to_f32(0xc000), -2.0);
+    assert_eq!(f16_to_f32(0x7bff), 65504.0);
+    // The smallest and largest subnormals.
+    assert_eq!(f16_to_f32(0x0001), 2f32.powi(-24));
+    assert_eq!(f16_to_f32(0x03ff), 1023.0 * 2f32.powi(-24));
+    assert_eq!(f16_to_f32(0x0400), 2f32.powi(-14));
+    assert_eq!(f16_to_f32(0x7c00), f32::INFINITY);
+    assert_eq!(f16_to_f32(0xfc00), f32::NEG_INFINITY);
+    assert!(f16_to_f32(0x7e00).is_nan());
+    assert!(f16_to_f32(0xfc01).is_nan());
+}
+
+#[test]
+fn test_read_f16_in_byte_order() {
+    let data = [0x3c, 0x00, 0x00, 0xc0];
+    let mut big = Reader::new(&data, ByteOrder::Big);
+    assert_eq!(big.read_f16().expect("Read Failure"), 1.0);
+    let mut little = Reader::new(&data, ByteOrder::Little);
+    assert_eq!(little.read_f16_list(2).expect("Read Failure"), [f16_to_f32(0x003c), -2.0]);
+    assert!(little.read_f16().is_err());
+    assert!(Reader::new(&data, ByteOrder::Little).read_f16_list(3).is_err());
+}
+
+#[test]
+fn test_normalized_to_f32() {
+    assert_eq!((unorm8_to_f32(0), unorm8_to_f32(255)), (0.0, 1.0));
+    assert_eq!((snorm8_to_f32(127), snorm8_to_f32(-127), snorm8_to_f32(-128)), (1.0, -1.0, -1.0));
+    assert_eq!((unorm16_to_f32(0), unorm16_to_f32(65535)), (0.0, 1.0));
+    assert_eq!((snorm16_to_f32(32767), snorm16_to_f32(-32767), snorm16_to_f32(-32768)), (1.0, -1.0, -1.0));
+    assert_eq!(snorm8_to_f32(0), 0.0);
+
+    let data = [0xff, 0x81, 0xff, 0x7f, 0x00, 0x80];
+    let mut r = Reader::new(&data, ByteOrder::Little);
+    assert_eq!(r.read_unorm8().expect("Read Failure"), 1.0);
+    assert_eq!(r.read_snorm8().expect("Read Failure"), -1.0);
+    assert_eq!(r.read_snorm16().expect("Read Failure"), 1.0);
+    assert_eq!(r.read_unorm16().expect("Read Failure"), 32768.0 / 65535.0);
+}
+
+#[test]
+fn test_read_bad_lengths() {
+    let data = [&i32::MAX.to_le_bytes()[..], b"abcd"].concat();
+    let mut r = Reader::new(&data, ByteOrder::Little);
+    let error = r.read_aligned_string().expect_err("Read a huge string");
+    assert_eq!(
+        error,
+        ReadError::BadLength(BadLength {
+            length: i32::MAX as i64,
+            remaining: 4,
+            max: DEFAULT_MAX_STRING_LENGTH
+        })
+    );
+
+    let data = [&(-1i32).to_le_bytes()[..], b"abcd"].concat();
+    assert!(matches!(Reader::new(&data, ByteOrder::Little).read_aligned_string(), Err(ReadError::BadLength(BadLength { length: -1, .. }))));
+    assert!(matches!(Reader::new(&data, ByteOrder::Little).read_string_list(), Err(ReadError::BadLength(BadLength { length: -1, .. }))));
+
+    // Each string takes at least 4 bytes, so two cannot fit in the 4 left.
+    let data = [&2i32.to_le_bytes()[..], &[0; 4]].concat();
+    assert!(matches!(Reader::new(&data, ByteOrder::Little).read_string_list(), Err(ReadError::BadLength(BadLength { length: 2, .. }))));
+
+    let mut r = Reader::new(b"abcd", ByteOrder::Little);
+    assert!(matches!(r.read_u8_list(5), Err(ReadError::BadLength(BadLength { length: 5, remaining: 4, .. }))));
+    assert_eq!(r.read_u8_list(4).expect("Read Failure"), b"abcd");
+}
+
+#[test]
+fn test_max_string_length() {
+    let data = [&5i32.to_le_bytes()[..], b"hello\0\0\0"].concat();
+    let mut r = Reader::new(&data, ByteOrder::Little);
+    r.set_max_string_length(4);
+    assert!(matches!(r.read_aligned_string(), Err(ReadError::BadLength(BadLength { length: 5, max: 4, .. }))));
+    assert_eq!(r.get_offset(), 4);
+    r.set_offset(0).expect("Seek Failure");
+    r.set_max_string_length(5);
+    assert_eq!(r.read_aligned_string().expect("Read Failure"), "hello");
+    assert_eq!(r.get_offset(), 12);
+}
+
+#[test]
+fn test_read_slices() {
+    let data = [0x00, 0x00, 0x80, 0x3f, 0x01, 0x02, 0x03, 0x04];
+    let mut little = Reader::new(&data, ByteOrder::Little);
+    assert_eq!(little.read_f32_slice(1).expect("Read Failure"), [1.0]);
+    assert_eq!(little.read_u16_slice(2).expect("Read Failure"), [0x0201, 0x0403]);
+    assert!(little.read_i32_slice(1).is_err());
+    let mut big = Reader::new(&data, ByteOrder::Big);
+    assert_eq!(big.read_i32_slice(2).expect("Read Failure"), [0x803f, 0x01020304]);
+    assert_eq!(Reader::new(&data, ByteOrder::Big).read_u64_slice(1).expect("Read Failure"), [0x0000803f01020304]);
+    assert_eq!(Reader::new(&data, ByteOrder::Little).read_i8_slice(8).expect("Read Failure"), [0, 0, -128, 63, 1, 2, 3, 4]);
+    // Nothing is read when the data is too short, even for a count that overflows.
+    let mut r = Reader::new(&data, ByteOrder::Little);
+    assert!(r.read_f64_slice(2).is_err());
+    assert!(r.read_u32_slice(usize::MAX).is_err());
+    assert_eq!(r.get_offset(), 0);
+}
+
+#[test]
+fn test_sub_reader() {
+    let data = [1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0];
+    let mut r = Reader::new(&data, ByteOrder::Big);
+    r.set_little_order();
+    r.read_u32().expect("Read Failure");
+    let mut window = r.at(4, 4).expect("Read Failure");
+    assert_eq!(window.len(), 4);
+    assert_eq!(window.read_u32().expect("Read Failure"), 2);
+    assert!(window.read_u8().is_err());
+    assert_eq!(r.get_offset(), 4);
+    assert!(r.at(9, 4).is_err());
+    assert!(r.at(usize::MAX, 2).is_err());
+    assert!(r.at(12, 0).expect("Read Failure").is_empty());
+}
+
+#[test]
+fn test_save_position() {
+    let data = [1, 0, 0, 0, 2, 0, 0, 0];
+    let mut r = Reader::new(&data, ByteOrder::Little);
+    r.read_u8().expect("Read Failure");
+    {
+        let mut saved = r.save_position();
+        saved.set_offset(4).expect("Seek Failure");
+        assert_eq!(saved.read_u32().expect("Read Failure"), 2);
+    }
+    assert_eq!(r.get_offset(), 1);
+    let failed = {
+        let mut saved = r.save_position();
+        saved.read_u32().and_then(|_| saved.read_u32())
+    };
+    assert!(failed.is_err());
+    assert_eq!(r.get_offset(), 1);
+}
+
+/// "テスト" in Shift-JIS, as an aligned string.
+const SHIFT_JIS: [u8; 12] = [6, 0, 0, 0, 0x83, 0x65, 0x83, 0x58, 0x83, 0x67, 0, 0];
+
+#[test]
+fn test_read_aligned_string_raw() {
+    let mut r = Reader::new(&SHIFT_JIS, ByteOrder::Little);
+    let raw = r.read_aligned_string_raw().expect("Read Failure");
+    assert_eq!(r.get_offset(), 12);
+    assert_eq!(raw.bytes, SHIFT_JIS[4..10]);
+    assert!(!raw.is_utf8());
+    assert!(raw.text.contains('\u{fffd}'));
+    assert_eq!(unity_rs::Env::new().decode_string(raw.clone()), raw.text);
+
+    let data = [&6i32.to_le_bytes()[..], "名前".as_bytes(), &[0, 0]].concat();
+    let raw = Reader::new(&data, ByteOrder::Little).read_aligned_string_raw().expect("Read Failure");
+    assert!(raw.is_utf8());
+    assert_eq!(unity_rs::Env::new().decode_string(raw), "名前");
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn test_fallback_encoding() {
+    let mut env = unity_rs::Env::new();
+    env.set_fallback_encoding(encoding_rs::SHIFT_JIS);
+    let raw = Reader::new(&SHIFT_JIS, ByteOrder::Little).read_aligned_string_raw().expect("Read Failure");
+    assert_eq!(env.decode_string(raw), "テスト");
+}
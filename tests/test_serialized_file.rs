@@ -0,0 +1,197 @@
+mod common;
+
+use std::sync::Arc;
+use unity_rs::bundle::FileData;
+use unity_rs::classes::{GameObject, TextAsset};
+use unity_rs::error::ParseContext;
+use unity_rs::{ClassID, Env, UnityError};
+
+use common::*;
+
+#[test]
+fn test_load_bare_serialized_file() {
+    let mut env = Env::new();
+    env.load_from_slice(&serialized_file()).expect("Load failure");
+    assert_text_asset(&env);
+}
+
+#[test]
+fn test_load_unity4_serialized_file() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_web_bundle(&unity4_serialized_file())).expect("Load failure");
+    assert_text_asset(&env);
+
+    let object = env.objects().find(|x| x.class() == ClassID::TextAsset).expect("Missing TextAsset");
+    let value: serde_json::Value = object.read_type_tree().expect("Read Failure");
+    assert_eq!(value["m_Name"], "greeting");
+    assert_eq!(value["m_Script"], TEXT);
+}
+
+#[test]
+fn test_load_legacy_type_trees() {
+    // Versions 9 and 11 nest each node's children after it, with the strings inline.
+    for version in [9, 11] {
+        let mut env = Env::new();
+        env.load_from_slice(&unity_web_bundle(&legacy_serialized_file(version))).expect("Load failure");
+        assert_text_asset(&env);
+        let asset = &env.serialized_files[0];
+        assert_eq!(asset.header.version, version);
+
+        let nodes = &asset.types[0].type_tree.nodes;
+        let tree: Vec<(i32, &str, &str)> = nodes.iter().map(|x| (x.level, x.type_.as_str(), x.name.as_str())).collect();
+        assert_eq!(tree[..5], [(0, "TextAsset", "Base"), (1, "string", "m_Name"), (2, "Array", "Array"), (3, "int", "size"), (3, "char", "data")]);
+        assert_eq!(nodes.len(), 9);
+        assert_eq!((nodes[1].size, nodes[1].meta_flag), (-1, 0x8000));
+
+        let object = env.objects().next().expect("Missing TextAsset");
+        let value: serde_json::Value = object.read_type_tree().expect("Read Failure");
+        assert_eq!(value["m_Script"], TEXT);
+    }
+}
+
+#[test]
+fn test_load_large_serialized_file() {
+    for lazy in [false, true] {
+        let mut env = Env::new();
+        let bundle = unity_fs_bundle(&large_serialized_file(), true);
+        if lazy {
+            env.load_from_data(FileData::from(bundle)).expect("Load failure");
+        } else {
+            env.load_from_slice(&bundle).expect("Load failure");
+        }
+        assert_text_asset(&env);
+
+        let asset = &env.serialized_files[0];
+        assert_eq!(asset.header.version, 22);
+        assert_eq!(asset.ref_types.len(), 1);
+        assert_eq!(asset.ref_types[0].klass_name, "Payload");
+        assert_eq!(asset.ref_types[0].name_space, "Game");
+        assert_eq!(asset.ref_types[0].asm_name, "Assembly-CSharp");
+    }
+}
+
+#[test]
+fn test_load_big_endian_serialized_file() {
+    let texts = ["first text", "a second, longer text"];
+    let load = |big: bool| {
+        let mut env = Env::new();
+        env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().texts(&texts).type_tree().big_endian(big).build(), true)).expect("Load failure");
+        env
+    };
+    let little = load(false);
+    let big = load(true);
+    assert_eq!(big.serialized_files[0].file_endian, 1);
+
+    for path_id in [1, 2] {
+        let a = little.find_object(path_id).expect("Missing object");
+        let b = big.find_object(path_id).expect("Missing object");
+        let a_value: serde_json::Value = a.read_type_tree().expect("Read Failure");
+        let b_value: serde_json::Value = b.read_type_tree().expect("Read Failure");
+        assert_eq!(a_value, b_value);
+        assert_eq!(b_value["m_Script"], texts[path_id as usize - 1]);
+
+        let a_text: TextAsset = a.read().expect("Read Failure");
+        let b_text: TextAsset = b.read().expect("Read Failure");
+        assert_eq!(a_text.name, b_text.name);
+        assert_eq!(a_text.script, b_text.script);
+    }
+}
+
+#[test]
+fn test_externals() {
+    let externals = ["archive:/CAB-shared/CAB-shared", "Library/unity default resources"];
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().externals(&externals).build(), true)).expect("Load failure");
+    assert_text_asset(&env);
+
+    let asset = &env.serialized_files[0];
+    let paths: Vec<_> = asset.externals().iter().map(|x| x.path_name.as_str()).collect();
+    assert_eq!(paths, externals);
+    assert_eq!(asset.externals()[1].guid, [2; 16]);
+    assert_eq!(asset.external(1).map(|x| x.file_name()), Some("CAB-shared"));
+    assert!(asset.external(0).is_none());
+    assert!(asset.external(3).is_none());
+
+    assert!(env.find_serialized_file("archive:/CAB-TEST/CAB-test").is_some());
+    assert!(env.find_serialized_file("cab-test").is_some());
+    assert!(env.find_serialized_file(asset.externals()[0].file_name()).is_none());
+}
+
+#[test]
+fn test_objects_share_serialized_type() {
+    let mut env = Env::new();
+    env.load_from_slice(&unity_fs_bundle(&TextAssetFile::new().texts(&["first", "second"]).type_tree().build(), true)).expect("Load failure");
+    let asset = &env.serialized_files[0];
+    let entries: Vec<(i64, ClassID)> = asset.object_entries().iter().map(|x| (x.path_id, x.class())).collect();
+    assert_eq!(entries, [(1, ClassID::TextAsset), (2, ClassID::TextAsset)]);
+
+    let (first, second) = (asset.object(1).expect("Missing object"), asset.object_at(1).expect("Missing object"));
+    assert!(Arc::ptr_eq(&first.serialized_type, &second.serialized_type));
+    assert!(Arc::ptr_eq(&first.serialized_type, &asset.types[0]));
+    assert!(std::ptr::eq(second, asset.object(2).expect("Missing object")));
+    assert!(asset.object_at(2).is_none());
+}
+
+#[test]
+fn test_object_raw_data() {
+    let bundles = [unity_fs_bundle(&serialized_file(), false), unity_fs_bundle(&serialized_file(), true), unity_web_bundle(&serialized_file())];
+    for bundle in bundles {
+        let mut env = Env::new();
+        env.load_from_data(FileData::from(bundle)).expect("Load failure");
+        let object = env.objects().find(|x| x.class() == ClassID::TextAsset).expect("Missing TextAsset");
+        let raw = object.info.raw_data().expect("Read Failure");
+        assert_eq!(raw.len(), object.info.bytes_size);
+        assert_eq!(&raw[..12], b"\x08\0\0\0greeting");
+        assert_eq!(object.info.data_slice().expect("Read Failure"), raw.as_slice());
+        drop(env);
+        assert_eq!(&raw[..12], b"\x08\0\0\0greeting");
+    }
+}
+
+#[test]
+fn test_load_bad_object_count() {
+    // The object count follows the 20 byte header, the version, the platform, the type tree
+    // flag and the one type.
+    let mut file = serialized_file();
+    let count = 20 + 12 + 4 + 1 + 4 + 4 + 1 + 2 + 16;
+    assert_eq!(file[count..count + 4], 1i32.to_le_bytes());
+    file[count..count + 4].copy_from_slice(&i32::MAX.to_le_bytes());
+    let error = Env::new().load_serialized_file("level0", FileData::from(file)).expect_err("Loaded too many objects");
+    match &error {
+        UnityError::Parse { context, .. } => {
+            let expected = ParseContext {
+                file: "level0".to_string(),
+                section: "objects".to_string(),
+                offset: count + 4,
+            };
+            assert_eq!(context, &expected);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+    assert!(matches!(error.root_cause(), UnityError::BadLength(_)), "{}", error);
+    assert_eq!(error.to_string(), "level0/objects at offset 0x44");
+}
+
+#[test]
+fn test_serialized_file_replace_object() {
+    let scene = serialized_file_with_objects(&[
+        (1, ClassID::GameObject, game_object("Camera", 2)),
+        (2, ClassID::Transform, transform(1, &[], 0)),
+        (3, ClassID::GameObject, game_object("World", 4)),
+        (4, ClassID::Transform, transform(3, &[], 0)),
+    ]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(scene)).expect("Load failure");
+
+    let asset = &mut env.serialized_files[0];
+    asset.replace_object(1, game_object("A much longer camera name", 2)).expect("Replace failure");
+    assert!(asset.replace_object(9, Vec::new()).is_err());
+    let written = asset.to_bytes().expect("Write failure");
+
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(written)).expect("Load failure");
+    let asset = &env.serialized_files[0];
+    assert!(asset.object_entries().iter().all(|x| (x.bytes_start - asset.header.data_offset) % 8 == 0));
+    let names: Vec<String> = env.roots().map(|x| x.read::<GameObject>().expect("Read Failure").name).collect();
+    assert_eq!(names, ["A much longer camera name", "World"]);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::classes::Shader;
+use unity_rs::{ClassID, Env, UnityError};
+
+use common::*;
+
+fn read_shader(data: Vec<u8>, test: impl FnOnce(Shader)) {
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(serialized_file_with_objects(&[(1, ClassID::Shader, data)]))).expect("Load failure");
+    test(env.objects_with_class(ClassID::Shader).next().expect("Missing shader").read::<Shader>().expect("Read Failure"));
+}
+
+#[test]
+fn test_shader_decompress_blobs() {
+    let d3d11 = b"DXBC vertex program for d3d11".repeat(4);
+    let gles = b"#version 300 es".to_vec();
+    read_shader(shader("Custom/Lit", &[], &[], &[(4, &d3d11), (9, &gles)]), |mut shader| {
+        let platforms: Vec<u32> = shader.platforms.iter().map(|x| x.platform).collect();
+        assert_eq!(platforms, [4, 9]);
+        assert_eq!(shader.decompress_blobs().expect("Decompress failure"), [vec![d3d11], vec![gles]]);
+
+        // A segment past the end of the blob is not read.
+        shader.platforms[1].offsets[0] += 1;
+        assert!(matches!(shader.decompress_blobs(), Err(UnityError::Eof)));
+    });
+}
+
+#[test]
+fn test_shader_sub_programs() {
+    read_shader(shader("Custom/Lit", &["FOG_LINEAR"], &[(0, 4, &[0]), (1, 9, &[])], &[(4, b"a"), (9, b"b")]), |shader| {
+        assert_eq!(shader.name, "shader");
+        assert_eq!(shader.name(), "Custom/Lit");
+        let form = shader.parsed_form.as_ref().expect("Missing parsed form");
+        assert_eq!(form.fallback_name, "Diffuse");
+
+        let pass = &form.sub_shaders[0].passes[0];
+        assert_eq!(pass.name, "FORWARD");
+        assert_eq!(pass.state.name, "state");
+        assert_eq!(pass.programs.len(), 5);
+        let sub_programs: Vec<(u32, i8, &[u16])> = pass.programs[0].sub_programs.iter().map(|x| (x.blob_index, x.gpu_program_type, x.keyword_indices.as_slice())).collect();
+        assert_eq!(sub_programs, [(0, 4, &[0][..]), (1, 9, &[][..])]);
+        assert!(pass.programs[1..].iter().all(|x| x.sub_programs.is_empty()));
+    });
+}
+
+#[test]
+fn test_shader_keywords() {
+    // Keywords are resolved through the pass' names, sorted and without duplicates; an index the
+    // pass does not name is dropped.
+    let keywords = ["SHADOWS_SCREEN", "FOG_LINEAR", "LIGHTMAP_ON"];
+    read_shader(shader("Custom/Lit", &keywords, &[(0, 4, &[2, 1]), (1, 4, &[1, 7])], &[]), |shader| {
+        assert_eq!(shader.keywords(), ["FOG_LINEAR", "LIGHTMAP_ON"]);
+        let pass = &shader.parsed_form.as_ref().expect("Missing parsed form").sub_shaders[0].passes[0];
+        assert_eq!(pass.keywords().into_iter().collect::<Vec<_>>(), ["FOG_LINEAR", "LIGHTMAP_ON"]);
+    });
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use unity_rs::bundle::FileData;
+use unity_rs::{ClassID, Env, Sprite};
+
+use common::*;
+
+#[test]
+fn test_cut_sprite_from_atlas() {
+    // Red numbers each pixel of the 4x4 atlas by its column and its row from the bottom.
+    let pixels: Vec<u8> = (0..16u8).flat_map(|i| [(i % 4) * 16 + i / 4, 0, 0, 255]).collect();
+    let file = serialized_file_with_objects(&[
+        (1, ClassID::Texture2D, texture2d("atlas", 4, 4, &pixels)),
+        (2, ClassID::Sprite, sprite("icon", 1, 3)),
+        (3, ClassID::SpriteAtlas, sprite_atlas((2, "icon"), 1, [1.0, 2.0, 2.0, 2.0], [1.0, 0.0])),
+    ]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(file)).expect("Load failure");
+    let sprite = env.objects_with_class(ClassID::Sprite).next().expect("Missing sprite").read::<Sprite>().expect("Read Failure");
+    let image = sprite.decode_image().expect("Decode failure");
+
+    // The atlas' 2x2 top right cut is placed one pixel right of the left edge of the 3x3 rect.
+    assert_eq!(image.dimensions(), (3, 3));
+    let rows: Vec<Vec<u8>> = (0..3).map(|y| (0..3).map(|x| image.get_pixel(x, y).0[0]).collect()).collect();
+    assert_eq!(rows, [[0, 0, 0], [0, 19, 35], [0, 18, 34]]);
+    assert_eq!(image.get_pixel(0, 2).0[3], 0);
+    assert_eq!(image.get_pixel(1, 2).0[3], 255);
+}
+
+#[test]
+fn test_sprite_metadata_json() {
+    let file = serialized_file_with_objects(&[(1, ClassID::Texture2D, texture2d("atlas", 4, 4, &[0; 64])), (2, ClassID::Sprite, sprite("icon", 1, 0))]);
+    let mut env = Env::new();
+    env.load_serialized_file("level0", FileData::from(file)).expect("Load failure");
+    let sprite = env.objects_with_class(ClassID::Sprite).next().expect("Missing sprite").read::<Sprite>().expect("Read Failure");
+    let metadata: serde_json::Value = serde_json::from_str(&sprite.to_metadata_json("icon.png")).expect("Invalid JSON");
+
+    let keys: Vec<&str> = metadata.as_object().expect("Not an object").keys().map(|x| x.as_str()).collect();
+    assert_eq!(keys, ["name", "image", "rect", "offset", "pivot", "border", "pixels_per_unit"]);
+    assert_eq!(
+        metadata,
+        serde_json::json!({
+            "name": "icon",
+            "image": "icon.png",
+            "rect": { "x": 0.0, "y": 0.0, "width": 3.0, "height": 3.0 },
+            "offset": { "x": 0.0, "y": 0.0 },
+            "pivot": { "x": 0.5, "y": 0.5 },
+            "border": { "left": 0.0, "bottom": 0.0, "right": 0.0, "top": 0.0 },
+            "pixels_per_unit": 100.0,
+        })
+    );
+}
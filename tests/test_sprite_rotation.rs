@@ -0,0 +1,62 @@
+use image::{Rgba, RgbaImage};
+use unity_rs::classes::{SpritePackingMode, SpritePackingRotation, SpriteSettings};
+
+/// A 3x2 image whose pixels are numbered row by row, top row first:
+///
+/// ```text
+/// 0 1 2
+/// 3 4 5
+/// ```
+fn numbered(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| Rgba([(y * width + x) as u8, 0, 0, 255]))
+}
+
+fn rows(image: &RgbaImage) -> Vec<Vec<u8>> {
+    (0..image.height()).map(|y| (0..image.width()).map(|x| image.get_pixel(x, y).0[0]).collect()).collect()
+}
+
+#[test]
+fn test_settings_from_raw() {
+    let settings = SpriteSettings::from_raw(1 | (4 << 2));
+    assert!(settings.packed);
+    assert_eq!(settings.packing_mode, SpritePackingMode::Tight);
+    assert_eq!(settings.packing_rotation, SpritePackingRotation::Rotate90);
+
+    let settings = SpriteSettings::from_raw(1 | (1 << 1) | (2 << 2));
+    assert_eq!(settings.packing_mode, SpritePackingMode::Rectangle);
+    assert_eq!(settings.packing_rotation, SpritePackingRotation::FlipVertical);
+
+    // Values the packer never writes fall back to no rotation instead of panicking.
+    assert_eq!(SpriteSettings::from_raw(15 << 2).packing_rotation, SpritePackingRotation::None);
+}
+
+#[test]
+fn test_unpack_none() {
+    let image = SpritePackingRotation::None.unpack(numbered(3, 2));
+    assert_eq!(rows(&image), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+}
+
+#[test]
+fn test_unpack_flip_horizontal() {
+    let image = SpritePackingRotation::FlipHorizontal.unpack(numbered(3, 2));
+    assert_eq!(rows(&image), vec![vec![2, 1, 0], vec![5, 4, 3]]);
+}
+
+#[test]
+fn test_unpack_flip_vertical() {
+    let image = SpritePackingRotation::FlipVertical.unpack(numbered(3, 2));
+    assert_eq!(rows(&image), vec![vec![3, 4, 5], vec![0, 1, 2]]);
+}
+
+#[test]
+fn test_unpack_rotate180() {
+    let image = SpritePackingRotation::Rotate180.unpack(numbered(3, 2));
+    assert_eq!(rows(&image), vec![vec![5, 4, 3], vec![2, 1, 0]]);
+}
+
+#[test]
+fn test_unpack_rotate90() {
+    let image = SpritePackingRotation::Rotate90.unpack(numbered(3, 2));
+    assert_eq!(image.dimensions(), (2, 3));
+    assert_eq!(rows(&image), vec![vec![3, 0], vec![4, 1], vec![5, 2]]);
+}
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use serde::Deserialize;
+use unity_rs::math::{Color, Matrix4x4, Quaternion, RectF32, Vector3};
+use unity_rs::object::Deserializer;
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::typetree::{builtin_nodes, TypeTreeBuilder, TypeTreeNode};
+use unity_rs::{ClassID, UnityVersion};
+
+#[test]
+fn test_build_type_tree() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour")
+        .field("int", "m_Value")
+        .field("bool", "m_Enabled")
+        .string("m_Title")
+        .array("vector", "m_Items", |b| b.field("UInt8", "data"))
+        .nested("Vector2f", "m_Offset", |b| b.field("float", "x").field("float", "y"))
+        .build();
+    let layout: Vec<(&str, &str, i32, i32, i32, i32)> = nodes.iter().map(|x| (x.type_.as_str(), x.name.as_str(), x.level, x.size, x.type_flag, x.meta_flag)).collect();
+    assert_eq!(
+        layout,
+        [
+            ("MonoBehaviour", "Base", 0, -1, 0, 0x8000),
+            ("int", "m_Value", 1, 4, 0, 0),
+            ("bool", "m_Enabled", 1, 1, 0, 0x4000),
+            ("string", "m_Title", 1, -1, 0, 0x8000),
+            ("Array", "Array", 2, -1, 1, 0x4001),
+            ("int", "size", 3, 4, 0, 0),
+            ("char", "data", 3, 1, 0, 0),
+            ("vector", "m_Items", 1, -1, 0, 0x8000),
+            ("Array", "Array", 2, -1, 1, 0x4000),
+            ("int", "size", 3, 4, 0, 0),
+            ("UInt8", "data", 3, 1, 0, 0),
+            ("Vector2f", "m_Offset", 1, 8, 0, 0),
+            ("float", "x", 2, 4, 0, 0),
+            ("float", "y", 2, 4, 0, 0),
+        ]
+    );
+    assert!(nodes.iter().enumerate().all(|(i, x)| x.index == i as i32 && x.version == 1));
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Offset {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Script {
+    #[serde(rename = "m_Value")]
+    value: i32,
+    #[serde(rename = "m_Enabled")]
+    enabled: bool,
+    #[serde(rename = "m_Title")]
+    title: String,
+    #[serde(rename = "m_Items")]
+    items: Vec<u8>,
+    #[serde(rename = "m_Offset")]
+    offset: Offset,
+}
+
+#[test]
+fn test_built_type_tree_deserializes() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour")
+        .field("int", "m_Value")
+        .field("bool", "m_Enabled")
+        .string("m_Title")
+        .array("vector", "m_Items", |b| b.field("UInt8", "data"))
+        .nested("Vector2f", "m_Offset", |b| b.field("float", "x").field("float", "y"))
+        .build();
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&7i32.to_le_bytes());
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    data.extend_from_slice(&5i32.to_le_bytes());
+    data.extend_from_slice(b"hello\0\0\0");
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(&[1, 2, 3, 0]);
+    data.extend_from_slice(&1.5f32.to_le_bytes());
+    data.extend_from_slice(&(-2f32).to_le_bytes());
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let value = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure");
+    let keys: Vec<&str> = value.as_object().expect("Not an object").keys().map(String::as_str).collect();
+    assert_eq!(keys, ["m_Value", "m_Enabled", "m_Title", "m_Items", "m_Offset"]);
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let script = Script::deserialize(&mut de).expect("Read Failure");
+    assert_eq!(
+        script,
+        Script {
+            value: 7,
+            enabled: true,
+            title: "hello".to_string(),
+            items: vec![1, 2, 3],
+            offset: Offset { x: 1.5, y: -2.0 },
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_from_stream() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour")
+        .string("m_Title")
+        .array("vector", "m_Items", |b| b.field("UInt8", "data"))
+        .nested("Vector2f", "m_Offset", |b| b.field("float", "x").field("float", "y"))
+        .build();
+    let mut data = Vec::new();
+    data.extend_from_slice(&5i32.to_le_bytes());
+    data.extend_from_slice(b"hello\0\0\0");
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(&[1, 2, 3, 0]);
+    data.extend_from_slice(&1.5f32.to_le_bytes());
+    data.extend_from_slice(&(-2f32).to_le_bytes());
+
+    let mut slice = Reader::new(&data, ByteOrder::Little);
+    let expected = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut slice)).expect("Read Failure");
+    let mut stream = Reader::from_stream(Cursor::new(data.clone()), ByteOrder::Little).expect("Seek Failure");
+    let mut de = Deserializer::new(&nodes, &mut stream);
+    assert_eq!(serde_json::Value::deserialize(&mut de).expect("Read Failure"), expected);
+    de.finish().expect("Data left");
+    assert_eq!(stream.get_offset(), data.len());
+
+    stream.set_offset(0).expect("Seek Failure");
+    assert_eq!(stream.read_aligned_string().expect("Read Failure"), "hello");
+    assert_eq!(stream.read_i32().expect("Read Failure"), 3);
+
+    let mut truncated = Reader::from_stream(Cursor::new(&data[..data.len() - 2]), ByteOrder::Little).expect("Seek Failure");
+    assert!(serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut truncated)).is_err());
+}
+
+#[derive(Debug, Deserialize)]
+struct Borrowed<'a> {
+    #[serde(rename = "m_Name")]
+    name: &'a str,
+    #[serde(rename = "image data")]
+    image: &'a [u8],
+}
+
+#[test]
+fn test_deserialize_borrowed() {
+    let mut nodes = TypeTreeBuilder::new("Texture2D").string("m_Name").build();
+    for (type_, name, level) in [("TypelessData", "image data", 1), ("int", "size", 2), ("UInt8", "data", 2)] {
+        nodes.push(TypeTreeNode {
+            type_: type_.to_string(),
+            name: name.to_string(),
+            level,
+            ..TypeTreeNode::default()
+        });
+    }
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(b"sky\0");
+    data.extend_from_slice(&4i32.to_le_bytes());
+    data.extend_from_slice(&[9, 8, 7, 6]);
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let texture = Borrowed::deserialize(&mut de).expect("Read Failure");
+    assert_eq!((texture.name, texture.image), ("sky", &[9, 8, 7, 6][..]));
+    assert!(std::ptr::eq(texture.name.as_ptr(), data[4..].as_ptr()));
+    assert!(std::ptr::eq(texture.image.as_ptr(), data[12..].as_ptr()));
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Versioned {
+    #[serde(rename = "m_Enabled")]
+    enabled: bool,
+    #[serde(rename = "m_Layer")]
+    layer: Option<i32>,
+    #[serde(rename = "m_Priority", default)]
+    priority: u32,
+    #[serde(rename = "m_Name")]
+    name: String,
+}
+
+#[test]
+fn test_deserialize_across_versions() {
+    // The older layout lacks m_Layer and m_Priority and has a field removed later.
+    let old = TypeTreeBuilder::new("Renderer").field("bool", "m_Enabled").field("float", "m_Legacy").string("m_Name").build();
+    let new = TypeTreeBuilder::new("Renderer").field("bool", "m_Enabled").field("int", "m_Layer").field("unsigned int", "m_Priority").string("m_Name").build();
+
+    let mut data = vec![1, 0, 0, 0];
+    data.extend_from_slice(&0.5f32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(b"old\0");
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let renderer = Versioned::deserialize(&mut Deserializer::new(&old, &mut reader)).expect("Read Failure");
+    assert_eq!(
+        renderer,
+        Versioned {
+            enabled: true,
+            layer: None,
+            priority: 0,
+            name: "old".to_string()
+        }
+    );
+
+    let mut data = vec![0, 0, 0, 0];
+    data.extend_from_slice(&5i32.to_le_bytes());
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(&2i32.to_le_bytes());
+    data.extend_from_slice(b"new\0");
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let renderer = Versioned::deserialize(&mut Deserializer::new(&new, &mut reader)).expect("Read Failure");
+    assert_eq!(
+        renderer,
+        Versioned {
+            enabled: false,
+            layer: Some(5),
+            priority: 3,
+            name: "new".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_build_builtin_fields() {
+    let nodes = TypeTreeBuilder::new("GameObject")
+        .with_asset_version(9)
+        .pptr("Component", "m_Component")
+        .packed("UInt16", "m_Tag")
+        .field("bool", "m_IsActive")
+        .typeless_data("image data")
+        .build();
+    let layout: Vec<(&str, &str, i32, i32, i32)> = nodes.iter().map(|x| (x.type_.as_str(), x.name.as_str(), x.level, x.size, x.meta_flag)).collect();
+    assert_eq!(
+        layout,
+        [
+            ("GameObject", "Base", 0, -1, 0x8000),
+            ("PPtr<Component>", "m_Component", 1, 8, 0),
+            ("int", "m_FileID", 2, 4, 0),
+            ("int", "m_PathID", 2, 4, 0),
+            ("UInt16", "m_Tag", 1, 2, 0),
+            ("bool", "m_IsActive", 1, 1, 0x4000),
+            ("TypelessData", "image data", 1, -1, 0),
+            ("int", "size", 2, 4, 0),
+            ("UInt8", "data", 2, 1, 0),
+        ]
+    );
+    let pptr = TypeTreeBuilder::new("Transform").pptr("GameObject", "m_GameObject").build();
+    assert_eq!((pptr[1].size, pptr[3].type_.as_str()), (12, "SInt64"));
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamData {
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Texture<'a> {
+    #[serde(rename = "m_Name")]
+    name: &'a str,
+    #[serde(rename = "m_Width")]
+    width: i32,
+    #[serde(rename = "m_TextureFormat")]
+    format: i32,
+    #[serde(rename = "m_IsReadable")]
+    readable: bool,
+    #[serde(rename = "m_ColorSpace")]
+    color_space: i32,
+    #[serde(rename = "image data")]
+    image: &'a [u8],
+    #[serde(rename = "m_StreamData")]
+    stream_data: StreamData,
+}
+
+#[test]
+fn test_read_builtin_templates() {
+    let version: UnityVersion = "2019.4.40f1".parse().expect("Parse failure");
+    let nodes = builtin_nodes(ClassID::Texture2D, version, 22).expect("Missing template");
+    let mut data = Vec::new();
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(b"sky\0");
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0]);
+    for x in [2i32, 1, 8, 4, 1] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    for x in [0i32, 1, 2, 1, 1, 0, 0, 0, 0, 0, 1, 8] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&[9, 8, 7, 6, 5, 4, 3, 2]);
+    data.extend_from_slice(&[0; 12]);
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let texture = Texture::deserialize(&mut de).expect("Read Failure");
+    de.finish().expect("Data left");
+    assert_eq!((texture.name, texture.width, texture.format, texture.readable, texture.color_space), ("sky", 2, 4, true, 1));
+    assert_eq!((texture.image, texture.stream_data.path.as_str()), (&[9, 8, 7, 6, 5, 4, 3, 2][..], ""));
+
+    // Empty objects are all counts and zeros.
+    let empty = [
+        (ClassID::Sprite, "2020.3.0f1", 212),
+        (ClassID::Mesh, "2019.4.40f1", 288),
+        (ClassID::Camera, "2018.3.0f2", 128),
+        (ClassID::Light, "2018.4.0f1", 116),
+        (ClassID::ParticleSystem, "2017.4.0f1", 28),
+    ];
+    for (class, version, size) in empty {
+        let nodes = builtin_nodes(class, version.parse().expect("Parse failure"), 22).expect("Missing template");
+        let data = vec![0; size];
+        let mut reader = Reader::new(&data, ByteOrder::Little);
+        let mut de = Deserializer::new(&nodes, &mut reader);
+        serde::de::IgnoredAny::deserialize(&mut de).expect("Read Failure");
+        de.finish().expect("Data left");
+    }
+    assert!(builtin_nodes(ClassID::Mesh, "2017.4.0f1".parse().expect("Parse failure"), 17).is_none());
+    assert!(builtin_nodes(ClassID::Camera, "2019.4.40f1".parse().expect("Parse failure"), 22).is_none());
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct PPtr {
+    #[serde(rename = "m_FileID")]
+    file_id: i32,
+    #[serde(rename = "m_PathID")]
+    path_id: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct AssetInfo {
+    #[serde(rename = "preloadIndex")]
+    preload_index: i32,
+    #[serde(rename = "preloadSize")]
+    preload_size: i32,
+    asset: PPtr,
+}
+
+#[derive(Debug, Deserialize)]
+struct Container<C> {
+    #[serde(rename = "m_Container")]
+    container: C,
+}
+
+fn read<T: serde::de::DeserializeOwned>(nodes: &[TypeTreeNode], data: &[u8]) -> T {
+    let mut reader = Reader::new(data, ByteOrder::Little);
+    T::deserialize(&mut Deserializer::new(nodes, &mut reader)).expect("Read Failure")
+}
+
+fn pairs(data: &mut Vec<u8>, entries: &[(&str, &[u8])]) {
+    data.extend_from_slice(&(entries.len() as i32).to_le_bytes());
+    for (key, value) in entries {
+        data.extend_from_slice(&(key.len() as i32).to_le_bytes());
+        data.extend_from_slice(key.as_bytes());
+        data.resize(data.len().next_multiple_of(4), 0);
+        data.extend_from_slice(value);
+    }
+}
+
+#[test]
+fn test_deserialize_pairs_as_map_or_seq() {
+    let pptr = |b: TypeTreeBuilder| b.field("int", "m_FileID").field("SInt64", "m_PathID");
+    let asset_info = |b: TypeTreeBuilder| b.field("int", "preloadIndex").field("int", "preloadSize").nested("PPtr<Object>", "asset", pptr);
+    let nodes = TypeTreeBuilder::new("AssetBundle")
+        .array("map", "m_Container", |b| b.nested("pair", "data", |b| b.string("first").nested("AssetInfo", "second", asset_info)))
+        .build();
+    let info = |index: i32, size: i32, path_id: i64| [&index.to_le_bytes()[..], &size.to_le_bytes(), &0i32.to_le_bytes(), &path_id.to_le_bytes()].concat();
+    let mut data = Vec::new();
+    pairs(&mut data, &[("assets/a.png", &info(0, 1, 10)), ("assets/b.prefab", &info(1, 2, 20))]);
+    let a = AssetInfo {
+        preload_index: 0,
+        preload_size: 1,
+        asset: PPtr { file_id: 0, path_id: 10 },
+    };
+    let b = AssetInfo {
+        preload_index: 1,
+        preload_size: 2,
+        asset: PPtr { file_id: 0, path_id: 20 },
+    };
+
+    let map: Container<HashMap<String, AssetInfo>> = read(&nodes, &data);
+    assert_eq!(map.container, HashMap::from([("assets/a.png".to_string(), a.clone()), ("assets/b.prefab".to_string(), b.clone())]));
+    let seq: Container<Vec<(String, AssetInfo)>> = read(&nodes, &data);
+    assert_eq!(seq.container, [("assets/a.png".to_string(), a), ("assets/b.prefab".to_string(), b)]);
+
+    // Newer materials keep their properties in vectors of pairs.
+    let nodes = TypeTreeBuilder::new("Material").array("vector", "m_Container", |b| b.nested("pair", "data", |b| b.string("first").field("float", "second"))).build();
+    let mut data = Vec::new();
+    pairs(&mut data, &[("_Glossiness", &0.5f32.to_le_bytes()), ("_Metallic", &1f32.to_le_bytes())]);
+    let seq: Container<Vec<(String, f32)>> = read(&nodes, &data);
+    assert_eq!(seq.container, [("_Glossiness".to_string(), 0.5), ("_Metallic".to_string(), 1.0)]);
+    let map: Container<HashMap<String, f32>> = read(&nodes, &data);
+    assert_eq!(map.container, HashMap::from([("_Glossiness".to_string(), 0.5), ("_Metallic".to_string(), 1.0)]));
+}
+
+#[derive(Deserialize)]
+struct Placed {
+    #[serde(rename = "m_LocalRotation")]
+    rotation: Quaternion,
+    #[serde(rename = "m_LocalPosition")]
+    position: Vector3,
+    #[serde(rename = "m_Color")]
+    color: Color,
+    #[serde(rename = "m_Rect")]
+    rect: RectF32,
+    #[serde(rename = "m_Matrix")]
+    matrix: Matrix4x4,
+}
+
+fn floats(data: &mut Vec<u8>, values: &[f32]) {
+    for x in values {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+}
+
+#[test]
+fn test_deserialize_math_types() {
+    let xyzw = |b: TypeTreeBuilder| b.field("float", "x").field("float", "y").field("float", "z").field("float", "w");
+    let tree = |color: &dyn Fn(TypeTreeBuilder) -> TypeTreeBuilder| {
+        TypeTreeBuilder::new("MonoBehaviour")
+            .nested("Quaternionf", "m_LocalRotation", xyzw)
+            .nested("Vector3f", "m_LocalPosition", |b| b.field("float", "x").field("float", "y").field("float", "z"))
+            .nested("ColorRGBA", "m_Color", color)
+            .nested("Rectf", "m_Rect", |b| b.field("float", "x").field("float", "y").field("float", "width").field("float", "height"))
+            .nested("Matrix4x4f", "m_Matrix", |b| (0..16).fold(b, |b, i| b.field("float", &format!("e{}{}", i / 4, i % 4))))
+            .build()
+    };
+    let matrix: Vec<f32> = (0..16).map(|x| x as f32).collect();
+
+    // Unity 5.6 and later store float channels, older versions a packed `rgba`.
+    let nodes = tree(&|b| b.field("float", "r").field("float", "g").field("float", "b").field("float", "a"));
+    let mut data = Vec::new();
+    floats(&mut data, &[0.0, 0.0, 0.5, 1.0, 1.0, 2.0, 3.0, 1.0, 0.5, 0.0, 1.0, 1.0, 2.0, 3.0, 4.0]);
+    floats(&mut data, &matrix);
+    let placed: Placed = read(&nodes, &data);
+    assert_eq!((placed.rotation.z, placed.rotation.w), (0.5, 1.0));
+    assert_eq!((placed.position.x, placed.position.y, placed.position.z), (1.0, 2.0, 3.0));
+    assert_eq!(placed.color.to_rgba8(), [255, 128, 0, 255]);
+    assert_eq!((placed.rect.x, placed.rect.y, placed.rect.w, placed.rect.h), (1.0, 2.0, 3.0, 4.0));
+    assert_eq!(placed.matrix.to_array().to_vec(), matrix);
+
+    let nodes = tree(&|b| b.field("unsigned int", "rgba"));
+    let mut data = Vec::new();
+    floats(&mut data, &[0.0, 0.0, 0.5, 1.0, 1.0, 2.0, 3.0]);
+    data.extend_from_slice(&[255, 128, 0, 255]);
+    floats(&mut data, &[1.0, 2.0, 3.0, 4.0]);
+    floats(&mut data, &matrix);
+    let placed: Placed = read(&nodes, &data);
+    assert_eq!(placed.color.to_rgba8(), [255, 128, 0, 255]);
+    assert_eq!(placed.matrix.get(1, 0), 1.0);
+
+    // The tuple form, such as from JSON arrays.
+    let position: Vector3 = serde_json::from_value(serde_json::json!([1, 2.5, 3])).expect("Read Failure");
+    assert_eq!((position.x, position.y, position.z), (1.0, 2.5, 3.0));
+    let color: Color = serde_json::from_value(serde_json::json!([1, 0.5, 0, 1])).expect("Read Failure");
+    assert_eq!(color.to_rgba8(), [255, 128, 0, 255]);
+    let color: Color = serde_json::from_value(serde_json::json!([0xff0080ffu32])).expect("Read Failure");
+    assert_eq!(color.to_rgba8(), [255, 128, 0, 255]);
+    let color: Color = serde_json::from_value(serde_json::json!({ "rgba": 0xff0080ffu32 })).expect("Read Failure");
+    assert_eq!(color.to_rgba8(), [255, 128, 0, 255]);
+    assert!(serde_json::from_value::<Vector3>(serde_json::json!([1, 2])).is_err());
+    let (x, y, z): (f32, f32, f32) = read(&nodes[6..10], &data[16..28]);
+    assert_eq!((x, y, z), (1.0, 2.0, 3.0));
+}
@@ -0,0 +1,83 @@
+use unity_rs::typetree::{generate_rust, TypeTreeNode};
+
+fn node(type_: &str, name: &str, level: i32) -> TypeTreeNode {
+    TypeTreeNode {
+        type_: type_.to_string(),
+        name: name.to_string(),
+        level,
+        ..TypeTreeNode::default()
+    }
+}
+
+fn pptr(name: &str, level: i32) -> [TypeTreeNode; 3] {
+    [node("PPtr<GameObject>", name, level), node("int", "m_FileID", level + 1), node("SInt64", "m_PathID", level + 1)]
+}
+
+#[test]
+fn test_generate_rust() {
+    let mut nodes = vec![node("MonoBehaviour", "Base", 0)];
+    nodes.extend(pptr("m_GameObject", 1));
+    nodes.extend([node("string", "m_Name", 1), node("Array", "Array", 2), node("int", "size", 3), node("char", "data", 3)]);
+    nodes.extend([node("vector", "m_Targets", 1), node("Array", "Array", 2), node("int", "size", 3)]);
+    nodes.extend(pptr("data", 3));
+    nodes.extend([node("map", "m_Weights", 1), node("Array", "Array", 2), node("int", "size", 3), node("pair", "data", 3)]);
+    nodes.extend(pptr("first", 4));
+    nodes.push(node("float", "second", 4));
+    nodes.extend([node("UInt16", "m_Flags", 1), node("unsigned long long", "m_Id", 1)]);
+
+    let expected = r#"#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Player {
+    #[serde(rename = "m_GameObject")]
+    pub game_object: PPtrGameObject,
+    #[serde(rename = "m_Name")]
+    pub name: String,
+    #[serde(rename = "m_Targets")]
+    pub targets: Vec<PPtrGameObject>,
+    #[serde(rename = "m_Weights")]
+    pub weights: std::collections::HashMap<PPtrGameObject, f32>,
+    #[serde(rename = "m_Flags")]
+    pub flags: u16,
+    #[serde(rename = "m_Id")]
+    pub id: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+pub struct PPtrGameObject {
+    #[serde(rename = "m_FileID")]
+    pub file_id: i32,
+    #[serde(rename = "m_PathID")]
+    pub path_id: i64,
+}
+"#;
+    assert_eq!(generate_rust(&nodes, "Player"), expected);
+}
+
+#[test]
+fn test_generate_rust_identifiers() {
+    let nodes = vec![
+        node("Settings", "Base", 0),
+        node("UInt8", "data[0]", 1),
+        node("UInt8", "data[1]", 1),
+        node("int", "type", 1),
+        node("int", "m_UVCount", 1),
+        node("int", "first value", 1),
+        node("Curve", "m_Curve", 1),
+        node("float", "m_Time", 2),
+        node("Curve", "m_Other", 1),
+        node("int", "m_Time", 2),
+        node("Curve", "m_Same", 1),
+        node("float", "m_Time", 2),
+    ];
+    let code = generate_rust(&nodes, "Settings");
+    assert!(code.contains("    #[serde(rename = \"data[0]\")]\n    pub data_0: u8,\n"));
+    assert!(code.contains("    #[serde(rename = \"data[1]\")]\n    pub data_1: u8,\n"));
+    assert!(code.contains("\n    pub r#type: i32,\n"));
+    assert!(code.contains("    #[serde(rename = \"m_UVCount\")]\n    pub uv_count: i32,\n"));
+    assert!(code.contains("    #[serde(rename = \"first value\")]\n    pub first_value: i32,\n"));
+    // Same named types with different fields get numbered, identical ones are shared.
+    assert!(code.contains("    pub curve: Curve,\n"));
+    assert!(code.contains("    pub other: Curve2,\n"));
+    assert!(code.contains("    pub same: Curve,\n"));
+    assert_eq!(code.matches("pub struct Curve {").count(), 1);
+    assert_eq!(code.matches("pub struct Curve2 {").count(), 1);
+}
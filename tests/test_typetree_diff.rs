@@ -0,0 +1,83 @@
+use unity_rs::typetree::{diff, dump_changes, TypeTreeChange, TypeTreeNode};
+
+fn node(type_: &str, name: &str, level: i32) -> TypeTreeNode {
+    TypeTreeNode {
+        type_: type_.to_string(),
+        name: name.to_string(),
+        level,
+        ..TypeTreeNode::default()
+    }
+}
+
+#[test]
+fn test_diff_unchanged() {
+    let nodes = vec![node("MonoBehaviour", "Base", 0), node("int", "m_Health", 1), node("Vector3f", "m_Position", 1), node("float", "x", 2)];
+    assert_eq!(diff(&nodes, &nodes), []);
+}
+
+#[test]
+fn test_diff() {
+    let old = vec![
+        node("MonoBehaviour", "Base", 0),
+        node("int", "m_Health", 1),
+        node("int", "m_Level", 1),
+        node("float", "m_Speed", 1),
+        node("Stats", "m_Stats", 1),
+        node("int", "m_Armor", 2),
+        node("Vector2f", "m_Position", 1),
+        node("float", "x", 2),
+    ];
+    let new = vec![
+        node("MonoBehaviour", "Base", 0),
+        node("int", "m_Hp", 1),
+        node("Progress", "m_Progress", 1),
+        node("int", "m_Level", 2),
+        node("double", "m_Speed", 1),
+        node("int", "m_Armor", 1),
+        node("Vector2f", "m_Position", 1),
+        node("float", "x", 2),
+        node("float", "y", 2),
+    ];
+    let changes = diff(&old, &new);
+    assert_eq!(
+        changes,
+        [
+            TypeTreeChange::Retyped {
+                path: "m_Speed".to_string(),
+                old_type: "float".to_string(),
+                new_type: "double".to_string()
+            },
+            TypeTreeChange::Moved {
+                old_path: "m_Level".to_string(),
+                new_path: "m_Progress.m_Level".to_string(),
+                type_: "int".to_string()
+            },
+            TypeTreeChange::Moved {
+                old_path: "m_Stats.m_Armor".to_string(),
+                new_path: "m_Armor".to_string(),
+                type_: "int".to_string()
+            },
+            TypeTreeChange::Renamed {
+                old_path: "m_Health".to_string(),
+                new_path: "m_Hp".to_string(),
+                type_: "int".to_string()
+            },
+            TypeTreeChange::Removed {
+                path: "m_Stats".to_string(),
+                type_: "Stats".to_string()
+            },
+            TypeTreeChange::Added {
+                path: "m_Progress".to_string(),
+                type_: "Progress".to_string()
+            },
+            TypeTreeChange::Added {
+                path: "m_Position.y".to_string(),
+                type_: "float".to_string()
+            },
+        ]
+    );
+    assert_eq!(
+        dump_changes(&changes),
+        "~ m_Speed: float -> double\n> m_Level -> m_Progress.m_Level: int\n> m_Stats.m_Armor -> m_Armor: int\n~ m_Health -> m_Hp: int\n- m_Stats: Stats\n+ m_Progress: Progress\n+ m_Position.y: float\n"
+    );
+}
@@ -0,0 +1,56 @@
+use unity_rs::typetree::{JsonTypeTreeProvider, TypeTree, TypeTreeNode, TypeTreeProvider};
+
+/// Nodes of one class as TypeTreeGenerator dumps them, with only type, name, level and meta flag.
+const GENERATOR_NODES: &str = r#"[
+    {"m_Type": "MonoBehaviour", "m_Name": "Base", "m_Level": 0, "m_MetaFlag": 0},
+    {"m_Type": "int", "m_Name": "m_Health", "m_Level": 1, "m_MetaFlag": 0},
+    {"m_Type": "string", "m_Name": "m_Title", "m_Level": 1, "m_MetaFlag": 32768},
+    {"m_Type": "Array", "m_Name": "Array", "m_Level": 2, "m_MetaFlag": 16385},
+    {"m_Type": "int", "m_Name": "size", "m_Level": 3, "m_MetaFlag": 0},
+    {"m_Type": "char", "m_Name": "data", "m_Level": 3, "m_MetaFlag": 0}
+]"#;
+
+#[test]
+fn test_read_generator_nodes() {
+    let tree = TypeTree::from_json_nodes(GENERATOR_NODES).expect("Parse failure");
+    assert_eq!(tree.nodes.len(), 6);
+    assert_eq!(tree.nodes[2].type_, "string");
+    assert_eq!(tree.nodes[2].name, "m_Title");
+    assert_eq!(tree.nodes[3].level, 2);
+    assert_eq!(tree.nodes[3].meta_flag, 0x4001);
+    assert_eq!(tree.nodes[3].size, 0);
+
+    let dump = format!(r#"{{"Assembly-CSharp.dll": {{"Game.Player": {}}}}}"#, GENERATOR_NODES);
+    let provider = JsonTypeTreeProvider::from_json(&dump).expect("Parse failure");
+    assert_eq!(provider.get_nodes("Assembly-CSharp", "Game.Player", "2019.4.40f1"), Some(tree.nodes));
+}
+
+#[test]
+fn test_typetree_json_round_trip() {
+    let tree = TypeTree {
+        nodes: vec![
+            TypeTreeNode {
+                type_: "TextAsset".to_string(),
+                name: "Base".to_string(),
+                size: -1,
+                version: 1,
+                meta_flag: 0x8000,
+                ..TypeTreeNode::default()
+            },
+            TypeTreeNode {
+                type_: "PPtr<Object>".to_string(),
+                name: "m_Ref".to_string(),
+                size: 12,
+                index: 1,
+                level: 1,
+                ref_type_hash: 0x0123_4567_89ab_cdef,
+                ..TypeTreeNode::default()
+            },
+        ],
+        string_buffer: Vec::new(),
+    };
+    let json = tree.to_json_nodes().expect("Write failure");
+    assert!(json.starts_with(r#"[{"m_Type":"TextAsset","m_Name":"Base","m_ByteSize":-1"#));
+    let read = TypeTree::from_json_nodes(&json).expect("Parse failure");
+    assert_eq!(read.nodes, tree.nodes);
+}
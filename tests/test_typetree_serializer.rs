@@ -0,0 +1,99 @@
+use serde::Deserialize;
+use serde_json::json;
+use unity_rs::object::{Deserializer, Serializer, WriteTypeTreeError};
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::typetree::{TypeTreeBuilder, TypeTreeNode};
+
+fn nodes() -> Vec<TypeTreeNode> {
+    let mut nodes = TypeTreeBuilder::new("MonoBehaviour")
+        .field("bool", "m_On")
+        .field("SInt64", "m_Id")
+        .array("vector", "m_Points", |b| b.nested("Vector2f", "data", |b| b.field("float", "x").field("float", "y")))
+        .field("UInt16", "m_Count")
+        .build();
+    // map<string, int>, which the builder has no shorthand for.
+    let map = [
+        ("map", "m_Lookup", 1, 0),
+        ("Array", "Array", 2, 0x4000),
+        ("int", "size", 3, 0),
+        ("pair", "data", 3, 0),
+        ("string", "first", 4, 0),
+        ("Array", "Array", 5, 0x4001),
+        ("int", "size", 6, 0),
+        ("char", "data", 6, 0),
+        ("int", "second", 4, 0),
+    ];
+    for (type_, name, level, meta_flag) in map {
+        nodes.push(TypeTreeNode {
+            type_: type_.to_string(),
+            name: name.to_string(),
+            level,
+            meta_flag,
+            ..TypeTreeNode::default()
+        });
+    }
+    nodes
+}
+
+fn value() -> serde_json::Value {
+    json!({
+        "m_On": true,
+        "m_Id": -2,
+        "m_Points": [{ "x": 1.5, "y": -1.0 }],
+        "m_Count": 3,
+        "m_Lookup": { "ab": 7 },
+    })
+}
+
+#[test]
+fn test_serialize_layout() {
+    let data = Serializer::new(&nodes(), ByteOrder::Little).write(&value()).expect("Write Failure");
+    let mut expected = vec![1, 0, 0, 0];
+    expected.extend_from_slice(&(-2i64).to_le_bytes());
+    expected.extend_from_slice(&1i32.to_le_bytes());
+    expected.extend_from_slice(&1.5f32.to_le_bytes());
+    expected.extend_from_slice(&(-1f32).to_le_bytes());
+    expected.extend_from_slice(&[3, 0, 0, 0]);
+    expected.extend_from_slice(&1i32.to_le_bytes());
+    expected.extend_from_slice(&2i32.to_le_bytes());
+    expected.extend_from_slice(b"ab\0\0");
+    expected.extend_from_slice(&7i32.to_le_bytes());
+    assert_eq!(data, expected);
+
+    let big = Serializer::new(&nodes(), ByteOrder::Big).write(&value()).expect("Write Failure");
+    assert_eq!(&big[4..12], (-2i64).to_be_bytes());
+    assert_eq!(big.len(), data.len());
+}
+
+#[test]
+fn test_serialize_round_trip() {
+    let nodes = nodes();
+    let data = Serializer::new(&nodes, ByteOrder::Big).write(&value()).expect("Write Failure");
+    let mut reader = Reader::new(&data, ByteOrder::Big);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let read = serde_json::Value::deserialize(&mut de).expect("Read Failure");
+    assert_eq!(read, value());
+    assert_eq!(Serializer::new(&nodes, ByteOrder::Big).serialize(&read).expect("Write Failure"), data);
+}
+
+#[test]
+fn test_serialize_mismatch() {
+    let nodes = nodes();
+    let write = |value: serde_json::Value| Serializer::new(&nodes, ByteOrder::Little).write(&value);
+    let mut value = value();
+    value["m_Count"] = json!(70000);
+    match write(value) {
+        Err(WriteTypeTreeError::Mismatch { path, .. }) => assert_eq!(path, "m_Count"),
+        other => panic!("unexpected result {:?}", other),
+    }
+    let mut value = self::value();
+    value["m_Points"][0]["y"] = json!("one");
+    match write(value) {
+        Err(WriteTypeTreeError::Mismatch { path, .. }) => assert_eq!(path, "m_Points.data.y"),
+        other => panic!("unexpected result {:?}", other),
+    }
+    let mut value = self::value();
+    value.as_object_mut().expect("Missing object").remove("m_On");
+    assert!(write(value).is_err());
+    assert!(write(json!([])).is_err());
+}
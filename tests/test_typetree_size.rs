@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use unity_rs::object::{Deserializer, ReadLimits, ReadTypeTreeError, Serializer};
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::typetree::{skip_subtree, subtree_size, TypeTreeBuilder, TypeTreeNode};
+
+fn nodes() -> Vec<TypeTreeNode> {
+    TypeTreeBuilder::new("MonoBehaviour")
+        .nested("Vector3f", "m_Position", |b| b.field("float", "x").field("float", "y").field("float", "z"))
+        .nested("Flags", "m_Flags", |b| b.field("bool", "m_On").field("int", "m_Mask"))
+        .string("m_Title")
+        .array("vector", "m_Points", |b| b.nested("Vector2f", "data", |b| b.field("float", "x").field("float", "y")))
+        .array("vector", "m_Names", |b| b.string("data"))
+        .field("int", "m_Last")
+        .build()
+}
+
+fn data() -> Vec<u8> {
+    let mut data = Vec::new();
+    for x in [1f32, 2.0, 3.0] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&[1, 0, 0, 0]);
+    data.extend_from_slice(&(-1i32).to_le_bytes());
+    data.extend_from_slice(&3i32.to_le_bytes());
+    data.extend_from_slice(b"abc\0");
+    data.extend_from_slice(&2i32.to_le_bytes());
+    for x in [0.5f32, 1.5, 2.5, 3.5] {
+        data.extend_from_slice(&x.to_le_bytes());
+    }
+    data.extend_from_slice(&2i32.to_le_bytes());
+    for name in ["hello", "hi"] {
+        data.extend_from_slice(&(name.len() as i32).to_le_bytes());
+        data.extend_from_slice(name.as_bytes());
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+    data.extend_from_slice(&42i32.to_le_bytes());
+    data
+}
+
+fn index(nodes: &[TypeTreeNode], name: &str) -> usize {
+    nodes.iter().position(|x| x.level == 1 && x.name == name).expect("Missing node")
+}
+
+#[test]
+fn test_subtree_size() {
+    let nodes = nodes();
+    assert_eq!(subtree_size(&nodes, index(&nodes, "m_Position")), Some(12));
+    assert_eq!(subtree_size(&nodes, index(&nodes, "m_Flags")), Some(8));
+    assert_eq!(subtree_size(&nodes, index(&nodes, "m_Last")), Some(4));
+    assert_eq!(subtree_size(&nodes, index(&nodes, "m_Title")), None);
+    assert_eq!(subtree_size(&nodes, index(&nodes, "m_Points")), None);
+    assert_eq!(subtree_size(&nodes, 0), None);
+}
+
+#[test]
+fn test_skip_subtree() {
+    let nodes = nodes();
+    let data = data();
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    for (name, end) in [("m_Position", 12), ("m_Flags", 20), ("m_Title", 28), ("m_Points", 48), ("m_Names", 72), ("m_Last", 76)] {
+        skip_subtree(&mut reader, &nodes, index(&nodes, name)).expect("Skip Failure");
+        assert_eq!(reader.get_offset(), end, "{}", name);
+    }
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    skip_subtree(&mut reader, &nodes, 0).expect("Skip Failure");
+    assert_eq!(reader.get_offset(), data.len());
+
+    let mut reader = Reader::new(&data[..40], ByteOrder::Little);
+    assert!(skip_subtree(&mut reader, &nodes, 0).is_err());
+}
+
+#[derive(Deserialize)]
+struct Last {
+    #[serde(rename = "m_Last")]
+    last: i32,
+}
+
+#[test]
+fn test_deserialize_skips_ignored_fields() {
+    let nodes = nodes();
+    let data = data();
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let value = Last::deserialize(&mut de).expect("Read Failure");
+    assert_eq!(value.last, 42);
+}
+
+#[test]
+fn test_deserialize_finish() {
+    let nodes = nodes();
+    let mut data = data();
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let error = de.finish().expect_err("Finished before reading");
+    assert!(matches!(error, ReadTypeTreeError::Trailing { bytes: 76, nodes: left } if left == nodes.len() - 1), "{}", error);
+    Last::deserialize(&mut de).expect("Read Failure");
+    de.finish().expect("Bytes left");
+
+    data.extend_from_slice(&[0; 6]);
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    Last::deserialize(&mut de).expect("Read Failure");
+    let error = de.finish().expect_err("Finished with bytes left");
+    assert!(matches!(error, ReadTypeTreeError::Trailing { bytes: 6, nodes: 0 }), "{}", error);
+
+    // Padding up to the final alignment is not left over.
+    let nodes = TypeTreeBuilder::new("MonoBehaviour").field("int", "m_Value").field("bool", "m_On").build();
+    let nodes = [&nodes[..2], &[TypeTreeNode { meta_flag: 0, ..nodes[2].clone() }]].concat();
+    let data = [7, 0, 0, 0, 1, 0, 0, 0];
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    serde_json::Value::deserialize(&mut de).expect("Read Failure");
+    de.finish().expect("Padding left");
+}
+
+#[derive(Deserialize)]
+struct Texture {
+    #[serde(rename = "m_Name")]
+    name: String,
+    #[serde(rename = "m_Height")]
+    height: i32,
+}
+
+#[test]
+fn test_deserialize_skips_large_fields() {
+    let mut nodes = TypeTreeBuilder::new("Texture2D").string("m_Name").field("int", "m_Width").build();
+    for (type_, name, level) in [("TypelessData", "image data", 1), ("int", "size", 2), ("UInt8", "data", 2)] {
+        nodes.push(TypeTreeNode {
+            type_: type_.to_string(),
+            name: name.to_string(),
+            level,
+            meta_flag: if level == 1 { 0x4000 } else { 0 },
+            ..TypeTreeNode::default()
+        });
+    }
+    nodes.extend(TypeTreeBuilder::new("Texture2D").field("int", "m_Height").build().into_iter().skip(1));
+
+    let image = 10 << 20;
+    let mut data = Vec::with_capacity(image + 32);
+    data.extend_from_slice(&4i32.to_le_bytes());
+    data.extend_from_slice(b"chip");
+    data.extend_from_slice(&2048i32.to_le_bytes());
+    data.extend_from_slice(&(image as i32).to_le_bytes());
+    data.resize(data.len() + image, 0xcd);
+    data.extend_from_slice(&1024i32.to_le_bytes());
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    let texture = Texture::deserialize(&mut de).expect("Read Failure");
+    assert_eq!((texture.name.as_str(), texture.height), ("chip", 1024));
+}
+
+#[derive(Debug, Deserialize)]
+struct Items {
+    #[serde(rename = "m_Items")]
+    _items: Vec<i32>,
+}
+
+#[test]
+fn test_deserialize_limits() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour").array("vector", "m_Items", |b| b.field("int", "data")).build();
+    for length in [1_000_000i32, -1] {
+        let mut data = length.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0; 16]);
+        let mut reader = Reader::new(&data, ByteOrder::Little);
+        let error = Items::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read too many elements");
+        assert!(matches!(error.inner(), ReadTypeTreeError::TooManyElements(x) if *x == length as i64), "{}", error);
+    }
+
+    let data = [&4i32.to_le_bytes()[..], &[0; 16]].concat();
+    let limits = ReadLimits { max_elements: 3, ..ReadLimits::default() };
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let error = Items::deserialize(&mut Deserializer::new(&nodes, &mut reader).with_limits(limits)).expect_err("Read over the limit");
+    assert!(matches!(error.inner(), ReadTypeTreeError::TooManyElements(4)), "{}", error);
+
+    let mut deep = TypeTreeBuilder::new("MonoBehaviour").field("int", "m_Value").build();
+    for level in 1..=200 {
+        deep.push(TypeTreeNode {
+            type_: "Nested".to_string(),
+            name: "m_Child".to_string(),
+            level,
+            ..TypeTreeNode::default()
+        });
+    }
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let error = serde_json::Value::deserialize(&mut Deserializer::new(&deep, &mut reader)).expect_err("Read too deep");
+    assert!(matches!(error.inner(), ReadTypeTreeError::TooDeep(128)), "{}", error);
+}
+
+#[test]
+fn test_deserialize_bad_string_length() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour").string("m_Name").build();
+    for length in [i32::MAX, -4, 5] {
+        let data = [&length.to_le_bytes()[..], b"abcd"].concat();
+        let mut reader = Reader::new(&data, ByteOrder::Little);
+        let error = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read a bad length");
+        assert!(matches!(error.inner(), ReadTypeTreeError::BadLength(e) if e.length == length as i64 && e.remaining == 4), "{}", error);
+    }
+
+    let data = [&4i32.to_le_bytes()[..], b"abcd"].concat();
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    reader.set_max_string_length(3);
+    let error = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read over the limit");
+    assert!(matches!(error.inner(), ReadTypeTreeError::BadLength(e) if e.max == 3), "{}", error);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Arrays {
+    #[serde(rename = "m_Floats")]
+    floats: Vec<f32>,
+    #[serde(rename = "m_Shorts")]
+    shorts: Vec<u16>,
+    #[serde(rename = "m_Bytes")]
+    bytes: Vec<u8>,
+    #[serde(rename = "m_Last")]
+    last: i64,
+}
+
+#[test]
+fn test_deserialize_number_arrays() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour")
+        .array("vector", "m_Floats", |b| b.field("float", "data"))
+        .array("vector", "m_Shorts", |b| b.field("UInt16", "data"))
+        .array("vector", "m_Bytes", |b| b.field("UInt8", "data"))
+        .field("SInt64", "m_Last")
+        .build();
+    let mut data = Vec::new();
+    data.extend_from_slice(&2i32.to_be_bytes());
+    for x in [1.5f32, -2.0] {
+        data.extend_from_slice(&x.to_be_bytes());
+    }
+    data.extend_from_slice(&3i32.to_be_bytes());
+    for x in [1u16, 0x0102, 0xffff] {
+        data.extend_from_slice(&x.to_be_bytes());
+    }
+    data.extend_from_slice(&[0, 0]);
+    data.extend_from_slice(&3i32.to_be_bytes());
+    data.extend_from_slice(&[7, 8, 9, 0]);
+    data.extend_from_slice(&(-5i64).to_be_bytes());
+    let expected = Arrays {
+        floats: vec![1.5, -2.0],
+        shorts: vec![1, 0x0102, 0xffff],
+        bytes: vec![7, 8, 9],
+        last: -5,
+    };
+
+    let mut reader = Reader::new(&data, ByteOrder::Big);
+    let mut de = Deserializer::new(&nodes, &mut reader);
+    assert_eq!(Arrays::deserialize(&mut de).expect("Read Failure"), expected);
+    de.finish().expect("Trailing data");
+
+    let mut reader = Reader::new(&data, ByteOrder::Big);
+    let value = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure");
+    assert_eq!(value, serde_json::json!({"m_Floats": [1.5, -2.0], "m_Shorts": [1, 258, 65535], "m_Bytes": [7, 8, 9], "m_Last": -5}));
+
+    // An array cut short fails rather than reading past its data.
+    let mut reader = Reader::new(&data[..10], ByteOrder::Big);
+    assert!(Arrays::deserialize(&mut Deserializer::new(&nodes, &mut reader)).is_err());
+}
+
+#[derive(Debug, Deserialize)]
+struct FlaggedPoint {
+    #[serde(rename = "x")]
+    _x: f32,
+    #[serde(rename = "y")]
+    _y: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct FlaggedPoints {
+    #[serde(rename = "m_Points")]
+    _points: Vec<FlaggedPoint>,
+}
+
+#[test]
+fn test_deserialize_error_position() {
+    let (nodes, data) = (nodes(), data());
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let error = FlaggedPoints::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read a float as bool");
+    let ReadTypeTreeError::At { path, node, offset, source } = &error else {
+        panic!("no position in {:?}", error);
+    };
+    assert_eq!((path.as_str(), *node, *offset), ("m_Points[0].y", 17, 36));
+    assert!(matches!(**source, ReadTypeTreeError::Custom(_)));
+    assert!(error.to_string().ends_with("at m_Points[0].y (node 17, offset 36)"), "{}", error);
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Entry {
+    a: u8,
+    b: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entries {
+    #[serde(rename = "m_Map")]
+    map: HashMap<i32, Entry>,
+    #[serde(rename = "m_Last")]
+    last: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Last {
+    #[serde(rename = "m_Last")]
+    last: i32,
+}
+
+#[test]
+fn test_map_entry_alignment() {
+    let mut nodes = TypeTreeBuilder::new("MonoBehaviour")
+        .array("map", "m_Map", |b| b.nested("pair", "data", |b| b.field("int", "first").nested("Entry", "second", |b| b.field("UInt8", "a").field("UInt8", "b"))))
+        .field("int", "m_Last")
+        .build();
+    for node in nodes.iter_mut().filter(|x| x.name == "a" || x.name == "b") {
+        node.meta_flag = 0;
+    }
+    let position = |nodes: &[TypeTreeNode], name: &str| nodes.iter().position(|x| x.name == name).expect("Missing node");
+    let entries = |padding: usize| {
+        let mut data = 2i32.to_le_bytes().to_vec();
+        for (key, a, b) in [(1i32, 2u8, 3u8), (4, 5, 6)] {
+            data.extend_from_slice(&key.to_le_bytes());
+            data.extend_from_slice(&[a, b]);
+            data.resize(data.len() + padding, 0);
+        }
+        data.resize(data.len().next_multiple_of(4), 0);
+        data.extend_from_slice(&42i32.to_le_bytes());
+        data
+    };
+
+    let second = position(&nodes, "second");
+    let pair = position(&nodes, "data");
+    let mut second_aligned = nodes.clone();
+    second_aligned[second].meta_flag |= 0x4000;
+    let mut pair_aligned = nodes.clone();
+    pair_aligned[pair].meta_flag |= 0x4000;
+    // Unaligned entries are packed, the second key starting at offset 10.
+    for (nodes, data) in [(nodes, entries(0)), (second_aligned, entries(2)), (pair_aligned, entries(2))] {
+        let mut reader = Reader::new(&data, ByteOrder::Little);
+        let read = Entries::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure");
+        assert_eq!(read.map, HashMap::from([(1, Entry { a: 2, b: 3 }), (4, Entry { a: 5, b: 6 })]));
+        assert_eq!(read.last, 42);
+        assert_eq!(reader.get_offset(), data.len());
+
+        let mut reader = Reader::new(&data, ByteOrder::Little);
+        let read = Last::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure");
+        assert_eq!(read.last, 42);
+
+        let value = serde_json::json!({ "m_Map": { "1": { "a": 2, "b": 3 }, "4": { "a": 5, "b": 6 } }, "m_Last": 42 });
+        assert_eq!(Serializer::new(&nodes, ByteOrder::Little).serialize(&value).expect("Write Failure"), data);
+    }
+}
+
+#[test]
+fn test_deserialize_empty_struct() {
+    let nodes = TypeTreeBuilder::new("MonoBehaviour").nested("Empty", "m_Empty", |b| b).field("int", "m_Value").build();
+    let data = 7i32.to_le_bytes();
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let value = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect("Read Failure");
+    assert_eq!(value, serde_json::json!({ "m_Empty": {}, "m_Value": 7 }));
+
+    // Elements taking no bytes count one against the data left.
+    let nodes = TypeTreeBuilder::new("MonoBehaviour").array("vector", "m_Items", |b| b.nested("Empty", "data", |b| b)).build();
+    let data = [&100_000i32.to_le_bytes()[..], &[0; 16]].concat();
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let error = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read too many elements");
+    assert!(matches!(error.inner(), ReadTypeTreeError::TooManyElements(100_000)), "{}", error);
+}
+
+#[test]
+fn test_flat_arrays() {
+    // Arrays whose elements are the next array at the same level, which nest without nesting
+    // levels.
+    let mut nodes = TypeTreeBuilder::new("MonoBehaviour").build();
+    for i in 0..1000 {
+        for type_ in ["vector", "Array", "int"] {
+            nodes.push(TypeTreeNode {
+                type_: type_.to_string(),
+                name: format!("m_{}", i),
+                level: 1,
+                ..TypeTreeNode::default()
+            });
+        }
+    }
+    let data = 1i32.to_le_bytes().repeat(2000);
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    let error = serde_json::Value::deserialize(&mut Deserializer::new(&nodes, &mut reader)).expect_err("Read too deep");
+    assert!(matches!(error.inner(), ReadTypeTreeError::TooDeep(128)), "{}", error);
+
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    assert!(skip_subtree(&mut reader, &nodes, 1).is_err());
+    let mut reader = Reader::new(&data, ByteOrder::Little);
+    assert!(skip_subtree(&mut reader, &nodes, 0).is_err());
+}
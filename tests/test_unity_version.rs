@@ -0,0 +1,28 @@
+use unity_rs::version::ReleaseType;
+use unity_rs::UnityVersion;
+
+#[test]
+fn test_parse_unity_version() {
+    let version: UnityVersion = "2019.4.40f1".parse().expect("Parse failure");
+    assert_eq!(version, UnityVersion::new(2019, 4, 40, ReleaseType::Final, 1));
+    assert_eq!(version.to_string(), "2019.4.40f1");
+
+    let china: UnityVersion = "2019.4.40f1c1".parse().expect("Parse failure");
+    assert_eq!(china, version);
+    assert_eq!("5.6.0".parse::<UnityVersion>().expect("Parse failure"), UnityVersion::new(5, 6, 0, ReleaseType::Final, 0));
+    assert_eq!("2022.1.0b5".parse::<UnityVersion>().expect("Parse failure").release_type, ReleaseType::Beta);
+    assert!("".parse::<UnityVersion>().is_err());
+    assert!("unknown".parse::<UnityVersion>().is_err());
+}
+
+#[test]
+fn test_compare_unity_version() {
+    let parse = |s: &str| s.parse::<UnityVersion>().expect("Parse failure");
+    assert!(parse("2018.4.36f1") < parse("2019.1.0a1"));
+    assert!(parse("2021.3.0b1") < parse("2021.3.0f1"));
+    assert!(parse("5.4.1f1") < parse("5.4.1p3"));
+    assert!(parse("2019.4.40f1").is_at_least(2019, 3));
+    assert!(!parse("2019.2.21f1").is_at_least(2019, 3));
+    assert!(parse("2020.3.2f1").is_at_least_patch(2020, 3, 2));
+    assert!(!parse("2020.3.1f1").is_at_least_patch(2020, 3, 2));
+}
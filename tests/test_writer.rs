@@ -0,0 +1,126 @@
+use unity_rs::math::{Matrix4x4, RectF32, Vector3};
+use unity_rs::reader::{ByteOrder, Reader};
+use unity_rs::writer::Writer;
+
+fn round_trip(order: ByteOrder) {
+    let mut w = Writer::new(order);
+    w.write_u8(0xfe);
+    w.write_i8(-3);
+    w.write_bool(true);
+    w.write_u16(0xbeef);
+    w.write_i16(-2);
+    w.write_u32(0xdead_beef);
+    w.write_i32(-123_456);
+    w.write_u64(u64::MAX - 1);
+    w.write_i64(i64::MIN);
+    w.write_f32(1.5);
+    w.write_f64(-0.25);
+    w.write_f16(-2.0);
+    w.write_7bit_u32(300);
+    w.write_string_util_null("null");
+    w.write_string_with_7bit_length("seven");
+    w.write_aligned_string("hello");
+    w.write_i32_list(&[1, -1]);
+    w.write_u16_list(&[7]);
+    w.write_u32_list(&[8]);
+    w.write_f32_list(&[0.5]);
+    w.write_string_list(&["a".to_string(), "four".to_string()]);
+    w.write_rect_f32(&RectF32 { x: 1.0, y: 2.0, w: 3.0, h: 4.0 });
+    w.write_vector3(&Vector3 { x: 5.0, y: 6.0, z: 7.0 });
+    let matrix = Matrix4x4::from_array(std::array::from_fn(|i| i as f32));
+    w.write_matrix4x4(&matrix);
+    w.write_u8_list(b"raw");
+    let data = w.into_inner();
+
+    let mut r = Reader::new(&data, order);
+    assert_eq!(r.read_u8().expect("Read Failure"), 0xfe);
+    assert_eq!(r.read_i8().expect("Read Failure"), -3);
+    assert!(r.read_bool().expect("Read Failure"));
+    assert_eq!(r.read_u16().expect("Read Failure"), 0xbeef);
+    assert_eq!(r.read_i16().expect("Read Failure"), -2);
+    assert_eq!(r.read_u32().expect("Read Failure"), 0xdead_beef);
+    assert_eq!(r.read_i32().expect("Read Failure"), -123_456);
+    assert_eq!(r.read_u64().expect("Read Failure"), u64::MAX - 1);
+    assert_eq!(r.read_i64().expect("Read Failure"), i64::MIN);
+    assert_eq!(r.read_f32().expect("Read Failure"), 1.5);
+    assert_eq!(r.read_f64().expect("Read Failure"), -0.25);
+    assert_eq!(r.read_f16().expect("Read Failure"), -2.0);
+    assert_eq!(r.read_7bit_u32().expect("Read Failure"), 300);
+    assert_eq!(r.read_string_util_null().expect("Read Failure"), "null");
+    assert_eq!(r.read_string_with_7bit_length().expect("Read Failure"), "seven");
+    assert_eq!(r.read_aligned_string().expect("Read Failure"), "hello");
+    assert_eq!(r.read_i32_list(2).expect("Read Failure"), [1, -1]);
+    assert_eq!(r.read_u16_list(1).expect("Read Failure"), [7]);
+    assert_eq!(r.read_u32_list(1).expect("Read Failure"), [8]);
+    assert_eq!(r.read_f32_list(1).expect("Read Failure"), [0.5]);
+    assert_eq!(r.read_string_list().expect("Read Failure"), ["a", "four"]);
+    let rect = r.read_rect_f32().expect("Read Failure");
+    assert_eq!((rect.x, rect.y, rect.w, rect.h), (1.0, 2.0, 3.0, 4.0));
+    let vector = r.read_vector3().expect("Read Failure");
+    assert_eq!((vector.x, vector.y, vector.z), (5.0, 6.0, 7.0));
+    assert_eq!(r.read_matrix4x4().expect("Read Failure").to_array(), matrix.to_array());
+    assert_eq!(r.read_u8_list(3).expect("Read Failure"), b"raw");
+    assert_eq!(r.get_offset(), data.len());
+}
+
+#[test]
+fn test_write_round_trips() {
+    round_trip(ByteOrder::Little);
+    round_trip(ByteOrder::Big);
+}
+
+#[test]
+fn test_write_byte_order() {
+    let mut w = Writer::new(ByteOrder::Big);
+    w.write_u32(0x0102_0304);
+    w.set_little_order();
+    w.write_u32(0x0102_0304);
+    assert_eq!(w.as_slice(), [1, 2, 3, 4, 4, 3, 2, 1]);
+}
+
+#[test]
+fn test_write_alignment() {
+    let mut w = Writer::new(ByteOrder::Little);
+    w.write_aligned_string("");
+    assert_eq!(w.len(), 4);
+    w.write_aligned_string("four");
+    assert_eq!(w.len(), 12);
+    w.write_aligned_string("five5");
+    assert_eq!(w.len(), 24);
+    assert_eq!(&w.as_slice()[16..24], b"five5\0\0\0");
+
+    assert_eq!(w.align(4), 24);
+    w.write_bool(true);
+    assert_eq!(w.align(4), 28);
+    assert_eq!(&w.as_slice()[24..], [1, 0, 0, 0]);
+    w.write_u8(9);
+    assert_eq!(w.align(8), 32);
+
+    let data = w.into_inner();
+    let mut r = Reader::new(&data, ByteOrder::Little);
+    assert_eq!(r.read_aligned_string().expect("Read Failure"), "");
+    assert_eq!(r.read_aligned_string().expect("Read Failure"), "four");
+    assert_eq!(r.read_aligned_string().expect("Read Failure"), "five5");
+    assert!(r.read_bool().expect("Read Failure"));
+    assert_eq!(r.align(4).expect("Align Failure"), 28);
+}
+
+#[test]
+fn test_patch_length() {
+    let mut w = Writer::new(ByteOrder::Big);
+    let length_at = w.get_offset();
+    w.write_i32(0);
+    w.write_u8_slice(b"payload");
+    let length = (w.get_offset() - length_at - 4) as i32;
+    w.patch_i32(length_at, length).expect("Patch Failure");
+    assert_eq!(w.get_offset(), 11);
+    w.write_u8(0);
+    assert!(w.patch_i32(9, 1).is_err());
+    assert!(w.set_offset(13).is_err());
+
+    // Seeking back overwrites in place.
+    w.set_offset(4).expect("Seek Failure");
+    w.write_u8_slice(b"P");
+    assert_eq!(w.len(), 12);
+    assert_eq!(w.into_inner(), b"\0\0\0\x07Payload\0");
+}
@@ -1,5 +1,3 @@
-use std::io;
-
 use crate::error::DecodeImageError;
 use crate::pixel_info::{Pixel, WritePixelBuf};
 use crate::write_buffer::WriteBuff;
@@ -36,12 +34,12 @@ pub trait ImageDecoder<const PIXEL_NUM: usize = 1> {
             let pixels = Self::decode_pixel(&mut buff)?;
             pixels.write_buf(&mut write_buf);
 
-            Ok::<_, io::Error>(())
+            Ok::<_, DecodeImageError>(())
         })?;
         Ok(buf.inner())
     }
 
     const DECODE_PIXEL_BYTE: usize;
 
-    fn decode_pixel(data: &mut &[u8]) -> io::Result<[Pixel; PIXEL_NUM]>;
+    fn decode_pixel(data: &mut &[u8]) -> Result<[Pixel; PIXEL_NUM], DecodeImageError>;
 }
@@ -1,15 +1,14 @@
+use crate::error::DecodeImageError;
 use crate::pixel_info::{Pixel, SinglePixel};
 use crate::ImageDecoder;
 use byteorder::{BigEndian, ReadBytesExt};
 
-use std::io;
-
 pub struct ARGB4444;
 
 impl ImageDecoder for ARGB4444 {
     const DECODE_PIXEL_BYTE: usize = 2;
 
-    fn decode_pixel(data: &mut &[u8]) -> io::Result<SinglePixel> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<SinglePixel, DecodeImageError> {
         let mut pixel_buff = [0u8; 4];
         let pixel_old = data.read_u16::<BigEndian>()?;
         pixel_buff[0] = (pixel_old & 0x000f) as _;
@@ -9,9 +9,15 @@ pub struct DXT1;
 
 impl DXT1 {
     pub fn decode(data: &[u8], width: u32, height: u32) -> Result<RgbaImage, DecodeImageError> {
-        let mut buffer = vec![0u8; (width * height * 4) as usize];
-        let blocks_x = (width + 3) / 4;
+        let (width, height) = (width as usize, height as usize);
         let block_size = 8;
+        let blocks = width.div_ceil(4).saturating_mul(height.div_ceil(4));
+        if data.len() / block_size < blocks {
+            return Err(DecodeImageError::SizeNotMatch(data.len() / block_size, blocks));
+        }
+        let len = width.checked_mul(height).and_then(|x| x.checked_mul(4)).ok_or(DecodeImageError::ImageDecode)?;
+        let mut buffer = vec![0u8; len];
+        let blocks_x = width.div_ceil(4).max(1);
 
         for (i, chunk) in data.chunks(block_size).enumerate() {
             if chunk.len() < block_size {
@@ -20,8 +26,11 @@ impl DXT1 {
 
             let pixels_in_block = Self::decode_block(chunk).map_err(|_| DecodeImageError::InvalidData)?;
 
-            let block_x = (i as u32 % blocks_x) * 4;
-            let block_y = (i as u32 / blocks_x) * 4;
+            let block_x = (i % blocks_x) * 4;
+            let block_y = (i / blocks_x) * 4;
+            if block_y >= height {
+                break;
+            }
 
             for row in 0..4 {
                 for col in 0..4 {
@@ -33,14 +42,14 @@ impl DXT1 {
                     }
 
                     let flipped_y = height - 1 - y;
-                    let global_idx = ((flipped_y * width + x) * 4) as usize;
-                    let pixel = pixels_in_block[(row * 4 + col) as usize];
+                    let global_idx = (flipped_y * width + x) * 4;
+                    let pixel = pixels_in_block[row * 4 + col];
 
                     buffer[global_idx..global_idx + 4].copy_from_slice(&pixel);
                 }
             }
         }
-        RgbaImage::from_raw(width, height, buffer).ok_or(DecodeImageError::ImageDecode)
+        RgbaImage::from_raw(width as u32, height as u32, buffer).ok_or(DecodeImageError::ImageDecode)
     }
 
     fn decode_block(data: &[u8]) -> std::io::Result<[[u8; 4]; 16]> {
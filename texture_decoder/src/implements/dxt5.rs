@@ -1,25 +1,36 @@
 use byteorder::{LittleEndian, ReadBytesExt};
 use image::RgbaImage;
-use std::io::{self, Cursor, Read};
+use std::io::{Cursor, Read};
+
+use crate::error::DecodeImageError;
 
 pub struct DXT5;
 
 impl DXT5 {
-    pub fn decode(data: &[u8], width: u32, height: u32) -> io::Result<RgbaImage> {
-        let mut buffer = vec![0u8; (width * height * 4) as usize];
-
-        let blocks_x = (width + 3) / 4;
+    pub fn decode(data: &[u8], width: u32, height: u32) -> Result<RgbaImage, DecodeImageError> {
+        let (width, height) = (width as usize, height as usize);
         let block_size = 16;
+        let blocks = width.div_ceil(4).saturating_mul(height.div_ceil(4));
+        if data.len() / block_size < blocks {
+            return Err(DecodeImageError::SizeNotMatch(data.len() / block_size, blocks));
+        }
+        let len = width.checked_mul(height).and_then(|x| x.checked_mul(4)).ok_or(DecodeImageError::ImageDecode)?;
+        let mut buffer = vec![0u8; len];
+
+        let blocks_x = width.div_ceil(4).max(1);
 
         for (i, chunk) in data.chunks(block_size).enumerate() {
             if chunk.len() < block_size {
                 break;
             }
 
-            let pixels_in_block = Self::decode_block(chunk)?;
+            let pixels_in_block = Self::decode_block(chunk).map_err(|_| DecodeImageError::InvalidData)?;
 
-            let block_x = (i as u32 % blocks_x) * 4;
-            let block_y = (i as u32 / blocks_x) * 4;
+            let block_x = (i % blocks_x) * 4;
+            let block_y = (i / blocks_x) * 4;
+            if block_y >= height {
+                break;
+            }
 
             for row in 0..4 {
                 for col in 0..4 {
@@ -31,9 +42,9 @@ impl DXT5 {
                     }
 
                     let flipped_y = height - 1 - y;
-                    let global_idx = ((flipped_y * width + x) * 4) as usize;
+                    let global_idx = (flipped_y * width + x) * 4;
 
-                    let local_idx = (row * 4 + col) as usize;
+                    let local_idx = row * 4 + col;
                     let pixel = pixels_in_block[local_idx];
 
                     buffer[global_idx] = pixel[0];
@@ -43,7 +54,7 @@ impl DXT5 {
                 }
             }
         }
-        Ok(RgbaImage::from_raw(width, height, buffer).unwrap())
+        RgbaImage::from_raw(width as u32, height as u32, buffer).ok_or(DecodeImageError::ImageDecode)
     }
 
     fn decode_block(data: &[u8]) -> std::io::Result<[[u8; 4]; 16]> {
@@ -1,5 +1,6 @@
 use byteorder::{BigEndian, ReadBytesExt};
 
+use crate::error::DecodeImageError;
 use crate::pixel_info::{Pixel, SinglePixel};
 use crate::utils::FloatConvU8;
 use crate::ImageDecoder;
@@ -9,7 +10,7 @@ pub struct RFloat;
 impl ImageDecoder for RFloat {
     const DECODE_PIXEL_BYTE: usize = 4;
 
-    fn decode_pixel(data: &mut &[u8]) -> std::io::Result<SinglePixel> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<SinglePixel, DecodeImageError> {
         Ok(Pixel::builder().rad(data.read_f32::<BigEndian>()?.to_u8()).build().into())
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::DecodeImageError;
 use crate::pixel_info::{Pixel, SinglePixel};
 use crate::utils::{FloatConvU8, ReadHalfFloat};
 use crate::ImageDecoder;
@@ -8,7 +9,7 @@ pub struct RGHalf;
 impl ImageDecoder for RGHalf {
     const DECODE_PIXEL_BYTE: usize = 4;
 
-    fn decode_pixel(data: &mut &[u8]) -> std::io::Result<SinglePixel> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<SinglePixel, DecodeImageError> {
         let (r, g) = (data.read_f16::<BigEndian>()?.to_u8(), data.read_f16::<BigEndian>()?.to_u8());
 
         Ok(Pixel::builder().rad(r).green(g).build().into())
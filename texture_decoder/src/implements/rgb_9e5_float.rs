@@ -1,15 +1,15 @@
+use crate::error::DecodeImageError;
 use crate::pixel_info::Pixel;
 use crate::utils::FloatConvU8;
 use crate::ImageDecoder;
 use byteorder::{BigEndian, ReadBytesExt};
-use std::io::Error;
 
 pub struct RGB9e5Float;
 
 impl ImageDecoder for RGB9e5Float {
     const DECODE_PIXEL_BYTE: usize = 4;
 
-    fn decode_pixel(data: &mut &[u8]) -> Result<[Pixel; 1], Error> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<[Pixel; 1], DecodeImageError> {
         let val = data.read_i32::<BigEndian>()?;
         let scale = val >> 27 & 0x1f;
         let scale = 2f64.powf((scale - 24) as _);
@@ -1,3 +1,4 @@
+use crate::error::DecodeImageError;
 use crate::pixel_info::{Pixel, SinglePixel};
 use crate::ImageDecoder;
 use byteorder::ReadBytesExt;
@@ -7,7 +8,7 @@ pub struct RGBA32;
 impl ImageDecoder for RGBA32 {
     const DECODE_PIXEL_BYTE: usize = 4;
 
-    fn decode_pixel(data: &mut &[u8]) -> std::io::Result<SinglePixel> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<SinglePixel, DecodeImageError> {
         let (r, g, b, a) = (data.read_u8()?, data.read_u8()?, data.read_u8()?, data.read_u8()?);
         let pixel = Pixel::new_rgba(r, g, b, a);
         Ok(pixel.into())
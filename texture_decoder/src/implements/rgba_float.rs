@@ -1,3 +1,4 @@
+use crate::error::DecodeImageError;
 use crate::pixel_info::{Pixel, SinglePixel};
 use crate::utils::FloatConvU8;
 use crate::ImageDecoder;
@@ -8,7 +9,7 @@ pub struct RGBAFloat;
 impl ImageDecoder for RGBAFloat {
     const DECODE_PIXEL_BYTE: usize = 16;
 
-    fn decode_pixel(data: &mut &[u8]) -> std::io::Result<SinglePixel> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<SinglePixel, DecodeImageError> {
         Ok(Pixel::builder()
             .rad(data.read_f32::<BigEndian>()?.to_u8())
             .green(data.read_f32::<BigEndian>()?.to_u8())
@@ -1,15 +1,15 @@
+use crate::error::DecodeImageError;
 use crate::pixel_info::Pixel;
 use crate::utils::clamp_byte;
 use crate::ImageDecoder;
 use byteorder::ReadBytesExt;
-use std::io;
 
 pub struct YUY2;
 
 impl ImageDecoder<2> for YUY2 {
     const DECODE_PIXEL_BYTE: usize = 4;
 
-    fn decode_pixel(data: &mut &[u8]) -> Result<[Pixel; 2], io::Error> {
+    fn decode_pixel(data: &mut &[u8]) -> Result<[Pixel; 2], DecodeImageError> {
         let (y0, u0) = (data.read_u8()? as u16, data.read_u8()? as u16);
         let (y1, v0) = (data.read_u8()? as u16, data.read_u8()? as u16);
         let c = y0 - 16;
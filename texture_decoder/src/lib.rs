@@ -32,11 +32,12 @@ impl ImageSize {
         Self { width, height }
     }
 
+    /// Saturates rather than overflowing, so that sizes too large for the data fail to decode.
     pub fn size(&self) -> usize {
-        self.width * self.height
+        self.width.saturating_mul(self.height)
     }
     pub fn output_size(&self) -> usize {
-        self.size() * Pixel::PIXEL_SPACE
+        self.size().saturating_mul(Pixel::PIXEL_SPACE)
     }
 }
 